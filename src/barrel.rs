@@ -0,0 +1,150 @@
+use std::{
+  fs,
+  path::{Path, PathBuf},
+};
+
+use anyhow::Result;
+
+/// Module extensions that can be re-exported with `export * from`
+const EXPORTABLE_EXTENSIONS: &[&str] = &["ts", "tsx", "js", "jsx", "mts", "mjs"];
+
+/// Determine the barrel entry (relative to `ui_dir`) for an installed file.
+///
+/// Directory-based components (`ui/button/index.ts`) export the directory,
+/// file-based components (`ui/button.tsx`) export the module without its
+/// extension. Files that can't be re-exported (e.g. `.svelte`, `.css`) or
+/// live outside the UI directory yield `None`.
+pub fn export_entry(ui_dir: &Path, file: &Path) -> Option<String> {
+  let relative = file.strip_prefix(ui_dir).ok()?;
+  let mut components = relative.components();
+  let first = components.next()?.as_os_str().to_str()?;
+
+  if components.next().is_some() {
+    // Nested file: export the component directory if it has an index module
+    let has_index = EXPORTABLE_EXTENSIONS
+      .iter()
+      .any(|ext| ui_dir.join(first).join(format!("index.{}", ext)).exists());
+    return has_index.then(|| first.to_string());
+  }
+
+  let path = PathBuf::from(first);
+  let extension = path.extension()?.to_str()?;
+  let stem = path.file_stem()?.to_str()?;
+  if stem == "index" || !EXPORTABLE_EXTENSIONS.contains(&extension) {
+    return None;
+  }
+
+  Some(stem.to_string())
+}
+
+fn export_line(entry: &str) -> String {
+  format!("export * from \"./{}\";", entry)
+}
+
+/// Whether a line of the barrel file re-exports `entry`
+fn is_export_of(line: &str, entry: &str) -> bool {
+  let line = line.trim();
+  line.starts_with("export")
+    && (line.contains(&format!("\"./{}\"", entry)) || line.contains(&format!("'./{}'", entry)))
+}
+
+/// Append an export for `entry` to the barrel file, creating it if needed.
+/// Returns whether the file changed.
+pub fn add_export(index_path: &Path, entry: &str) -> Result<bool> {
+  let content = fs::read_to_string(index_path).unwrap_or_default();
+
+  if content.lines().any(|line| is_export_of(line, entry)) {
+    return Ok(false);
+  }
+
+  let mut updated = content;
+  if !updated.is_empty() && !updated.ends_with('\n') {
+    updated.push('\n');
+  }
+  updated.push_str(&export_line(entry));
+  updated.push('\n');
+
+  if let Some(parent) = index_path.parent() {
+    fs::create_dir_all(parent)?;
+  }
+  fs::write(index_path, updated)?;
+
+  Ok(true)
+}
+
+/// Remove the export of `entry` from the barrel file. Returns whether the file
+/// changed.
+pub fn remove_export(index_path: &Path, entry: &str) -> Result<bool> {
+  let Ok(content) = fs::read_to_string(index_path) else {
+    return Ok(false);
+  };
+
+  let kept: Vec<&str> = content
+    .lines()
+    .filter(|line| !is_export_of(line, entry))
+    .collect();
+
+  if kept.len() == content.lines().count() {
+    return Ok(false);
+  }
+
+  let mut updated = kept.join("\n");
+  if !updated.is_empty() {
+    updated.push('\n');
+  }
+  fs::write(index_path, updated)?;
+
+  Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+  use tempfile::TempDir;
+
+  use super::*;
+
+  #[test]
+  fn test_export_entry() {
+    let temp_dir = TempDir::new().unwrap();
+    let ui_dir = temp_dir.path();
+    fs::create_dir_all(ui_dir.join("button")).unwrap();
+    fs::write(ui_dir.join("button/index.ts"), "").unwrap();
+
+    assert_eq!(
+      export_entry(ui_dir, &ui_dir.join("button/button.svelte")),
+      Some("button".to_string())
+    );
+    assert_eq!(
+      export_entry(ui_dir, &ui_dir.join("card.tsx")),
+      Some("card".to_string())
+    );
+    assert_eq!(export_entry(ui_dir, &ui_dir.join("card.svelte")), None);
+    assert_eq!(
+      export_entry(ui_dir, &ui_dir.join("dialog/dialog.svelte")),
+      None
+    );
+    assert_eq!(export_entry(ui_dir, Path::new("/elsewhere/card.tsx")), None);
+  }
+
+  #[test]
+  fn test_add_and_remove_export() {
+    let temp_dir = TempDir::new().unwrap();
+    let index_path = temp_dir.path().join("index.ts");
+    fs::write(&index_path, "// custom\nexport { helper } from './helper'").unwrap();
+
+    assert!(add_export(&index_path, "button").unwrap());
+    assert!(!add_export(&index_path, "button").unwrap());
+    assert!(add_export(&index_path, "card").unwrap());
+    assert_eq!(
+      fs::read_to_string(&index_path).unwrap(),
+      "// custom\nexport { helper } from './helper'\nexport * from \"./button\";\nexport * from \"./card\";\n"
+    );
+
+    assert!(remove_export(&index_path, "button").unwrap());
+    assert!(!remove_export(&index_path, "button").unwrap());
+    assert_eq!(
+      fs::read_to_string(&index_path).unwrap(),
+      "// custom\nexport { helper } from './helper'\nexport * from \"./card\";\n"
+    );
+  }
+}
@@ -0,0 +1,88 @@
+use std::process::{Command, Stdio};
+
+use anyhow::{anyhow, Result};
+use url::Url;
+
+/// Open a URL in the user's default browser. Only http(s) URLs are opened,
+/// since URLs can come from remote registries and other schemes can start
+/// local programs.
+pub fn open_url(url: &str) -> Result<()> {
+  let url = browser_url(url)?;
+
+  #[cfg(target_os = "macos")]
+  let mut command = {
+    let mut command = Command::new("open");
+    command.arg(url.as_str());
+    command
+  };
+
+  // Not `cmd /C start`, which would parse `&` and other metacharacters in
+  // the URL as commands
+  #[cfg(windows)]
+  let mut command = {
+    let mut command = Command::new("rundll32");
+    command.args(["url.dll,FileProtocolHandler", url.as_str()]);
+    command
+  };
+
+  #[cfg(not(any(target_os = "macos", windows)))]
+  let mut command = {
+    let mut command = Command::new("xdg-open");
+    command.arg(url.as_str());
+    command
+  };
+
+  let status = command
+    .stdout(Stdio::null())
+    .stderr(Stdio::null())
+    .status()
+    .map_err(|e| anyhow!("Failed to open browser: {}", e))?;
+
+  if !status.success() {
+    return Err(anyhow!("Failed to open '{}' in the browser", url));
+  }
+
+  Ok(())
+}
+
+/// Parse a URL to open, refusing anything but http and https
+fn browser_url(url: &str) -> Result<Url> {
+  let parsed = Url::parse(url).map_err(|e| anyhow!("Invalid URL '{}': {}", url, e))?;
+  if !matches!(parsed.scheme(), "http" | "https") {
+    return Err(anyhow!(
+      "Refusing to open '{}': only http and https URLs are opened",
+      url
+    ));
+  }
+  Ok(parsed)
+}
+
+/// Check whether a string looks like an http(s) URL
+pub fn is_url(value: &str) -> bool {
+  value.starts_with("http://") || value.starts_with("https://")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_browser_url() {
+    assert_eq!(
+      browser_url("https://acme.dev/docs?a=1&b=2")
+        .unwrap()
+        .as_str(),
+      "https://acme.dev/docs?a=1&b=2"
+    );
+    assert!(browser_url("http://localhost:3000").is_ok());
+    for url in [
+      "file:///etc/passwd",
+      "javascript:alert(1)",
+      "vscode://extension/x",
+      "not a url",
+      "HTTPS:/",
+    ] {
+      assert!(browser_url(url).is_err(), "{}", url);
+    }
+  }
+}
@@ -1,13 +1,23 @@
 use std::{
-  collections::HashMap,
+  collections::{BTreeSet, HashMap},
   fs,
   path::{Path, PathBuf},
 };
 
 use anyhow::{anyhow, Result};
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 
-use crate::registry::{Component, ComponentInfo, RegistryIndex};
+use crate::{
+  compression::Encoding,
+  imports::{npm_dependencies, parse_imports, registry_dependencies},
+  output::status,
+  package_manager::parse_dependency_spec,
+  paths,
+  registry::{
+    deserialize_dependencies, Changelog, Component, ComponentInfo, CssVars, RegistryIndex,
+  },
+};
 
 /// Registry configuration for building components
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -24,6 +34,8 @@ pub struct RegistryConfig {
   pub docs: Option<String>,
   /// Registry author information
   pub author: Option<RegistryAuthor>,
+  /// Default SPDX license for components that don't declare one
+  pub license: Option<String>,
   /// Available styles for this registry
   pub styles: Option<Vec<String>>,
   /// Default style
@@ -32,6 +44,16 @@ pub struct RegistryConfig {
   pub components: HashMap<String, ComponentDefinition>,
 }
 
+impl RegistryConfig {
+  /// Read and parse a registry.json file
+  pub fn load(path: &Path) -> Result<Self> {
+    let content =
+      fs::read_to_string(path).map_err(|e| anyhow!("Failed to read registry config: {}", e))?;
+
+    serde_json::from_str(&content).map_err(|e| anyhow!("Failed to parse registry config: {}", e))
+  }
+}
+
 /// Registry author information
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct RegistryAuthor {
@@ -50,27 +72,82 @@ pub struct ComponentDefinition {
   pub component_type: Option<String>,
   /// Component description
   pub description: Option<String>,
+  /// Component version
+  pub version: Option<String>,
+  /// Documentation URL or markdown
+  pub docs: Option<String>,
+  /// SPDX license identifier (defaults to the registry license)
+  pub license: Option<String>,
+  /// Related pages by name, e.g. "figma", "storybook" or "playground"
+  pub links: Option<IndexMap<String, String>>,
+  /// Short example of using the component, shown after install and by
+  /// `uiget info --usage`
+  pub usage: Option<String>,
+  /// Change history, newest version first, or the URL serving it
+  pub changelog: Option<Changelog>,
+  /// uiget.json settings applied with the components of a
+  /// `registry:starter`
+  pub config: Option<serde_json::Map<String, serde_json::Value>>,
+  /// Notes printed by the installer after a successful install
+  #[serde(rename = "postInstallMessage")]
+  pub post_install_message: Option<String>,
+  /// Whether the component is deprecated
+  pub deprecated: Option<bool>,
+  /// Name of the component that replaces a deprecated one
+  #[serde(rename = "replacedBy")]
+  pub replaced_by: Option<String>,
   /// Registry dependencies (other components this depends on)
   #[serde(rename = "registryDependencies")]
   pub registry_dependencies: Option<Vec<String>>,
   /// Development dependencies (npm packages)
-  #[serde(rename = "devDependencies")]
+  #[serde(
+    rename = "devDependencies",
+    default,
+    deserialize_with = "deserialize_dependencies"
+  )]
   pub dev_dependencies: Option<Vec<String>>,
-  /// Dependencies (npm packages)
+  /// Dependencies (npm packages), as `name@version` specifiers or
+  /// `{ "name", "version" }` objects
+  #[serde(default, deserialize_with = "deserialize_dependencies")]
   pub dependencies: Option<Vec<String>>,
   /// Peer dependencies (npm packages)
-  #[serde(rename = "peerDependencies")]
+  #[serde(
+    rename = "peerDependencies",
+    default,
+    deserialize_with = "deserialize_dependencies"
+  )]
   pub peer_dependencies: Option<Vec<String>>,
+  /// Required runtime versions, e.g. { "node": ">=18" }
+  pub engines: Option<HashMap<String, String>>,
+  /// CSS variables of a style or theme
+  #[serde(rename = "cssVars")]
+  pub css_vars: Option<CssVars>,
+  /// CSS rules of a style or theme, as nested objects
+  pub css: Option<serde_json::Map<String, serde_json::Value>>,
   /// File mappings for different styles
   pub files: Option<HashMap<String, Vec<ComponentFileSource>>>,
   /// Default files (used when no style is specified)
   pub default_files: Option<Vec<ComponentFileSource>>,
   /// Tags for categorization
   pub tags: Option<Vec<String>>,
+  /// Group shown in the installer's picker and `uiget list`
+  pub category: Option<String>,
   /// Whether the component is external (not built locally)
   pub external: Option<bool>,
 }
 
+impl ComponentDefinition {
+  /// Source files for a style, falling back to the `default` style and then
+  /// to `default_files`
+  pub fn files_for_style(&self, style: &str) -> Option<&Vec<ComponentFileSource>> {
+    self
+      .files
+      .as_ref()
+      .and_then(|files| files.get(style).or_else(|| files.get("default")))
+      .or(self.default_files.as_ref())
+  }
+}
+
 /// Component file source definition
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct ComponentFileSource {
@@ -81,6 +158,8 @@ pub struct ComponentFileSource {
   /// File type (optional)
   #[serde(rename = "type")]
   pub file_type: Option<String>,
+  /// Install the file only when this expression holds for the project
+  pub condition: Option<String>,
 }
 
 /// Registry builder for generating shadcn-compatible JSON files
@@ -88,6 +167,15 @@ pub struct RegistryBuilder {
   config: RegistryConfig,
   base_path: PathBuf,
   output_path: PathBuf,
+  infer_dependencies: bool,
+  compress: bool,
+}
+
+/// npm and registry dependencies resolved for a component
+#[derive(Debug, Default, Clone, PartialEq)]
+struct ResolvedDependencies {
+  dependencies: Option<Vec<String>>,
+  registry_dependencies: Option<Vec<String>>,
 }
 
 impl RegistryBuilder {
@@ -98,23 +186,52 @@ impl RegistryBuilder {
       .unwrap_or_else(|| Path::new("."))
       .to_path_buf();
 
-    let config_content = fs::read_to_string(config_path)
-      .map_err(|e| anyhow!("Failed to read registry config: {}", e))?;
-
-    let config: RegistryConfig = serde_json::from_str(&config_content)
-      .map_err(|e| anyhow!("Failed to parse registry config: {}", e))?;
+    let config = RegistryConfig::load(config_path)?;
 
     Ok(Self {
       config,
       base_path,
       output_path: output_path.to_path_buf(),
+      infer_dependencies: false,
+      compress: false,
     })
   }
 
+  /// Fill in `dependencies` and `registryDependencies` from the imports of
+  /// each component's sources, warning where they disagree with the
+  /// declared lists
+  pub fn with_dependency_inference(mut self, enabled: bool) -> Self {
+    self.infer_dependencies = enabled;
+    self
+  }
+
+  /// Also write gzip and Brotli compressed copies (`.json.gz` and
+  /// `.json.br`) next to each JSON file, for hosts that serve
+  /// pre-compressed files
+  pub fn with_compression(mut self, enabled: bool) -> Self {
+    self.compress = enabled;
+    self
+  }
+
+  /// Write a JSON output file, plus its compressed copies when compressing
+  fn write_output(&self, path: &Path, content: String) -> std::io::Result<()> {
+    if self.compress {
+      for encoding in [Encoding::Gzip, Encoding::Brotli] {
+        let mut compressed_path = path.as_os_str().to_owned();
+        compressed_path.push(format!(".{}", encoding.extension()));
+        paths::write_file(
+          Path::new(&compressed_path),
+          encoding.compress(content.as_bytes()),
+        )?;
+      }
+    }
+    paths::write_file(path, content)
+  }
+
   /// Build all registry JSON files
   pub fn build(&self) -> Result<()> {
     // Create output directory
-    fs::create_dir_all(&self.output_path)
+    paths::create_dir_all(&self.output_path)
       .map_err(|e| anyhow!("Failed to create output directory: {}", e))?;
 
     // Generate index.json
@@ -123,7 +240,7 @@ impl RegistryBuilder {
     // Generate individual component files
     self.build_components()?;
 
-    println!(
+    status!(
       "✓ Registry built successfully to {}",
       self.output_path.display()
     );
@@ -136,13 +253,29 @@ impl RegistryBuilder {
     let mut components = Vec::new();
 
     for (name, definition) in &self.config.components {
+      let resolved = if self.infer_dependencies && !definition.external.unwrap_or(false) {
+        let sources = self.read_sources(name, definition, self.index_style())?;
+        self.resolve_dependencies(name, definition, &sources, None)
+      } else {
+        ResolvedDependencies {
+          dependencies: definition.dependencies.clone(),
+          registry_dependencies: definition.registry_dependencies.clone(),
+        }
+      };
+
       let component_info = ComponentInfo {
         name: name.clone(),
         component_type: definition.component_type.clone(),
-        dependencies: definition.dependencies.clone(),
-        registry_dependencies: definition.registry_dependencies.clone(),
+        description: definition.description.clone(),
+        docs: definition.docs.clone(),
+        dependencies: resolved.dependencies,
+        registry_dependencies: resolved.registry_dependencies,
         dev_dependencies: definition.dev_dependencies.clone(),
         relative_url: None,
+        deprecated: definition.deprecated,
+        replaced_by: definition.replaced_by.clone(),
+        category: definition.category.clone(),
+        meta: None,
       };
       components.push(component_info);
     }
@@ -156,10 +289,11 @@ impl RegistryBuilder {
 
     let index_path = self.output_path.join("index.json");
     let index_content = serde_json::to_string_pretty(&index)?;
-    fs::write(&index_path, index_content)
+    self
+      .write_output(&index_path, index_content)
       .map_err(|e| anyhow!("Failed to write index.json: {}", e))?;
 
-    println!("✓ Generated index.json");
+    status!("✓ Generated index.json");
 
     Ok(())
   }
@@ -183,21 +317,31 @@ impl RegistryBuilder {
     Ok(())
   }
 
-  /// Build a single component for a specific style
-  fn build_component(
+  /// Style whose sources describe a component in the index
+  fn index_style(&self) -> &str {
+    self
+      .config
+      .default_style
+      .as_deref()
+      .or_else(|| {
+        self
+          .config
+          .styles
+          .as_ref()
+          .and_then(|styles| styles.first().map(String::as_str))
+      })
+      .unwrap_or("default")
+  }
+
+  /// Read the source files of a component for a style
+  fn read_sources(
     &self,
     name: &str,
     definition: &ComponentDefinition,
     style: &str,
-  ) -> Result<()> {
+  ) -> Result<Vec<(ComponentFileSource, String)>> {
     // Get files for this style
-    let file_sources = if let Some(files) = &definition.files {
-      files.get(style).or_else(|| files.get("default"))
-    } else {
-      None
-    }
-    .or(definition.default_files.as_ref())
-    .ok_or_else(|| {
+    let file_sources = definition.files_for_style(style).ok_or_else(|| {
       anyhow!(
         "No files defined for component '{}' with style '{}'",
         name,
@@ -205,8 +349,7 @@ impl RegistryBuilder {
       )
     })?;
 
-    // Build component files
-    let mut component_files = Vec::new();
+    let mut sources = Vec::new();
     for file_source in file_sources {
       let source_path = self.base_path.join(&file_source.source);
 
@@ -218,30 +361,89 @@ impl RegistryBuilder {
         ));
       }
 
-      let content = fs::read_to_string(&source_path)
+      let content = paths::read_to_string(&source_path)
         .map_err(|e| anyhow!("Failed to read source file '{}': {}", file_source.source, e))?;
 
-      let component_file = crate::registry::ComponentFile {
-        content,
-        file_type: file_source.file_type.clone(),
-        target: Some(file_source.target.clone()),
-        path: None,
+      sources.push((file_source.clone(), content));
+    }
+
+    Ok(sources)
+  }
+
+  /// Resolve a component's dependencies. Without inference the declared
+  /// lists are used as-is; with it, imported packages and sibling components
+  /// are added and mismatches are reported when `warn_style` is set.
+  fn resolve_dependencies(
+    &self,
+    name: &str,
+    definition: &ComponentDefinition,
+    sources: &[(ComponentFileSource, String)],
+    warn_style: Option<&str>,
+  ) -> ResolvedDependencies {
+    if !self.infer_dependencies {
+      return ResolvedDependencies {
+        dependencies: definition.dependencies.clone(),
+        registry_dependencies: definition.registry_dependencies.clone(),
       };
+    }
 
-      component_files.push(component_file);
+    let imports: Vec<String> = sources
+      .iter()
+      .flat_map(|(_, content)| parse_imports(content))
+      .collect();
+    let known: BTreeSet<String> = self.config.components.keys().cloned().collect();
+
+    let dependencies = merge_inferred(
+      definition.dependencies.as_deref(),
+      npm_dependencies(&imports),
+      |spec| parse_dependency_spec(spec).0.to_string(),
+    );
+    let registry_deps = merge_inferred(
+      definition.registry_dependencies.as_deref(),
+      registry_dependencies(&imports, &known, name),
+      |spec| spec.to_string(),
+    );
+
+    if let Some(style) = warn_style {
+      for (field, merged) in [
+        ("dependencies", &dependencies),
+        ("registryDependencies", &registry_deps),
+      ] {
+        if !merged.undeclared.is_empty() {
+          status!(
+            "⚠ {} ({}): imported but not declared in {}: {}",
+            name,
+            style,
+            field,
+            merged.undeclared.join(", ")
+          );
+        }
+        if !merged.unused.is_empty() {
+          status!(
+            "⚠ {} ({}): declared in {} but never imported: {}",
+            name,
+            style,
+            field,
+            merged.unused.join(", ")
+          );
+        }
+      }
     }
 
-    // Create component
-    let component = Component {
-      schema: Some("https://ui.shadcn.com/schema.json".to_string()),
-      name: name.to_string(),
-      component_type: definition.component_type.clone(),
-      dependencies: definition.dependencies.clone(),
-      dev_dependencies: definition.dev_dependencies.clone(),
-      registry_dependencies: definition.registry_dependencies.clone(),
-      files: component_files,
-      registry: None,
-    };
+    ResolvedDependencies {
+      dependencies: dependencies.into_list(),
+      registry_dependencies: registry_deps.into_list(),
+    }
+  }
+
+  /// Build a single component for a specific style
+  fn build_component(
+    &self,
+    name: &str,
+    definition: &ComponentDefinition,
+    style: &str,
+  ) -> Result<()> {
+    let component = self.compose_component(name, definition, style, Some(style))?;
 
     // Write component file
     let component_dir = if style == "default" {
@@ -250,35 +452,153 @@ impl RegistryBuilder {
       self.output_path.join(style)
     };
 
-    fs::create_dir_all(&component_dir)
+    paths::create_dir_all(&component_dir)
       .map_err(|e| anyhow!("Failed to create component directory: {}", e))?;
 
     let component_path = component_dir.join(format!("{}.json", name));
     let component_content = serde_json::to_string_pretty(&component)?;
-    fs::write(&component_path, component_content)
+    self
+      .write_output(&component_path, component_content)
       .map_err(|e| anyhow!("Failed to write component file: {}", e))?;
 
     let relative_path = component_path
       .strip_prefix(&self.output_path)
       .unwrap_or(&component_path);
-    println!("✓ Generated {}", relative_path.display());
+    status!("✓ Generated {}", relative_path.display());
 
     Ok(())
   }
 
+  /// Build a component for a style in memory, without writing it
+  pub fn component(&self, name: &str, style: &str) -> Result<Component> {
+    let definition = self
+      .config
+      .components
+      .get(name)
+      .ok_or_else(|| crate::registry::RegistryError::ComponentNotFound(name.to_string()))?;
+
+    self.compose_component(name, definition, style, None)
+  }
+
+  /// Absolute paths of a component's source files for a style
+  pub fn source_paths(&self, name: &str, style: &str) -> Vec<PathBuf> {
+    self
+      .config
+      .components
+      .get(name)
+      .and_then(|definition| definition.files_for_style(style))
+      .map(|files| {
+        files
+          .iter()
+          .map(|file| self.base_path.join(&file.source))
+          .collect()
+      })
+      .unwrap_or_default()
+  }
+
+  /// Assemble the registry item for a component. Dependency mismatches are
+  /// reported when `warn_style` is set.
+  fn compose_component(
+    &self,
+    name: &str,
+    definition: &ComponentDefinition,
+    style: &str,
+    warn_style: Option<&str>,
+  ) -> Result<Component> {
+    let sources = self.read_sources(name, definition, style)?;
+    let resolved = self.resolve_dependencies(name, definition, &sources, warn_style);
+
+    // Build component files
+    let component_files = sources
+      .into_iter()
+      .map(|(file_source, content)| crate::registry::ComponentFile {
+        content,
+        file_type: file_source.file_type,
+        target: Some(file_source.target),
+        path: None,
+        condition: file_source.condition,
+      })
+      .collect();
+
+    // Create component
+    let component = Component {
+      schema: Some("https://ui.shadcn.com/schema.json".to_string()),
+      name: name.to_string(),
+      component_type: definition.component_type.clone(),
+      description: definition.description.clone(),
+      version: definition.version.clone(),
+      tags: definition.tags.clone(),
+      docs: definition.docs.clone(),
+      license: definition
+        .license
+        .clone()
+        .or_else(|| self.config.license.clone()),
+      links: definition.links.clone(),
+      usage: definition.usage.clone(),
+      changelog: definition.changelog.clone(),
+      config: definition.config.clone(),
+      post_install_message: definition.post_install_message.clone(),
+      deprecated: definition.deprecated,
+      replaced_by: definition.replaced_by.clone(),
+      dependencies: resolved.dependencies,
+      dev_dependencies: definition.dev_dependencies.clone(),
+      peer_dependencies: definition.peer_dependencies.clone(),
+      engines: definition.engines.clone(),
+      registry_dependencies: resolved.registry_dependencies,
+      frameworks: None,
+      css_vars: definition.css_vars.clone(),
+      css: definition.css.clone(),
+      files: component_files,
+      registry: None,
+      git_ref: None,
+      commit: None,
+    };
+
+    Ok(component)
+  }
+
   /// Get the registry configuration
   pub fn config(&self) -> &RegistryConfig {
     &self.config
   }
+}
 
-  /// Get the base path
-  pub fn base_path(&self) -> &Path {
-    &self.base_path
+/// Declared dependencies combined with the inferred ones
+#[derive(Debug, Default)]
+struct MergedDependencies {
+  list: Vec<String>,
+  /// Inferred from imports but missing from the declared list
+  undeclared: Vec<String>,
+  /// Declared but not found in any import
+  unused: Vec<String>,
+}
+
+impl MergedDependencies {
+  fn into_list(self) -> Option<Vec<String>> {
+    (!self.list.is_empty()).then_some(self.list)
   }
+}
 
-  /// Get the output path
-  pub fn output_path(&self) -> &Path {
-    &self.output_path
+/// Keep the declared entries (they may carry versions) and append inferred
+/// names that are missing. `name_of` extracts the bare name from an entry.
+fn merge_inferred(
+  declared: Option<&[String]>,
+  inferred: BTreeSet<String>,
+  name_of: impl Fn(&str) -> String,
+) -> MergedDependencies {
+  let declared = declared.unwrap_or_default();
+  let declared_names: BTreeSet<String> = declared.iter().map(|spec| name_of(spec)).collect();
+
+  let undeclared: Vec<String> = inferred.difference(&declared_names).cloned().collect();
+  let unused: Vec<String> = declared_names.difference(&inferred).cloned().collect();
+
+  let mut list = declared.to_vec();
+  list.extend(undeclared.iter().cloned());
+
+  MergedDependencies {
+    list,
+    undeclared,
+    unused,
   }
 }
 
@@ -330,6 +650,7 @@ mod tests {
       homepage: None,
       docs: None,
       author: None,
+      license: None,
       styles: None,
       default_style: None,
       components: HashMap::new(),
@@ -343,4 +664,107 @@ mod tests {
 
     Ok(())
   }
+
+  #[test]
+  fn test_merge_inferred() {
+    let declared = vec!["clsx@^2.0.0".to_string(), "lodash".to_string()];
+    let inferred: BTreeSet<String> = ["clsx", "lucide-react"]
+      .into_iter()
+      .map(String::from)
+      .collect();
+
+    let merged = merge_inferred(Some(&declared), inferred, |spec| {
+      parse_dependency_spec(spec).0.to_string()
+    });
+    assert_eq!(merged.list, ["clsx@^2.0.0", "lodash", "lucide-react"]);
+    assert_eq!(merged.undeclared, ["lucide-react"]);
+    assert_eq!(merged.unused, ["lodash"]);
+
+    let empty = merge_inferred(None, BTreeSet::new(), |spec| spec.to_string());
+    assert_eq!(empty.into_list(), None);
+  }
+
+  #[test]
+  fn test_build_infers_dependencies() -> Result<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let config_path = temp_dir.path().join("registry.json");
+    let output_path = temp_dir.path().join("public/r");
+    fs::create_dir_all(temp_dir.path().join("src"))?;
+    fs::write(
+      temp_dir.path().join("src/button.tsx"),
+      "import { Slot } from \"@radix-ui/react-slot\"\nimport { cn } from \"@/lib/utils\"\n",
+    )?;
+    fs::write(
+      temp_dir.path().join("src/utils.ts"),
+      "import { clsx } from \"clsx\"\n",
+    )?;
+    fs::write(
+      &config_path,
+      r#"{
+        "name": "test",
+        "components": {
+          "button": {
+            "name": "button",
+            "type": "registry:ui",
+            "links": { "figma": "https://figma.com/file/abc", "storybook": "https://sb.acme.dev" },
+            "usage": "<Button variant=\"outline\">Click</Button>",
+            "changelog": [
+              { "version": "1.1.0", "date": "2024-05-01", "notes": "Add the ghost variant" },
+              { "version": "1.0.0", "notes": "First release" }
+            ],
+            "default_files": [{ "source": "src/button.tsx", "target": "ui/button.tsx" }]
+          },
+          "utils": {
+            "name": "utils",
+            "type": "registry:lib",
+            "dependencies": ["clsx@^2.0.0"],
+            "default_files": [{ "source": "src/utils.ts", "target": "lib/utils.ts" }]
+          }
+        }
+      }"#,
+    )?;
+
+    RegistryBuilder::new(&config_path, &output_path)?
+      .with_dependency_inference(true)
+      .with_compression(true)
+      .build()?;
+
+    for encoding in [Encoding::Gzip, Encoding::Brotli] {
+      let compressed = fs::read(output_path.join(format!("button.json.{}", encoding.extension())))?;
+      assert_eq!(
+        encoding.decompress(&compressed, usize::MAX)?,
+        fs::read(output_path.join("button.json"))?
+      );
+    }
+
+    let button: Component =
+      serde_json::from_str(&fs::read_to_string(output_path.join("button.json"))?)?;
+    assert_eq!(
+      button.dependencies,
+      Some(vec!["@radix-ui/react-slot".to_string()])
+    );
+    assert_eq!(
+      button.registry_dependencies,
+      Some(vec!["utils".to_string()])
+    );
+    let links = button.links.unwrap();
+    assert_eq!(links.keys().collect::<Vec<_>>(), ["figma", "storybook"]);
+    assert_eq!(
+      button.usage.as_deref(),
+      Some(r#"<Button variant="outline">Click</Button>"#)
+    );
+    let Some(Changelog::Entries(changelog)) = button.changelog else {
+      panic!("changelog entries should be copied");
+    };
+    assert_eq!(
+      crate::registry::changes_since(&changelog, Some("1.0.0")),
+      &changelog[..1]
+    );
+
+    let utils: Component =
+      serde_json::from_str(&fs::read_to_string(output_path.join("utils.json"))?)?;
+    assert_eq!(utils.dependencies, Some(vec!["clsx@^2.0.0".to_string()]));
+
+    Ok(())
+  }
 }
@@ -0,0 +1,381 @@
+use std::{
+  collections::{BTreeMap, HashSet},
+  fs, io,
+  path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{lockfile::now_timestamp, paths};
+
+/// A registry response kept in the cache, looked up by its URL
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheEntry {
+  pub url: String,
+  /// Namespace of the registry the response came from
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub registry: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub component: Option<String>,
+  /// SHA-256 of the body, which names its blob
+  pub hash: String,
+  pub size: u64,
+  /// Validators sent back to revalidate the response
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub etag: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub last_modified: Option<String>,
+  /// Unix timestamp of the download
+  pub fetched_at: u64,
+  /// Pinned entries are used without asking the registry and survive
+  /// `cache clean`
+  #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+  pub pinned: bool,
+}
+
+/// What's known about a response besides its body
+#[derive(Debug, Default)]
+pub struct ResponseInfo {
+  pub registry: Option<String>,
+  pub component: Option<String>,
+  pub etag: Option<String>,
+  pub last_modified: Option<String>,
+}
+
+/// Selects cache entries for `clean`, `pin` and `unpin`
+#[derive(Debug, Default)]
+pub struct CacheFilter {
+  pub registry: Option<String>,
+  pub component: Option<String>,
+  /// Only entries downloaded more than this many seconds ago
+  pub older_than: Option<u64>,
+}
+
+impl CacheFilter {
+  fn matches(&self, entry: &CacheEntry, now: u64) -> bool {
+    self
+      .registry
+      .as_ref()
+      .is_none_or(|registry| entry.registry.as_ref() == Some(registry))
+      && self
+        .component
+        .as_ref()
+        .is_none_or(|component| entry.component.as_ref() == Some(component))
+      && self
+        .older_than
+        .is_none_or(|seconds| entry.fetched_at < now.saturating_sub(seconds))
+  }
+}
+
+/// Cached responses of one registry
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct RegistryUsage {
+  pub entries: usize,
+  pub bytes: u64,
+}
+
+/// Size of the cache, or of what a clean removed
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct CacheStats {
+  pub entries: usize,
+  pub blobs: usize,
+  pub bytes: u64,
+  pub pinned: usize,
+  /// Entries and their total size per registry namespace
+  pub registries: BTreeMap<String, RegistryUsage>,
+}
+
+/// User-level cache of downloaded registry responses, shared by every
+/// project. Bodies are stored once per content hash under `blobs/`, and
+/// `responses/` maps each URL to its blob and HTTP validators.
+pub struct BlobCache {
+  root: PathBuf,
+}
+
+impl BlobCache {
+  pub fn new(root: impl Into<PathBuf>) -> Self {
+    Self { root: root.into() }
+  }
+
+  /// The cache in `UIGET_CACHE_DIR`, or uiget's directory in the user's
+  /// cache directory
+  pub fn user() -> Option<Self> {
+    match std::env::var_os("UIGET_CACHE_DIR").filter(|dir| !dir.is_empty()) {
+      Some(dir) => Some(Self::new(dir)),
+      None => dirs::cache_dir().map(|dir| Self::new(dir.join("uiget"))),
+    }
+  }
+
+  pub fn root(&self) -> &Path {
+    &self.root
+  }
+
+  fn blob_path(&self, hash: &str) -> PathBuf {
+    self.root.join("blobs").join(&hash[..2]).join(hash)
+  }
+
+  fn entry_path(&self, url: &str) -> PathBuf {
+    self
+      .root
+      .join("responses")
+      .join(format!("{:x}.json", Sha256::digest(url.as_bytes())))
+  }
+
+  /// The cached response for `url`, if any
+  pub fn entry(&self, url: &str) -> Option<CacheEntry> {
+    let content = fs::read_to_string(self.entry_path(url)).ok()?;
+    serde_json::from_str::<CacheEntry>(&content)
+      .ok()
+      .filter(|entry| entry.url == url)
+  }
+
+  /// Body of a cached response. None when the blob is gone or no longer
+  /// matches its hash.
+  pub fn read(&self, entry: &CacheEntry) -> Option<Vec<u8>> {
+    let body = fs::read(self.blob_path(&entry.hash)).ok()?;
+    (format!("{:x}", Sha256::digest(&body)) == entry.hash).then_some(body)
+  }
+
+  /// Cache `body` as the response for `url`, keeping the entry pinned if
+  /// it was
+  pub fn store(&self, url: &str, body: &[u8], info: ResponseInfo) -> io::Result<CacheEntry> {
+    let hash = format!("{:x}", Sha256::digest(body));
+    let blob_path = self.blob_path(&hash);
+    if !blob_path.exists() {
+      paths::create_dir_all(paths::parent_dir(&blob_path))?;
+      paths::write_atomic(&blob_path, body)?;
+    }
+
+    let entry = CacheEntry {
+      url: url.to_string(),
+      registry: info.registry,
+      component: info.component,
+      hash,
+      size: body.len() as u64,
+      etag: info.etag,
+      last_modified: info.last_modified,
+      fetched_at: now_timestamp(),
+      pinned: self.entry(url).is_some_and(|entry| entry.pinned),
+    };
+    self.write_entry(&entry)?;
+    Ok(entry)
+  }
+
+  fn write_entry(&self, entry: &CacheEntry) -> io::Result<()> {
+    let path = self.entry_path(&entry.url);
+    paths::create_dir_all(paths::parent_dir(&path))?;
+    paths::write_atomic(&path, serde_json::to_vec_pretty(entry)?)
+  }
+
+  /// Pin or unpin the entries `filter` selects. Returns how many changed.
+  pub fn set_pinned(&self, filter: &CacheFilter, pinned: bool) -> io::Result<usize> {
+    let now = now_timestamp();
+    let mut changed = 0;
+    for mut entry in self.entries() {
+      if entry.pinned != pinned && filter.matches(&entry, now) {
+        entry.pinned = pinned;
+        self.write_entry(&entry)?;
+        changed += 1;
+      }
+    }
+    Ok(changed)
+  }
+
+  /// Every cached response, most recent first
+  pub fn entries(&self) -> Vec<CacheEntry> {
+    let Ok(dir) = fs::read_dir(self.root.join("responses")) else {
+      return vec![];
+    };
+    let mut entries: Vec<CacheEntry> = dir
+      .flatten()
+      .filter_map(|file| fs::read_to_string(file.path()).ok())
+      .filter_map(|content| serde_json::from_str(&content).ok())
+      .collect();
+    entries.sort_by(|a, b| b.fetched_at.cmp(&a.fetched_at).then(a.url.cmp(&b.url)));
+    entries
+  }
+
+  /// Paths and sizes of the stored blobs
+  fn blobs(&self) -> Vec<(PathBuf, u64)> {
+    let Ok(dirs) = fs::read_dir(self.root.join("blobs")) else {
+      return vec![];
+    };
+    dirs
+      .flatten()
+      .filter_map(|dir| fs::read_dir(dir.path()).ok())
+      .flat_map(|files| files.flatten())
+      .filter_map(|file| Some((file.path(), file.metadata().ok()?.len())))
+      .collect()
+  }
+
+  pub fn stats(&self) -> CacheStats {
+    let blobs = self.blobs();
+    let entries = self.entries();
+    let mut registries = BTreeMap::new();
+    for entry in &entries {
+      let usage: &mut RegistryUsage = registries
+        .entry(
+          entry
+            .registry
+            .clone()
+            .unwrap_or_else(|| "(unknown)".to_string()),
+        )
+        .or_default();
+      usage.entries += 1;
+      usage.bytes += entry.size;
+    }
+    CacheStats {
+      entries: entries.len(),
+      blobs: blobs.len(),
+      bytes: blobs.iter().map(|(_, size)| size).sum(),
+      pinned: entries.iter().filter(|entry| entry.pinned).count(),
+      registries,
+    }
+  }
+
+  /// Remove the unpinned responses `filter` selects, then the blobs no
+  /// response uses anymore. Returns what was removed.
+  pub fn clean(&self, filter: &CacheFilter) -> io::Result<CacheStats> {
+    let now = now_timestamp();
+    let mut removed = CacheStats::default();
+    let mut kept = HashSet::new();
+    for entry in self.entries() {
+      if entry.pinned || !filter.matches(&entry, now) {
+        kept.insert(entry.hash);
+      } else {
+        fs::remove_file(self.entry_path(&entry.url))?;
+        removed.entries += 1;
+      }
+    }
+
+    for (path, size) in self.blobs() {
+      let hash = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default();
+      if !kept.contains(hash) {
+        fs::remove_file(&path)?;
+        removed.blobs += 1;
+        removed.bytes += size;
+      }
+    }
+    Ok(removed)
+  }
+}
+
+/// Human-readable size, e.g. "1.4 MB"
+pub fn format_size(bytes: u64) -> String {
+  const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+  let mut size = bytes as f64;
+  let mut unit = 0;
+  while size >= 1024.0 && unit < UNITS.len() - 1 {
+    size /= 1024.0;
+    unit += 1;
+  }
+  match unit {
+    0 => format!("{} B", bytes),
+    _ => format!("{:.1} {}", size, UNITS[unit]),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_store_and_read() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let cache = BlobCache::new(temp_dir.path());
+    let url = "https://acme.dev/r/button.json";
+    assert!(cache.entry(url).is_none());
+
+    let stored = cache
+      .store(
+        url,
+        b"{\"name\":\"button\"}",
+        ResponseInfo {
+          etag: Some("\"v1\"".to_string()),
+          ..Default::default()
+        },
+      )
+      .unwrap();
+    let entry = cache.entry(url).unwrap();
+    assert_eq!(entry, stored);
+    assert_eq!(entry.etag.as_deref(), Some("\"v1\""));
+    assert_eq!(cache.read(&entry).unwrap(), b"{\"name\":\"button\"}");
+
+    // The same content from another URL shares the blob
+    cache
+      .store(
+        "https://mirror.dev/r/button.json",
+        b"{\"name\":\"button\"}",
+        ResponseInfo::default(),
+      )
+      .unwrap();
+    let stats = cache.stats();
+    assert_eq!((stats.entries, stats.blobs, stats.bytes), (2, 1, 17));
+
+    // A tampered blob is ignored
+    fs::write(cache.blob_path(&entry.hash), "{}").unwrap();
+    assert!(cache.read(&entry).is_none());
+  }
+
+  #[test]
+  fn test_format_size() {
+    assert_eq!(format_size(512), "512 B");
+    assert_eq!(format_size(1536), "1.5 KB");
+    assert_eq!(format_size(3 * 1024 * 1024), "3.0 MB");
+  }
+
+  #[test]
+  fn test_clean() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let cache = BlobCache::new(temp_dir.path());
+    let store = |url: &str, registry: &str, component: &str| {
+      let info = ResponseInfo {
+        registry: Some(registry.to_string()),
+        component: Some(component.to_string()),
+        ..Default::default()
+      };
+      cache.store(url, url.as_bytes(), info).unwrap();
+    };
+    store("https://acme.dev/r/a.json", "acme", "a");
+    store("https://acme.dev/r/b.json", "acme", "b");
+    store("https://ui.shadcn.com/r/b.json", "shadcn", "b");
+
+    let stats = cache.stats();
+    assert_eq!(stats.registries["acme"].entries, 2);
+    assert_eq!(stats.registries["shadcn"].entries, 1);
+
+    // Everything is recent
+    let old = CacheFilter {
+      older_than: Some(3600),
+      ..Default::default()
+    };
+    assert_eq!(cache.clean(&old).unwrap(), CacheStats::default());
+
+    let pin = CacheFilter {
+      registry: Some("acme".to_string()),
+      component: Some("b".to_string()),
+      ..Default::default()
+    };
+    assert_eq!(cache.set_pinned(&pin, true).unwrap(), 1);
+    assert_eq!(cache.stats().pinned, 1);
+
+    let acme = CacheFilter {
+      registry: Some("acme".to_string()),
+      ..Default::default()
+    };
+    let removed = cache.clean(&acme).unwrap();
+    assert_eq!((removed.entries, removed.blobs), (1, 1));
+
+    // The pinned entry survives a full clean
+    cache.clean(&CacheFilter::default()).unwrap();
+    let entries = cache.entries();
+    assert_eq!(entries.len(), 1);
+    assert!(entries[0].pinned);
+    assert!(cache.read(&entries[0]).is_some());
+  }
+}
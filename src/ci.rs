@@ -0,0 +1,302 @@
+//! Drift checks for `uiget ci`: managed files edited since install,
+//! outdated components and stale installs, judged against the policy flags
+//! and printed as lines or GitHub Actions annotations
+
+use std::path::Path;
+
+use colored::*;
+use thiserror::Error;
+
+use crate::lockfile::Lockfile;
+
+/// Seconds in a day, for `--max-age`
+const DAY: u64 = 24 * 60 * 60;
+
+/// Errors that fail a `uiget ci` run
+#[derive(Debug, Error)]
+pub enum CiError {
+  /// Findings that a policy flag turned into errors
+  #[error("{0} CI policy violation(s)")]
+  PolicyViolated(usize),
+}
+
+/// Which kinds of drift fail the run rather than only being reported
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Policy {
+  /// `--deny-modified`: managed files edited or deleted since install
+  pub deny_modified: bool,
+  /// `--deny-outdated`: components with a newer registry version
+  pub deny_outdated: bool,
+  /// `--max-age`: days since a component was last installed or updated
+  pub max_age: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+  Warning,
+  Error,
+}
+
+impl Severity {
+  fn from_denied(denied: bool) -> Self {
+    if denied {
+      Self::Error
+    } else {
+      Self::Warning
+    }
+  }
+}
+
+/// One problem with an installed component
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+  pub severity: Severity,
+  /// Short kind of problem, the annotation's title
+  pub title: &'static str,
+  pub component: String,
+  /// File the finding points at, relative to the project
+  pub file: Option<String>,
+  pub message: String,
+}
+
+impl Finding {
+  /// The finding as a GitHub Actions workflow command, which the runner
+  /// turns into an annotation on `file`
+  pub fn annotation(&self) -> String {
+    let command = match self.severity {
+      Severity::Error => "error",
+      Severity::Warning => "warning",
+    };
+    let mut properties = Vec::new();
+    if let Some(file) = &self.file {
+      properties.push(format!("file={}", escape_property(file)));
+    }
+    properties.push(format!("title={}", escape_property(self.title)));
+    format!(
+      "::{} {}::{}",
+      command,
+      properties.join(","),
+      escape_data(&self.message)
+    )
+  }
+
+  /// The finding as a line of terminal output
+  pub fn line(&self) -> String {
+    let mark = match self.severity {
+      Severity::Error => "✗".red(),
+      Severity::Warning => "⚠".yellow(),
+    };
+    format!("  {} {}: {}", mark, self.title.bold(), self.message)
+  }
+}
+
+/// Findings that need only the lockfile and the project's files: managed
+/// files edited or deleted since install, and, with `--max-age`, components
+/// installed more than that many days before `now`
+pub fn check_lockfile(
+  lockfile: &Lockfile,
+  project_dir: &Path,
+  policy: &Policy,
+  now: u64,
+) -> Vec<Finding> {
+  let mut findings = Vec::new();
+  for (name, locked) in &lockfile.components {
+    for file in locked
+      .files
+      .iter()
+      .filter(|file| file.is_modified(project_dir))
+    {
+      let deleted = !project_dir.join(&file.path).exists();
+      findings.push(Finding {
+        severity: Severity::from_denied(policy.deny_modified),
+        title: if deleted {
+          "deleted file"
+        } else {
+          "modified file"
+        },
+        component: name.clone(),
+        file: Some(file.path.clone()),
+        message: format!(
+          "{} of component '{}' was {} since it was installed",
+          file.path,
+          name,
+          if deleted { "deleted" } else { "edited" }
+        ),
+      });
+    }
+
+    // Records from before install times were kept have none to compare
+    let Some(max_age) = policy.max_age else {
+      continue;
+    };
+    if locked.installed_at == 0 {
+      continue;
+    }
+    let age = now.saturating_sub(locked.installed_at) / DAY;
+    if age > max_age {
+      findings.push(Finding {
+        severity: Severity::Error,
+        title: "stale component",
+        component: name.clone(),
+        file: locked.files.first().map(|file| file.path.clone()),
+        message: format!(
+          "Component '{}' was installed {} days ago, more than the {} allowed",
+          name, age, max_age
+        ),
+      });
+    }
+  }
+  findings
+}
+
+/// Findings for the components found outdated, pointing at each one's
+/// first managed file when the lockfile has it
+pub fn outdated_findings(
+  lockfile: &Lockfile,
+  outdated: &[String],
+  policy: &Policy,
+) -> Vec<Finding> {
+  outdated
+    .iter()
+    .map(|name| {
+      let locked = lockfile.get(name);
+      Finding {
+        severity: Severity::from_denied(policy.deny_outdated),
+        title: "outdated component",
+        component: name.clone(),
+        file: locked
+          .and_then(|locked| locked.files.first())
+          .map(|file| file.path.clone()),
+        message: match locked.and_then(|locked| locked.version.as_deref()) {
+          Some(version) => format!(
+            "Component '{}' {} has a newer version in its registry",
+            name, version
+          ),
+          None => format!("Component '{}' has a newer version in its registry", name),
+        },
+      }
+    })
+    .collect()
+}
+
+/// Count the findings that fail the run
+pub fn errors(findings: &[Finding]) -> usize {
+  findings
+    .iter()
+    .filter(|finding| finding.severity == Severity::Error)
+    .count()
+}
+
+/// Escape an annotation message
+fn escape_data(value: &str) -> String {
+  value
+    .replace('%', "%25")
+    .replace('\r', "%0D")
+    .replace('\n', "%0A")
+}
+
+/// Escape an annotation property such as the file path
+fn escape_property(value: &str) -> String {
+  escape_data(value).replace(':', "%3A").replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod tests {
+  use std::fs;
+
+  use tempfile::TempDir;
+
+  use super::*;
+  use crate::lockfile::{LockedComponent, LockedFile};
+
+  #[test]
+  fn test_check_lockfile() {
+    let temp_dir = TempDir::new().unwrap();
+    let project_dir = temp_dir.path();
+    let mut lockfile = Lockfile::default();
+    let mut files = Vec::new();
+    for (path, content) in [
+      ("button.svelte", "a"),
+      ("card.svelte", "b"),
+      ("input.svelte", "c"),
+    ] {
+      let path = project_dir.join(path);
+      fs::write(&path, content).unwrap();
+      files.push(LockedFile::new(project_dir, &path, content));
+    }
+    lockfile.components.insert(
+      "button".to_string(),
+      LockedComponent {
+        files: files.clone(),
+        installed_at: 100 * DAY,
+        ..Default::default()
+      },
+    );
+    lockfile
+      .components
+      .insert("legacy".to_string(), LockedComponent::default());
+
+    let policy = Policy::default();
+    assert!(check_lockfile(&lockfile, project_dir, &policy, 100 * DAY).is_empty());
+
+    fs::write(project_dir.join("button.svelte"), "edited").unwrap();
+    fs::remove_file(project_dir.join("card.svelte")).unwrap();
+    let findings = check_lockfile(&lockfile, project_dir, &policy, 100 * DAY);
+    assert_eq!(findings.len(), 2);
+    assert_eq!(findings[0].title, "modified file");
+    assert_eq!(findings[0].severity, Severity::Warning);
+    assert_eq!(findings[1].title, "deleted file");
+    assert_eq!(findings[1].file.as_deref(), Some("card.svelte"));
+
+    let policy = Policy {
+      deny_modified: true,
+      max_age: Some(30),
+      ..Default::default()
+    };
+    let findings = check_lockfile(&lockfile, project_dir, &policy, 131 * DAY);
+    assert_eq!(errors(&findings), 3);
+    assert_eq!(findings[2].title, "stale component");
+    assert_eq!(
+      findings[2].message,
+      "Component 'button' was installed 31 days ago, more than the 30 allowed"
+    );
+    assert_eq!(
+      check_lockfile(&lockfile, project_dir, &policy, 130 * DAY).len(),
+      2
+    );
+  }
+
+  #[test]
+  fn test_annotation() {
+    let mut lockfile = Lockfile::default();
+    lockfile.components.insert(
+      "button".to_string(),
+      LockedComponent {
+        version: Some("1.0.0".to_string()),
+        files: vec![LockedFile {
+          path: "src/ui/button,v2.svelte".to_string(),
+          hash: String::new(),
+        }],
+        ..Default::default()
+      },
+    );
+
+    let outdated = ["button".to_string(), "card".to_string()];
+    let findings = outdated_findings(&lockfile, &outdated, &Policy::default());
+    assert_eq!(
+      findings[0].annotation(),
+      "::warning file=src/ui/button%2Cv2.svelte,title=outdated component::Component 'button' 1.0.0 has a newer version in its registry"
+    );
+
+    let policy = Policy {
+      deny_outdated: true,
+      ..Default::default()
+    };
+    let mut finding = outdated_findings(&lockfile, &outdated, &policy).remove(1);
+    finding.message.push_str("\n100%");
+    assert_eq!(
+      finding.annotation(),
+      "::error title=outdated component::Component 'card' has a newer version in its registry%0A100%25"
+    );
+  }
+}
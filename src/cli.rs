@@ -1,5 +1,9 @@
+use std::io::IsTerminal;
+
 use clap::{Parser, Subcommand};
 
+use crate::{framework::Framework, i18n::Locale, output::Verbosity};
+
 /// A CLI tool for downloading shadcn components from multiple registries
 #[derive(Parser)]
 #[command(name = "uiget")]
@@ -17,6 +21,28 @@ pub struct Cli {
   /// Enable verbose output
   #[arg(short, long, global = true)]
   pub verbose: bool,
+
+  /// Only print errors
+  #[arg(short, long, global = true, conflicts_with = "verbose")]
+  pub quiet: bool,
+
+  /// Language of messages: en or pt-BR (defaults to the config, then the
+  /// environment)
+  #[arg(long, global = true, value_name = "LOCALE", value_parser = parse_locale)]
+  pub lang: Option<Locale>,
+
+  /// Framework whose component variants to fetch: react, svelte or vue
+  /// (defaults to the one detected from package.json)
+  #[arg(long, global = true, value_parser = parse_framework)]
+  pub framework: Option<Framework>,
+
+  /// Write progress events to stderr, one JSON object per line
+  #[arg(long, global = true, value_name = "FORMAT", value_parser = ["ndjson"])]
+  pub progress: Option<String>,
+
+  /// Never prompt: accept safe defaults, or fail when a choice is required
+  #[arg(short, long, global = true, visible_alias = "non-interactive")]
+  pub yes: bool,
 }
 
 #[derive(Subcommand)]
@@ -27,46 +53,179 @@ pub enum Commands {
     #[arg(short, long)]
     force: bool,
 
-    /// Base color for the theme
-    #[arg(long, default_value = "slate")]
-    base_color: String,
+    /// Start from a shared configuration template (a uiget.json URL or path)
+    /// instead of the built-in defaults
+    #[arg(long, value_name = "URL|PATH")]
+    from: Option<String>,
 
-    /// CSS file path
-    #[arg(long, default_value = "src/app.css")]
-    css: String,
+    /// Base color for the theme (default: slate)
+    #[arg(long)]
+    base_color: Option<String>,
 
-    /// Components alias
-    #[arg(long, default_value = "$lib/components")]
-    components: String,
+    /// CSS file path (default: src/app.css)
+    #[arg(long)]
+    css: Option<String>,
 
-    /// Utils alias
-    #[arg(long, default_value = "$lib/utils")]
-    utils: String,
+    /// Components alias (default: $lib/components)
+    #[arg(long)]
+    components: Option<String>,
+
+    /// Utils alias (default: $lib/utils)
+    #[arg(long)]
+    utils: Option<String>,
   },
 
   /// Add a component from a registry
   Add {
     /// Component name to add (optional - if not provided, shows interactive
-    /// menu)
+    /// menu). Supports `*` and `?` wildcards, e.g. 'data-table*'
     component: Option<String>,
 
+    /// Add every component from the registry index (combine with --type)
+    #[arg(long, conflicts_with = "component")]
+    all: bool,
+
+    /// Only add components of this type when using wildcards or --all
+    #[arg(long = "type", value_name = "TYPE")]
+    component_type: Option<String>,
+
     /// Registry namespace to use (defaults to auto-detect)
     #[arg(short, long)]
     registry: Option<String>,
 
+    /// Use this registry URL for this run only, without adding it to the
+    /// config
+    #[arg(long, value_name = "URL", conflicts_with_all = ["registry", "file", "stdin"])]
+    registry_url: Option<String>,
+
     /// Skip dependency installation
     #[arg(long)]
     skip_deps: bool,
 
-    /// Overwrite existing files
+    /// Branch, tag or commit to read Git registries at; the resolved
+    /// commit is recorded in the lockfile
+    #[arg(long = "ref", value_name = "REF")]
+    git_ref: Option<String>,
+
+    /// Don't run the package manager; list the npm dependencies to add and
+    /// record them in the lockfile
+    #[arg(long)]
+    no_install: bool,
+
+    /// Print the package manager commands that would install the npm
+    /// dependencies instead of running them
+    #[arg(long, conflicts_with = "no_install")]
+    print_commands: bool,
+
+    /// Overwrite existing files. Files changed since they were installed
+    /// are shown as a diff and need confirmation; repeat (--force --force)
+    /// to overwrite them without asking
+    #[arg(short, long, action = clap::ArgAction::Count)]
+    force: u8,
+
+    /// Install a component from a local JSON file instead of a registry
+    #[arg(long, conflicts_with_all = ["component", "stdin"])]
+    file: Option<String>,
+
+    /// Read the component JSON from standard input
+    #[arg(long, conflicts_with = "component")]
+    stdin: bool,
+
+    /// Pre-check installed components in the interactive picker; selecting
+    /// one that is installed and unmodified does nothing
+    #[arg(long, conflicts_with_all = ["component", "all", "file", "stdin"])]
+    sync: bool,
+  },
+
+  /// Install a starter: a curated set of components, plus the uiget.json
+  /// settings it needs, after one confirmation
+  Apply {
+    /// Starter name (a `registry:starter` item)
+    starter: String,
+
+    /// Registry namespace to use (defaults to auto-detect)
+    #[arg(short, long)]
+    registry: Option<String>,
+
+    /// Skip dependency installation
+    #[arg(long)]
+    skip_deps: bool,
+
+    /// Reinstall components that are already installed
     #[arg(short, long)]
     force: bool,
+
+    /// Don't run the package manager; list the npm dependencies to add and
+    /// record them in the lockfile
+    #[arg(long)]
+    no_install: bool,
+  },
+
+  /// Symlink a component's files from a local registry instead of copying
+  /// them, to develop a component against a consuming app
+  Link {
+    /// Component name in the local registry
+    component: String,
+
+    /// Local registry.json (or the directory containing it)
+    #[arg(short, long, default_value = "./registry.json")]
+    registry: String,
+
+    /// Create hard links instead of symlinks
+    #[arg(long)]
+    hard: bool,
+
+    /// Replace existing files
+    #[arg(short, long)]
+    force: bool,
+  },
+
+  /// Watch a local registry and reinstall components when their sources
+  /// change
+  Dev {
+    /// Components to watch (defaults to the installed ones)
+    components: Vec<String>,
+
+    /// Local registry.json (or the directory containing it)
+    #[arg(short, long, default_value = "./registry.json")]
+    registry: String,
   },
 
   /// Remove a component
   Remove {
     /// Component name to remove
     component: String,
+
+    /// Also delete files edited since the component was installed
+    #[arg(short, long)]
+    force: bool,
+  },
+
+  /// Stop managing a component, keeping its files as the project's own
+  Eject {
+    /// Component name to eject
+    component: String,
+  },
+
+  /// Delete files removed or renamed components left in uiget's directories
+  Prune {
+    /// Only list the orphaned files
+    #[arg(long)]
+    dry_run: bool,
+  },
+
+  /// Start managing existing files as a registry component
+  Adopt {
+    /// File or directory holding the component
+    path: String,
+
+    /// Component name, if it differs from the file or directory name
+    #[arg(long = "as", value_name = "NAME")]
+    name: Option<String>,
+
+    /// Registry namespace to look in
+    #[arg(short, long)]
+    registry: Option<String>,
   },
 
   /// List available components
@@ -75,9 +234,18 @@ pub enum Commands {
     #[arg(short, long)]
     registry: Option<String>,
 
-    /// Category to filter by
+    /// Use this registry URL for this run only, without adding it to the
+    /// config
+    #[arg(long, value_name = "URL", conflicts_with = "registry")]
+    registry_url: Option<String>,
+
+    /// Category to filter by, e.g. "Hooks" or a registry-defined group
     #[arg(long)]
     category: Option<String>,
+
+    /// Show installed components by directory on disk, with untracked files
+    #[arg(long, conflicts_with_all = ["registry", "registry_url", "category"])]
+    tree: bool,
   },
 
   /// Search for components
@@ -88,6 +256,11 @@ pub enum Commands {
     /// Registry namespace to search in
     #[arg(short, long)]
     registry: Option<String>,
+
+    /// Use this registry URL for this run only, without adding it to the
+    /// config
+    #[arg(long, value_name = "URL", conflicts_with = "registry")]
+    registry_url: Option<String>,
   },
 
   /// Manage registries
@@ -101,9 +274,24 @@ pub enum Commands {
     /// Specific component to update
     component: Option<String>,
 
+    /// Only update these components, comma-separated, e.g. names from
+    /// `outdated --json`; each must be installed
+    #[arg(
+      long,
+      value_name = "NAMES",
+      value_delimiter = ',',
+      conflicts_with = "component"
+    )]
+    only: Vec<String>,
+
     /// Registry namespace
     #[arg(short, long)]
     registry: Option<String>,
+
+    /// Write what was updated to PATH, as JSON for a .json file and
+    /// Markdown otherwise, e.g. for a pull request body
+    #[arg(long, value_name = "PATH")]
+    write_report: Option<std::path::PathBuf>,
   },
 
   /// Show information about a component
@@ -114,6 +302,43 @@ pub enum Commands {
     /// Registry namespace
     #[arg(short, long)]
     registry: Option<String>,
+
+    /// Print the contents of every component file
+    #[arg(long)]
+    files: bool,
+
+    /// Open one of the component's links in the browser, e.g. figma or
+    /// storybook
+    #[arg(long, value_name = "LINK", conflicts_with = "files")]
+    open: Option<String>,
+
+    /// Print only the component's usage example
+    #[arg(long, conflicts_with_all = ["files", "open"])]
+    usage: bool,
+  },
+
+  /// Show the documentation of a component
+  Docs {
+    /// Component name
+    component: String,
+
+    /// Registry namespace
+    #[arg(short, long)]
+    registry: Option<String>,
+
+    /// Open the documentation URL in the browser
+    #[arg(long)]
+    open: bool,
+  },
+
+  /// Show what changed in each version of a component
+  Changelog {
+    /// Component name
+    component: String,
+
+    /// Registry namespace
+    #[arg(short, long)]
+    registry: Option<String>,
   },
 
   /// List outdated components
@@ -121,6 +346,103 @@ pub enum Commands {
     /// Registry namespace to check
     #[arg(short, long)]
     registry: Option<String>,
+
+    /// Output every checked component as JSON (see `uiget schema outdated`)
+    #[arg(long)]
+    json: bool,
+  },
+
+  /// Summarize licenses of installed components and their npm dependencies
+  Licenses {
+    /// Output the report as JSON
+    #[arg(long)]
+    json: bool,
+  },
+
+  /// Summarize the project's installed components, locally
+  Report {
+    /// Output the report as JSON
+    #[arg(long)]
+    json: bool,
+
+    /// Don't ask registries which components are outdated
+    #[arg(long)]
+    offline: bool,
+  },
+
+  /// Check installed components for drift without prompting, failing on
+  /// the kinds the policy flags deny
+  Ci {
+    /// Fail when managed files were edited or deleted since install
+    #[arg(long)]
+    deny_modified: bool,
+
+    /// Fail when components have a newer version in their registry
+    #[arg(long)]
+    deny_outdated: bool,
+
+    /// Fail when components were installed more than DAYS days ago
+    #[arg(long, value_name = "DAYS")]
+    max_age: Option<u64>,
+
+    /// Output format; GitHub Actions annotations by default on its runners
+    #[arg(long, value_parser = ["text", "github"])]
+    format: Option<String>,
+  },
+
+  /// Serve uiget to editors and other tools
+  Serve {
+    /// Expose list/search/info/add as JSON-RPC 2.0 over stdin/stdout
+    #[arg(long)]
+    api: bool,
+  },
+
+  /// Run a Model Context Protocol server over stdio for AI assistants
+  Mcp {
+    /// Only offer browsing tools (no add_component)
+    #[arg(long)]
+    read_only: bool,
+  },
+
+  /// Sign in to a registry service listed under `oauth` in uiget.json
+  Login {
+    /// Provider name
+    provider: String,
+    /// Don't open the verification page in the browser
+    #[arg(long)]
+    no_browser: bool,
+  },
+
+  /// Forget the tokens from `uiget login`
+  Logout {
+    /// Provider name
+    provider: String,
+  },
+
+  /// Update uiget to the latest GitHub release
+  SelfUpdate {
+    /// Only report whether a newer version is available
+    #[arg(long)]
+    check: bool,
+  },
+
+  /// Manage the cache of downloaded components shared by all projects
+  Cache {
+    #[command(subcommand)]
+    action: CacheAction,
+  },
+
+  /// Print the JSON Schema of uiget.json, registry.json or the output of
+  /// `outdated --json`
+  Schema {
+    #[arg(default_value = "config", value_parser = ["config", "registry", "outdated"])]
+    kind: String,
+  },
+
+  /// Generate new registry content
+  New {
+    #[command(subcommand)]
+    action: NewAction,
   },
 
   /// Build components for a shadcn registry
@@ -132,11 +454,164 @@ pub enum Commands {
     /// Destination directory for json files
     #[arg(short, long, default_value = "./public/r")]
     output: String,
+
+    /// Infer dependencies and registryDependencies from source imports
+    #[arg(long)]
+    infer_deps: bool,
+
+    /// Also write gzip and Brotli compressed .json.gz and .json.br files next
+    /// to the JSON files
+    #[arg(long)]
+    compress: bool,
   },
+
+  /// Run a command in every uiget project of a monorepo
+  Ws {
+    #[command(subcommand)]
+    action: WsAction,
+
+    /// Only projects whose path matches, e.g. 'apps/*' or '!apps/legacy'
+    /// (defaults to the monorepo's workspace packages)
+    #[arg(long = "glob", value_name = "PATTERN", global = true)]
+    globs: Vec<String>,
+
+    /// Projects to run at once (defaults to the number of CPUs, or 1 for
+    /// update since package managers share the workspace lockfile)
+    #[arg(long, value_name = "N", global = true, value_parser = clap::value_parser!(u16).range(1..))]
+    max_parallel: Option<u16>,
+
+    /// Run in the current project only and write the outcome to this file
+    /// (used by `ws` for each project)
+    #[arg(long, value_name = "FILE", global = true, hide = true)]
+    result: Option<std::path::PathBuf>,
+  },
+
+  /// Any other command runs the `uiget-<command>` executable from PATH
+  #[command(external_subcommand)]
+  External(Vec<String>),
+}
+
+#[derive(Subcommand)]
+pub enum NewAction {
+  /// Add a component to a registry: stub sources for each style and its
+  /// registry.json entry
+  Component {
+    /// Component name (lowercase, dashes)
+    name: String,
+
+    /// Path to registry.json file
+    #[arg(short, long, default_value = "./registry.json")]
+    registry: String,
+
+    /// Component type
+    #[arg(long = "type", value_name = "TYPE", default_value = "registry:ui")]
+    component_type: String,
+
+    /// Component description
+    #[arg(short, long)]
+    description: Option<String>,
+
+    /// Overwrite an existing component and its files
+    #[arg(short, long)]
+    force: bool,
+  },
+}
+
+#[derive(Subcommand, Clone, Copy)]
+pub enum WsAction {
+  /// List the projects and their installed components
+  List,
+
+  /// List outdated components in every project
+  Outdated,
+
+  /// Update outdated components in every project
+  Update,
+}
+
+impl WsAction {
+  /// Subcommand name, as typed after `ws`
+  pub fn name(self) -> &'static str {
+    match self {
+      WsAction::List => "list",
+      WsAction::Outdated => "outdated",
+      WsAction::Update => "update",
+    }
+  }
+}
+
+#[derive(Subcommand)]
+pub enum CacheAction {
+  /// List cached responses, most recent first
+  Ls,
+
+  /// Remove cached responses (pinned ones are kept)
+  Clean {
+    /// Only remove responses from this registry namespace
+    #[arg(long)]
+    registry: Option<String>,
+
+    /// Only remove responses downloaded more than this many days ago
+    #[arg(long, value_name = "DAYS")]
+    older_than: Option<u64>,
+  },
+
+  /// Pin cached components so they're used offline and never cleaned
+  Pin {
+    /// Component name (all cached components when omitted)
+    component: Option<String>,
+
+    /// Only components from this registry namespace
+    #[arg(long)]
+    registry: Option<String>,
+  },
+
+  /// Unpin cached components
+  Unpin {
+    /// Component name (all cached components when omitted)
+    component: Option<String>,
+
+    /// Only components from this registry namespace
+    #[arg(long)]
+    registry: Option<String>,
+  },
+
+  /// Show where the cache is and how big it is, per registry
+  Stats,
 }
 
 #[derive(Subcommand)]
 pub enum RegistryAction {
+  /// Scaffold a new registry project to publish your own components
+  Init {
+    /// Directory to create the registry in
+    #[arg(default_value = ".")]
+    dir: String,
+
+    /// Registry name (defaults to the directory name)
+    #[arg(short, long)]
+    name: Option<String>,
+
+    /// Overwrite existing files
+    #[arg(short, long)]
+    force: bool,
+  },
+
+  /// Generate registry.json entries from an existing project's components/ui
+  /// folder
+  Import {
+    /// Project directory to scan
+    dir: String,
+
+    /// Path to registry.json file (created if missing)
+    #[arg(short, long, default_value = "./registry.json")]
+    registry: String,
+
+    /// Regenerate components that already have an entry
+    #[arg(short, long)]
+    force: bool,
+  },
+
   /// Add a new registry
   Add {
     /// Registry namespace
@@ -144,6 +619,18 @@ pub enum RegistryAction {
 
     /// Registry URL
     url: String,
+
+    /// HTTP header sent with every request (repeatable)
+    #[arg(long = "header", value_name = "KEY=VALUE", value_parser = parse_key_value)]
+    headers: Vec<(String, String)>,
+
+    /// Query parameter added to every request (repeatable)
+    #[arg(long = "param", value_name = "KEY=VALUE", value_parser = parse_key_value)]
+    params: Vec<(String, String)>,
+
+    /// Style substituted for {style} in this registry's URL
+    #[arg(long)]
+    style: Option<String>,
   },
 
   /// Remove a registry
@@ -152,6 +639,37 @@ pub enum RegistryAction {
     namespace: String,
   },
 
+  /// Rename a registry, updating the lockfile's records of components
+  /// installed from it
+  Rename {
+    /// Current namespace
+    old: String,
+
+    /// New namespace
+    new: String,
+  },
+
+  /// Print the configured registries as JSON to share with teammates,
+  /// leaving out credentials
+  Export {
+    /// Registry namespaces to export (default: all)
+    namespaces: Vec<String>,
+
+    /// Write to this file instead of stdout
+    #[arg(short, long)]
+    output: Option<String>,
+  },
+
+  /// Add the registries of a set written by `registry export`
+  ImportSet {
+    /// File or URL of the registry set
+    source: String,
+
+    /// Replace registries that are configured differently without asking
+    #[arg(short, long)]
+    force: bool,
+  },
+
   /// List all registries
   List,
 
@@ -160,6 +678,44 @@ pub enum RegistryAction {
     /// Registry namespace to test
     namespace: String,
   },
+
+  /// Compare the components of two registries
+  Diff {
+    /// First registry namespace
+    left: String,
+
+    /// Second registry namespace
+    right: String,
+
+    /// Also fetch components present in both and compare their files
+    #[arg(long)]
+    contents: bool,
+  },
+}
+
+/// Parse a `--lang` value
+fn parse_locale(arg: &str) -> Result<Locale, String> {
+  Locale::parse(arg).ok_or_else(|| format!("unsupported locale '{}' (expected en or pt-BR)", arg))
+}
+
+/// Parse the `--framework` argument
+fn parse_framework(arg: &str) -> Result<Framework, String> {
+  Framework::parse(arg).ok_or_else(|| {
+    format!(
+      "unsupported framework '{}' (expected react, svelte or vue)",
+      arg
+    )
+  })
+}
+
+/// Parse a `KEY=VALUE` argument
+fn parse_key_value(arg: &str) -> Result<(String, String), String> {
+  match arg.split_once('=') {
+    Some((key, value)) if !key.trim().is_empty() => {
+      Ok((key.trim().to_string(), value.trim().to_string()))
+    }
+    _ => Err(format!("expected KEY=VALUE, got '{}'", arg)),
+  }
 }
 
 impl Cli {
@@ -188,10 +744,92 @@ impl Cli {
     }
   }
 
+  /// Whether `add --sync` was given
+  pub fn sync_selection(&self) -> bool {
+    matches!(self.command, Commands::Add { sync: true, .. })
+  }
+
+  /// Git ref given with `add --ref`, if any
+  pub fn git_ref(&self) -> Option<&str> {
+    match &self.command {
+      Commands::Add { git_ref, .. } => git_ref.as_deref(),
+      _ => None,
+    }
+  }
+
+  /// Whether `add --no-install` or `apply --no-install` was given
+  pub fn no_install(&self) -> bool {
+    matches!(
+      self.command,
+      Commands::Add {
+        no_install: true,
+        ..
+      } | Commands::Apply {
+        no_install: true,
+        ..
+      }
+    )
+  }
+
+  /// Whether `add --print-commands` was given
+  pub fn print_commands(&self) -> bool {
+    matches!(
+      self.command,
+      Commands::Add {
+        print_commands: true,
+        ..
+      }
+    )
+  }
+
+  /// Whether `add --force --force` was given
+  pub fn overwrite_modified(&self) -> bool {
+    matches!(self.command, Commands::Add { force: 2.., .. })
+  }
+
+  /// Ad hoc registry URL given with `--registry-url`, if any
+  pub fn registry_url(&self) -> Option<&str> {
+    match &self.command {
+      Commands::Add { registry_url, .. }
+      | Commands::List { registry_url, .. }
+      | Commands::Search { registry_url, .. } => registry_url.as_deref(),
+      _ => None,
+    }
+  }
+
   /// Check if verbose mode is enabled
   pub fn is_verbose(&self) -> bool {
     self.verbose
   }
+
+  /// Check if non-error output is silenced
+  pub fn is_quiet(&self) -> bool {
+    self.quiet
+  }
+
+  /// Output level for this run. `--json` output is quiet so the report is
+  /// the only thing on stdout.
+  pub fn verbosity(&self) -> Verbosity {
+    if self.quiet
+      || matches!(
+        self.command,
+        Commands::Licenses { json: true }
+          | Commands::Report { json: true, .. }
+          | Commands::Outdated { json: true, .. }
+      )
+    {
+      Verbosity::Quiet
+    } else if self.verbose {
+      Verbosity::Verbose
+    } else {
+      Verbosity::Normal
+    }
+  }
+
+  /// Check if prompts may be shown (not disabled and stdin is a terminal)
+  pub fn is_interactive(&self) -> bool {
+    !self.yes && std::io::stdin().is_terminal()
+  }
 }
 
 #[cfg(test)]
@@ -203,4 +841,61 @@ mod tests {
     use clap::CommandFactory;
     Cli::command().debug_assert()
   }
+
+  #[test]
+  fn test_verbosity() {
+    let verbosity = |args: &[&str]| Cli::parse_from(args).verbosity();
+
+    assert_eq!(verbosity(&["uiget", "list"]), Verbosity::Normal);
+    assert_eq!(
+      verbosity(&["uiget", "list", "--verbose"]),
+      Verbosity::Verbose
+    );
+    assert_eq!(verbosity(&["uiget", "-q", "list"]), Verbosity::Quiet);
+    assert_eq!(
+      verbosity(&["uiget", "licenses", "--json"]),
+      Verbosity::Quiet
+    );
+    assert_eq!(
+      verbosity(&["uiget", "outdated", "--json"]),
+      Verbosity::Quiet
+    );
+    assert!(Cli::try_parse_from(["uiget", "-q", "-v", "list"]).is_err());
+  }
+
+  #[test]
+  fn test_update_only() {
+    let cli = Cli::parse_from([
+      "uiget",
+      "update",
+      "--only",
+      "button,card",
+      "--only",
+      "input",
+    ]);
+    let Commands::Update { only, .. } = cli.command else {
+      panic!("expected update");
+    };
+    assert_eq!(only, ["button", "card", "input"]);
+    assert!(Cli::try_parse_from(["uiget", "update", "button", "--only", "card"]).is_err());
+  }
+
+  #[test]
+  fn test_overwrite_modified() {
+    let overwrite = |args: &[&str]| Cli::parse_from(args).overwrite_modified();
+
+    assert!(!overwrite(&["uiget", "add", "button", "--force"]));
+    assert!(overwrite(&["uiget", "add", "button", "--force", "--force"]));
+    assert!(overwrite(&["uiget", "add", "button", "-ff"]));
+  }
+
+  #[test]
+  fn test_parse_key_value() {
+    assert_eq!(
+      parse_key_value("Authorization=Bearer a=b"),
+      Ok(("Authorization".to_string(), "Bearer a=b".to_string()))
+    );
+    assert!(parse_key_value("missing").is_err());
+    assert!(parse_key_value("=value").is_err());
+  }
 }
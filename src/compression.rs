@@ -0,0 +1,174 @@
+use std::{
+  fmt,
+  io::{self, Read, Write},
+};
+
+use flate2::{read::MultiGzDecoder, write::GzEncoder, Compression};
+use thiserror::Error;
+
+/// Errors raised while inflating compressed data
+#[derive(Debug, Error)]
+pub enum DecompressError {
+  /// The data is not valid in its encoding
+  #[error("Invalid {encoding} data: {source}")]
+  Invalid {
+    encoding: Encoding,
+    #[source]
+    source: io::Error,
+  },
+  /// The data inflates to more than the allowed size
+  #[error("Compressed data inflates to more than {0} bytes")]
+  TooLarge(usize),
+}
+
+/// Encodings of pre-compressed registry files
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+  Gzip,
+  Brotli,
+}
+
+impl Encoding {
+  /// Extension added to the name of a file compressed this way
+  pub fn extension(self) -> &'static str {
+    match self {
+      Encoding::Gzip => "gz",
+      Encoding::Brotli => "br",
+    }
+  }
+
+  /// Encoding of a file served from `path` whose content starts with
+  /// `data`. Gzip is told by its magic bytes; Brotli has none, so it's told
+  /// by the `.br` extension.
+  pub fn detect(path: &str, data: &[u8]) -> Option<Self> {
+    if data.starts_with(&[0x1f, 0x8b]) {
+      Some(Encoding::Gzip)
+    } else if path.ends_with(".br") {
+      Some(Encoding::Brotli)
+    } else {
+      None
+    }
+  }
+
+  /// Compress `data` at the best compression level, since files are
+  /// compressed once when the registry is built
+  pub fn compress(self, data: &[u8]) -> Vec<u8> {
+    match self {
+      Encoding::Gzip => {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+        encoder
+          .write_all(data)
+          .and_then(|_| encoder.finish())
+          .expect("writing to memory doesn't fail")
+      }
+      Encoding::Brotli => {
+        let mut out = Vec::new();
+        let mut encoder = brotli::CompressorWriter::new(&mut out, 4096, 11, 22);
+        encoder
+          .write_all(data)
+          .expect("writing to memory doesn't fail");
+        drop(encoder);
+        out
+      }
+    }
+  }
+
+  /// Decompress `data`, failing once the output passes `limit` bytes.
+  /// Concatenated gzip members decompress to their concatenated contents,
+  /// as with `gzip -d`.
+  pub fn decompress(self, data: &[u8], limit: usize) -> Result<Vec<u8>, DecompressError> {
+    let reader: Box<dyn Read + '_> = match self {
+      Encoding::Gzip => Box::new(MultiGzDecoder::new(data)),
+      Encoding::Brotli => Box::new(brotli::Decompressor::new(data, 4096)),
+    };
+    let mut out = Vec::new();
+    reader
+      .take((limit as u64).saturating_add(1))
+      .read_to_end(&mut out)
+      .map_err(|source| DecompressError::Invalid {
+        encoding: self,
+        source,
+      })?;
+    if out.len() > limit {
+      return Err(DecompressError::TooLarge(limit));
+    }
+    Ok(out)
+  }
+}
+
+impl fmt::Display for Encoding {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Encoding::Gzip => write!(f, "gzip"),
+      Encoding::Brotli => write!(f, "Brotli"),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_round_trip() {
+    let json =
+      r#"{"name":"button","files":[{"path":"ui/button.tsx","content":"export"}]}"#.repeat(200);
+    for encoding in [Encoding::Gzip, Encoding::Brotli] {
+      for data in [&b""[..], b"a", json.as_bytes()] {
+        let compressed = encoding.compress(data);
+        assert_eq!(encoding.decompress(&compressed, usize::MAX).unwrap(), data);
+      }
+      assert!(encoding.compress(json.as_bytes()).len() < json.len() / 10);
+    }
+  }
+
+  #[test]
+  fn test_detect() {
+    let gzip = Encoding::Gzip.compress(b"{}");
+    assert_eq!(
+      Encoding::detect("/r/button.json", &gzip),
+      Some(Encoding::Gzip)
+    );
+    assert_eq!(
+      Encoding::detect("/r/button.json.br", b"\x1b\x01"),
+      Some(Encoding::Brotli)
+    );
+    assert_eq!(Encoding::detect("/r/button.json", b"{}"), None);
+  }
+
+  #[test]
+  fn test_decompress_limit() {
+    for encoding in [Encoding::Gzip, Encoding::Brotli] {
+      let compressed = encoding.compress(&[b'x'; 10_000]);
+      assert!(matches!(
+        encoding.decompress(&compressed, 1_000),
+        Err(DecompressError::TooLarge(1_000))
+      ));
+      assert!(matches!(
+        encoding.decompress(b"{}", usize::MAX),
+        Err(DecompressError::Invalid { .. })
+      ));
+    }
+
+    let mut members = Encoding::Gzip.compress(&[b'x'; 600]);
+    members.extend(Encoding::Gzip.compress(&[b'x'; 600]));
+    assert!(matches!(
+      Encoding::Gzip.decompress(&members, 1_000),
+      Err(DecompressError::TooLarge(1_000))
+    ));
+  }
+
+  #[test]
+  fn test_decompress_members() {
+    let mut members = Encoding::Gzip.compress(b"{\"name\":");
+    members.extend(Encoding::Gzip.compress(b"\"button\"}"));
+    assert_eq!(
+      Encoding::Gzip.decompress(&members, usize::MAX).unwrap(),
+      b"{\"name\":\"button\"}"
+    );
+
+    // Bytes after a member that aren't another member
+    members.extend(b"garbage");
+    assert!(Encoding::Gzip.decompress(&members, usize::MAX).is_err());
+  }
+}
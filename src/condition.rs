@@ -0,0 +1,299 @@
+//! `files[].condition` expressions, evaluated against the project to pick
+//! which files of a component get installed, e.g.
+//! `typescript && framework == "sveltekit"`
+
+use std::collections::HashMap;
+
+use thiserror::Error;
+
+/// A condition that can't be evaluated
+#[derive(Debug, Error, PartialEq)]
+pub enum ConditionError {
+  #[error("unexpected {found} at position {position}")]
+  Syntax { found: String, position: usize },
+  #[error("unknown name '{0}'")]
+  UnknownName(String),
+}
+
+/// What a condition name stands for
+#[derive(Debug, Clone)]
+pub enum Fact {
+  /// A name that is true or false on its own, e.g. `typescript`
+  Flag(bool),
+  /// A name compared with `==`/`!=`. It equals each of its values, so
+  /// `framework` can be both "svelte" and "sveltekit".
+  Values(Vec<String>),
+}
+
+/// Names a condition can use, and their values for the project
+pub type Facts = HashMap<&'static str, Fact>;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+  Name(String),
+  Text(String),
+  Equal,
+  NotEqual,
+  Not,
+  And,
+  Or,
+  Open,
+  Close,
+}
+
+impl Token {
+  /// How the token reads in error messages
+  fn describe(&self) -> String {
+    match self {
+      Token::Name(name) => format!("'{}'", name),
+      Token::Text(text) => format!("\"{}\"", text),
+      Token::Equal => "'=='".to_string(),
+      Token::NotEqual => "'!='".to_string(),
+      Token::Not => "'!'".to_string(),
+      Token::And => "'&&'".to_string(),
+      Token::Or => "'||'".to_string(),
+      Token::Open => "'('".to_string(),
+      Token::Close => "')'".to_string(),
+    }
+  }
+}
+
+fn tokenize(condition: &str) -> Result<Vec<(usize, Token)>, ConditionError> {
+  let chars: Vec<char> = condition.chars().collect();
+  let mut tokens = Vec::new();
+  let mut i = 0;
+
+  while i < chars.len() {
+    let start = i;
+    let pair = chars
+      .get(i..i + 2)
+      .map(|pair| pair.iter().collect::<String>());
+    let token = match (chars[i], pair.as_deref()) {
+      (c, _) if c.is_whitespace() => {
+        i += 1;
+        continue;
+      }
+      (_, Some("==")) => Token::Equal,
+      (_, Some("!=")) => Token::NotEqual,
+      (_, Some("&&")) => Token::And,
+      (_, Some("||")) => Token::Or,
+      ('!', _) => Token::Not,
+      ('(', _) => Token::Open,
+      (')', _) => Token::Close,
+      (quote @ ('"' | '\''), _) => {
+        let end = chars[i + 1..]
+          .iter()
+          .position(|&c| c == quote)
+          .ok_or_else(|| ConditionError::Syntax {
+            found: "unterminated string".to_string(),
+            position: start,
+          })?;
+        i += end + 2;
+        tokens.push((
+          start,
+          Token::Text(chars[start + 1..start + 1 + end].iter().collect()),
+        ));
+        continue;
+      }
+      (c, _) if c.is_alphanumeric() || c == '_' => {
+        while i < chars.len() && (chars[i].is_alphanumeric() || matches!(chars[i], '_' | '-' | '.'))
+        {
+          i += 1;
+        }
+        tokens.push((start, Token::Name(chars[start..i].iter().collect())));
+        continue;
+      }
+      (c, _) => {
+        return Err(ConditionError::Syntax {
+          found: format!("'{}'", c),
+          position: start,
+        })
+      }
+    };
+    i += match token {
+      Token::Equal | Token::NotEqual | Token::And | Token::Or => 2,
+      _ => 1,
+    };
+    tokens.push((start, token));
+  }
+
+  Ok(tokens)
+}
+
+/// Recursive descent over `||`, then `&&`, then `!`, then comparisons and
+/// parentheses
+struct Parser<'a> {
+  tokens: Vec<(usize, Token)>,
+  position: usize,
+  facts: &'a Facts,
+  length: usize,
+}
+
+impl Parser<'_> {
+  fn peek(&self) -> Option<&Token> {
+    self.tokens.get(self.position).map(|(_, token)| token)
+  }
+
+  fn unexpected(&self) -> ConditionError {
+    match self.tokens.get(self.position) {
+      Some((position, token)) => ConditionError::Syntax {
+        found: token.describe(),
+        position: *position,
+      },
+      None => ConditionError::Syntax {
+        found: "end of condition".to_string(),
+        position: self.length,
+      },
+    }
+  }
+
+  fn or(&mut self) -> Result<bool, ConditionError> {
+    let mut value = self.and()?;
+    while self.peek() == Some(&Token::Or) {
+      self.position += 1;
+      // Both sides are evaluated so errors surface regardless of the values
+      value = self.and()? || value;
+    }
+    Ok(value)
+  }
+
+  fn and(&mut self) -> Result<bool, ConditionError> {
+    let mut value = self.unary()?;
+    while self.peek() == Some(&Token::And) {
+      self.position += 1;
+      value = self.unary()? && value;
+    }
+    Ok(value)
+  }
+
+  fn unary(&mut self) -> Result<bool, ConditionError> {
+    if self.peek() == Some(&Token::Not) {
+      self.position += 1;
+      return Ok(!self.unary()?);
+    }
+    self.primary()
+  }
+
+  fn primary(&mut self) -> Result<bool, ConditionError> {
+    match self.peek().cloned() {
+      Some(Token::Open) => {
+        self.position += 1;
+        let value = self.or()?;
+        if self.peek() != Some(&Token::Close) {
+          return Err(self.unexpected());
+        }
+        self.position += 1;
+        Ok(value)
+      }
+      Some(Token::Name(name)) => {
+        self.position += 1;
+        let fact = self
+          .facts
+          .get(name.as_str())
+          .ok_or(ConditionError::UnknownName(name))?;
+        let negate = match self.peek() {
+          Some(Token::Equal) => false,
+          Some(Token::NotEqual) => true,
+          _ => {
+            return Ok(match fact {
+              Fact::Flag(value) => *value,
+              Fact::Values(values) => !values.is_empty(),
+            })
+          }
+        };
+        self.position += 1;
+        let Some(Token::Text(expected)) = self.peek().cloned() else {
+          return Err(self.unexpected());
+        };
+        self.position += 1;
+        let equal = match fact {
+          Fact::Flag(value) => expected == value.to_string(),
+          Fact::Values(values) => values
+            .iter()
+            .any(|value| value.eq_ignore_ascii_case(&expected)),
+        };
+        Ok(equal != negate)
+      }
+      _ => Err(self.unexpected()),
+    }
+  }
+}
+
+/// Evaluate `condition` against `facts`
+pub fn evaluate(condition: &str, facts: &Facts) -> Result<bool, ConditionError> {
+  let mut parser = Parser {
+    tokens: tokenize(condition)?,
+    position: 0,
+    facts,
+    length: condition.chars().count(),
+  };
+  let value = parser.or()?;
+  if parser.position < parser.tokens.len() {
+    return Err(parser.unexpected());
+  }
+  Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn facts() -> Facts {
+    HashMap::from([
+      ("typescript", Fact::Flag(true)),
+      (
+        "framework",
+        Fact::Values(vec!["svelte".to_string(), "sveltekit".to_string()]),
+      ),
+      ("style", Fact::Values(vec!["new-york".to_string()])),
+      ("tailwind", Fact::Values(Vec::new())),
+    ])
+  }
+
+  #[test]
+  fn test_evaluate() {
+    let facts = facts();
+    let cases = [
+      ("typescript", true),
+      ("!typescript", false),
+      (r#"framework == "sveltekit""#, true),
+      (r#"framework == 'svelte'"#, true),
+      (r#"framework != "react""#, true),
+      (r#"style == "new-york" && !typescript"#, false),
+      (r#"style == "default" || typescript"#, true),
+      (r#"!(framework == "vue" || framework == "react")"#, true),
+      ("tailwind", false),
+      (r#"typescript == "true""#, true),
+    ];
+    for (condition, expected) in cases {
+      assert_eq!(evaluate(condition, &facts), Ok(expected), "{}", condition);
+    }
+  }
+
+  #[test]
+  fn test_invalid_conditions() {
+    let facts = facts();
+    assert_eq!(
+      evaluate("svelte5", &facts),
+      Err(ConditionError::UnknownName("svelte5".to_string()))
+    );
+    assert!(matches!(
+      evaluate("typescript &&", &facts),
+      Err(ConditionError::Syntax { position: 13, .. })
+    ));
+    assert!(matches!(
+      evaluate(r#"framework == "svelte"#, &facts),
+      Err(ConditionError::Syntax { .. })
+    ));
+    assert!(matches!(
+      evaluate("(typescript", &facts),
+      Err(ConditionError::Syntax { .. })
+    ));
+    assert_eq!(
+      evaluate("framework == svelte", &facts)
+        .unwrap_err()
+        .to_string(),
+      "unexpected 'svelte' at position 13"
+    );
+  }
+}
@@ -0,0 +1,194 @@
+use std::{
+  collections::HashMap,
+  fs,
+  path::{Path, PathBuf},
+  time::{Duration, SystemTime},
+};
+
+use anyhow::Result;
+use colored::*;
+
+use crate::{
+  builder::RegistryBuilder, installer::ComponentInstaller, output::status, registry::RegistryError,
+  scaffold::OUTPUT_DIR,
+};
+
+/// How often the registry sources are checked for changes
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Modification times of a component's source files
+type Snapshot = HashMap<PathBuf, Option<SystemTime>>;
+
+fn modified(path: &Path) -> Option<SystemTime> {
+  fs::metadata(path)
+    .and_then(|metadata| metadata.modified())
+    .ok()
+}
+
+/// Watches a local registry and reinstalls components whose sources change
+pub struct DevWatcher {
+  registry_path: PathBuf,
+  style: String,
+  components: Vec<String>,
+  builder: RegistryBuilder,
+  registry_modified: Option<SystemTime>,
+  snapshots: HashMap<String, Snapshot>,
+}
+
+impl DevWatcher {
+  /// Watch `components` of the registry.json at `registry_path`, built for
+  /// `style`
+  pub fn new(registry_path: &Path, style: &str, components: Vec<String>) -> Result<Self> {
+    let builder = RegistryBuilder::new(registry_path, Path::new(OUTPUT_DIR))?;
+
+    if let Some(unknown) = components
+      .iter()
+      .find(|name| !builder.config().components.contains_key(*name))
+    {
+      return Err(RegistryError::ComponentNotFound(unknown.clone()).into());
+    }
+
+    let mut watcher = Self {
+      registry_path: registry_path.to_path_buf(),
+      style: style.to_string(),
+      components,
+      builder,
+      registry_modified: modified(registry_path),
+      snapshots: HashMap::new(),
+    };
+    watcher.snapshots = watcher.take_snapshots();
+
+    Ok(watcher)
+  }
+
+  fn take_snapshots(&self) -> HashMap<String, Snapshot> {
+    self
+      .components
+      .iter()
+      .map(|name| {
+        let snapshot = self
+          .builder
+          .source_paths(name, &self.style)
+          .into_iter()
+          .map(|path| {
+            let time = modified(&path);
+            (path, time)
+          })
+          .collect();
+        (name.clone(), snapshot)
+      })
+      .collect()
+  }
+
+  /// Components whose sources changed since the last call. An edit to
+  /// registry.json reloads it, so components whose file list changed are
+  /// reported too.
+  pub fn changed_components(&mut self) -> Result<Vec<String>> {
+    let registry_modified = modified(&self.registry_path);
+    if registry_modified != self.registry_modified {
+      self.registry_modified = registry_modified;
+      self.builder = RegistryBuilder::new(&self.registry_path, Path::new(OUTPUT_DIR))?;
+    }
+
+    let snapshots = self.take_snapshots();
+    let changed = self
+      .components
+      .iter()
+      .filter(|name| snapshots.get(*name) != self.snapshots.get(*name))
+      .cloned()
+      .collect();
+    self.snapshots = snapshots;
+
+    Ok(changed)
+  }
+
+  /// Poll for changes until interrupted, reinstalling changed components
+  /// with `--force` semantics. Failures are reported and watching goes on.
+  pub async fn run(mut self, installer: &ComponentInstaller) -> Result<()> {
+    status!(
+      "{} Watching {} for changes to {} (Ctrl+C to stop)",
+      "→".blue(),
+      self.registry_path.display().to_string().cyan(),
+      self.components.join(", ").cyan()
+    );
+
+    loop {
+      tokio::time::sleep(POLL_INTERVAL).await;
+
+      let changed = match self.changed_components() {
+        Ok(changed) => changed,
+        Err(e) => {
+          status!("{} {}", "✗".red(), e);
+          continue;
+        }
+      };
+
+      for name in changed {
+        status!("{} {} changed, reinstalling...", "→".blue(), name.cyan());
+
+        let result = match self.builder.component(&name, &self.style) {
+          Ok(component) => {
+            installer
+              .install_local_component(component, None, true, true)
+              .await
+          }
+          Err(e) => Err(e),
+        };
+
+        match result {
+          Ok(()) => status!("{} Reinstalled {}", "✓".green(), name.cyan()),
+          Err(e) => status!("{} Failed to reinstall {}: {}", "✗".red(), name.cyan(), e),
+        }
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_changed_components() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let registry_path = temp_dir.path().join("registry.json");
+    let button = temp_dir.path().join("src/button.tsx");
+    fs::create_dir_all(button.parent().unwrap()).unwrap();
+    fs::write(&button, "export {}").unwrap();
+    fs::write(temp_dir.path().join("src/card.tsx"), "export {}").unwrap();
+    fs::write(
+      &registry_path,
+      r#"{
+        "name": "test",
+        "components": {
+          "button": {
+            "name": "button",
+            "default_files": [{ "source": "src/button.tsx", "target": "ui/button.tsx" }]
+          },
+          "card": {
+            "name": "card",
+            "default_files": [{ "source": "src/card.tsx", "target": "ui/card.tsx" }]
+          }
+        }
+      }"#,
+    )
+    .unwrap();
+
+    let mut watcher = DevWatcher::new(
+      &registry_path,
+      "default",
+      vec!["button".to_string(), "card".to_string()],
+    )
+    .unwrap();
+    assert!(watcher.changed_components().unwrap().is_empty());
+
+    let file = fs::File::options().write(true).open(&button).unwrap();
+    file
+      .set_modified(SystemTime::now() + Duration::from_secs(5))
+      .unwrap();
+    assert_eq!(watcher.changed_components().unwrap(), ["button"]);
+    assert!(watcher.changed_components().unwrap().is_empty());
+
+    assert!(DevWatcher::new(&registry_path, "default", vec!["missing".to_string()]).is_err());
+  }
+}
@@ -0,0 +1,165 @@
+use colored::*;
+
+/// Unchanged lines shown around each change
+const CONTEXT_LINES: usize = 2;
+
+/// One line of a line-by-line diff
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffLine<'a> {
+  Same(&'a str),
+  Removed(&'a str),
+  Added(&'a str),
+}
+
+/// Diff `old` against `new` line by line (longest common subsequence)
+pub fn diff_lines<'a>(old: &'a str, new: &'a str) -> Vec<DiffLine<'a>> {
+  let old: Vec<&str> = old.lines().collect();
+  let new: Vec<&str> = new.lines().collect();
+
+  // common[i][j]: length of the LCS of old[i..] and new[j..]
+  let mut common = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+  for i in (0..old.len()).rev() {
+    for j in (0..new.len()).rev() {
+      common[i][j] = if old[i] == new[j] {
+        common[i + 1][j + 1] + 1
+      } else {
+        common[i + 1][j].max(common[i][j + 1])
+      };
+    }
+  }
+
+  let (mut i, mut j) = (0, 0);
+  let mut lines = Vec::new();
+  while i < old.len() || j < new.len() {
+    if i < old.len() && j < new.len() && old[i] == new[j] {
+      lines.push(DiffLine::Same(old[i]));
+      i += 1;
+      j += 1;
+    } else if i < old.len() && (j == new.len() || common[i + 1][j] >= common[i][j + 1]) {
+      lines.push(DiffLine::Removed(old[i]));
+      i += 1;
+    } else {
+      lines.push(DiffLine::Added(new[j]));
+      j += 1;
+    }
+  }
+
+  lines
+}
+
+/// How much `new` differs from `old`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DiffStats {
+  pub same: usize,
+  pub added: usize,
+  pub removed: usize,
+}
+
+impl DiffStats {
+  /// Count the lines a diff keeps, adds and removes
+  pub fn of(old: &str, new: &str) -> Self {
+    let mut stats = Self::default();
+    for line in diff_lines(old, new) {
+      match line {
+        DiffLine::Same(_) => stats.same += 1,
+        DiffLine::Added(_) => stats.added += 1,
+        DiffLine::Removed(_) => stats.removed += 1,
+      }
+    }
+    stats
+  }
+
+  /// Share of lines the two sides have in common, from 0.0 to 1.0
+  pub fn similarity(&self) -> f64 {
+    let total = 2 * self.same + self.added + self.removed;
+    match total {
+      0 => 1.0,
+      _ => (2 * self.same) as f64 / total as f64,
+    }
+  }
+}
+
+/// Colored diff of `old` against `new` showing changed lines with a little
+/// context; skipped unchanged lines are marked with `...`
+pub fn render_diff(old: &str, new: &str) -> String {
+  let lines = diff_lines(old, new);
+  let changed: Vec<usize> = lines
+    .iter()
+    .enumerate()
+    .filter(|(_, line)| !matches!(line, DiffLine::Same(_)))
+    .map(|(index, _)| index)
+    .collect();
+  let near_change = |index: usize| {
+    changed
+      .iter()
+      .any(|&change| index.abs_diff(change) <= CONTEXT_LINES)
+  };
+
+  let mut output = Vec::new();
+  let mut skipped = false;
+  for (index, line) in lines.iter().enumerate() {
+    let rendered = match line {
+      DiffLine::Removed(text) => format!("- {}", text).red().to_string(),
+      DiffLine::Added(text) => format!("+ {}", text).green().to_string(),
+      DiffLine::Same(text) if near_change(index) => format!("  {}", text).dimmed().to_string(),
+      DiffLine::Same(_) => {
+        skipped = true;
+        continue;
+      }
+    };
+
+    if skipped {
+      output.push("  ...".dimmed().to_string());
+      skipped = false;
+    }
+    output.push(rendered);
+  }
+
+  output.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_diff_lines() {
+    assert_eq!(
+      diff_lines("a\nb\nc\n", "a\nx\nc\nd\n"),
+      [
+        DiffLine::Same("a"),
+        DiffLine::Removed("b"),
+        DiffLine::Added("x"),
+        DiffLine::Same("c"),
+        DiffLine::Added("d"),
+      ]
+    );
+    assert!(diff_lines("same\n", "same\n")
+      .iter()
+      .all(|line| matches!(line, DiffLine::Same(_))));
+  }
+
+  #[test]
+  fn test_diff_stats() {
+    let stats = DiffStats::of("a\nb\nc\n", "a\nx\nc\nd\n");
+    assert_eq!(
+      stats,
+      DiffStats {
+        same: 2,
+        added: 2,
+        removed: 1
+      }
+    );
+    assert!((stats.similarity() - 4.0 / 7.0).abs() < 1e-9);
+    assert_eq!(DiffStats::of("", "").similarity(), 1.0);
+  }
+
+  #[test]
+  fn test_render_diff_elides_unchanged_lines() {
+    colored::control::set_override(false);
+    let old = "1\n2\n3\n4\n5\n6\n7\n8\n";
+    let new = "1\n2\n3\n4\n5\n6\n7\nchanged\n";
+
+    assert_eq!(render_diff(old, new), "  ...\n  6\n  7\n- 8\n+ changed");
+  }
+}
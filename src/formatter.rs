@@ -0,0 +1,148 @@
+use std::{
+  fs,
+  path::{Path, PathBuf},
+  process::Command,
+};
+
+use anyhow::{anyhow, Result};
+
+use crate::package_manager;
+
+/// Code formatters uiget knows how to run on installed files
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Formatter {
+  Prettier,
+  Biome,
+  Dprint,
+}
+
+impl Formatter {
+  /// Name of the formatter binary
+  pub fn name(&self) -> &'static str {
+    match self {
+      Formatter::Prettier => "prettier",
+      Formatter::Biome => "biome",
+      Formatter::Dprint => "dprint",
+    }
+  }
+
+  /// Arguments that format the given files in place
+  fn args(&self) -> Vec<&'static str> {
+    match self {
+      Formatter::Prettier => vec!["--write"],
+      Formatter::Biome => vec!["format", "--write"],
+      Formatter::Dprint => vec!["fmt"],
+    }
+  }
+
+  /// Format files in place, preferring the project-local binary (run
+  /// through Yarn under Plug'n'Play) and falling back to `npx`
+  pub fn format_files(&self, project_root: &Path, files: &[PathBuf]) -> Result<()> {
+    if files.is_empty() {
+      return Ok(());
+    }
+
+    let local_bin = project_root
+      .join("node_modules")
+      .join(".bin")
+      .join(self.name());
+
+    // Plug'n'Play projects have no node_modules: Yarn runs the binary
+    let mut command = if package_manager::uses_pnp(project_root) {
+      let mut command = Command::new(if cfg!(windows) { "yarn.cmd" } else { "yarn" });
+      command.arg(self.name());
+      command
+    } else if local_bin.exists() {
+      Command::new(local_bin)
+    } else {
+      let mut command = Command::new(if cfg!(windows) { "npx.cmd" } else { "npx" });
+      command.arg(self.name());
+      command
+    };
+
+    let status = command
+      .args(self.args())
+      .args(files)
+      .current_dir(project_root)
+      .status()
+      .map_err(|e| anyhow!("Failed to run {}: {}", self.name(), e))?;
+
+    if !status.success() {
+      return Err(anyhow!(
+        "{} exited with code {}",
+        self.name(),
+        status.code().unwrap_or(-1)
+      ));
+    }
+
+    Ok(())
+  }
+}
+
+/// Detect the formatter configured in a project from its config files
+pub fn detect_formatter(project_root: &Path) -> Option<Formatter> {
+  let has_any = |names: &[&str]| names.iter().any(|name| project_root.join(name).exists());
+
+  if has_any(&["biome.json", "biome.jsonc"]) {
+    return Some(Formatter::Biome);
+  }
+
+  if has_any(&[
+    "dprint.json",
+    ".dprint.json",
+    "dprint.jsonc",
+    ".dprint.jsonc",
+  ]) {
+    return Some(Formatter::Dprint);
+  }
+
+  if has_any(&[
+    ".prettierrc",
+    ".prettierrc.json",
+    ".prettierrc.yaml",
+    ".prettierrc.yml",
+    ".prettierrc.js",
+    ".prettierrc.cjs",
+    ".prettierrc.mjs",
+    ".prettierrc.toml",
+    "prettier.config.js",
+    "prettier.config.cjs",
+    "prettier.config.mjs",
+    "prettier.config.ts",
+  ]) {
+    return Some(Formatter::Prettier);
+  }
+
+  // Prettier can also be configured through a "prettier" key in package.json
+  let package_json = fs::read_to_string(project_root.join("package.json")).ok()?;
+  let package_json: serde_json::Value = serde_json::from_str(&package_json).ok()?;
+  if package_json.get("prettier").is_some() {
+    return Some(Formatter::Prettier);
+  }
+
+  None
+}
+
+#[cfg(test)]
+mod tests {
+  use tempfile::TempDir;
+
+  use super::*;
+
+  #[test]
+  fn test_detect_formatter() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+
+    assert_eq!(detect_formatter(root), None);
+
+    fs::write(root.join("package.json"), r#"{"prettier": {}}"#).unwrap();
+    assert_eq!(detect_formatter(root), Some(Formatter::Prettier));
+
+    fs::write(root.join("dprint.json"), "{}").unwrap();
+    assert_eq!(detect_formatter(root), Some(Formatter::Dprint));
+
+    fs::write(root.join("biome.json"), "{}").unwrap();
+    assert_eq!(detect_formatter(root), Some(Formatter::Biome));
+  }
+}
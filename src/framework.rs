@@ -0,0 +1,108 @@
+use std::{fmt, path::Path};
+
+use crate::package_manager::read_declared_packages;
+
+/// UI framework a registry can publish component variants for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Framework {
+  React,
+  Svelte,
+  Vue,
+}
+
+impl Framework {
+  /// Parse a framework name, ignoring case
+  pub fn parse(name: &str) -> Option<Self> {
+    match name.trim().to_ascii_lowercase().as_str() {
+      "react" => Some(Self::React),
+      "svelte" => Some(Self::Svelte),
+      "vue" => Some(Self::Vue),
+      _ => None,
+    }
+  }
+
+  /// Name used in registry URLs and `frameworks` maps
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Self::React => "react",
+      Self::Svelte => "svelte",
+      Self::Vue => "vue",
+    }
+  }
+
+  /// Detect the framework from the packages in the project's package.json.
+  /// Svelte and Vue are checked first, since their projects sometimes pull
+  /// in React for tooling.
+  pub fn detect(project_root: &Path) -> Option<Self> {
+    let declared = read_declared_packages(project_root);
+    let has = |packages: &[&str]| {
+      declared
+        .iter()
+        .any(|name| packages.contains(&name.as_str()))
+    };
+
+    if has(&["svelte", "@sveltejs/kit"]) {
+      Some(Self::Svelte)
+    } else if has(&["vue", "nuxt"]) {
+      Some(Self::Vue)
+    } else if has(&["react", "next", "react-dom"]) {
+      Some(Self::React)
+    } else {
+      None
+    }
+  }
+}
+
+/// Meta-framework the project is built on, from its package.json:
+/// "sveltekit", "next" or "nuxt"
+pub fn detect_meta_framework(project_root: &Path) -> Option<&'static str> {
+  let declared = read_declared_packages(project_root);
+  [
+    ("@sveltejs/kit", "sveltekit"),
+    ("next", "next"),
+    ("nuxt", "nuxt"),
+  ]
+  .into_iter()
+  .find(|(package, _)| declared.iter().any(|name| name == package))
+  .map(|(_, meta)| meta)
+}
+
+impl fmt::Display for Framework {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::fs;
+
+  use tempfile::TempDir;
+
+  use super::*;
+
+  #[test]
+  fn test_parse() {
+    assert_eq!(Framework::parse("React"), Some(Framework::React));
+    assert_eq!(Framework::parse("svelte"), Some(Framework::Svelte));
+    assert_eq!(Framework::parse(" vue "), Some(Framework::Vue));
+    assert_eq!(Framework::parse("angular"), None);
+  }
+
+  #[test]
+  fn test_detect() {
+    let temp_dir = TempDir::new().unwrap();
+    assert_eq!(Framework::detect(temp_dir.path()), None);
+
+    let package_json = temp_dir.path().join("package.json");
+    fs::write(&package_json, r#"{"dependencies": {"next": "14"}}"#).unwrap();
+    assert_eq!(Framework::detect(temp_dir.path()), Some(Framework::React));
+
+    fs::write(
+      &package_json,
+      r#"{"devDependencies": {"@sveltejs/kit": "2", "react": "18"}}"#,
+    )
+    .unwrap();
+    assert_eq!(Framework::detect(temp_dir.path()), Some(Framework::Svelte));
+  }
+}
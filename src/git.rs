@@ -0,0 +1,368 @@
+use serde_json::Value;
+
+use crate::package_manager::parse_version;
+
+/// Git hosting service a registry is read from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GitHost {
+  GitHub,
+  GitLab,
+  Bitbucket,
+}
+
+impl GitHost {
+  /// Shorthand prefix of registry URLs on this host, e.g. `gitlab`
+  fn prefix(&self) -> &'static str {
+    match self {
+      Self::GitHub => "github",
+      Self::GitLab => "gitlab",
+      Self::Bitbucket => "bitbucket",
+    }
+  }
+
+  /// Environment variable holding an access token for this host
+  fn token_variable(&self) -> &'static str {
+    match self {
+      Self::GitHub => "GITHUB_TOKEN",
+      Self::GitLab => "GITLAB_TOKEN",
+      Self::Bitbucket => "BITBUCKET_TOKEN",
+    }
+  }
+}
+
+/// A registry stored in a Git repository, written as
+/// `github:owner/repo/path`, `gitlab:group/project/-/path` or
+/// `bitbucket:workspace/repo/path`, optionally followed by `#ref` for the
+/// branch, tag or commit to read (default: the default branch). Self-hosted
+/// instances go after `//`, e.g. `gitlab://git.example.com/team/ui/-/r`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitSource {
+  pub host: GitHost,
+  /// Base URL of a self-hosted instance (GitHub Enterprise, GitLab,
+  /// Bitbucket Server)
+  pub base_url: Option<String>,
+  /// `owner/repo`, `group/subgroup/project` or `workspace/repo`
+  pub project: String,
+  /// Path of component files in the repository, with `{name}`
+  pub path: String,
+  pub reference: Option<String>,
+}
+
+/// A tag as listed by the hosting service's API
+#[derive(Debug)]
+pub struct Tag {
+  pub name: String,
+}
+
+impl GitSource {
+  /// Parse a `github:`, `gitlab:` or `bitbucket:` registry URL. Returns None
+  /// for other URLs.
+  pub fn parse(url: &str) -> Option<Self> {
+    let (host, rest) = [GitHost::GitHub, GitHost::GitLab, GitHost::Bitbucket]
+      .into_iter()
+      .find_map(|host| {
+        let rest = url.strip_prefix(host.prefix())?.strip_prefix(':')?;
+        Some((host, rest))
+      })?;
+
+    let (location, reference) = match rest.split_once('#') {
+      Some((location, reference)) => (location, Some(reference.to_string())),
+      None => (rest, None),
+    };
+    let (base_url, location) = match location.strip_prefix("//") {
+      Some(location) => {
+        let (instance, location) = location.split_once('/')?;
+        (Some(format!("https://{}", instance)), location)
+      }
+      None => (None, location),
+    };
+
+    // GitLab projects can sit in nested groups, so `/-/` separates the
+    // project from the path there (as in GitLab's own URLs)
+    let location = location.trim_matches('/');
+    let (project, path) = match location.split_once("/-/") {
+      Some((project, path)) if host == GitHost::GitLab => (project.to_string(), path),
+      _ => {
+        let mut parts = location.splitn(3, '/');
+        let owner = parts.next().filter(|owner| !owner.is_empty())?;
+        let repo = parts.next().filter(|repo| !repo.is_empty())?;
+        (format!("{}/{}", owner, repo), parts.next().unwrap_or(""))
+      }
+    };
+    if !project.contains('/') {
+      return None;
+    }
+    let path = match path.trim_matches('/') {
+      "" => "{name}.json".to_string(),
+      path if path.contains("{name}") => path.to_string(),
+      path => format!("{}/{{name}}.json", path),
+    };
+
+    Some(Self {
+      host,
+      base_url,
+      project,
+      path,
+      reference: reference.filter(|reference| !reference.is_empty()),
+    })
+  }
+
+  /// `(owner, repo)` of GitHub and Bitbucket projects
+  fn owner_repo(&self) -> (&str, &str) {
+    self.project.split_once('/').unwrap_or((&self.project, ""))
+  }
+
+  /// Base URL of the host's API
+  fn api_url(&self) -> String {
+    match (self.host, &self.base_url) {
+      (GitHost::GitHub, None) => "https://api.github.com".to_string(),
+      (GitHost::GitHub, Some(base)) => format!("{}/api/v3", base),
+      (GitHost::GitLab, base) => format!(
+        "{}/api/v4/projects/{}",
+        base.as_deref().unwrap_or("https://gitlab.com"),
+        encode_path(&self.project)
+      ),
+      (GitHost::Bitbucket, None) => format!(
+        "https://api.bitbucket.org/2.0/repositories/{}",
+        self.project
+      ),
+      (GitHost::Bitbucket, Some(base)) => {
+        let (project, repo) = self.owner_repo();
+        format!("{}/rest/api/1.0/projects/{}/repos/{}", base, project, repo)
+      }
+    }
+  }
+
+  /// URL template of the raw files at `reference`, or at the source's own
+  /// ref (the default branch when it has none)
+  pub fn raw_url(&self, reference: Option<&str>) -> String {
+    let reference = reference.or(self.reference.as_deref());
+    let head = reference.unwrap_or("HEAD");
+    match (self.host, &self.base_url) {
+      (GitHost::GitHub, None) => format!(
+        "https://raw.githubusercontent.com/{}/{}/{}",
+        self.project, head, self.path
+      ),
+      (GitHost::GitHub, Some(base)) => {
+        format!("{}/raw/{}/{}/{}", base, self.project, head, self.path)
+      }
+      (GitHost::GitLab, _) => format!(
+        "{}/repository/files/{}/raw?ref={}",
+        self.api_url(),
+        encode_path(&self.path),
+        head
+      ),
+      (GitHost::Bitbucket, None) => format!("{}/src/{}/{}", self.api_url(), head, self.path),
+      (GitHost::Bitbucket, Some(_)) => {
+        let url = format!("{}/raw/{}", self.api_url(), self.path);
+        match reference {
+          Some(reference) => format!("{}?at={}", url, reference),
+          None => url,
+        }
+      }
+    }
+  }
+
+  /// API URL describing the commit a ref points to
+  pub fn commit_url(&self, reference: Option<&str>) -> String {
+    let reference = reference.or(self.reference.as_deref()).unwrap_or("HEAD");
+    match self.host {
+      GitHost::GitHub => format!(
+        "{}/repos/{}/commits/{}",
+        self.api_url(),
+        self.project,
+        reference
+      ),
+      GitHost::GitLab => format!("{}/repository/commits/{}", self.api_url(), reference),
+      GitHost::Bitbucket if self.base_url.is_none() => {
+        format!("{}/commit/{}", self.api_url(), reference)
+      }
+      GitHost::Bitbucket => format!("{}/commits/{}", self.api_url(), reference),
+    }
+  }
+
+  /// API URL listing the repository's tags
+  pub fn tags_url(&self) -> String {
+    match self.host {
+      GitHost::GitHub => format!(
+        "{}/repos/{}/tags?per_page=100",
+        self.api_url(),
+        self.project
+      ),
+      GitHost::GitLab => format!("{}/repository/tags?per_page=100", self.api_url()),
+      GitHost::Bitbucket if self.base_url.is_none() => {
+        format!("{}/refs/tags?pagelen=100", self.api_url())
+      }
+      GitHost::Bitbucket => format!("{}/tags?limit=100", self.api_url()),
+    }
+  }
+
+  /// Commit SHA from a commit API response
+  pub fn parse_commit(&self, body: &str) -> Option<String> {
+    let value: Value = serde_json::from_str(body).ok()?;
+    let key = match self.host {
+      GitHost::Bitbucket if self.base_url.is_none() => "hash",
+      GitHost::GitHub => "sha",
+      _ => "id",
+    };
+    value.get(key)?.as_str().map(String::from)
+  }
+
+  /// Tags from a tags API response
+  pub fn parse_tags(&self, body: &str) -> Vec<Tag> {
+    let Ok(value) = serde_json::from_str::<Value>(body) else {
+      return Vec::new();
+    };
+    let (list, key) = match self.host {
+      GitHost::Bitbucket if self.base_url.is_some() => (value.get("values"), "displayId"),
+      GitHost::Bitbucket => (value.get("values"), "name"),
+      _ => (Some(&value), "name"),
+    };
+
+    list
+      .and_then(Value::as_array)
+      .into_iter()
+      .flatten()
+      .filter_map(|tag| tag.get(key)?.as_str())
+      .map(|name| Tag {
+        name: name.to_string(),
+      })
+      .collect()
+  }
+
+  /// Authentication header built from the host's token variable
+  /// (`GITHUB_TOKEN`, `GITLAB_TOKEN` or `BITBUCKET_TOKEN`), if it is set
+  pub fn token_header(&self) -> Option<(&'static str, String)> {
+    let token = std::env::var(self.host.token_variable())
+      .ok()
+      .filter(|token| !token.is_empty())?;
+    Some(match self.host {
+      GitHost::GitLab => ("PRIVATE-TOKEN", token),
+      GitHost::GitHub | GitHost::Bitbucket => ("Authorization", format!("Bearer {}", token)),
+    })
+  }
+}
+
+/// Percent-encode the slashes of a repository path for GitLab's API,
+/// keeping `{...}` placeholders intact
+fn encode_path(path: &str) -> String {
+  path.replace('/', "%2F")
+}
+
+/// Whether a ref looks like a version tag, e.g. `v1.4.0` or `2.0`
+pub fn is_version_tag(reference: &str) -> bool {
+  let version = reference.strip_prefix('v').unwrap_or(reference);
+  version.starts_with(|c: char| c.is_ascii_digit())
+    && version
+      .chars()
+      .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+'))
+}
+
+/// The highest version tag newer than `current`, if any. Prerelease tags
+/// (`v2.0.0-rc.1`) are left out.
+pub fn newer_tag<'a>(tags: &'a [Tag], current: &str) -> Option<&'a str> {
+  let version = |tag: &str| parse_version(tag.strip_prefix('v').unwrap_or(tag));
+  let current = version(current);
+
+  tags
+    .iter()
+    .map(|tag| tag.name.as_str())
+    .filter(|tag| is_version_tag(tag) && !tag.contains('-') && version(tag) > current)
+    .max_by_key(|tag| version(tag))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse() {
+    let source = GitSource::parse("github:acme/ui/registry#v1.4.0").unwrap();
+    assert_eq!(source.project, "acme/ui");
+    assert_eq!(source.path, "registry/{name}.json");
+    assert_eq!(source.reference.as_deref(), Some("v1.4.0"));
+    assert_eq!(
+      source.raw_url(None),
+      "https://raw.githubusercontent.com/acme/ui/v1.4.0/registry/{name}.json"
+    );
+    assert_eq!(
+      source.raw_url(Some("abc123")),
+      "https://raw.githubusercontent.com/acme/ui/abc123/registry/{name}.json"
+    );
+
+    let source = GitSource::parse("github:acme/ui/r/{style}/{name}.json").unwrap();
+    assert_eq!(source.path, "r/{style}/{name}.json");
+    assert_eq!(
+      source.commit_url(None),
+      "https://api.github.com/repos/acme/ui/commits/HEAD"
+    );
+
+    assert!(GitSource::parse("github:acme").is_none());
+    assert!(GitSource::parse("https://example.com/{name}.json").is_none());
+  }
+
+  #[test]
+  fn test_gitlab() {
+    let source = GitSource::parse("gitlab:acme/design/ui/-/public/r#main").unwrap();
+    assert_eq!(source.host, GitHost::GitLab);
+    assert_eq!(source.project, "acme/design/ui");
+    assert_eq!(
+      source.raw_url(None),
+      "https://gitlab.com/api/v4/projects/acme%2Fdesign%2Fui/repository/files/public%2Fr%2F{name}.json/raw?ref=main"
+    );
+
+    let source = GitSource::parse("gitlab://git.example.com/team/ui/r").unwrap();
+    assert_eq!(source.base_url.as_deref(), Some("https://git.example.com"));
+    assert_eq!(source.project, "team/ui");
+    assert_eq!(
+      source.tags_url(),
+      "https://git.example.com/api/v4/projects/team%2Fui/repository/tags?per_page=100"
+    );
+    assert_eq!(
+      source.parse_commit(r#"{"id": "abc123", "short_id": "abc"}"#),
+      Some("abc123".to_string())
+    );
+  }
+
+  #[test]
+  fn test_bitbucket() {
+    let source = GitSource::parse("bitbucket:acme/ui/registry").unwrap();
+    assert_eq!(
+      source.raw_url(Some("v1.0.0")),
+      "https://api.bitbucket.org/2.0/repositories/acme/ui/src/v1.0.0/registry/{name}.json"
+    );
+    assert_eq!(
+      source.parse_commit(r#"{"hash": "abc123"}"#),
+      Some("abc123".to_string())
+    );
+    let tags = source.parse_tags(r#"{"values": [{"name": "v1.0.0"}]}"#);
+    assert_eq!(tags[0].name, "v1.0.0");
+
+    let source = GitSource::parse("bitbucket://bb.example.com/PROJ/ui/registry").unwrap();
+    assert_eq!(
+      source.raw_url(None),
+      "https://bb.example.com/rest/api/1.0/projects/PROJ/repos/ui/raw/registry/{name}.json"
+    );
+    assert_eq!(
+      source.raw_url(Some("v1.0.0")),
+      "https://bb.example.com/rest/api/1.0/projects/PROJ/repos/ui/raw/registry/{name}.json?at=v1.0.0"
+    );
+    let tags = source.parse_tags(r#"{"values": [{"id": "refs/tags/v2", "displayId": "v2"}]}"#);
+    assert_eq!(tags[0].name, "v2");
+  }
+
+  #[test]
+  fn test_newer_tag() {
+    let tags: Vec<Tag> = ["v1.3.0", "v1.4.0", "v1.10.0", "nightly", "v2.0.0-rc.1"]
+      .iter()
+      .map(|name| Tag {
+        name: name.to_string(),
+      })
+      .collect();
+
+    assert!(is_version_tag("v1.4.0"));
+    assert!(!is_version_tag("main"));
+    assert_eq!(newer_tag(&tags, "v1.4.0"), Some("v1.10.0"));
+    assert_eq!(newer_tag(&tags, "v1.10.0"), None);
+  }
+}
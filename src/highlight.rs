@@ -0,0 +1,186 @@
+use colored::*;
+use regex::Regex;
+
+/// Keywords highlighted for JavaScript, TypeScript and Svelte/Vue sources
+const KEYWORDS: &[&str] = &[
+  "as",
+  "async",
+  "await",
+  "break",
+  "case",
+  "catch",
+  "class",
+  "const",
+  "continue",
+  "default",
+  "else",
+  "export",
+  "extends",
+  "false",
+  "for",
+  "from",
+  "function",
+  "if",
+  "import",
+  "in",
+  "interface",
+  "let",
+  "new",
+  "null",
+  "return",
+  "switch",
+  "this",
+  "throw",
+  "true",
+  "try",
+  "type",
+  "typeof",
+  "undefined",
+  "var",
+  "while",
+];
+
+/// Apply lightweight terminal syntax highlighting to source code.
+///
+/// This is intentionally simple (comments, strings, keywords and numbers) so
+/// it works for the file kinds registries usually ship without pulling in a
+/// full grammar engine. The language is inferred from the file name.
+pub fn highlight_code(content: &str, file_name: &str) -> String {
+  let extension = file_name.rsplit('.').next().unwrap_or("");
+  let with_keywords = matches!(
+    extension,
+    "ts" | "tsx" | "js" | "jsx" | "mjs" | "cjs" | "svelte" | "vue"
+  );
+
+  let token_regex = Regex::new(
+    r#"(?P<comment>//.*$|/\*.*?\*/|<!--.*?-->)|(?P<string>"(?:\\.|[^"\\])*"|'(?:\\.|[^'\\])*'|`(?:\\.|[^`\\])*`)|(?P<number>\b\d+(?:\.\d+)?\b)|(?P<word>\b[A-Za-z_$][A-Za-z0-9_$]*\b)"#,
+  )
+  .unwrap();
+
+  content
+    .lines()
+    .map(|line| {
+      token_regex
+        .replace_all(line, |caps: &regex::Captures| {
+          if let Some(m) = caps.name("comment") {
+            m.as_str().dimmed().to_string()
+          } else if let Some(m) = caps.name("string") {
+            m.as_str().green().to_string()
+          } else if let Some(m) = caps.name("number") {
+            m.as_str().yellow().to_string()
+          } else {
+            let word = &caps["word"];
+            if with_keywords && KEYWORDS.contains(&word) {
+              word.magenta().to_string()
+            } else {
+              word.to_string()
+            }
+          }
+        })
+        .to_string()
+    })
+    .collect::<Vec<_>>()
+    .join("\n")
+}
+
+/// Render a markdown document for the terminal: headings, lists, emphasis,
+/// inline code and fenced code blocks (highlighted with `highlight_code`)
+pub fn render_markdown(markdown: &str) -> String {
+  let inline_code = Regex::new(r"`([^`]+)`").unwrap();
+  let bold = Regex::new(r"\*\*([^*]+)\*\*").unwrap();
+  let link = Regex::new(r"\[([^\]]+)\]\(([^)]+)\)").unwrap();
+
+  let mut output = Vec::new();
+  let mut code_block: Option<(String, Vec<&str>)> = None;
+
+  for line in markdown.lines() {
+    let trimmed = line.trim_start();
+
+    if let Some(fence) = trimmed.strip_prefix("```") {
+      match code_block.take() {
+        Some((lang, lines)) => {
+          let pseudo_name = format!("snippet.{}", lang);
+          for code_line in highlight_code(&lines.join("\n"), &pseudo_name).lines() {
+            output.push(format!("    {}", code_line));
+          }
+        }
+        None => code_block = Some((fence.trim().to_string(), Vec::new())),
+      }
+      continue;
+    }
+
+    if let Some((_, lines)) = code_block.as_mut() {
+      lines.push(line);
+      continue;
+    }
+
+    let render_inline = |text: &str| {
+      let text = link.replace_all(text, |caps: &regex::Captures| {
+        format!("{} ({})", &caps[1], caps[2].blue())
+      });
+      let text = bold.replace_all(&text, |caps: &regex::Captures| caps[1].bold().to_string());
+      inline_code
+        .replace_all(&text, |caps: &regex::Captures| caps[1].cyan().to_string())
+        .to_string()
+    };
+
+    let rendered = if let Some(heading) = trimmed.strip_prefix('#') {
+      let heading = heading.trim_start_matches('#').trim();
+      heading.bold().underline().to_string()
+    } else if let Some(item) = trimmed
+      .strip_prefix("- ")
+      .or_else(|| trimmed.strip_prefix("* "))
+    {
+      let indent = &line[..line.len() - trimmed.len()];
+      format!("{}  {} {}", indent, "•".blue(), render_inline(item))
+    } else {
+      render_inline(line)
+    };
+
+    output.push(rendered);
+  }
+
+  // Unterminated code fence: still show its content
+  if let Some((_, lines)) = code_block {
+    for code_line in lines {
+      output.push(format!("    {}", code_line));
+    }
+  }
+
+  output.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn strip_ansi(text: &str) -> String {
+    Regex::new(r"\x1b\[[0-9;]*m")
+      .unwrap()
+      .replace_all(text, "")
+      .to_string()
+  }
+
+  #[test]
+  fn test_highlight_preserves_text() {
+    let source = "import { cn } from \"$lib/utils\"; // helper\nconst size = 42;";
+    let highlighted = highlight_code(source, "button.ts");
+    assert_eq!(strip_ansi(&highlighted), source);
+
+    let css = ".btn { color: red; } /* note */";
+    assert_eq!(strip_ansi(&highlight_code(css, "theme.css")), css);
+  }
+
+  #[test]
+  fn test_render_markdown() {
+    let markdown =
+      "# Button\n\nUse `Button` for **actions**.\n\n- one\n\n```svelte\n<Button />\n```";
+    let rendered = strip_ansi(&render_markdown(markdown));
+
+    assert!(rendered.starts_with("Button\n"));
+    assert!(rendered.contains("Use Button for actions."));
+    assert!(rendered.contains("  • one"));
+    assert!(rendered.contains("    <Button />"));
+    assert!(!rendered.contains("```"));
+  }
+}
@@ -0,0 +1,155 @@
+use std::{
+  path::{Path, PathBuf},
+  process::Command,
+};
+
+use anyhow::{anyhow, Result};
+use colored::*;
+
+use crate::{config::HooksConfig, output::status};
+
+/// Lifecycle events that can trigger a configured shell hook
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookEvent {
+  PreAdd,
+  PostAdd,
+  PreRemove,
+  PostRemove,
+  PreUpdate,
+  PostUpdate,
+}
+
+impl HookEvent {
+  /// Name of the event as written in the configuration
+  pub fn name(&self) -> &'static str {
+    match self {
+      HookEvent::PreAdd => "preAdd",
+      HookEvent::PostAdd => "postAdd",
+      HookEvent::PreRemove => "preRemove",
+      HookEvent::PostRemove => "postRemove",
+      HookEvent::PreUpdate => "preUpdate",
+      HookEvent::PostUpdate => "postUpdate",
+    }
+  }
+
+  /// Get the command configured for this event, if any
+  pub fn command<'a>(&self, hooks: &'a HooksConfig) -> Option<&'a str> {
+    match self {
+      HookEvent::PreAdd => hooks.pre_add.as_deref(),
+      HookEvent::PostAdd => hooks.post_add.as_deref(),
+      HookEvent::PreRemove => hooks.pre_remove.as_deref(),
+      HookEvent::PostRemove => hooks.post_remove.as_deref(),
+      HookEvent::PreUpdate => hooks.pre_update.as_deref(),
+      HookEvent::PostUpdate => hooks.post_update.as_deref(),
+    }
+  }
+}
+
+/// Context passed to a hook through environment variables
+pub struct HookContext<'a> {
+  pub component: &'a str,
+  pub registry: Option<&'a str>,
+  pub files: &'a [PathBuf],
+}
+
+/// Run the hook configured for `event`, if any.
+///
+/// The command runs through the platform shell in `working_dir` with
+/// `UIGET_HOOK`, `UIGET_COMPONENT`, `UIGET_REGISTRY` and `UIGET_FILES`
+/// (newline-separated paths) set. A non-zero exit status is an error.
+pub fn run_hook(
+  hooks: Option<&HooksConfig>,
+  event: HookEvent,
+  context: &HookContext,
+  working_dir: &Path,
+) -> Result<()> {
+  let Some(command) = hooks.and_then(|hooks| event.command(hooks)) else {
+    return Ok(());
+  };
+
+  status!(
+    "{} Running {} hook: {}",
+    "→".blue(),
+    event.name().cyan(),
+    command.dimmed()
+  );
+
+  let files = context
+    .files
+    .iter()
+    .map(|path| path.display().to_string())
+    .collect::<Vec<_>>()
+    .join("\n");
+
+  let status = shell_command(command)
+    .current_dir(working_dir)
+    .env("UIGET_HOOK", event.name())
+    .env("UIGET_COMPONENT", context.component)
+    .env("UIGET_REGISTRY", context.registry.unwrap_or(""))
+    .env("UIGET_FILES", files)
+    .status()
+    .map_err(|e| anyhow!("Failed to run {} hook: {}", event.name(), e))?;
+
+  if !status.success() {
+    return Err(anyhow!(
+      "{} hook failed with exit code {}",
+      event.name(),
+      status.code().unwrap_or(-1)
+    ));
+  }
+
+  Ok(())
+}
+
+/// Command that runs `command` through the platform shell: `sh -c` on
+/// Unix and `cmd /C` on Windows
+pub fn shell_command(command: &str) -> Command {
+  if cfg!(windows) {
+    let mut shell = Command::new("cmd");
+    shell.args(["/C", command]);
+    shell
+  } else {
+    let mut shell = Command::new("sh");
+    shell.args(["-c", command]);
+    shell
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[cfg(unix)]
+  #[test]
+  fn test_run_hook_environment() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let hooks = HooksConfig {
+      post_add: Some("printf '%s|%s' \"$UIGET_COMPONENT\" \"$UIGET_FILES\" > out.txt".to_string()),
+      pre_add: Some("exit 3".to_string()),
+      ..Default::default()
+    };
+    let files = vec![PathBuf::from("a.ts"), PathBuf::from("b.ts")];
+    let context = HookContext {
+      component: "button",
+      registry: Some("default"),
+      files: &files,
+    };
+
+    run_hook(Some(&hooks), HookEvent::PostAdd, &context, temp_dir.path()).unwrap();
+    let output = std::fs::read_to_string(temp_dir.path().join("out.txt")).unwrap();
+    assert_eq!(output, "button|a.ts\nb.ts");
+
+    let err = run_hook(Some(&hooks), HookEvent::PreAdd, &context, temp_dir.path()).unwrap_err();
+    assert!(err.to_string().contains("preAdd hook failed"));
+
+    // Events without a configured command are a no-op
+    run_hook(
+      Some(&hooks),
+      HookEvent::PreRemove,
+      &context,
+      temp_dir.path(),
+    )
+    .unwrap();
+    run_hook(None, HookEvent::PostAdd, &context, temp_dir.path()).unwrap();
+  }
+}
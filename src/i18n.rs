@@ -0,0 +1,160 @@
+use std::{
+  fmt::Display,
+  sync::atomic::{AtomicU8, Ordering},
+};
+
+/// Languages uiget can print messages in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Locale {
+  En,
+  PtBr,
+}
+
+impl Locale {
+  /// Parse a language tag such as `en`, `pt-BR` or a POSIX locale like
+  /// `pt_BR.UTF-8`
+  pub fn parse(tag: &str) -> Option<Self> {
+    let language = tag
+      .split(['.', '@'])
+      .next()
+      .unwrap_or_default()
+      .split(['-', '_'])
+      .next()
+      .unwrap_or_default()
+      .to_ascii_lowercase();
+
+    match language.as_str() {
+      "en" | "c" | "posix" => Some(Locale::En),
+      "pt" => Some(Locale::PtBr),
+      _ => None,
+    }
+  }
+
+  /// Locale from UIGET_LANG, then the standard LC_ALL, LC_MESSAGES and LANG
+  /// variables
+  pub fn from_env() -> Option<Self> {
+    ["UIGET_LANG", "LC_ALL", "LC_MESSAGES", "LANG"]
+      .iter()
+      .filter_map(|name| std::env::var(name).ok())
+      .find(|value| !value.is_empty())
+      .and_then(|value| Self::parse(&value))
+  }
+}
+
+static LOCALE: AtomicU8 = AtomicU8::new(Locale::En as u8);
+
+/// Set the language for the rest of the run
+pub fn set_locale(locale: Locale) {
+  LOCALE.store(locale as u8, Ordering::Relaxed);
+}
+
+/// The current language
+pub fn locale() -> Locale {
+  match LOCALE.load(Ordering::Relaxed) {
+    1 => Locale::PtBr,
+    _ => Locale::En,
+  }
+}
+
+/// Localized messages. Each template has one `{}` per argument.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Message {
+  Error,
+  NoNodeProject,
+  IoError,
+  InvalidJson,
+  PackageManagerDetected,
+  PackageManagerDetectFailed,
+  SourcePackageJsonField,
+  SourceLockfile,
+  SourceYarnArtifacts,
+  SourcePnpmArtifacts,
+  SourceUserAgent,
+  SourceHeuristic,
+}
+
+impl Message {
+  fn template(self, locale: Locale) -> &'static str {
+    match locale {
+      Locale::En => match self {
+        Message::Error => "Error: {}",
+        Message::NoNodeProject => "no Node project (package.json) found from {}",
+        Message::IoError => "I/O error: {}",
+        Message::InvalidJson => "invalid JSON in {}: {}",
+        Message::PackageManagerDetected => "Detected {} via {} at {}",
+        Message::PackageManagerDetectFailed => "Failed to detect package manager: {}",
+        Message::SourcePackageJsonField => "package.json field",
+        Message::SourceLockfile => "lockfile: {}",
+        Message::SourceYarnArtifacts => "yarn artifacts: {}",
+        Message::SourcePnpmArtifacts => "pnpm artifacts: {}",
+        Message::SourceUserAgent => "user agent: {}",
+        Message::SourceHeuristic => "heuristic",
+      },
+      Locale::PtBr => match self {
+        Message::Error => "Erro: {}",
+        Message::NoNodeProject => "nenhum projeto Node encontrado (package.json) a partir de {}",
+        Message::IoError => "erro de IO: {}",
+        Message::InvalidJson => "json inválido em {}: {}",
+        Message::PackageManagerDetected => "{} detectado via {} em {}",
+        Message::PackageManagerDetectFailed => "Falha ao detectar o package manager: {}",
+        Message::SourcePackageJsonField => "campo do package.json",
+        Message::SourceLockfile => "lockfile: {}",
+        Message::SourceYarnArtifacts => "artefatos do yarn: {}",
+        Message::SourcePnpmArtifacts => "artefatos do pnpm: {}",
+        Message::SourceUserAgent => "user agent: {}",
+        Message::SourceHeuristic => "heurística",
+      },
+    }
+  }
+}
+
+/// Fill `message`'s template for `locale` with `args`, in order
+pub fn format_message(locale: Locale, message: Message, args: &[&dyn Display]) -> String {
+  let mut parts = message.template(locale).split("{}");
+  let mut text = parts.next().unwrap_or_default().to_string();
+
+  for (index, part) in parts.enumerate() {
+    if let Some(arg) = args.get(index) {
+      text.push_str(&arg.to_string());
+    }
+    text.push_str(part);
+  }
+
+  text
+}
+
+/// Fill `message` in the current language
+pub fn tr(message: Message, args: &[&dyn Display]) -> String {
+  format_message(locale(), message, args)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_locale() {
+    assert_eq!(Locale::parse("en"), Some(Locale::En));
+    assert_eq!(Locale::parse("en-US"), Some(Locale::En));
+    assert_eq!(Locale::parse("pt-BR"), Some(Locale::PtBr));
+    assert_eq!(Locale::parse("pt_BR.UTF-8"), Some(Locale::PtBr));
+    assert_eq!(Locale::parse("C"), Some(Locale::En));
+    assert_eq!(Locale::parse("fr_FR"), None);
+  }
+
+  #[test]
+  fn test_format_message() {
+    assert_eq!(
+      format_message(Locale::En, Message::InvalidJson, &[&"a.json", &"eof"]),
+      "invalid JSON in a.json: eof"
+    );
+    assert_eq!(
+      format_message(Locale::PtBr, Message::InvalidJson, &[&"a.json", &"eof"]),
+      "json inválido em a.json: eof"
+    );
+    assert_eq!(
+      format_message(Locale::PtBr, Message::SourceHeuristic, &[]),
+      "heurística"
+    );
+  }
+}
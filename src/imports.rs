@@ -0,0 +1,149 @@
+use std::collections::BTreeSet;
+
+use regex::Regex;
+
+/// Packages provided by the framework itself, never listed as dependencies
+const IMPLICIT_PACKAGES: [&str; 6] = ["react", "react-dom", "svelte", "vue", "next", "solid-js"];
+
+/// Module specifiers imported by a JS/TS/Svelte/Vue source file: static
+/// imports, side-effect imports, re-exports and dynamic `import()` calls
+pub fn parse_imports(content: &str) -> Vec<String> {
+  let pattern = Regex::new(
+    r#"(?m)(?:\bimport\s+(?:[^'";]*?\s+from\s+)?|\bexport\s+[^'";]*?\s+from\s+|\bimport\s*\(\s*)['"]([^'"]+)['"]"#,
+  )
+  .expect("valid import regex");
+
+  let mut seen = BTreeSet::new();
+  pattern
+    .captures_iter(content)
+    .map(|captures| captures[1].to_string())
+    .filter(|specifier| seen.insert(specifier.clone()))
+    .collect()
+}
+
+/// Check whether a specifier points into the project (relative paths and
+/// the usual path aliases) rather than at an npm package
+pub fn is_local_import(specifier: &str) -> bool {
+  specifier.starts_with('.')
+    || specifier.starts_with('/')
+    || specifier.starts_with("@/")
+    || specifier.starts_with("~/")
+    || specifier.starts_with('$')
+}
+
+/// npm package name of a bare specifier, without subpaths:
+/// `@radix-ui/react-slot/dist` → `@radix-ui/react-slot`, `lodash/debounce`
+/// → `lodash`. Returns None for local imports, Node builtins and framework
+/// packages.
+pub fn package_name(specifier: &str) -> Option<String> {
+  if is_local_import(specifier) || specifier.starts_with("node:") {
+    return None;
+  }
+
+  let mut parts = specifier.split('/');
+  let name = match parts.next()? {
+    scope if scope.starts_with('@') => format!("{}/{}", scope, parts.next()?),
+    name => name.to_string(),
+  };
+
+  (!IMPLICIT_PACKAGES.contains(&name.as_str())).then_some(name)
+}
+
+/// npm packages imported by a set of sources
+pub fn npm_dependencies<'a>(imports: impl IntoIterator<Item = &'a String>) -> BTreeSet<String> {
+  imports
+    .into_iter()
+    .filter_map(|specifier| package_name(specifier))
+    .collect()
+}
+
+/// Components of the same registry imported by a set of sources. A local
+/// import matches when one of its path segments (without extension) is a
+/// known component name, e.g. `@/components/ui/button` or `../button/index`.
+pub fn registry_dependencies<'a>(
+  imports: impl IntoIterator<Item = &'a String>,
+  known_components: &BTreeSet<String>,
+  component: &str,
+) -> BTreeSet<String> {
+  imports
+    .into_iter()
+    .filter(|specifier| is_local_import(specifier))
+    .filter_map(|specifier| {
+      specifier
+        .split('/')
+        .map(|segment| segment.split('.').next().unwrap_or(segment))
+        .find(|segment| *segment != component && known_components.contains(*segment))
+        .map(String::from)
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_imports() {
+    let source = r#"
+      import * as React from "react"
+      import { Slot } from "@radix-ui/react-slot"
+      import { cva, type VariantProps } from 'class-variance-authority'
+      import "./styles.css"
+      export { Label } from "../label/label"
+      const Chart = React.lazy(() => import("recharts"))
+      import { cn } from "@/lib/utils"
+      import { Slot as Again } from "@radix-ui/react-slot"
+    "#;
+
+    assert_eq!(
+      parse_imports(source),
+      [
+        "react",
+        "@radix-ui/react-slot",
+        "class-variance-authority",
+        "./styles.css",
+        "../label/label",
+        "recharts",
+        "@/lib/utils",
+      ]
+    );
+  }
+
+  #[test]
+  fn test_package_name() {
+    assert_eq!(package_name("lodash/debounce").as_deref(), Some("lodash"));
+    assert_eq!(
+      package_name("@radix-ui/react-slot/dist").as_deref(),
+      Some("@radix-ui/react-slot")
+    );
+    assert_eq!(package_name("react"), None);
+    assert_eq!(package_name("node:fs"), None);
+    assert_eq!(package_name("$lib/utils"), None);
+    assert_eq!(package_name("./button"), None);
+  }
+
+  #[test]
+  fn test_registry_dependencies() {
+    let known: BTreeSet<String> = ["button", "label", "utils", "dialog"]
+      .into_iter()
+      .map(String::from)
+      .collect();
+    let imports: Vec<String> = [
+      "@/components/ui/button",
+      "../label/label.svelte",
+      "@/lib/utils",
+      "./dialog-content",
+      "./dialog",
+      "lucide-react",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect();
+
+    let deps = registry_dependencies(&imports, &known, "dialog");
+    assert_eq!(
+      deps.into_iter().collect::<Vec<_>>(),
+      ["button", "label", "utils"]
+    );
+  }
+}
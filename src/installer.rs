@@ -1,22 +1,204 @@
-use std::{collections::HashMap, fs, path::PathBuf};
+use std::{
+  borrow::Cow,
+  collections::{BTreeMap, HashMap, HashSet},
+  fs,
+  path::{Path, PathBuf},
+  sync::{Mutex, OnceLock},
+};
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, bail, Result};
 use colored::*;
 use dialoguer::{theme::ColorfulTheme, Confirm, MultiSelect, Select};
+use futures_util::{stream, StreamExt, TryStreamExt};
+use indexmap::IndexMap;
 use sha2::{Digest, Sha256};
+use thiserror::Error;
 
 use crate::{
-  config::{Config, ResolvedPaths},
-  package_manager::{detect_package_manager, Detection},
-  registry::{Component, ComponentFile, RegistryManager},
+  barrel,
+  browser::{is_url, open_url},
+  cache::format_size,
+  condition::{self, ConditionError, Fact, Facts},
+  config::{self, Config, DependencyInstallTarget, ResolvedPaths},
+  diff::{render_diff, DiffStats},
+  formatter::detect_formatter,
+  framework::{detect_meta_framework, Framework},
+  git,
+  highlight::{highlight_code, render_markdown},
+  hooks::{run_hook, HookContext, HookEvent},
+  i18n::{tr, Message},
+  lockfile::{hash_content, now_timestamp, LockedComponent, LockedFile, Lockfile, LOCKFILE_NAME},
+  output::{detail, progress, status, warning},
+  package_manager::{
+    detect_package_manager_cached, detection_cache_path, find_project_root, node_version,
+    parse_dependency_spec, read_declared_packages, read_package_name, satisfies_range, Detection,
+    InstallLocation,
+  },
+  paths,
+  pm_exec::{self, PackageManagerRunner},
+  provenance::{add_header, strip_header, Provenance},
+  region::{append_css_region, region_content, remove_region, replace_region},
+  registry::{
+    changes_since, glob_match, Changelog, ChangelogEntry, Component, ComponentFile, RegistryError,
+    RegistryManager,
+  },
+  report::{
+    ComponentUpdate, InstallReport, OutdatedComponent, OutdatedReport, ProjectReport, UpdateReport,
+  },
+  state::STATE_DIR,
+  transpile, variables,
 };
 
+/// Errors raised while installing components into a project
+#[derive(Debug, Error)]
+pub enum InstallError {
+  /// A target file exists and `--force` was not given
+  #[error("File '{}' already exists. Use --force to overwrite", .0.display())]
+  FileExists(PathBuf),
+  /// Two paths differ only in case and would overwrite each other on
+  /// case-insensitive file systems
+  #[error("'{}' collides with '{}' on case-insensitive file systems", .0.display(), .1.display())]
+  CaseCollision(PathBuf, PathBuf),
+  /// `--force` would overwrite a file edited since it was installed
+  #[error("'{}' has local changes. Use --force --force to overwrite them", .0.display())]
+  LocallyModified(PathBuf),
+  /// The component has no files in the project
+  #[error("Component '{0}' is not installed")]
+  NotInstalled(String),
+  /// No registries are configured to install from
+  #[error("No registries configured. Run 'uiget registry add' first.")]
+  NoRegistries,
+  /// The local Node version doesn't satisfy the component's engines
+  #[error("Node {version} does not satisfy '{required}' required by '{component}'")]
+  EngineMismatch {
+    version: String,
+    required: String,
+    component: String,
+  },
+  /// The package manager exited with a failure status
+  #[error("Package manager command failed for {0}")]
+  PackageManager(String),
+  /// A choice is required but prompts are disabled
+  #[error("{0} (prompts are disabled in non-interactive mode)")]
+  NonInteractive(String),
+  /// An install past the `confirmInstallSize` limits wasn't confirmed
+  #[error("Installing '{0}' was cancelled: it's larger than confirmInstallSize allows")]
+  SizeNotConfirmed(String),
+  /// A file's `condition` can't be evaluated
+  #[error("Invalid condition '{condition}' on '{file}' of '{component}': {source}")]
+  InvalidCondition {
+    component: String,
+    file: String,
+    condition: String,
+    #[source]
+    source: ConditionError,
+  },
+  /// `stripTypes` is on and a file uses TypeScript that can't be stripped
+  #[error("Can't install '{file}' as JavaScript: {source}. Set stripTypes to false to install it as TypeScript")]
+  Untranspilable {
+    file: String,
+    #[source]
+    source: transpile::Unsupported,
+  },
+  /// A registry file's target climbs out of the directory it's installed to
+  #[error("Refusing to install '{0}': its target is outside the project")]
+  UnsafeTarget(String),
+}
+
 /// Component installer handles downloading and installing components
 pub struct ComponentInstaller {
   config: Config,
   registry_manager: RegistryManager,
   typescript_paths: Option<ResolvedPaths>,
-  package_manager: Option<Detection>,
+  /// Detected on first use, since most commands never install packages
+  package_manager: OnceLock<Option<Detection>>,
+  /// Directory components are installed relative to, the current one
+  project_dir: PathBuf,
+  /// Nearest directory with a package.json, or the current directory
+  project_root: PathBuf,
+  /// Values for `$NAME$` template variables
+  variables: IndexMap<String, String>,
+  /// Framework files' conditions are checked against
+  framework: Option<Framework>,
+  /// "sveltekit", "next" or "nuxt", when the project uses one
+  meta_framework: Option<&'static str>,
+  interactive: bool,
+  sync_selection: bool,
+  overwrite_modified: bool,
+  run_package_manager: bool,
+  /// Print the package manager commands instead of running them
+  print_commands: bool,
+  /// Files the user chose to overwrite after seeing their diff, which
+  /// aren't asked about again
+  accepted_overwrites: Mutex<HashSet<PathBuf>>,
+  /// Changes made so far, summarized when the command is done
+  report: Mutex<InstallReport>,
+}
+
+/// Whether an install adds a component or updates an installed one, which
+/// decides the hooks it runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Lifecycle {
+  Add,
+  Update,
+}
+
+impl Lifecycle {
+  /// Hooks run before and after the component's files are written
+  fn hooks(self) -> (HookEvent, HookEvent) {
+    match self {
+      Lifecycle::Add => (HookEvent::PreAdd, HookEvent::PostAdd),
+      Lifecycle::Update => (HookEvent::PreUpdate, HookEvent::PostUpdate),
+    }
+  }
+}
+
+/// The registry and component name a lockfile key stands for: `acme/button`
+/// is `button` from the `acme` (or `@acme`) registry when `namespaceDirs` is
+/// on. Other keys are plain component names.
+pub(crate) fn lockfile_key_parts<'a>(config: &Config, key: &'a str) -> (Option<String>, &'a str) {
+  if config.namespace_dirs == Some(true) {
+    if let Some((dir, name)) = key.split_once('/') {
+      if let Some(namespace) = config
+        .registries
+        .keys()
+        .find(|namespace| namespace.trim_start_matches('@') == dir)
+      {
+        return (Some(namespace.clone()), name);
+      }
+    }
+  }
+  (None, key)
+}
+
+/// Emit a `write` progress event for a file of `component`
+fn progress_write(component: &str, path: &Path) {
+  progress(
+    "write",
+    serde_json::json!({ "component": component, "path": path.display().to_string() }),
+  );
+}
+
+/// Read the license of an installed npm package from node_modules
+fn read_package_license(project_root: &std::path::Path, package: &str) -> String {
+  let manifest = project_root
+    .join("node_modules")
+    .join(package)
+    .join("package.json");
+
+  fs::read_to_string(manifest)
+    .ok()
+    .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+    .and_then(|value| match value.get("license") {
+      Some(serde_json::Value::String(license)) => Some(license.clone()),
+      // Legacy format: { "type": "MIT", "url": "..." }
+      Some(serde_json::Value::Object(license)) => license
+        .get("type")
+        .and_then(|t| t.as_str())
+        .map(|t| t.to_string()),
+      _ => None,
+    })
+    .unwrap_or_else(|| "UNKNOWN".to_string())
 }
 
 /// Component installation context with type information
@@ -25,6 +207,483 @@ pub struct ComponentContext {
   pub name: String,
   pub component_type: Option<String>,
   pub registry: Option<String>,
+  pub version: Option<String>,
+}
+
+impl ComponentContext {
+  /// The context a file is placed with: a file's own `type` picks its
+  /// alias, so a hook shipped with a UI component lands with the hooks
+  fn for_file(&self, file: &ComponentFile) -> Cow<'_, ComponentContext> {
+    match &file.file_type {
+      Some(file_type) if self.component_type.as_ref() != Some(file_type) => {
+        Cow::Owned(ComponentContext {
+          component_type: Some(file_type.clone()),
+          ..self.clone()
+        })
+      }
+      _ => Cow::Borrowed(self),
+    }
+  }
+}
+
+/// `arg` quoted for a POSIX shell when it has characters the shell would
+/// interpret
+fn shell_quote(arg: &str) -> String {
+  let plain = !arg.is_empty()
+    && arg
+      .chars()
+      .all(|c| c.is_ascii_alphanumeric() || "@%+=:,./_^~-".contains(c));
+  if plain {
+    arg.to_string()
+  } else {
+    format!("'{}'", arg.replace('\'', "'\\''"))
+  }
+}
+
+/// Registry components, closest by name, compared by content when
+/// `uiget adopt` finds none with the given name
+const ADOPT_CANDIDATES: usize = 5;
+
+/// How similar adopted files must be to a component found by content
+const ADOPT_MIN_SIMILARITY: f64 = 0.5;
+
+/// Files under `path` (or `path` itself), leaving out hidden files and
+/// directories
+fn collect_local_files(path: &Path) -> Result<Vec<PathBuf>> {
+  if path.is_file() {
+    return Ok(vec![path.to_path_buf()]);
+  }
+  if !path.is_dir() {
+    return Err(anyhow!("'{}' doesn't exist", path.display()));
+  }
+
+  let mut files = Vec::new();
+  let mut entries: Vec<_> = fs::read_dir(path)?.collect::<std::io::Result<_>>()?;
+  entries.sort_by_key(|entry| entry.file_name());
+  for entry in entries {
+    if entry.file_name().to_string_lossy().starts_with('.') {
+      continue;
+    }
+    files.extend(collect_local_files(&entry.path())?);
+  }
+  Ok(files)
+}
+
+/// Delete `path`, then the directories it leaves empty, stopping at any of
+/// `roots`
+fn remove_with_empty_parents(path: &Path, roots: &[PathBuf]) -> Result<()> {
+  paths::remove_file(path)?;
+  let mut dir = path.parent();
+  while let Some(current) = dir.filter(|dir| !roots.iter().any(|root| root == dir)) {
+    // Fails when the directory still has files
+    if fs::remove_dir(paths::extended(current)).is_err() {
+      break;
+    }
+    dir = current.parent();
+  }
+  Ok(())
+}
+
+/// Components in a UI directory, with whether each is a directory: the
+/// directories (like @svelte's `button/`) and the files (like @default's
+/// `button.tsx`) by name, minus hidden entries, barrels, maps and
+/// declarations
+fn component_entries(dir: &Path) -> Result<Vec<(String, bool)>> {
+  let mut entries = Vec::new();
+  if !dir.exists() {
+    return Ok(entries);
+  }
+
+  for entry in fs::read_dir(dir)? {
+    let path = entry?.path();
+    let Some(file_name) = path.file_name().and_then(|n| n.to_str()) else {
+      continue;
+    };
+    if file_name.starts_with('.') || file_name == "index.ts" || file_name == "index.js" {
+      continue;
+    }
+
+    if path.is_dir() {
+      entries.push((file_name.to_string(), true));
+    } else if path.is_file() && !file_name.ends_with(".d.ts") && !file_name.ends_with(".map") {
+      // Extract component name from file name (remove extension)
+      if let Some(component_name) = file_name.split('.').next().filter(|name| !name.is_empty()) {
+        entries.push((component_name.to_string(), false));
+      }
+    }
+  }
+  Ok(entries)
+}
+
+/// How alike two component names are, from 0 to 1: the Dice coefficient of
+/// their character pairs, ignoring case and separators
+fn name_similarity(a: &str, b: &str) -> f64 {
+  let bigrams = |name: &str| -> Vec<(char, char)> {
+    let chars: Vec<char> = name
+      .chars()
+      .filter(|c| c.is_alphanumeric())
+      .map(|c| c.to_ascii_lowercase())
+      .collect();
+    chars.windows(2).map(|pair| (pair[0], pair[1])).collect()
+  };
+  let (a, mut b) = (bigrams(a), bigrams(b));
+  if a.is_empty() && b.is_empty() {
+    return 1.0;
+  }
+
+  let total = a.len() + b.len();
+  let mut shared = 0;
+  for pair in a {
+    if let Some(i) = b.iter().position(|other| *other == pair) {
+      b.swap_remove(i);
+      shared += 1;
+    }
+  }
+  2.0 * shared as f64 / total as f64
+}
+
+/// What to do with a registry dependency whose files differ from the
+/// project's
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ConflictResolution {
+  KeepLocal,
+  TakeNew,
+  Skip,
+}
+
+impl ConflictResolution {
+  const ALL: [Self; 3] = [Self::KeepLocal, Self::TakeNew, Self::Skip];
+
+  fn label(self) -> &'static str {
+    match self {
+      Self::KeepLocal => "Keep the project's files",
+      Self::TakeNew => "Take the new version",
+      Self::Skip => "Skip this dependency",
+    }
+  }
+}
+
+/// "'registry' (version)", for messages about where a component came from
+fn describe_origin(registry: Option<&str>, version: Option<&str>) -> String {
+  let registry = match registry {
+    Some(registry) => format!("'{}'", registry),
+    None => "an unknown registry".to_string(),
+  };
+  match version {
+    Some(version) => format!("{} ({})", registry, version),
+    None => registry,
+  }
+}
+
+/// Format the deprecation marker shown next to a component in listings
+fn deprecation_label(component: &crate::registry::ComponentInfo) -> String {
+  if !component.is_deprecated() {
+    return String::new();
+  }
+
+  let label = match &component.replaced_by {
+    Some(replacement) => format!(" (deprecated, use '{}')", replacement),
+    None => " (deprecated)".to_string(),
+  };
+  label.yellow().to_string()
+}
+
+/// File name whose extension picks the highlighting of a usage example: the
+/// component's first file, since snippets are written in its language
+fn usage_file_name(component: &Component) -> String {
+  component
+    .files
+    .first()
+    .map(|file| file.get_target_path())
+    .unwrap_or_default()
+}
+
+/// `path: value` lines for the leaves of starter settings, e.g.
+/// `aliases.hooks: "$lib/hooks"`
+fn setting_lines(prefix: &str, value: &serde_json::Value) -> Vec<(String, String)> {
+  match value {
+    serde_json::Value::Object(object) if !object.is_empty() => object
+      .iter()
+      .flat_map(|(key, value)| {
+        let path = match prefix {
+          "" => key.clone(),
+          _ => format!("{}.{}", prefix, key),
+        };
+        setting_lines(&path, value)
+      })
+      .collect(),
+    _ => vec![(prefix.to_string(), value.to_string())],
+  }
+}
+
+/// Print changelog entries with their notes rendered as markdown, marking
+/// the `installed` version
+fn print_changelog(entries: &[ChangelogEntry], installed: Option<&str>) {
+  if entries.is_empty() {
+    status!("  {}", "No entries for these versions".dimmed());
+  }
+  for entry in entries {
+    status!(
+      "\n  {}{}{}",
+      entry.version.bold(),
+      entry
+        .date
+        .as_ref()
+        .map(|date| format!(" ({})", date).dimmed().to_string())
+        .unwrap_or_default(),
+      if installed == Some(entry.version.as_str()) {
+        " installed".green().to_string()
+      } else {
+        String::new()
+      }
+    );
+    for line in render_markdown(&entry.notes).lines() {
+      status!("    {}", line);
+    }
+  }
+}
+
+/// Point `target` at `source` with a symlink, or a hard link when `hard`
+/// is set. Windows falls back to a hard link when symlinks aren't allowed.
+fn link_file(source: &Path, target: &Path, hard: bool) -> std::io::Result<()> {
+  if hard {
+    return fs::hard_link(source, target);
+  }
+
+  #[cfg(unix)]
+  {
+    std::os::unix::fs::symlink(source, target)
+  }
+
+  #[cfg(windows)]
+  {
+    std::os::windows::fs::symlink_file(source, target).or_else(|_| fs::hard_link(source, target))
+  }
+}
+
+/// A titled set of components in the interactive picker and `list`
+struct ComponentGroup<'a> {
+  icon: &'static str,
+  title: String,
+  /// Component type the group was built from; None for registry categories
+  /// and the catch-all group
+  component_type: Option<&'static str>,
+  components: Vec<&'a crate::registry::ComponentInfo>,
+}
+
+impl ComponentGroup<'_> {
+  fn styled_title(&self) -> ColoredString {
+    match self.component_type {
+      Some("registry:ui") => self.title.green(),
+      Some("registry:block") => self.title.blue(),
+      Some("registry:hook") => self.title.yellow(),
+      Some("registry:lib") => self.title.purple(),
+      Some("registry:style") => self.title.cyan(),
+      _ if self.icon == OTHER_GROUP.0 => self.title.dimmed(),
+      _ => self.title.bold(),
+    }
+  }
+}
+
+/// Groups used when the registry defines no categories
+const TYPE_GROUPS: [(&str, &str, &str); 8] = [
+  ("registry:ui", "📦", "UI Components"),
+  ("registry:component", "🧱", "Components"),
+  ("registry:block", "🧩", "Blocks"),
+  ("registry:hook", "🪝", "Hooks"),
+  ("registry:lib", "📚", "Libraries"),
+  ("registry:style", "🎨", "Styles"),
+  ("registry:example", "💡", "Examples"),
+  ("registry:starter", "🚀", "Starters"),
+];
+
+/// Catch-all group for components that fit no other
+const OTHER_GROUP: (&str, &str) = ("⚙️", "Other");
+
+/// Group components by the categories their registry assigns (`category` or
+/// `meta.group`), in index order. Registries without categories are grouped
+/// by component type. Empty groups are left out.
+fn group_components<'a>(
+  components: impl IntoIterator<Item = &'a crate::registry::ComponentInfo>,
+) -> Vec<ComponentGroup<'a>> {
+  let components: Vec<_> = components.into_iter().collect();
+  let has_categories = components
+    .iter()
+    .any(|component| component.group().is_some());
+
+  let mut groups: Vec<ComponentGroup> = if has_categories {
+    Vec::new()
+  } else {
+    TYPE_GROUPS
+      .iter()
+      .map(|(component_type, icon, title)| ComponentGroup {
+        icon,
+        title: title.to_string(),
+        component_type: Some(component_type),
+        components: Vec::new(),
+      })
+      .collect()
+  };
+  let mut other = Vec::new();
+
+  for component in components {
+    let group = if has_categories {
+      component.group().map(|category| {
+        match groups.iter().position(|group| group.title == category) {
+          Some(position) => position,
+          None => {
+            groups.push(ComponentGroup {
+              icon: "📁",
+              title: category.to_string(),
+              component_type: None,
+              components: Vec::new(),
+            });
+            groups.len() - 1
+          }
+        }
+      })
+    } else {
+      groups
+        .iter()
+        .position(|group| group.component_type == component.component_type.as_deref())
+    };
+
+    match group {
+      Some(position) => groups[position].components.push(component),
+      None => other.push(component),
+    }
+  }
+
+  groups.push(ComponentGroup {
+    icon: OTHER_GROUP.0,
+    title: OTHER_GROUP.1.to_string(),
+    component_type: None,
+    components: other,
+  });
+  groups.retain(|group| !group.components.is_empty());
+
+  groups
+}
+
+/// Keep the components whose group (category or type group) matches
+/// `category`, ignoring case
+fn filter_by_group(
+  components: Vec<&crate::registry::ComponentInfo>,
+  category: Option<&str>,
+) -> Vec<crate::registry::ComponentInfo> {
+  match category {
+    Some(category) => group_components(components)
+      .into_iter()
+      .filter(|group| group.title.eq_ignore_ascii_case(category))
+      .flat_map(|group| group.components)
+      .cloned()
+      .collect(),
+    None => components.into_iter().cloned().collect(),
+  }
+}
+
+/// Project-relative path of a target that names its own place in the
+/// project: shadcn's `~/` prefix, an absolute path, or a path under `src/`.
+/// Other targets are relative to the alias of the component's type.
+fn project_rooted_target(target: &str, project_dir: &Path) -> Option<PathBuf> {
+  if let Some(rooted) = target.strip_prefix("~/") {
+    return Some(PathBuf::from(rooted));
+  }
+
+  let path = Path::new(target);
+  if path.has_root() {
+    let rooted = path
+      .strip_prefix(project_dir)
+      .unwrap_or_else(|_| Path::new(target.trim_start_matches(['/', '\\'])));
+    return Some(rooted.to_path_buf());
+  }
+
+  target.starts_with("src/").then(|| path.to_path_buf())
+}
+
+/// Resolve the `.` and `..` of a relative path without touching the file
+/// system. `None` when the path is absolute or climbs out of the directory
+/// it's relative to, as `~/../../.bashrc` would.
+fn contained_path(path: &Path) -> Option<PathBuf> {
+  let mut contained = PathBuf::new();
+  for component in path.components() {
+    match component {
+      std::path::Component::Normal(part) => contained.push(part),
+      std::path::Component::CurDir => {}
+      std::path::Component::ParentDir => {
+        if !contained.pop() {
+          return None;
+        }
+      }
+      std::path::Component::RootDir | std::path::Component::Prefix(_) => return None,
+    }
+  }
+  Some(contained)
+}
+
+/// Component types uiget knows where to install. Others go to the
+/// components alias with a warning.
+const KNOWN_COMPONENT_TYPES: &[&str] = &[
+  "registry:ui",
+  "registry:component",
+  "registry:block",
+  "registry:hook",
+  "registry:lib",
+  "registry:util",
+  "registry:example",
+  "registry:internal",
+  "registry:style",
+  "registry:theme",
+  "registry:page",
+  "registry:file",
+  STARTER_TYPE,
+];
+
+/// Type of the registry items `uiget apply` installs: a curated set of
+/// components with settings
+pub const STARTER_TYPE: &str = "registry:starter";
+
+/// Component types whose CSS goes into the project's Tailwind CSS file
+const INJECTED_CSS_TYPES: &[&str] = &["registry:style", "registry:theme"];
+
+/// Registry requests in flight while checking component status
+const STATUS_CONCURRENCY: usize = 8;
+
+/// Component files written at the same time
+const WRITE_CONCURRENCY: usize = 16;
+
+/// What installing components adds to the project
+#[derive(Debug, Default, PartialEq)]
+struct InstallSize {
+  files: usize,
+  bytes: u64,
+  lines: usize,
+}
+
+impl InstallSize {
+  fn add(&mut self, other: &InstallSize) {
+    self.files += other.files;
+    self.bytes += other.bytes;
+    self.lines += other.lines;
+  }
+}
+
+/// A checked component file, ready to be written
+struct FileWrite {
+  path: PathBuf,
+  content: String,
+  /// The file was there before
+  existed: bool,
+  /// Only this component's managed region in it changes
+  region: bool,
+  /// The path is a `uiget link` symlink to remove first
+  replace_link: bool,
+}
+
+/// Deprecation status of a component
+#[derive(Debug, Clone)]
+struct Deprecation {
+  replaced_by: Option<String>,
 }
 
 /// Dependencies to be installed
@@ -37,40 +696,173 @@ pub struct ComponentDependencies {
 impl ComponentInstaller {
   /// Create a new component installer
   pub fn new(config: Config) -> Result<Self> {
-    let mut registry_manager = RegistryManager::new();
-
     // Add all registries from config
-    for (namespace, registry_config) in &config.registries {
-      registry_manager.add_registry_config_with_style(
-        namespace.clone(),
-        registry_config.clone(),
-        config.style.clone(),
-      )?;
-    }
+    let mut registry_manager = RegistryManager::from_config(&config)?;
 
     // Resolve TypeScript paths if TypeScript is enabled
     let typescript_paths = config.resolve_typescript_paths().unwrap_or(None);
 
-    // Detect package manager
-    let package_manager = match detect_package_manager(std::env::current_dir()?) {
-      Ok(detection) => {
-        println!("{} {}", "📦".blue(), detection.info());
-        Some(detection)
-      }
-      Err(e) => {
-        eprintln!("{} Failed to detect package manager: {:?}", "!".yellow(), e);
-        None
-      }
-    };
+    let project_dir = std::env::current_dir()?;
+    let project_root = find_project_root(&project_dir).unwrap_or_else(|| project_dir.clone());
+
+    // Pick component variants for the project's framework
+    let framework = Framework::detect(&project_root);
+    registry_manager.set_framework(framework);
+    let meta_framework = detect_meta_framework(&project_root);
+    let variables = variables::project_variables(&project_root, config.variables.as_ref());
 
     Ok(Self {
       config,
       registry_manager,
       typescript_paths,
-      package_manager,
+      package_manager: OnceLock::new(),
+      project_dir,
+      project_root,
+      variables,
+      framework,
+      meta_framework,
+      interactive: true,
+      sync_selection: false,
+      overwrite_modified: false,
+      run_package_manager: true,
+      print_commands: false,
+      accepted_overwrites: Mutex::new(HashSet::new()),
+      report: Mutex::new(InstallReport::default()),
     })
   }
 
+  /// The project's package manager, detected (or read from the detection
+  /// cache) the first time it's needed
+  fn package_manager(&self) -> Option<&Detection> {
+    self
+      .package_manager
+      .get_or_init(|| {
+        let cache_path = detection_cache_path();
+        match detect_package_manager_cached(&self.project_root, cache_path.as_deref()) {
+          Ok(detection) => {
+            status!("{} {}", "📦".blue(), detection.info());
+            Some(detection)
+          }
+          Err(e) => {
+            warning!(
+              "{} {}",
+              "!".yellow(),
+              tr(Message::PackageManagerDetectFailed, &[&e])
+            );
+            None
+          }
+        }
+      })
+      .as_ref()
+  }
+
+  /// Enable or disable prompts. When disabled, confirmations take their
+  /// safe default and choices that need the user fail with an error.
+  pub fn with_interactive(mut self, interactive: bool) -> Self {
+    self.interactive = interactive;
+    self
+  }
+
+  /// Fetch component variants for `framework` instead of the detected one
+  pub fn with_framework(mut self, framework: Option<Framework>) -> Self {
+    if framework.is_some() {
+      self.registry_manager.set_framework(framework);
+      self.framework = framework;
+    }
+    self
+  }
+
+  /// Read Git registries at `reference` (a branch, tag or commit) instead
+  /// of the ref in their URL
+  pub fn with_git_ref(mut self, reference: Option<String>) -> Self {
+    if reference.is_some() && !self.registry_manager.set_git_ref(reference) {
+      warning!(
+        "{} --ref only applies to Git registries (github:, gitlab:, bitbucket:); none is configured",
+        "!".yellow()
+      );
+    }
+    self
+  }
+
+  /// Pre-check installed components in the interactive picker and skip
+  /// selected ones that are installed and unmodified
+  pub fn with_sync_selection(mut self, sync_selection: bool) -> Self {
+    self.sync_selection = sync_selection;
+    self
+  }
+
+  /// Let `--force` overwrite files edited since install without showing
+  /// the diff and asking first
+  pub fn with_overwrite_modified(mut self, overwrite_modified: bool) -> Self {
+    self.overwrite_modified = overwrite_modified;
+    self
+  }
+
+  /// Run the package manager for component dependencies, or only report
+  /// them (`--no-install`)
+  pub fn with_run_package_manager(mut self, run_package_manager: bool) -> Self {
+    self.run_package_manager = run_package_manager;
+    self
+  }
+
+  /// Install relative to `project_dir` instead of the current directory
+  #[cfg(test)]
+  fn with_project_dir(mut self, project_dir: &Path) -> Self {
+    self.project_dir = project_dir.to_path_buf();
+    self
+  }
+
+  /// Print the commands that would install component dependencies instead
+  /// of running them (`--print-commands`)
+  pub fn with_print_commands(mut self, print_commands: bool) -> Self {
+    self.print_commands = print_commands;
+    self
+  }
+
+  /// Add to the summary of this run
+  fn record(&self, update: impl FnOnce(&mut InstallReport)) {
+    if let Ok(mut report) = self.report.lock() {
+      update(&mut report);
+    }
+  }
+
+  /// Print the summary of what this run changed, if anything
+  pub fn print_report(&self) -> Result<()> {
+    let Ok(report) = self.report.lock() else {
+      return Ok(());
+    };
+    if report.is_empty() {
+      return Ok(());
+    }
+
+    status!();
+    for line in report.lines(&self.project_dir) {
+      status!("{}", line);
+    }
+    Ok(())
+  }
+
+  /// Ask a yes/no question, answering with `default` when prompts are
+  /// disabled
+  fn confirm(&self, prompt: &str, default: bool) -> Result<bool> {
+    if !self.interactive {
+      status!(
+        "{} {} {}",
+        "?".dimmed(),
+        prompt,
+        if default { "yes" } else { "no" }.dimmed()
+      );
+      return Ok(default);
+    }
+
+    Ok(
+      Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt(prompt)
+        .default(default)
+        .interact()?,
+    )
+  }
+
   /// Get the appropriate alias path based on component type
   fn get_alias_for_component_type(&self, component_type: Option<&str>) -> &str {
     match component_type {
@@ -93,16 +885,123 @@ impl ComponentInstaller {
         .lib
         .as_deref()
         .unwrap_or(&self.config.aliases.components),
-      _ => &self.config.aliases.components, // Default fallback
+      Some("registry:example") => self
+        .config
+        .aliases
+        .examples
+        .as_deref()
+        .unwrap_or(&self.config.aliases.components),
+      Some("registry:internal") => self
+        .config
+        .aliases
+        .internal
+        .as_deref()
+        .unwrap_or(&self.config.aliases.components),
+      // registry:component, blocks, pages and unknown types
+      _ => &self.config.aliases.components,
+    }
+  }
+
+  /// Get the alias for a component, honouring its registry's
+  /// `targetOverride` before falling back to the type-based alias
+  fn get_alias_for_context(&self, context: &ComponentContext) -> &str {
+    self
+      .component_override(&context.name)
+      .or_else(|| {
+        context
+          .registry
+          .as_deref()
+          .and_then(|namespace| self.config.registries.get(namespace))
+          .and_then(|registry| registry.target_override())
+      })
+      .unwrap_or_else(|| self.get_alias_for_component_type(context.component_type.as_deref()))
+  }
+
+  /// Subdirectory for the component's registry when `namespaceDirs` is on:
+  /// the namespace without its `@`. Components of the default registry,
+  /// utils, hooks and libs, and components with an explicit target keep the
+  /// standard layout.
+  fn namespace_dir(&self, context: &ComponentContext) -> Option<String> {
+    if self.config.namespace_dirs != Some(true)
+      || self.component_override(&context.name).is_some()
+      || matches!(
+        context.component_type.as_deref(),
+        Some("registry:hook" | "registry:util" | "registry:lib")
+      )
+    {
+      return None;
+    }
+
+    let namespace = context.registry.as_deref()?;
+    let registry = self.config.registries.get(namespace)?;
+    if matches!(namespace, "default" | "@default") || registry.target_override().is_some() {
+      return None;
+    }
+
+    Some(namespace.trim_start_matches('@').to_string())
+  }
+
+  /// Point imports of `dependencies` under the UI alias at their namespace
+  /// directory, e.g. `$lib/components/ui/button` becomes
+  /// `$lib/components/ui/acme/button`
+  fn rewrite_namespaced_imports(&self, content: &str, dir: &str, dependencies: &[&str]) -> String {
+    let ui_alias = self.get_alias_for_component_type(Some("registry:ui"));
+    let mut prefixes = vec![ui_alias.to_string()];
+    prefixes.extend(self.resolve_import_path_manually(ui_alias));
+    prefixes.dedup();
+
+    let mut rewritten = content.to_string();
+    for prefix in prefixes {
+      for dependency in dependencies {
+        let pattern = format!(
+          r#"{}/{}(["'/.])"#,
+          regex::escape(&prefix),
+          regex::escape(dependency)
+        );
+        let Ok(regex) = regex::Regex::new(&pattern) else {
+          continue;
+        };
+        let replacement = format!("{}/{}/{}$1", prefix, dir, dependency);
+        rewritten = regex
+          .replace_all(&rewritten, replacement.as_str())
+          .into_owned();
+      }
+    }
+
+    rewritten
+  }
+
+  /// Lockfile key of a component: its name, prefixed with its namespace
+  /// directory when `namespaceDirs` puts it in one
+  fn lockfile_key(&self, component: &Component) -> String {
+    match self.namespace_dir(&self.create_component_context(component)) {
+      Some(dir) => format!("{}/{}", dir, component.name),
+      None => component.name.clone(),
     }
   }
 
+  /// The registry and component name a lockfile key stands for
+  fn key_parts<'a>(&self, key: &'a str) -> (Option<String>, &'a str) {
+    lockfile_key_parts(&self.config, key)
+  }
+
+  /// Target directory configured for a component in `overrides`
+  fn component_override(&self, component_name: &str) -> Option<&str> {
+    self
+      .config
+      .overrides
+      .as_ref()?
+      .get(component_name)
+      .map(String::as_str)
+  }
+
   /// Create component context from component information
   fn create_component_context(&self, component: &Component) -> ComponentContext {
     ComponentContext {
       name: component.name.clone(),
       component_type: component.component_type.clone(),
       registry: component.registry.clone(),
+      version: component.version.clone(),
     }
   }
 
@@ -119,6 +1018,13 @@ impl ComponentInstaller {
       self
         .install_component(name, registry_namespace, force, skip_deps)
         .await
+    } else if !self.interactive {
+      Err(
+        InstallError::NonInteractive(
+          "No component given; pass a component name, a wildcard pattern or --all".to_string(),
+        )
+        .into(),
+      )
     } else {
       // Show interactive menu
       self
@@ -127,600 +1033,2541 @@ impl ComponentInstaller {
     }
   }
 
-  /// Install a component
-  pub async fn install_component(
-    &self,
-    component_name: &str,
-    registry_namespace: Option<&str>,
-    force: bool,
-    skip_deps: bool,
-  ) -> Result<()> {
-    Box::pin(self.install_component_inner(component_name, registry_namespace, force, skip_deps))
-      .await
-  }
-
-  /// Internal recursive installation function
-  async fn install_component_inner(
+  /// Install every component whose name matches a glob pattern (and
+  /// optionally a component type) after confirming the resolved list
+  pub async fn install_matching_components(
     &self,
-    component_name: &str,
+    pattern: &str,
+    component_type: Option<&str>,
     registry_namespace: Option<&str>,
     force: bool,
     skip_deps: bool,
   ) -> Result<()> {
-    println!(
-      "{} Installing component '{}'...",
-      "→".blue(),
-      component_name.cyan()
-    );
-
-    // Fetch component
-    let component = if let Some(namespace) = registry_namespace {
-      self
-        .registry_manager
-        .fetch_component(namespace, component_name)
-        .await?
+    let namespaces: Vec<String> = if let Some(namespace) = registry_namespace {
+      vec![namespace.to_string()]
     } else {
-      self
+      let mut namespaces: Vec<String> = self
         .registry_manager
-        .fetch_component_auto(component_name)
-        .await?
+        .namespaces()
+        .into_iter()
+        .cloned()
+        .collect();
+      namespaces.sort();
+      namespaces
     };
 
-    // Install dependencies first (if not skipped)
-    if !skip_deps {
-      if let Some(dependencies) = &component.registry_dependencies {
-        for dep in dependencies {
-          println!("{} Installing dependency '{}'...", "→".yellow(), dep.cyan());
-          Box::pin(self.install_component_inner(dep, registry_namespace, force, true)).await?;
-        }
-      }
-    }
+    // Resolve the pattern against each registry index
+    let mut matches: Vec<(String, String)> = Vec::new();
+    for namespace in &namespaces {
+      let registry = self
+        .registry_manager
+        .get_registry(namespace)
+        .ok_or_else(|| RegistryError::RegistryNotFound(namespace.to_string()))?;
 
-    // Create component context for proper alias resolution
-    let component_context = self.create_component_context(&component);
+      let index = registry.fetch_index().await?;
+      let mut names: Vec<String> = index
+        .as_slice()
+        .into_iter()
+        .filter(|info| glob_match(pattern, &info.name))
+        .filter(|info| component_type.is_none() || info.component_type.as_deref() == component_type)
+        .map(|info| info.name.clone())
+        .collect();
+      names.sort();
 
-    // Install component files with context
-    self.install_component_files(&component, &component_context, force)?;
+      for name in names {
+        // The same component may be published by several registries; the
+        // first registry wins
+        if !matches.iter().any(|(_, existing)| existing == &name) {
+          matches.push((namespace.clone(), name));
+        }
+      }
+    }
 
-    // Install dependencies if component has any dependencies and package manager
-    // was detected
-    let deps = ComponentDependencies {
-      dependencies: component.dependencies.clone().unwrap_or_default(),
-      dev_dependencies: component.dev_dependencies.clone().unwrap_or_default(),
-    };
+    if matches.is_empty() {
+      status!(
+        "{} No components found matching '{}'",
+        "!".yellow(),
+        pattern.cyan()
+      );
+      return Ok(());
+    }
 
-    if !deps.dependencies.is_empty() || !deps.dev_dependencies.is_empty() {
-      self.install_dependencies(&deps)?;
+    status!("Components to be installed:");
+    for (i, (namespace, name)) in matches.iter().enumerate() {
+      status!(
+        "  {}. {} {}",
+        (i + 1).to_string().dimmed(),
+        name.cyan(),
+        format!("({})", namespace).dimmed()
+      );
     }
 
-    println!(
-      "{} Successfully installed '{}'",
-      "✓".green(),
-      component_name.cyan()
+    if !self.confirm(&format!("Install all {} components?", matches.len()), true)? {
+      status!("{} Installation cancelled", "❌".red());
+      return Ok(());
+    }
+
+    for (namespace, name) in &matches {
+      status!();
+      self
+        .install_component(name, Some(namespace), force, skip_deps)
+        .await?;
+    }
+
+    status!(
+      "\n{} All matching components installed successfully!",
+      "✓".green()
     );
+
     Ok(())
   }
 
-  /// Interactive component selection menu
-  async fn interactive_component_selection(
+  /// Install a component
+  pub async fn install_component(
     &self,
+    component_name: &str,
     registry_namespace: Option<&str>,
     force: bool,
     skip_deps: bool,
   ) -> Result<()> {
-    // Determine which registry to use
-    let namespace = if let Some(ns) = registry_namespace {
-      ns.to_string()
-    } else {
-      // Let user select registry if multiple are available
-      let registries: Vec<String> = self
-        .registry_manager
-        .namespaces()
-        .into_iter()
-        .cloned()
-        .collect();
-
-      if registries.is_empty() {
-        return Err(anyhow!(
-          "No registries configured. Run 'uiget registry add' first."
-        ));
-      }
-
-      if registries.len() == 1 {
-        registries[0].clone()
-      } else {
-        let selection = Select::with_theme(&ColorfulTheme::default())
-          .with_prompt("Select a registry:")
-          .items(&registries)
-          .default(0)
-          .interact()?;
-
-        registries[selection].clone()
-      }
-    };
-
-    // Fetch components from selected registry
-    let registry = self
-      .registry_manager
-      .get_registry(&namespace)
-      .ok_or_else(|| anyhow!("Registry '{}' not found", namespace))?;
+    let component = self
+      .fetch_component(component_name, registry_namespace)
+      .await?;
+
+    // Offer the replacement of deprecated components instead
+    if let Some(replacement) = self.prompt_deprecation_replacement(&component).await? {
+      let namespace = registry_namespace
+        .map(|ns| ns.to_string())
+        .or_else(|| component.registry.clone());
+      return Box::pin(self.install_component(
+        &replacement,
+        namespace.as_deref(),
+        force,
+        skip_deps,
+      ))
+      .await;
+    }
 
-    println!(
-      "{} Fetching components from '{}'...",
-      "→".blue(),
-      namespace.cyan()
-    );
-    let index = registry.fetch_index().await?;
+    self
+      .confirm_install_size(&component, registry_namespace, skip_deps)
+      .await?;
+    Box::pin(self.install_resolved_component(
+      component,
+      registry_namespace,
+      force,
+      skip_deps,
+      Lifecycle::Add,
+    ))
+    .await
+  }
 
-    if index.is_empty() {
-      println!(
-        "{} No components available in registry '{}'",
-        "!".yellow(),
-        namespace.cyan()
+  /// Fetch a starter and show what applying it does: the components it
+  /// installs, the settings it changes and its own files. Returns the
+  /// starter once the user goes ahead.
+  pub async fn plan_starter(
+    &self,
+    starter_name: &str,
+    registry_namespace: Option<&str>,
+  ) -> Result<Option<Component>> {
+    let starter = self
+      .fetch_component(starter_name, registry_namespace)
+      .await?;
+    if starter.component_type.as_deref() != Some(STARTER_TYPE) {
+      bail!(
+        "'{}' is not a starter ({}); install it with 'uiget add {}'",
+        starter.name,
+        starter.component_type.as_deref().unwrap_or("no type"),
+        starter.name
       );
-      return Ok(());
     }
 
-    // Get list of installed components
-    let installed_components = self.get_installed_components().unwrap_or_default();
-
-    // Pre-load outdated status for all installed components
-    println!("{} Checking component status...", "→".blue());
-    let outdated_results = self
-      .check_outdated_components(&installed_components, Some(&namespace))
-      .await
-      .unwrap_or_default();
-
-    let outdated_components: std::collections::HashSet<String> = outdated_results
-      .into_iter()
-      .filter_map(|(name, is_outdated)| if is_outdated { Some(name) } else { None })
-      .collect();
-
-    // Group components by type
-    let mut ui_components = Vec::new();
-    let mut blocks = Vec::new();
-    let mut hooks = Vec::new();
-    let mut libs = Vec::new();
-    let mut other = Vec::new();
+    status!("\n{} Starter: {}", "🚀".blue(), starter.name.cyan().bold());
+    if let Some(description) = &starter.description {
+      status!("  {}", description.dimmed());
+    }
 
-    for component in index.as_slice() {
-      match component.component_type.as_deref() {
-        Some("registry:ui") => ui_components.push(component),
-        Some("registry:block") => blocks.push(component),
-        Some("registry:hook") => hooks.push(component),
-        Some("registry:lib") => libs.push(component),
-        _ => other.push(component),
+    let components = starter.registry_dependencies.as_deref().unwrap_or_default();
+    status!("\nComponents ({}):", components.len());
+    for name in components {
+      if self.is_component_installed(name) {
+        status!("  - {} {}", name.cyan(), "(installed)".dimmed());
+      } else {
+        status!("  - {}", name.cyan());
       }
     }
 
-    // Create display items with categories and track category indices
-    let mut display_items = Vec::new();
-    let mut component_map = Vec::new();
-    let mut category_ranges = Vec::new(); // (category_index, start_index, end_index)
-
-    if !ui_components.is_empty() {
-      let category_index = display_items.len();
-      display_items.push(format!("📦 UI Components ({})", ui_components.len()));
-      component_map.push(None); // Category header
-
-      let start_index = display_items.len();
-      for component in &ui_components {
-        let is_installed = installed_components.contains(&component.name);
-        let status_icon = if is_installed {
-          if outdated_components.contains(&component.name) {
-            "⚠"
-          } else {
-            "✓"
-          }
-        } else {
-          " "
-        };
-        display_items.push(format!(
-          "  {} {} {}",
-          "→".dimmed(),
-          status_icon,
-          component.name
-        ));
-        component_map.push(Some(*component));
+    if let Some(settings) = starter
+      .config
+      .as_ref()
+      .filter(|settings| !settings.is_empty())
+    {
+      let (allowed, protected) = config::split_protected_settings(settings);
+      if !allowed.is_empty() {
+        status!("\nSettings ({}):", "uiget.json".cyan());
+        for (key, value) in setting_lines("", &serde_json::Value::Object(allowed)) {
+          status!("  {}: {}", key, value.green());
+        }
+      }
+      for key in protected {
+        warning!(
+          "{} Ignoring '{}' from the starter; set it yourself if you trust the registry",
+          "!".yellow(),
+          key
+        );
       }
-      let end_index = display_items.len() - 1;
-      category_ranges.push((category_index, start_index, end_index));
     }
 
-    if !blocks.is_empty() {
-      let category_index = display_items.len();
-      display_items.push(format!("🧩 Blocks ({})", blocks.len()));
-      component_map.push(None); // Category header
-
-      let start_index = display_items.len();
-      for component in &blocks {
-        let is_installed = installed_components.contains(&component.name);
-        let status_icon = if is_installed {
-          if outdated_components.contains(&component.name) {
-            "⚠"
-          } else {
-            "✓"
-          }
-        } else {
-          " "
-        };
-        display_items.push(format!(
-          "  {} {} {}",
-          "→".dimmed(),
-          status_icon,
-          component.name
-        ));
-        component_map.push(Some(*component));
+    if !starter.files.is_empty() {
+      status!("\nFiles:");
+      for file in &starter.files {
+        status!("  - {}", file.get_target_path().cyan());
       }
-      let end_index = display_items.len() - 1;
-      category_ranges.push((category_index, start_index, end_index));
     }
 
-    if !hooks.is_empty() {
-      let category_index = display_items.len();
-      display_items.push(format!("🪝 Hooks ({})", hooks.len()));
-      component_map.push(None); // Category header
-
-      let start_index = display_items.len();
-      for component in &hooks {
-        let is_installed = installed_components.contains(&component.name);
-        let status_icon = if is_installed {
-          if outdated_components.contains(&component.name) {
-            "⚠"
-          } else {
-            "✓"
-          }
-        } else {
-          " "
-        };
-        display_items.push(format!(
-          "  {} {} {}",
-          "→".dimmed(),
-          status_icon,
-          component.name
-        ));
-        component_map.push(Some(*component));
-      }
-      let end_index = display_items.len() - 1;
-      category_ranges.push((category_index, start_index, end_index));
+    let packages: Vec<&String> = [&starter.dependencies, &starter.dev_dependencies]
+      .into_iter()
+      .flatten()
+      .flatten()
+      .collect();
+    if !packages.is_empty() {
+      status!(
+        "\nnpm packages: {}",
+        packages
+          .iter()
+          .map(|package| package.as_str())
+          .collect::<Vec<_>>()
+          .join(", ")
+      );
     }
 
-    if !libs.is_empty() {
-      let category_index = display_items.len();
-      display_items.push(format!("📚 Libraries ({})", libs.len()));
-      component_map.push(None); // Category header
+    status!();
+    if !self.confirm(&format!("Apply '{}'?", starter.name), true)? {
+      return Ok(None);
+    }
+    Ok(Some(starter))
+  }
 
-      let start_index = display_items.len();
-      for component in &libs {
-        let is_installed = installed_components.contains(&component.name);
-        let status_icon = if is_installed {
-          if outdated_components.contains(&component.name) {
-            "⚠"
-          } else {
-            "✓"
-          }
-        } else {
-          " "
-        };
-        display_items.push(format!(
-          "  {} {} {}",
-          "→".dimmed(),
-          status_icon,
-          component.name
-        ));
-        component_map.push(Some(*component));
+  /// Install the components of a starter, then its own files and npm
+  /// dependencies. Components already installed are left alone unless
+  /// `force` is given.
+  pub async fn install_starter(
+    &self,
+    starter: Component,
+    registry_namespace: Option<&str>,
+    force: bool,
+    skip_deps: bool,
+  ) -> Result<()> {
+    let namespace = registry_namespace
+      .map(String::from)
+      .or_else(|| starter.registry.clone());
+    for name in starter.registry_dependencies.as_deref().unwrap_or_default() {
+      if !force && self.is_component_installed(name) {
+        status!("{} '{}' is already installed", "✓".green(), name.cyan());
+        continue;
       }
-      let end_index = display_items.len() - 1;
-      category_ranges.push((category_index, start_index, end_index));
+      Box::pin(self.install_component(name, namespace.as_deref(), force, skip_deps)).await?;
     }
 
-    if !other.is_empty() {
-      let category_index = display_items.len();
-      display_items.push(format!("⚙️ Other ({})", other.len()));
-      component_map.push(None); // Category header
+    if starter.files.is_empty()
+      && starter.dependencies.is_none()
+      && starter.dev_dependencies.is_none()
+    {
+      self.print_post_install_message(&starter);
+      return Ok(());
+    }
+    // Its components are installed above, each with their own dependencies
+    Box::pin(self.install_resolved_component(
+      starter,
+      namespace.as_deref(),
+      force,
+      true,
+      Lifecycle::Add,
+    ))
+    .await
+  }
 
-      let start_index = display_items.len();
-      for component in &other {
-        let is_installed = installed_components.contains(&component.name);
-        let status_icon = if is_installed {
-          if outdated_components.contains(&component.name) {
-            "⚠"
-          } else {
-            "✓"
+  /// Report how many files and how much content a component, and its
+  /// registry dependencies unless `skip_deps`, add to the project. Files
+  /// already in the project with the same content don't count. Past the
+  /// `confirmInstallSize` limits, the user is asked first.
+  async fn confirm_install_size(
+    &self,
+    component: &Component,
+    registry_namespace: Option<&str>,
+    skip_deps: bool,
+  ) -> Result<()> {
+    let mut size = self.install_size(component)?;
+    let mut dependencies = 0;
+    if !skip_deps {
+      let mut seen = HashSet::from([component.name.clone()]);
+      let mut queue: Vec<String> = component.registry_dependencies.clone().unwrap_or_default();
+      while let Some(name) = queue.pop() {
+        if !seen.insert(name.clone()) {
+          continue;
+        }
+        let namespace = registry_namespace
+          .map(String::from)
+          .or_else(|| self.installed_registry(&name));
+        let dependency = match namespace {
+          Some(namespace) => {
+            self
+              .registry_manager
+              .fetch_component(&namespace, &name)
+              .await
           }
-        } else {
-          " "
+          None => self.registry_manager.fetch_component_auto(&name).await,
         };
-        display_items.push(format!(
-          "  {} {} {}",
-          "→".dimmed(),
-          status_icon,
-          component.name
-        ));
-        component_map.push(Some(*component));
+        // The install itself reports dependencies that can't be fetched
+        let Ok(dependency) = dependency else {
+          continue;
+        };
+        let dependency_size = self.install_size(&dependency)?;
+        if dependency_size.files > 0 {
+          dependencies += 1;
+          size.add(&dependency_size);
+        }
+        queue.extend(dependency.registry_dependencies.unwrap_or_default());
       }
-      let end_index = display_items.len() - 1;
-      category_ranges.push((category_index, start_index, end_index));
     }
-
-    // First, show category selection menu
-    let mut category_options = vec!["🔍 Browse and select individual components".to_string()];
-    let mut category_data = vec![None]; // None for individual browsing
-
-    if !ui_components.is_empty() {
-      category_options.push(format!(
-        "📦 Select ALL UI Components ({} items)",
-        ui_components.len()
-      ));
-      category_data.push(Some(("ui", &ui_components)));
+    if size.files == 0 {
+      return Ok(());
     }
 
-    if !blocks.is_empty() {
-      category_options.push(format!("🧩 Select ALL Blocks ({} items)", blocks.len()));
-      category_data.push(Some(("blocks", &blocks)));
-    }
+    let with_dependencies = match dependencies {
+      0 => String::new(),
+      1 => " and 1 registry dependency".to_string(),
+      n => format!(" and {} registry dependencies", n),
+    };
+    status!(
+      "{} '{}'{} add {} {} ({}, {} lines)",
+      "→".blue(),
+      component.name.cyan(),
+      with_dependencies,
+      size.files,
+      if size.files == 1 { "file" } else { "files" },
+      format_size(size.bytes),
+      size.lines
+    );
 
-    if !hooks.is_empty() {
-      category_options.push(format!("🪝 Select ALL Hooks ({} items)", hooks.len()));
-      category_data.push(Some(("hooks", &hooks)));
+    let Some(limits) = &self.config.confirm_install_size else {
+      return Ok(());
+    };
+    let too_many_files = limits.files.is_some_and(|files| size.files > files);
+    let too_large = limits
+      .kilobytes
+      .is_some_and(|kilobytes| size.bytes > kilobytes * 1024);
+    if (too_many_files || too_large)
+      && !self.confirm(
+        "That's more than confirmInstallSize allows. Install anyway?",
+        false,
+      )?
+    {
+      return Err(InstallError::SizeNotConfirmed(component.name.clone()).into());
     }
+    Ok(())
+  }
 
-    if !libs.is_empty() {
-      category_options.push(format!("📚 Select ALL Libraries ({} items)", libs.len()));
-      category_data.push(Some(("libs", &libs)));
-    }
+  /// Files of `component` that installing it would add or change, and their
+  /// size
+  fn install_size(&self, component: &Component) -> Result<InstallSize> {
+    let context = self.create_component_context(component);
+    self.check_conditions(component, &context)?;
 
-    if !other.is_empty() {
-      category_options.push(format!("⚙️ Select ALL Other ({} items)", other.len()));
-      category_data.push(Some(("other", &other)));
+    let mut size = InstallSize::default();
+    for (_, local, new) in self.compare_with_project(component, &context)? {
+      if local.is_some_and(|local| strip_header(&local) == new) {
+        continue;
+      }
+      size.files += 1;
+      size.bytes += new.len() as u64;
+      size.lines += new.lines().count();
     }
+    Ok(size)
+  }
 
-    category_options.push("❌ Cancel".to_string());
-    category_data.push(None);
-
-    let choice = Select::with_theme(&ColorfulTheme::default())
-      .with_prompt("What would you like to do?")
-      .items(&category_options)
-      .default(0)
-      .interact()?;
+  /// Fetch a component from a specific registry. Without a namespace, an
+  /// installed component comes from the registry it was installed from and
+  /// anything else from the first registry that has it.
+  async fn fetch_component(
+    &self,
+    component_name: &str,
+    registry_namespace: Option<&str>,
+  ) -> Result<Component> {
+    // Components in a namespace directory are known by their lockfile key
+    let (key_namespace, component_name) = self.key_parts(component_name);
+    let recorded = match registry_namespace.or(key_namespace.as_deref()) {
+      Some(_) => None,
+      None => self.installed_registry(component_name),
+    };
+    let namespace = registry_namespace
+      .or(key_namespace.as_deref())
+      .or(recorded.as_deref());
+    progress(
+      "resolve",
+      serde_json::json!({ "component": component_name, "registry": namespace }),
+    );
 
-    let selected_components: Vec<&crate::registry::ComponentInfo> = match category_data.get(choice)
-    {
-      Some(Some((category_name, components))) => {
+    let component = if let Some(namespace) = namespace {
+      self
+        .registry_manager
+        .fetch_component(namespace, component_name)
+        .await?
+    } else {
+      self
+        .registry_manager
+        .fetch_component_auto(component_name)
+        .await?
+    };
+
+    progress(
+      "download",
+      serde_json::json!({
+        "component": component.name,
+        "registry": component.registry,
+        "files": component.files.len(),
+      }),
+    );
+    Ok(component)
+  }
+
+  /// Install a registry dependency of a component. When the project already
+  /// has different content in the dependency's files (e.g. `utils` from
+  /// another registry or version), the diff is shown and the user picks
+  /// which to keep; without prompts the local files are kept unless
+  /// `--force` is given.
+  async fn install_registry_dependency(
+    &self,
+    dependency: &str,
+    registry_namespace: Option<&str>,
+    force: bool,
+  ) -> Result<()> {
+    let component = self.fetch_component(dependency, registry_namespace).await?;
+    let context = self.create_component_context(&component);
+    self.check_conditions(&component, &context)?;
+
+    let files = self.compare_with_project(&component, &context)?;
+    let conflicts: Vec<_> = files
+      .iter()
+      .filter_map(|(path, local, new)| Some((path, local.as_ref()?, new)))
+      .filter(|(_, local, new)| strip_header(local) != **new)
+      .collect();
+    if conflicts.is_empty() {
+      if !files.is_empty() && files.iter().all(|(_, local, _)| local.is_some()) {
+        detail!(
+          "  {} '{}' is already installed",
+          "✓".green(),
+          component.name
+        );
+        return Ok(());
+      }
+      return Box::pin(self.install_resolved_component(
+        component,
+        registry_namespace,
+        force,
+        true,
+        Lifecycle::Add,
+      ))
+      .await;
+    }
+
+    let lockfile = Lockfile::load(&Lockfile::path_for(&self.project_dir))?;
+    let installed = match lockfile.get(&self.lockfile_key(&component)) {
+      Some(locked) => format!(
+        "installed from {}",
+        describe_origin(locked.registry.as_deref(), locked.version.as_deref())
+      ),
+      None => "already in the project".to_string(),
+    };
+    status!(
+      "{} '{}' is {}, and {} has different content:",
+      "!".yellow(),
+      component.name.cyan(),
+      installed,
+      describe_origin(component.registry.as_deref(), component.version.as_deref())
+    );
+    for (path, local, new) in &conflicts {
+      status!("{}", path.display().to_string().cyan());
+      status!("{}", render_diff(&strip_header(local), new));
+    }
+
+    match self.choose_conflict_resolution(&component.name, force)? {
+      ConflictResolution::KeepLocal => {
+        status!(
+          "  {} Keeping the project's '{}'",
+          "✓".green(),
+          component.name
+        );
+        Ok(())
+      }
+      ConflictResolution::Skip => {
+        warning!(
+          "{} Skipped '{}'; components that import it may not work until it's installed",
+          "!".yellow(),
+          component.name
+        );
+        Ok(())
+      }
+      ConflictResolution::TakeNew => {
+        if let Ok(mut accepted) = self.accepted_overwrites.lock() {
+          accepted.extend(conflicts.iter().map(|(path, _, _)| (*path).clone()));
+        }
+        Box::pin(self.install_resolved_component(
+          component,
+          registry_namespace,
+          true,
+          true,
+          Lifecycle::Add,
+        ))
+        .await
+      }
+    }
+  }
+
+  /// Target path, current content (when the file exists) and installed
+  /// content of each file of `component`
+  fn compare_with_project(
+    &self,
+    component: &Component,
+    context: &ComponentContext,
+  ) -> Result<Vec<(PathBuf, Option<String>, String)>> {
+    let files = self.namespaced_files(component, context);
+    self
+      .installable_files(&files, context)
+      .filter(|file| !self.injects_css(file, context))
+      .map(|file| {
+        let target_path = self.target_path(file, context)?;
+        let local = if target_path.is_symlink() {
+          None
+        } else {
+          paths::read_to_string(&target_path).ok()
+        };
+        Ok((target_path, local, self.installed_content(file, context)?))
+      })
+      .collect()
+  }
+
+  /// Ask what to do about a registry dependency whose files differ from the
+  /// project's
+  fn choose_conflict_resolution(
+    &self,
+    dependency: &str,
+    force: bool,
+  ) -> Result<ConflictResolution> {
+    if !self.interactive {
+      let resolution = if force {
+        ConflictResolution::TakeNew
+      } else {
+        ConflictResolution::KeepLocal
+      };
+      status!(
+        "{} {} {}",
+        "?".dimmed(),
+        format!("Which '{}' do you want?", dependency),
+        resolution.label().dimmed()
+      );
+      return Ok(resolution);
+    }
+
+    let choices = ConflictResolution::ALL;
+    let labels: Vec<_> = choices.iter().map(|choice| choice.label()).collect();
+    let selection = Select::with_theme(&ColorfulTheme::default())
+      .with_prompt(format!("Which '{}' do you want?", dependency))
+      .items(&labels)
+      .default(0)
+      .interact()?;
+    Ok(choices[selection])
+  }
+
+  /// Install an already fetched component, reporting progress
+  async fn install_resolved_component(
+    &self,
+    component: Component,
+    registry_namespace: Option<&str>,
+    force: bool,
+    skip_deps: bool,
+    lifecycle: Lifecycle,
+  ) -> Result<()> {
+    status!(
+      "{} Installing component '{}'...",
+      "→".blue(),
+      component.name.cyan()
+    );
+
+    self
+      .install_fetched_component(&component, registry_namespace, force, skip_deps, lifecycle)
+      .await?;
+
+    status!(
+      "{} Successfully installed '{}'",
+      "✓".green(),
+      component.name.cyan()
+    );
+    self.print_post_install_message(&component);
+    Ok(())
+  }
+
+  /// Look up the deprecation status of a component, from its own JSON or
+  /// from its registry index entry
+  async fn deprecation_of(&self, component: &Component) -> Option<Deprecation> {
+    if component.deprecated == Some(true) {
+      return Some(Deprecation {
+        replaced_by: component.replaced_by.clone(),
+      });
+    }
+
+    let registry = self
+      .registry_manager
+      .get_registry(component.registry.as_deref()?)?;
+    let index = registry.fetch_index().await.ok()?;
+    index
+      .as_slice()
+      .into_iter()
+      .find(|info| info.name == component.name && info.is_deprecated())
+      .map(|info| Deprecation {
+        replaced_by: info.replaced_by.clone(),
+      })
+  }
+
+  /// Warn about a deprecated component and ask whether to install its
+  /// replacement. Returns the replacement name when the user accepts.
+  async fn prompt_deprecation_replacement(&self, component: &Component) -> Result<Option<String>> {
+    let Some(deprecation) = self.deprecation_of(component).await else {
+      return Ok(None);
+    };
+
+    status!(
+      "{} '{}' is deprecated{}",
+      "⚠".yellow(),
+      component.name.cyan(),
+      deprecation
+        .replaced_by
+        .as_ref()
+        .map(|replacement| format!(", use '{}' instead", replacement))
+        .unwrap_or_default()
+    );
+
+    // Without prompts, install exactly what was asked for
+    let Some(replacement) = deprecation.replaced_by.filter(|_| self.interactive) else {
+      return Ok(None);
+    };
+
+    let install_replacement = self.confirm(
+      &format!("Install '{}' instead of '{}'?", replacement, component.name),
+      true,
+    )?;
+
+    Ok(install_replacement.then_some(replacement))
+  }
+
+  /// Registry `component_name` was installed from, according to `lockfile`,
+  /// when that registry is still configured
+  fn recorded_registry(&self, lockfile: &Lockfile, component_name: &str) -> Option<String> {
+    lockfile
+      .get(component_name)
+      .and_then(|locked| locked.registry.clone())
+      .filter(|namespace| self.registry_manager.get_registry(namespace).is_some())
+  }
+
+  /// Registry an installed component came from, read from the project's
+  /// lockfile
+  fn installed_registry(&self, component_name: &str) -> Option<String> {
+    let lockfile = Lockfile::load(&Lockfile::path_for(&self.project_dir.clone())).ok()?;
+    self.recorded_registry(&lockfile, component_name)
+  }
+
+  /// Install a component that was loaded from a local JSON file or stdin.
+  /// Registry dependencies are still resolved against the configured
+  /// registries.
+  pub async fn install_local_component(
+    &self,
+    component: Component,
+    registry_namespace: Option<&str>,
+    force: bool,
+    skip_deps: bool,
+  ) -> Result<()> {
+    self
+      .confirm_install_size(&component, registry_namespace, skip_deps)
+      .await?;
+    self
+      .install_resolved_component(
+        component,
+        registry_namespace,
+        force,
+        skip_deps,
+        Lifecycle::Add,
+      )
+      .await
+  }
+
+  /// Install the registry dependencies, files and npm dependencies of an
+  /// already fetched component
+  async fn install_fetched_component(
+    &self,
+    component: &Component,
+    registry_namespace: Option<&str>,
+    force: bool,
+    skip_deps: bool,
+    lifecycle: Lifecycle,
+  ) -> Result<()> {
+    // Install dependencies first (if not skipped)
+    if !skip_deps {
+      if let Some(dependencies) = &component.registry_dependencies {
+        for dep in dependencies {
+          status!("{} Installing dependency '{}'...", "→".yellow(), dep.cyan());
+          Box::pin(self.install_registry_dependency(dep, registry_namespace, force)).await?;
+        }
+      }
+    }
+
+    // Verify engines and peer dependencies before writing anything
+    let current_dir = self.project_dir.clone();
+    let missing_peers = self.check_requirements(component)?;
+
+    // Create component context for proper alias resolution
+    let component_context = self.create_component_context(component);
+    if let Some(component_type) = component.component_type.as_deref() {
+      if !KNOWN_COMPONENT_TYPES.contains(&component_type) {
+        warning!(
+          "{} Unknown type '{}' for '{}'; installing into {}",
+          "!".yellow(),
+          component_type,
+          component.name,
+          self.config.aliases.components
+        );
+      }
+    }
+
+    self.check_conditions(component, &component_context)?;
+    let planned_files = self
+      .installable_files(&component.files, &component_context)
+      .map(|file| self.target_path(file, &component_context))
+      .collect::<Result<Vec<_>>>()?;
+    let (pre_hook, post_hook) = lifecycle.hooks();
+    run_hook(
+      self.config.hooks.as_ref(),
+      pre_hook,
+      &HookContext {
+        component: &component.name,
+        registry: component.registry.as_deref(),
+        files: &planned_files,
+      },
+      &current_dir,
+    )?;
+
+    // Install component files with context
+    let written_files = self
+      .install_component_files(component, &component_context, force)
+      .await?;
+
+    if self.config.format_on_install == Some(true) {
+      self.format_installed_files(&written_files);
+    }
+
+    if self.config.barrel_exports == Some(true) {
+      self.update_barrel_exports(&written_files)?;
+    }
+
+    run_hook(
+      self.config.hooks.as_ref(),
+      post_hook,
+      &HookContext {
+        component: &component.name,
+        registry: component.registry.as_deref(),
+        files: &written_files,
+      },
+      &current_dir,
+    )?;
+
+    self.record_installed_component(component, &written_files, &current_dir)?;
+    self.record(|report| report.components.push(component.name.clone()));
+
+    // Install dependencies if component has any dependencies and package manager
+    // was detected
+    let mut deps = ComponentDependencies {
+      dependencies: component.dependencies.clone().unwrap_or_default(),
+      dev_dependencies: component.dev_dependencies.clone().unwrap_or_default(),
+    };
+    deps.dependencies.extend(missing_peers);
+
+    if !deps.dependencies.is_empty() || !deps.dev_dependencies.is_empty() {
+      self.install_dependencies(&deps)?;
+    }
+
+    Ok(())
+  }
+
+  /// Record an installed component and the hashes of its files (as they are
+  /// on disk after formatting and hooks) in the lockfile
+  fn record_installed_component(
+    &self,
+    component: &Component,
+    files: &[PathBuf],
+    project_dir: &std::path::Path,
+  ) -> Result<()> {
+    // The Tailwind CSS file belongs to the project; a style or theme only
+    // owns its region in it
+    let tailwind_css = self.tailwind_css_path()?;
+    let locked_files = files
+      .iter()
+      .filter(|path| **path != tailwind_css)
+      .map(|path| {
+        let content = paths::read_to_string(path)?;
+        Ok(LockedFile::new(project_dir, path, &content))
+      })
+      .collect::<Result<Vec<_>>>()?;
+
+    let locked = LockedComponent {
+      registry: component.registry.clone(),
+      component_type: component.component_type.clone(),
+      version: component.version.clone(),
+      license: component.license.clone(),
+      git_ref: component.git_ref.clone(),
+      commit: component.commit.clone(),
+      dependencies: component.dependencies.clone().unwrap_or_default(),
+      dev_dependencies: component.dev_dependencies.clone().unwrap_or_default(),
+      files: locked_files,
+      installed_at: now_timestamp(),
+    };
+
+    let key = self.lockfile_key(component);
+    Lockfile::update(&Lockfile::path_for(project_dir), |lockfile| {
+      lockfile.insert(&key, locked)
+    })
+  }
+
+  /// Summarize the installed components for `uiget report`. Registries are
+  /// asked which components are outdated only when `check_outdated` is set.
+  pub async fn project_report(&self, check_outdated: bool) -> Result<ProjectReport> {
+    let current_dir = self.project_dir.clone();
+    let lockfile = Lockfile::load(&Lockfile::path_for(&current_dir))?;
+    let mut report = ProjectReport::from_lockfile(&lockfile, &current_dir);
+    if let Ok(detection) =
+      detect_package_manager_cached(&self.project_root, detection_cache_path().as_deref())
+    {
+      report.package_manager = Some(detection.manager.name().to_string());
+      report.lockfile = detection.lockfile().map(|(_, format)| format.to_string());
+    }
+
+    if check_outdated {
+      let installed = self.get_installed_components()?;
+      let outdated = self.check_outdated_components(&installed, None).await?;
+      report.outdated = Some(outdated.iter().filter(|(_, outdated)| *outdated).count());
+    }
+
+    Ok(report)
+  }
+
+  /// Summarize the licenses of installed components and of their npm
+  /// dependencies (read from node_modules)
+  pub fn show_licenses(&self, json: bool) -> Result<()> {
+    let current_dir = self.project_dir.clone();
+    let lockfile = Lockfile::load(&Lockfile::path_for(&current_dir))?;
+
+    let project_root = &self.project_root;
+
+    let components: std::collections::BTreeMap<&str, &str> = lockfile
+      .components
+      .iter()
+      .map(|(name, locked)| {
+        (
+          name.as_str(),
+          locked.license.as_deref().unwrap_or("UNKNOWN"),
+        )
+      })
+      .collect();
+
+    let mut packages: std::collections::BTreeMap<String, String> =
+      std::collections::BTreeMap::new();
+    for locked in lockfile.components.values() {
+      for dep in locked.dependencies.iter().chain(&locked.dev_dependencies) {
+        let (name, _) = parse_dependency_spec(dep);
+        packages
+          .entry(name.to_string())
+          .or_insert_with(|| read_package_license(project_root, name));
+      }
+    }
+
+    if json {
+      let report = serde_json::json!({
+        "components": components,
+        "packages": packages,
+      });
+      println!("{}", serde_json::to_string_pretty(&report)?);
+      return Ok(());
+    }
+
+    if components.is_empty() {
+      status!(
+        "{} No components recorded in {}/{}",
+        "!".yellow(),
+        STATE_DIR,
+        LOCKFILE_NAME
+      );
+      return Ok(());
+    }
+
+    status!("\n{} Component licenses:", "📜".blue());
+    for (name, license) in &components {
+      status!("  {} {} {}", "→".dimmed(), name.cyan(), license.yellow());
+    }
+
+    if !packages.is_empty() {
+      status!("\n{} npm dependency licenses:", "📦".blue());
+      for (name, license) in &packages {
+        status!("  {} {} {}", "→".dimmed(), name.cyan(), license.yellow());
+      }
+    }
+
+    // Totals per license across components and packages
+    let mut totals: std::collections::BTreeMap<&str, usize> = std::collections::BTreeMap::new();
+    for license in components
+      .values()
+      .copied()
+      .chain(packages.values().map(|s| s.as_str()))
+    {
+      *totals.entry(license).or_default() += 1;
+    }
+
+    status!("\n{} Summary:", "📊".blue());
+    for (license, count) in totals {
+      status!("  {} {}", license.yellow(), count.to_string().dimmed());
+    }
+
+    Ok(())
+  }
+
+  /// Check the component's `engines` and `peerDependencies` against the
+  /// local environment. Returns the missing peers the user chose to install.
+  fn check_requirements(&self, component: &Component) -> Result<Vec<String>> {
+    if let Some(required) = component
+      .engines
+      .as_ref()
+      .and_then(|engines| engines.get("node"))
+    {
+      match node_version() {
+        Some(version) if !satisfies_range(&version, required) => {
+          status!(
+            "{} '{}' requires Node {} but {} is installed",
+            "⚠".yellow(),
+            component.name.cyan(),
+            required.yellow(),
+            version.yellow()
+          );
+          if !self.confirm("Continue anyway?", false)? {
+            return Err(
+              InstallError::EngineMismatch {
+                version,
+                required: required.to_string(),
+                component: component.name.clone(),
+              }
+              .into(),
+            );
+          }
+        }
+        Some(_) => {}
+        None => status!(
+          "{} Could not determine the Node version ('{}' requires Node {})",
+          "!".yellow(),
+          component.name.cyan(),
+          required.yellow()
+        ),
+      }
+    }
+
+    let peers = component.peer_dependencies.clone().unwrap_or_default();
+    if peers.is_empty() {
+      return Ok(Vec::new());
+    }
+
+    let declared = read_declared_packages(&self.project_root);
+
+    let missing: Vec<String> = peers
+      .into_iter()
+      .filter(|peer| {
+        !declared
+          .iter()
+          .any(|name| name == parse_dependency_spec(peer).0)
+      })
+      .collect();
+
+    if missing.is_empty() {
+      return Ok(Vec::new());
+    }
+
+    status!(
+      "{} '{}' has missing peer dependencies: {}",
+      "⚠".yellow(),
+      component.name.cyan(),
+      missing.join(", ").yellow()
+    );
+
+    if self.confirm("Install missing peer dependencies?", true)? {
+      Ok(missing)
+    } else {
+      status!(
+        "{} Continuing without peer dependencies, the component may not work",
+        "!".yellow()
+      );
+      Ok(Vec::new())
+    }
+  }
+
+  /// Add re-exports for newly installed components to the barrel file of the
+  /// UI components directory
+  fn update_barrel_exports(&self, files: &[PathBuf]) -> Result<()> {
+    let ui_dir = self.ui_components_dir();
+    let index_path = ui_dir.join(self.barrel_file_name());
+
+    let mut entries: Vec<String> = files
+      .iter()
+      .filter_map(|file| barrel::export_entry(&ui_dir, file))
+      .collect();
+    entries.dedup();
+
+    for entry in entries {
+      if barrel::add_export(&index_path, &entry)? {
+        status!(
+          "  {} {} {}",
+          "✓".green(),
+          index_path.display().to_string().dimmed(),
+          format!("(+ ./{})", entry).dimmed()
+        );
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Name of the barrel file, following the project's TypeScript setting
+  fn barrel_file_name(&self) -> &'static str {
+    if self.is_typescript_enabled() {
+      "index.ts"
+    } else {
+      "index.js"
+    }
+  }
+
+  /// Run the project's formatter on freshly written files. Formatting is
+  /// best-effort: failures are reported but don't fail the install.
+  fn format_installed_files(&self, files: &[PathBuf]) {
+    let project_root = &self.project_root;
+
+    let Some(formatter) = detect_formatter(project_root) else {
+      status!(
+        "{} formatOnInstall is enabled but no prettier, biome or dprint config was found",
+        "!".yellow()
+      );
+      return;
+    };
+
+    status!(
+      "{} Formatting {} file(s) with {}",
+      "→".blue(),
+      files.len().to_string().cyan(),
+      formatter.name().cyan()
+    );
+
+    if let Err(e) = formatter.format_files(project_root, files) {
+      status!("{} Failed to format installed files: {}", "!".yellow(), e);
+    }
+  }
+
+  /// Print the registry-provided post-install notes of a component, unless
+  /// disabled in the configuration
+  fn print_post_install_message(&self, component: &Component) {
+    if self.config.post_install_messages == Some(false) {
+      return;
+    }
+
+    if let Some(message) = &component.post_install_message {
+      status!("\n{} Note from '{}':", "💡".blue(), component.name.cyan());
+      for line in message.lines() {
+        status!("  {}", line);
+      }
+    }
+
+    if let Some(usage) = &component.usage {
+      status!("\n{} Usage:", "→".blue());
+      for line in highlight_code(usage.trim_end(), &usage_file_name(component)).lines() {
+        status!("  {}", line);
+      }
+    }
+  }
+
+  /// Interactive component selection menu
+  async fn interactive_component_selection(
+    &self,
+    registry_namespace: Option<&str>,
+    force: bool,
+    skip_deps: bool,
+  ) -> Result<()> {
+    // Determine which registry to use
+    let namespace = if let Some(ns) = registry_namespace {
+      ns.to_string()
+    } else {
+      // Let user select registry if multiple are available
+      let registries: Vec<String> = self
+        .registry_manager
+        .namespaces()
+        .into_iter()
+        .cloned()
+        .collect();
+
+      if registries.is_empty() {
+        return Err(InstallError::NoRegistries.into());
+      }
+
+      if registries.len() == 1 {
+        registries[0].clone()
+      } else {
+        if !self.interactive {
+          return Err(
+            InstallError::NonInteractive(
+              "Multiple registries are configured; pass --registry to choose one".to_string(),
+            )
+            .into(),
+          );
+        }
+
+        let selection = Select::with_theme(&ColorfulTheme::default())
+          .with_prompt("Select a registry:")
+          .items(&registries)
+          .default(0)
+          .interact()?;
+
+        registries[selection].clone()
+      }
+    };
+
+    // Fetch components from selected registry
+    let registry = self
+      .registry_manager
+      .get_registry(&namespace)
+      .ok_or_else(|| RegistryError::RegistryNotFound(namespace.to_string()))?;
+
+    status!(
+      "{} Fetching components from '{}'...",
+      "→".blue(),
+      namespace.cyan()
+    );
+    let index = registry.fetch_index().await?;
+
+    if index.is_empty() {
+      status!(
+        "{} No components available in registry '{}'",
+        "!".yellow(),
+        namespace.cyan()
+      );
+      return Ok(());
+    }
+
+    // Get list of installed components
+    let installed_components = self.get_installed_components().unwrap_or_default();
+
+    // Group components by the registry's categories, or by type
+    let groups = group_components(index.as_slice());
+
+    // First, show category selection menu
+    let mut category_options = vec!["🔍 Browse and select individual components".to_string()];
+    let mut category_data = vec![None]; // None for individual browsing
+
+    for group in &groups {
+      category_options.push(format!(
+        "{} Select ALL {} ({} items)",
+        group.icon,
+        group.title,
+        group.components.len()
+      ));
+      category_data.push(Some((group.title.as_str(), &group.components)));
+    }
+
+    category_options.push("❌ Cancel".to_string());
+    category_data.push(None);
+
+    // Load the outdated status of installed components while the menu is
+    // shown; the prompt blocks, so it runs on a blocking thread
+    let menu = tokio::task::spawn_blocking(move || {
+      Select::with_theme(&ColorfulTheme::default())
+        .with_prompt("What would you like to do?")
+        .items(&category_options)
+        .default(0)
+        .interact()
+    });
+    let (choice, outdated_results) = tokio::join!(
+      menu,
+      self.check_outdated_components(&installed_components, Some(&namespace))
+    );
+    let choice = choice??;
+
+    let outdated_components: std::collections::HashSet<String> = outdated_results
+      .unwrap_or_default()
+      .into_iter()
+      .filter_map(|(name, is_outdated)| if is_outdated { Some(name) } else { None })
+      .collect();
+
+    // Create display items with categories and track category indices
+    let mut display_items = Vec::new();
+    let mut component_map = Vec::new();
+
+    for group in &groups {
+      display_items.push(format!(
+        "{} {} ({})",
+        group.icon,
+        group.title,
+        group.components.len()
+      ));
+      component_map.push(None); // Category header
+
+      for component in &group.components {
+        let is_installed = installed_components.contains(&component.name);
+        let status_icon = if is_installed {
+          if outdated_components.contains(&component.name) {
+            "⚠"
+          } else {
+            "✓"
+          }
+        } else {
+          " "
+        };
+        display_items.push(format!(
+          "  {} {} {}",
+          "→".dimmed(),
+          status_icon,
+          component.name
+        ));
+        component_map.push(Some(*component));
+      }
+    }
+
+    let selected_components: Vec<&crate::registry::ComponentInfo> = match category_data.get(choice)
+    {
+      Some(Some((category_name, components))) => {
         // Bulk selection confirmed
-        println!(
+        status!(
           "\n{} Selected ALL {} ({} components)",
           "✅".green(),
           category_name,
           components.len()
         );
 
-        // Show preview of what will be installed
-        println!("Components to be installed:");
-        for (i, component) in components.iter().enumerate() {
-          println!(
-            "  {}. {}",
-            (i + 1).to_string().dimmed(),
-            component.name.cyan()
-          );
-          if i >= 9 {
-            println!(
-              "  ... and {} more",
-              (components.len() - 10).to_string().dimmed()
+        // Show preview of what will be installed
+        status!("Components to be installed:");
+        for (i, component) in components.iter().enumerate() {
+          status!(
+            "  {}. {}",
+            (i + 1).to_string().dimmed(),
+            component.name.cyan()
+          );
+          if i >= 9 {
+            status!(
+              "  ... and {} more",
+              (components.len() - 10).to_string().dimmed()
+            );
+            break;
+          }
+        }
+
+        if !self.confirm(
+          &format!("Install all {} components?", components.len()),
+          true,
+        )? {
+          status!("{} Installation cancelled", "❌".red());
+          return Ok(());
+        }
+
+        components.to_vec()
+      }
+      Some(None) if choice == 0 => {
+        // Individual component selection
+        status!("\n{} Component Browser", "🔍".blue());
+        status!(
+          "{}",
+          "Use ↑↓ to navigate, Space to select multiple, Enter to confirm".dimmed()
+        );
+
+        // In sync mode the installed components start out checked
+        let checked: Vec<bool> = component_map
+          .iter()
+          .map(|component| {
+            self.sync_selection
+              && component.is_some_and(|component| installed_components.contains(&component.name))
+          })
+          .collect();
+
+        let selections = MultiSelect::with_theme(&ColorfulTheme::default())
+          .with_prompt("Select components to install:")
+          .items(&display_items)
+          .defaults(&checked)
+          .interact()?;
+
+        if self.sync_selection {
+          let unchecked: Vec<&str> = component_map
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| checked[*i] && !selections.contains(i))
+            .filter_map(|(_, component)| component.map(|component| component.name.as_str()))
+            .collect();
+          if !unchecked.is_empty() {
+            status!(
+              "{} Unchecked components are kept: {} (use 'uiget remove' to delete them)",
+              "!".yellow(),
+              unchecked.join(", ").cyan()
+            );
+          }
+        }
+
+        // Filter out category headers and get components
+        selections
+          .into_iter()
+          .filter_map(|i| component_map.get(i).and_then(|opt| *opt))
+          .collect()
+      }
+      _ => {
+        // Cancel
+        status!("{} Operation cancelled", "👋".yellow());
+        return Ok(());
+      }
+    };
+
+    // Selecting an installed, unmodified component is a no-op in sync mode
+    let selected_components: Vec<_> = if self.sync_selection {
+      let current_dir = self.project_dir.clone();
+      let lockfile = Lockfile::load(&Lockfile::path_for(&current_dir))?;
+      let (unchanged, selected): (Vec<_>, Vec<_>) =
+        selected_components.into_iter().partition(|component| {
+          lockfile
+            .get(&component.name)
+            .is_some_and(|locked| !locked.is_modified(&current_dir))
+        });
+
+      if !unchanged.is_empty() {
+        let names: Vec<&str> = unchanged.iter().map(|c| c.name.as_str()).collect();
+        self.record(|report| {
+          report
+            .unchanged
+            .extend(names.iter().map(|name| name.to_string()))
+        });
+        status!(
+          "{} Already installed and unmodified: {}",
+          "✓".green(),
+          names.join(", ").dimmed()
+        );
+      }
+      if selected.is_empty() {
+        status!("{} Nothing to install", "✓".green());
+        return Ok(());
+      }
+      selected
+    } else {
+      selected_components
+    };
+
+    if selected_components.is_empty() {
+      status!("{} No components selected", "!".yellow());
+      return Ok(());
+    }
+
+    // Install selected components
+    status!(
+      "\n{} Installing {} component(s)...",
+      "→".blue(),
+      selected_components.len().to_string().cyan()
+    );
+
+    for component in selected_components {
+      status!();
+      self
+        .install_component(&component.name, Some(&namespace), force, skip_deps)
+        .await?;
+    }
+
+    status!(
+      "\n{} All selected components installed successfully!",
+      "✓".green()
+    );
+
+    Ok(())
+  }
+
+  /// Install component files to the filesystem, returning the written paths
+  async fn install_component_files(
+    &self,
+    component: &Component,
+    context: &ComponentContext,
+    force: bool,
+  ) -> Result<Vec<PathBuf>> {
+    let targets = self
+      .installable_files(&component.files, context)
+      .map(|file| self.target_path(file, context))
+      .collect::<Result<Vec<_>>>()?;
+    if let Some((first, second)) = paths::case_collisions(&targets).into_iter().next() {
+      return Err(InstallError::CaseCollision(second, first).into());
+    }
+
+    let files = self.namespaced_files(component, context);
+    let files: Vec<_> = self
+      .installable_files(&files, context)
+      .filter(|file| !self.injects_css(file, context))
+      .collect();
+
+    let mut written_files = self.install_files(&files, context, force).await?;
+    if let Some(css) = self.injected_css(component, context)? {
+      written_files.push(self.inject_css(&css, context)?);
+    }
+    Ok(written_files)
+  }
+
+  /// Files of a component as they are installed: imports of the component
+  /// itself and of its registry dependencies follow them into the namespace
+  /// directory
+  fn namespaced_files<'c>(
+    &self,
+    component: &'c Component,
+    context: &ComponentContext,
+  ) -> Cow<'c, [ComponentFile]> {
+    match self.namespace_dir(context) {
+      Some(dir) => {
+        let mut dependencies: Vec<&str> = component
+          .registry_dependencies
+          .iter()
+          .flatten()
+          .map(String::as_str)
+          .filter(|dependency| !dependency.contains(['/', ':']))
+          .collect();
+        dependencies.push(&component.name);
+
+        Cow::Owned(
+          self
+            .installable_files(&component.files, context)
+            .map(|file| ComponentFile {
+              content: self.rewrite_namespaced_imports(&file.content, &dir, &dependencies),
+              ..file.clone()
+            })
+            .collect(),
+        )
+      }
+      None => Cow::Borrowed(&component.files),
+    }
+  }
+
+  /// Whether a file is CSS of a style or theme, merged into the Tailwind CSS
+  /// file instead of being installed on its own
+  fn injects_css(&self, file: &ComponentFile, context: &ComponentContext) -> bool {
+    let injected_type = context
+      .component_type
+      .as_deref()
+      .is_some_and(|component_type| INJECTED_CSS_TYPES.contains(&component_type));
+    injected_type
+      && Path::new(&file.get_target_path())
+        .extension()
+        .is_some_and(|ext| ext == "css")
+  }
+
+  /// Path of the CSS file that imports Tailwind CSS (`tailwind.css`)
+  fn tailwind_css_path(&self) -> Result<PathBuf> {
+    Ok(self.project_dir.join(&self.config.tailwind.css))
+  }
+
+  /// CSS a component merges into the Tailwind CSS file: the stylesheet
+  /// generated from its `cssVars` and `css`, followed by the CSS files of a
+  /// style or theme. None when it has neither.
+  fn injected_css(
+    &self,
+    component: &Component,
+    context: &ComponentContext,
+  ) -> Result<Option<String>> {
+    let files = self.namespaced_files(component, context);
+    let css_files: Vec<_> = self
+      .installable_files(&files, context)
+      .filter(|file| self.injects_css(file, context))
+      .collect();
+    let stylesheet = component.stylesheet();
+    if stylesheet.is_none() && css_files.is_empty() {
+      return Ok(None);
+    }
+
+    let mut css: Vec<String> = stylesheet.into_iter().collect();
+    for file in css_files {
+      css.push(self.installed_content(file, context)?);
+    }
+    Ok(Some(css.join("\n")))
+  }
+
+  /// Merge a component's CSS into the Tailwind CSS file, inside a region
+  /// named after the component, so installing it again replaces that region
+  /// instead of adding the CSS twice
+  fn inject_css(&self, css: &str, context: &ComponentContext) -> Result<PathBuf> {
+    let css_path = self.tailwind_css_path()?;
+
+    let existed = paths::exists(&css_path);
+    let existing = if existed {
+      paths::read_to_string(&css_path)?
+    } else {
+      String::new()
+    };
+    let merged = replace_region(&existing, &context.name, css)
+      .unwrap_or_else(|| append_css_region(&existing, &context.name, css));
+    paths::write_file(&css_path, merged)?;
+
+    let follow_up = format!(
+      "CSS of '{}' merged into {}",
+      context.name, self.config.tailwind.css
+    );
+    self.record(|report| {
+      report.record_file(css_path.clone(), existed);
+      report.follow_ups.push(follow_up);
+    });
+    progress_write(&context.name, &css_path);
+    detail!(
+      "  {} {} {}",
+      "✓".green(),
+      css_path.display().to_string().dimmed(),
+      format!("({} styles)", context.name).dimmed()
+    );
+
+    Ok(css_path)
+  }
+
+  /// Files of a component to install. Declaration files are left out when
+  /// types are stripped, and example files unless `installExamples` is on
+  /// or the component is an example itself.
+  fn installable_files<'f>(
+    &self,
+    files: &'f [ComponentFile],
+    context: &ComponentContext,
+  ) -> impl Iterator<Item = &'f ComponentFile> {
+    let strip_types = self.strips_types();
+    let skip_examples = self.config.install_examples != Some(true)
+      && context.component_type.as_deref() != Some("registry:example");
+    let facts = self.condition_facts(context);
+    files.iter().filter(move |file| {
+      let declaration =
+        strip_types && transpile::is_declaration_file(Path::new(&file.get_target_path()));
+      let example = skip_examples && file.file_type.as_deref() == Some("registry:example");
+      // Invalid conditions were reported by check_conditions
+      let excluded = file
+        .condition
+        .as_deref()
+        .is_some_and(|condition| !condition::evaluate(condition, &facts).unwrap_or(false));
+      !declaration && !example && !excluded
+    })
+  }
+
+  /// What `files[].condition` expressions can test: `typescript`,
+  /// `framework` (e.g. "svelte" and "sveltekit") and `style`
+  fn condition_facts(&self, context: &ComponentContext) -> Facts {
+    let frameworks = self
+      .framework
+      .map(|framework| framework.as_str())
+      .into_iter()
+      .chain(self.meta_framework)
+      .map(String::from)
+      .collect();
+    let style = context
+      .registry
+      .as_ref()
+      .and_then(|registry| self.config.registries.get(registry))
+      .and_then(|registry| registry.style())
+      .or(self.config.style.as_deref())
+      .map(String::from)
+      .into_iter()
+      .collect();
+
+    Facts::from([
+      ("typescript", Fact::Flag(self.is_typescript_enabled())),
+      ("framework", Fact::Values(frameworks)),
+      ("style", Fact::Values(style)),
+    ])
+  }
+
+  /// Fail on file conditions that can't be evaluated, rather than quietly
+  /// leaving the files out
+  fn check_conditions(&self, component: &Component, context: &ComponentContext) -> Result<()> {
+    let facts = self.condition_facts(context);
+    for file in &component.files {
+      let Some(condition) = &file.condition else {
+        continue;
+      };
+      condition::evaluate(condition, &facts).map_err(|source| InstallError::InvalidCondition {
+        component: component.name.clone(),
+        file: file.get_target_path(),
+        condition: condition.clone(),
+        source,
+      })?;
+    }
+    Ok(())
+  }
+
+  /// Whether installed files get their TypeScript syntax stripped: the
+  /// `stripTypes` option is on and the project doesn't use TypeScript
+  fn strips_types(&self) -> bool {
+    self.config.strip_types == Some(true) && !self.is_typescript_enabled()
+  }
+
+  /// Path a component file is installed to: the Tailwind CSS file for the
+  /// CSS of styles and themes, otherwise its resolved target, renamed to
+  /// `.js`/`.jsx` when types are stripped
+  fn target_path(&self, file: &ComponentFile, context: &ComponentContext) -> Result<PathBuf> {
+    if self.injects_css(file, context) {
+      return self.tailwind_css_path();
+    }
+
+    let path = self.resolve_file_path(&file.get_target_path(), &context.for_file(file))?;
+    Ok(if self.strips_types() {
+      transpile::js_path(&path)
+    } else {
+      path
+    })
+  }
+
+  /// Content of a component file as it is installed: placeholders filled
+  /// in and, for JavaScript projects, types stripped
+  fn installed_content(&self, file: &ComponentFile, context: &ComponentContext) -> Result<String> {
+    let content = self.process_placeholders(&file.content, Some(context))?;
+    if !self.strips_types() {
+      return Ok(content);
+    }
+    let target = file.get_target_path();
+    transpile::strip_types(Path::new(&target), &content).map_err(|source| {
+      InstallError::Untranspilable {
+        file: target.clone(),
+        source,
+      }
+      .into()
+    })
+  }
+
+  /// Check a single file and prepare its write, asking before overwriting
+  /// local changes. Nothing is written yet.
+  fn prepare_file(
+    &self,
+    file: &ComponentFile,
+    context: &ComponentContext,
+    force: bool,
+  ) -> Result<FileWrite> {
+    let target_path = self.target_path(file, context)?;
+
+    // A file with a managed region for this component only gets that region
+    // replaced, so it is updated even without --force
+    if !target_path.is_symlink() {
+      if let Ok(existing) = paths::read_to_string(&target_path) {
+        let update = self.installed_content(file, context)?;
+        if let Some(merged) = replace_region(&existing, &context.name, &update) {
+          return Ok(FileWrite {
+            path: target_path,
+            content: merged,
+            existed: true,
+            region: true,
+            replace_link: false,
+          });
+        }
+      }
+    }
+
+    // Check if file exists and force is not enabled
+    if paths::exists(&target_path) && !force {
+      return Err(InstallError::FileExists(target_path).into());
+    }
+    if !force {
+      if let Some(existing) = paths::existing_case_variant(&target_path) {
+        return Err(InstallError::CaseCollision(target_path, existing).into());
+      }
+    }
+
+    // Process placeholders in file content with component context
+    let mut processed_content = self.installed_content(file, context)?;
+
+    if self.config.provenance_header != Some(false) {
+      let provenance = Provenance {
+        component: &context.name,
+        registry: context.registry.as_deref(),
+        version: context.version.as_deref(),
+      };
+      processed_content = add_header(&target_path, &processed_content, &provenance);
+    }
+
+    // Don't silently destroy edits made since the file was installed
+    let accepted = self
+      .accepted_overwrites
+      .lock()
+      .is_ok_and(|accepted| accepted.contains(&target_path));
+    if !self.overwrite_modified && !accepted {
+      if let Some(local_content) = self.locally_modified_content(&target_path)? {
+        status!(
+          "{} {} was changed since it was installed:",
+          "!".yellow(),
+          target_path.display().to_string().cyan()
+        );
+        status!("{}", render_diff(&local_content, &processed_content));
+        if !self.confirm("Overwrite your changes?", false)? {
+          return Err(InstallError::LocallyModified(target_path).into());
+        }
+      }
+    }
+
+    Ok(FileWrite {
+      // A file linked by `uiget link` is replaced rather than written
+      // through the link into the registry sources
+      replace_link: target_path.is_symlink(),
+      existed: paths::exists(&target_path),
+      path: target_path,
+      content: processed_content,
+      region: false,
+    })
+  }
+
+  /// Check and write `files`. All checks and prompts come first, so a
+  /// refused file leaves the others untouched; the writes then run
+  /// concurrently off the async runtime's threads, and are reported in the
+  /// order of `files`.
+  async fn install_files(
+    &self,
+    files: &[&ComponentFile],
+    context: &ComponentContext,
+    force: bool,
+  ) -> Result<Vec<PathBuf>> {
+    let writes = files
+      .iter()
+      .map(|file| self.prepare_file(file, context, force))
+      .collect::<Result<Vec<_>>>()?;
+
+    let written: Vec<FileWrite> = stream::iter(writes)
+      .map(|write| async move {
+        if write.replace_link {
+          paths::remove_file_async(&write.path).await?;
+        }
+        paths::write_file_async(&write.path, &write.content).await?;
+        Ok::<_, anyhow::Error>(write)
+      })
+      .buffered(WRITE_CONCURRENCY)
+      .try_collect()
+      .await?;
+
+    Ok(
+      written
+        .into_iter()
+        .map(|write| {
+          self.record(|report| report.record_file(write.path.clone(), write.existed));
+          progress_write(&context.name, &write.path);
+          if write.region {
+            detail!(
+              "  {} {} {}",
+              "✓".green(),
+              write.path.display().to_string().dimmed(),
+              "(managed region)".dimmed()
+            );
+          } else {
+            detail!(
+              "  {} {}",
+              "✓".green(),
+              write.path.display().to_string().dimmed()
             );
-            break;
           }
-        }
+          write.path
+        })
+        .collect(),
+    )
+  }
 
-        if !Confirm::with_theme(&ColorfulTheme::default())
-          .with_prompt(&format!("Install all {} components?", components.len()))
-          .default(true)
-          .interact()?
-        {
-          println!("{} Installation cancelled", "❌".red());
-          return Ok(());
+  /// Content of `path` when it differs from the hash recorded in the
+  /// lockfile. Files the lockfile doesn't know are never reported.
+  fn locally_modified_content(&self, path: &Path) -> Result<Option<String>> {
+    if path.is_symlink() || !path.is_file() {
+      return Ok(None);
+    }
+
+    let project_dir = self.project_dir.clone();
+    let lockfile = Lockfile::load(&Lockfile::path_for(&project_dir))?;
+    let Some(locked) = lockfile.find_file(&project_dir, path) else {
+      return Ok(None);
+    };
+
+    let content = paths::read_to_string(path)?;
+    Ok((hash_content(&content) != locked.hash).then_some(content))
+  }
+
+  /// Resolve file path using aliases and component target paths
+  fn resolve_file_path(&self, target: &str, context: &ComponentContext) -> Result<PathBuf> {
+    // Targets that name their place in the project are kept, unless the
+    // user overrides where the component goes
+    let current_dir = self.project_dir.clone();
+    if self.component_override(&context.name).is_none() {
+      if let Some(rooted) = project_rooted_target(target, &current_dir) {
+        let rooted =
+          contained_path(&rooted).ok_or_else(|| InstallError::UnsafeTarget(target.to_string()))?;
+        return Ok(current_dir.join(rooted));
+      }
+    }
+
+    // The target format is like "button/button.svelte" or "button/index.ts"
+    // We need to place this in the appropriate directory based on component type
+
+    let alias_path = self.get_alias_for_context(context);
+
+    // First try to resolve using TypeScript paths if available
+    let resolved_alias_path = if let Some(ref ts_paths) = self.typescript_paths {
+      self.resolve_path_with_typescript(alias_path, &ts_paths.paths)
+    } else {
+      // Fallback to manual resolution
+      self.resolve_path_manually(alias_path)
+    };
+
+    // Handle path normalization for different component types
+    let normalized_target = if context.component_type.as_deref() == Some("registry:ui")
+      && target.starts_with("ui/")
+      && (resolved_alias_path.ends_with("/ui") || self.component_override(&context.name).is_some())
+    {
+      // Remove "ui/" prefix from target to avoid duplication for UI
+      // components, and to place overridden ones right in their directory
+      target.strip_prefix("ui/").unwrap_or(target)
+    } else {
+      target
+    };
+
+    // The target may not leave the alias's directory
+    let normalized_target = contained_path(Path::new(normalized_target))
+      .ok_or_else(|| InstallError::UnsafeTarget(target.to_string()))?;
+    let normalized_target = normalized_target.to_string_lossy();
+
+    let resolved_path = match self.namespace_dir(context) {
+      Some(dir) => format!("{}/{}/{}", resolved_alias_path, dir, normalized_target),
+      None => format!("{}/{}", resolved_alias_path, normalized_target),
+    };
+
+    // Convert to absolute path
+    let path = current_dir.join(&resolved_path);
+
+    Ok(path)
+  }
+
+  /// Resolve path using TypeScript path mappings
+  fn resolve_path_with_typescript(
+    &self,
+    ui_path: &str,
+    ts_paths: &HashMap<String, String>,
+  ) -> String {
+    // Try to find a matching TypeScript path mapping
+    for (alias, resolved_path) in ts_paths {
+      if ui_path.starts_with(alias) {
+        // Replace the alias with the resolved path
+        let remaining_path = ui_path.strip_prefix(alias).unwrap_or("");
+        let remaining_path = remaining_path.trim_start_matches('/');
+
+        if remaining_path.is_empty() {
+          return resolved_path.clone();
+        } else {
+          return format!("{}/{}", resolved_path, remaining_path);
         }
+      }
+    }
 
-        components.iter().copied().collect()
+    // If no TypeScript mapping found, fall back to manual resolution
+    self.resolve_path_manually(ui_path)
+  }
+
+  /// Resolve path manually (fallback method)
+  fn resolve_path_manually(&self, ui_path: &str) -> String {
+    // Replace $lib placeholder if present in ui_path
+    if ui_path.contains("$lib") {
+      if let Some(lib_path) = &self.config.aliases.lib {
+        return ui_path.replace("$lib", lib_path);
+      } else {
+        return ui_path.replace("$lib", "src/lib");
       }
-      Some(None) if choice == 0 => {
-        // Individual component selection
-        println!("\n{} Component Browser", "🔍".blue());
-        println!(
-          "{}",
-          "Use ↑↓ to navigate, Space to select multiple, Enter to confirm".dimmed()
-        );
+    }
 
-        let selections = MultiSelect::with_theme(&ColorfulTheme::default())
-          .with_prompt("Select components to install:")
-          .items(&display_items)
-          .interact()?;
+    // When there's no tsconfig.json, use the aliases exactly as configured
+    // Don't override or modify the paths - respect the user's configuration
+    ui_path.to_string()
+  }
 
-        // Filter out category headers and get components
-        selections
-          .into_iter()
-          .filter_map(|i| component_map.get(i).and_then(|opt| *opt))
-          .collect()
+  /// Link a component's source files from a local registry into the
+  /// project instead of copying them, so edits to the sources show up
+  /// immediately. `files` pairs each absolute source path with its target.
+  pub fn link_component(
+    &self,
+    name: &str,
+    component_type: Option<&str>,
+    files: &[(PathBuf, String)],
+    hard: bool,
+    force: bool,
+  ) -> Result<Vec<PathBuf>> {
+    let context = ComponentContext {
+      name: name.to_string(),
+      component_type: component_type.map(String::from),
+      registry: None,
+      version: None,
+    };
+
+    let mut linked = Vec::new();
+    for (source, target) in files {
+      let target_path = self.resolve_file_path(target, &context)?;
+
+      // symlink_metadata also sees dangling links from a previous `link`
+      if paths::extended(&target_path).symlink_metadata().is_ok() {
+        if !force {
+          return Err(InstallError::FileExists(target_path).into());
+        }
+        paths::remove_file(&target_path)?;
       }
-      _ => {
-        // Cancel
-        println!("{} Operation cancelled", "👋".yellow());
-        return Ok(());
+
+      if let Some(parent) = target_path.parent() {
+        paths::create_dir_all(parent)?;
       }
+
+      link_file(source, &paths::extended(&target_path), hard).map_err(|e| {
+        anyhow!(
+          "Failed to link '{}' to '{}': {}",
+          target_path.display(),
+          source.display(),
+          e
+        )
+      })?;
+
+      status!(
+        "  {} {} → {}",
+        "✓".green(),
+        target_path.display().to_string().dimmed(),
+        source.display().to_string().dimmed()
+      );
+      linked.push(target_path);
+    }
+
+    Ok(linked)
+  }
+
+  /// Remove a component: delete the files the lockfile records for it,
+  /// with the directories they leave empty, and drop it from the lockfile.
+  /// Files edited since install are only deleted with `force`.
+  pub fn remove_component(&self, component_name: &str, force: bool) -> Result<()> {
+    self.remove_component_in(&self.project_dir, component_name, force)
+  }
+
+  fn remove_component_in(
+    &self,
+    project_dir: &Path,
+    component_name: &str,
+    force: bool,
+  ) -> Result<()> {
+    let lockfile_path = Lockfile::path_for(project_dir);
+    let lockfile = Lockfile::load(&lockfile_path)?;
+    let Some(locked) = lockfile.get(component_name) else {
+      return Err(InstallError::NotInstalled(component_name.to_string()).into());
     };
 
-    if selected_components.is_empty() {
-      println!("{} No components selected", "!".yellow());
-      return Ok(());
+    if !force {
+      if let Some(modified) = locked
+        .files
+        .iter()
+        .find(|file| file.is_modified(project_dir) && paths::exists(&project_dir.join(&file.path)))
+      {
+        return Err(InstallError::LocallyModified(project_dir.join(&modified.path)).into());
+      }
     }
 
-    // Install selected components
-    println!(
-      "\n{} Installing {} component(s)...",
-      "→".blue(),
-      selected_components.len().to_string().cyan()
+    status!(
+      "{} Removing component '{}'...",
+      "→".red(),
+      component_name.cyan()
     );
 
-    for component in selected_components {
-      println!();
-      self
-        .install_component(&component.name, Some(&namespace), force, skip_deps)
-        .await?;
+    let files: Vec<PathBuf> = locked
+      .files
+      .iter()
+      .map(|file| project_dir.join(&file.path))
+      .collect();
+    let context = HookContext {
+      component: component_name,
+      registry: locked.registry.as_deref(),
+      files: &files,
+    };
+    run_hook(
+      self.config.hooks.as_ref(),
+      HookEvent::PreRemove,
+      &context,
+      project_dir,
+    )?;
+
+    let roots = self.managed_dirs();
+    let mut removed = 0;
+    for path in files.iter().filter(|path| paths::exists(path)) {
+      remove_with_empty_parents(path, &roots)?;
+      self.record(|report| report.deleted.push(path.clone()));
+      removed += 1;
     }
 
-    println!(
-      "\n{} All selected components installed successfully!",
-      "✓".green()
+    // The CSS a style or theme merged into the Tailwind CSS file, which the
+    // lockfile doesn't list among its files
+    let css_path = project_dir.join(&self.config.tailwind.css);
+    if let Ok(css) = paths::read_to_string(&css_path) {
+      if let Some(stripped) = remove_region(&css, self.key_parts(component_name).1) {
+        paths::write_file(&css_path, stripped)?;
+        self.record(|report| report.record_file(css_path, true));
+      }
+    }
+
+    if self.config.barrel_exports == Some(true) {
+      let index_path = self.ui_components_dir().join(self.barrel_file_name());
+      if barrel::remove_export(&index_path, component_name)? {
+        self.record(|report| report.record_file(index_path, true));
+      }
+    }
+
+    Lockfile::update(&lockfile_path, |lockfile| {
+      lockfile.remove(component_name);
+    })?;
+
+    run_hook(
+      self.config.hooks.as_ref(),
+      HookEvent::PostRemove,
+      &context,
+      project_dir,
+    )?;
+
+    self.record(|report| report.removed.push(component_name.to_string()));
+    status!(
+      "{} Removed '{}' ({} {} deleted)",
+      "✓".green(),
+      component_name.cyan(),
+      removed,
+      if removed == 1 { "file" } else { "files" }
+    );
+    Ok(())
+  }
+
+  /// Stop managing a component: its files stay as they are, minus the
+  /// provenance headers, and it's dropped from the lockfile so `outdated`
+  /// and `update` leave it alone
+  pub fn eject_component(&self, component_name: &str) -> Result<()> {
+    let current_dir = self.project_dir.clone();
+    let lockfile_path = Lockfile::path_for(&current_dir);
+    let lockfile = Lockfile::load(&lockfile_path)?;
+    if lockfile.is_ejected(component_name) {
+      status!(
+        "{} '{}' is already ejected",
+        "!".yellow(),
+        component_name.cyan()
+      );
+      return Ok(());
+    }
+
+    let files = match lockfile.get(component_name) {
+      Some(locked) => locked.files.clone(),
+      None
+        if self
+          .get_installed_components()?
+          .iter()
+          .any(|name| name == component_name) =>
+      {
+        warning!(
+          "{} '{}' isn't in the lockfile; its files are left untouched",
+          "!".yellow(),
+          component_name
+        );
+        Vec::new()
+      }
+      None => return Err(InstallError::NotInstalled(component_name.to_string()).into()),
+    };
+
+    let mut stripped = 0;
+    for file in &files {
+      let path = current_dir.join(&file.path);
+      let Ok(content) = paths::read_to_string(&path) else {
+        continue;
+      };
+      let without_header = strip_header(&content);
+      if without_header != content {
+        paths::write_atomic(&path, without_header)?;
+        stripped += 1;
+      }
+    }
+
+    Lockfile::update(&lockfile_path, |lockfile| {
+      lockfile.eject(component_name);
+    })?;
+
+    status!(
+      "{} Ejected '{}' ({} files kept as the project's own)",
+      "✓".green(),
+      component_name.cyan(),
+      files.len()
+    );
+    if stripped > 0 {
+      detail!("  Removed the uiget header from {} files", stripped);
+    }
+    status!(
+      "  {} and {} skip it; {} puts it back under uiget",
+      "uiget outdated".cyan(),
+      "update".cyan(),
+      format!("uiget add {} --force", component_name).cyan()
     );
 
     Ok(())
   }
 
-  /// Install component files to the filesystem
-  fn install_component_files(
-    &self,
-    component: &Component,
-    context: &ComponentContext,
-    force: bool,
-  ) -> Result<()> {
-    for file in &component.files {
-      self.install_file(file, context, force)?;
+  /// Show installed components by where their files live on disk, with
+  /// files in uiget's directories that no component tracks, and tracked
+  /// files that were edited or deleted
+  pub fn show_tree(&self) -> Result<()> {
+    let project_dir = self.project_dir.clone();
+    let lockfile = Lockfile::load(&Lockfile::path_for(&project_dir))?;
+    let roots = self.managed_dirs();
+
+    // Directory -> file path relative to it -> what the file is
+    let mut groups: BTreeMap<PathBuf, BTreeMap<PathBuf, String>> = BTreeMap::new();
+    let mut add = |path: &Path, label: String| {
+      let root = roots
+        .iter()
+        .filter(|root| path.starts_with(root))
+        .max_by_key(|root| root.components().count())
+        .map(PathBuf::as_path)
+        .unwrap_or_else(|| paths::parent_dir(path));
+      let relative = path.strip_prefix(root).unwrap_or(path).to_path_buf();
+      groups
+        .entry(root.to_path_buf())
+        .or_default()
+        .insert(relative, label);
+    };
+
+    for (name, locked) in &lockfile.components {
+      for file in &locked.files {
+        let path = project_dir.join(&file.path);
+        let state = match paths::read_to_string(&path) {
+          Err(_) => format!(" {}", "missing".red()),
+          Ok(content) if hash_content(&content) != file.hash => {
+            format!(" {}", "modified".yellow())
+          }
+          Ok(_) => String::new(),
+        };
+        add(&path, format!("{}{}", name.cyan(), state));
+      }
+    }
+    for path in self.untracked_files(&lockfile, &project_dir)? {
+      add(&path, "untracked".yellow().to_string());
+    }
+
+    if groups.is_empty() {
+      status!("No components installed");
+      return Ok(());
+    }
+    for (root, files) in groups {
+      let root = root.strip_prefix(&project_dir).unwrap_or(&root);
+      status!("{}/", root.display().to_string().bold());
+      let mut open_dirs: Vec<&std::ffi::OsStr> = Vec::new();
+      for (relative, label) in &files {
+        let dirs: Vec<_> = relative.parent().into_iter().flat_map(Path::iter).collect();
+        let shared = open_dirs
+          .iter()
+          .zip(&dirs)
+          .take_while(|(open, dir)| open == dir)
+          .count();
+        for (depth, dir) in dirs.iter().enumerate().skip(shared) {
+          status!("{}{}/", "  ".repeat(depth + 1), dir.to_string_lossy());
+        }
+        open_dirs = dirs;
+        status!(
+          "{}{}  {}",
+          "  ".repeat(open_dirs.len() + 1),
+          relative.file_name().unwrap_or_default().to_string_lossy(),
+          label
+        );
+      }
     }
     Ok(())
   }
 
-  /// Install a single file
-  fn install_file(
-    &self,
-    file: &ComponentFile,
-    context: &ComponentContext,
-    force: bool,
-  ) -> Result<()> {
-    let target_path = self.resolve_file_path(&file.get_target_path(), context)?;
+  /// Delete files in uiget's directories that a component once installed
+  /// and no installed component claims any more, such as leftovers of
+  /// renamed or removed components. Files the lockfile never recorded are
+  /// left alone, since they may be the project's own. The user picks which
+  /// to delete. Without prompts they are only listed, and the run fails
+  /// unless `assume_yes` (`--yes`) allows deleting all of them. With
+  /// `dry_run`, they are only listed.
+  pub fn prune(&self, dry_run: bool, assume_yes: bool) -> Result<()> {
+    self.prune_in(&self.project_dir, dry_run, assume_yes)
+  }
 
-    // Check if file exists and force is not enabled
-    if target_path.exists() && !force {
-      return Err(anyhow!(
-        "File '{}' already exists. Use --force to overwrite",
-        target_path.display()
-      ));
+  fn prune_in(&self, project_dir: &Path, dry_run: bool, assume_yes: bool) -> Result<()> {
+    let lockfile = Lockfile::load(&Lockfile::path_for(project_dir))?;
+    let orphans = self.orphaned_files(&lockfile, project_dir);
+    if orphans.is_empty() {
+      status!("{} No orphaned files", "✓".green());
+      return Ok(());
     }
+    let labels: Vec<String> = orphans
+      .iter()
+      .map(|path| {
+        path
+          .strip_prefix(project_dir)
+          .unwrap_or(path)
+          .display()
+          .to_string()
+      })
+      .collect();
 
-    // Create directory if it doesn't exist
-    if let Some(parent) = target_path.parent() {
-      fs::create_dir_all(parent)?;
+    if dry_run || !self.interactive {
+      status!(
+        "{} {} {} no component claims any more:",
+        "→".blue(),
+        orphans.len(),
+        if orphans.len() == 1 { "file" } else { "files" }
+      );
+      for label in &labels {
+        status!("  {}", label);
+      }
+    }
+    if dry_run {
+      return Ok(());
     }
 
-    // Process placeholders in file content with component context
-    let processed_content = self.process_placeholders(&file.content, Some(context))?;
-
-    // Write processed file content
-    fs::write(&target_path, processed_content)?;
+    let selected: Vec<&PathBuf> = if self.interactive {
+      let selections = MultiSelect::with_theme(&ColorfulTheme::default())
+        .with_prompt("Select orphaned files to delete:")
+        .items(&labels)
+        .interact()?;
+      selections.into_iter().map(|i| &orphans[i]).collect()
+    } else if assume_yes {
+      orphans.iter().collect()
+    } else {
+      return Err(
+        InstallError::NonInteractive(
+          "Deleting orphaned files needs confirmation; pass --yes to delete them".to_string(),
+        )
+        .into(),
+      );
+    };
 
-    println!(
-      "  {} {}",
+    let roots = self.managed_dirs();
+    for path in &selected {
+      remove_with_empty_parents(path, &roots)?;
+    }
+    Lockfile::update(&Lockfile::path_for(project_dir), |lockfile| {
+      for path in &selected {
+        lockfile
+          .dropped
+          .remove(&LockedFile::new(project_dir, path, "").path);
+      }
+    })?;
+    status!(
+      "{} Deleted {} orphaned {}",
       "✓".green(),
-      target_path.display().to_string().dimmed()
+      selected.len(),
+      if selected.len() == 1 { "file" } else { "files" }
     );
-
     Ok(())
   }
 
-  /// Resolve file path using aliases and component target paths
-  fn resolve_file_path(&self, target: &str, context: &ComponentContext) -> Result<PathBuf> {
-    // The target format is like "button/button.svelte" or "button/index.ts"
-    // We need to place this in the appropriate directory based on component type
-
-    let alias_path = self.get_alias_for_component_type(context.component_type.as_deref());
-
-    // First try to resolve using TypeScript paths if available
-    let resolved_alias_path = if let Some(ref ts_paths) = self.typescript_paths {
-      self.resolve_path_with_typescript(alias_path, &ts_paths.paths)
-    } else {
-      // Fallback to manual resolution
-      self.resolve_path_manually(alias_path)
-    };
-
-    // Handle path normalization for different component types
-    let normalized_target = if context.component_type.as_deref() == Some("registry:ui")
-      && target.starts_with("ui/")
-      && resolved_alias_path.ends_with("/ui")
-    {
-      // Remove "ui/" prefix from target to avoid duplication for UI components
-      target.strip_prefix("ui/").unwrap_or(target)
-    } else {
-      target
-    };
-
-    let resolved_path = format!("{}/{}", resolved_alias_path, normalized_target);
+  /// Directories uiget installs into and owns: the UI directory, and the
+  /// hooks directory when one is configured
+  fn managed_dirs(&self) -> Vec<PathBuf> {
+    let mut dirs = vec![self.ui_components_dir()];
+    if let Some(hooks) = &self.config.aliases.hooks {
+      let hooks_dir = self.alias_dir(hooks);
+      if !dirs.contains(&hooks_dir) {
+        dirs.push(hooks_dir);
+      }
+    }
+    dirs
+  }
 
-    // Convert to absolute path
-    let current_dir = std::env::current_dir()?;
-    let path = current_dir.join(&resolved_path);
+  /// Files in the managed directories that no component in `lockfile`
+  /// tracks. Only directories holding files the lockfile records are
+  /// searched, so hand-written files elsewhere and components installed
+  /// before the lockfile recorded files are left alone. The barrel uiget
+  /// maintains and the files of ejected components, which the project owns,
+  /// aren't counted.
+  fn untracked_files(&self, lockfile: &Lockfile, project_dir: &Path) -> Result<Vec<PathBuf>> {
+    let recorded_dirs: HashSet<PathBuf> = lockfile
+      .components
+      .values()
+      .flat_map(|component| &component.files)
+      .filter_map(|file| project_dir.join(&file.path).parent().map(Path::to_path_buf))
+      .collect();
+    let mut untracked = Vec::new();
+    for root in self.managed_dirs() {
+      if !root.is_dir() {
+        continue;
+      }
+      for path in collect_local_files(&root)? {
+        let relative = path.strip_prefix(&root).unwrap_or(&path);
+        let top_level = relative
+          .iter()
+          .next()
+          .and_then(|name| Path::new(name).file_stem())
+          .map(|name| name.to_string_lossy().to_string())
+          .unwrap_or_default();
+        let barrel = self.config.barrel_exports == Some(true)
+          && relative.parent() == Some(Path::new(""))
+          && top_level == "index";
+        if lockfile.find_file(project_dir, &path).is_none()
+          && recorded_dirs.iter().any(|dir| path.starts_with(dir))
+          && !lockfile.is_ejected(&top_level)
+          && !barrel
+        {
+          untracked.push(path);
+        }
+      }
+    }
+    Ok(untracked)
+  }
 
-    Ok(path)
+  /// Files in the managed directories the lockfile recorded as dropped
+  /// that are still on disk and that no component claims again
+  fn orphaned_files(&self, lockfile: &Lockfile, project_dir: &Path) -> Vec<PathBuf> {
+    let roots = self.managed_dirs();
+    lockfile
+      .dropped
+      .iter()
+      .map(|path| project_dir.join(path))
+      .filter(|path| {
+        path.is_file()
+          && roots.iter().any(|root| path.starts_with(root))
+          && lockfile.is_dropped(project_dir, path)
+      })
+      .collect()
   }
 
-  /// Resolve path using TypeScript path mappings
-  fn resolve_path_with_typescript(
+  /// Start managing files already in the project: match `path` to a
+  /// registry component by name (`name`, or the file or directory name) or,
+  /// failing that, by content, record the files in the lockfile as they are,
+  /// and report how far they have drifted from the registry's version
+  pub async fn adopt_component(
     &self,
-    ui_path: &str,
-    ts_paths: &HashMap<String, String>,
-  ) -> String {
-    // Try to find a matching TypeScript path mapping
-    for (alias, resolved_path) in ts_paths {
-      if ui_path.starts_with(alias) {
-        // Replace the alias with the resolved path
-        let remaining_path = ui_path.strip_prefix(alias).unwrap_or("");
-        let remaining_path = remaining_path.trim_start_matches('/');
+    path: &Path,
+    name: Option<&str>,
+    registry_namespace: Option<&str>,
+  ) -> Result<()> {
+    let current_dir = self.project_dir.clone();
+    let local_files = collect_local_files(&current_dir.join(path))?;
+    if local_files.is_empty() {
+      return Err(anyhow!("No files to adopt in '{}'", path.display()));
+    }
+    let name = match name {
+      Some(name) => name.to_string(),
+      None => path
+        .file_stem()
+        .map(|name| name.to_string_lossy().to_string())
+        .ok_or_else(|| {
+          anyhow!(
+            "Can't tell the component name from '{}'; pass --as",
+            path.display()
+          )
+        })?,
+    };
 
-        if remaining_path.is_empty() {
-          return resolved_path.clone();
-        } else {
-          return format!("{}/{}", resolved_path, remaining_path);
+    let fetched = match registry_namespace {
+      Some(namespace) => {
+        self
+          .registry_manager
+          .fetch_component(namespace, &name)
+          .await
+      }
+      None => self.registry_manager.fetch_component_auto(&name).await,
+    };
+    let component = match fetched {
+      Ok(component) => component,
+      Err(e)
+        if matches!(
+          e.downcast_ref::<RegistryError>(),
+          Some(RegistryError::ComponentNotFound(_))
+        ) =>
+      {
+        match self
+          .find_similar_component(&name, &local_files, registry_namespace)
+          .await
+        {
+          Some(component) => {
+            status!(
+              "{} No component named '{}'; its files look like '{}'",
+              "!".yellow(),
+              name,
+              component.name.cyan()
+            );
+            component
+          }
+          None => return Err(e),
         }
       }
-    }
+      Err(e) => return Err(e),
+    };
 
-    // If no TypeScript mapping found, fall back to manual resolution
-    self.resolve_path_manually(ui_path)
-  }
+    let context = self.create_component_context(&component);
+    self.check_conditions(&component, &context)?;
+    status!(
+      "{} Adopting {} as '{}' from '{}'",
+      "→".blue(),
+      path.display().to_string().cyan(),
+      component.name.cyan(),
+      component.registry.as_deref().unwrap_or("-")
+    );
 
-  /// Resolve path manually (fallback method)
-  fn resolve_path_manually(&self, ui_path: &str) -> String {
-    // Replace $lib placeholder if present in ui_path
-    if ui_path.contains("$lib") {
-      if let Some(lib_path) = &self.config.aliases.lib {
-        return ui_path.replace("$lib", lib_path);
+    let pairs = self.pair_files(&component, &context, &local_files)?;
+    let mut adopted = Vec::new();
+    let mut drift = DiffStats::default();
+    let mut matching = 0;
+    let mut elsewhere = false;
+    for (file, target, local) in &pairs {
+      let registry_content = self.installed_content(file, &context)?;
+      let Some(local) = local else {
+        drift.removed += registry_content.lines().count();
+        status!(
+          "  {} {} {}",
+          "-".red(),
+          target.display(),
+          "missing".dimmed()
+        );
+        continue;
+      };
+      let stats = DiffStats::of(&registry_content, &paths::read_to_string(local)?);
+      drift.same += stats.same;
+      drift.added += stats.added;
+      drift.removed += stats.removed;
+      if stats.added + stats.removed == 0 {
+        matching += 1;
+        status!("  {} {}", "✓".green(), local.display());
       } else {
-        return ui_path.replace("$lib", "src/lib");
+        status!(
+          "  {} {} {}",
+          "~".yellow(),
+          local.display(),
+          format!("+{} -{}", stats.added, stats.removed).dimmed()
+        );
       }
+      elsewhere |= local != target;
+      adopted.push(local.clone());
+    }
+    for local in local_files.iter().filter(|local| !adopted.contains(local)) {
+      status!(
+        "  {} {} {}",
+        "?".dimmed(),
+        local.display(),
+        "not part of the component".dimmed()
+      );
     }
 
-    // When there's no tsconfig.json, use the aliases exactly as configured
-    // Don't override or modify the paths - respect the user's configuration
-    ui_path.to_string()
+    self.record_installed_component(&component, &adopted, &current_dir)?;
+    status!(
+      "{} Adopted '{}': {} of {} files match the registry ({:.0}% similar)",
+      "✓".green(),
+      component.name.cyan(),
+      matching,
+      pairs.len(),
+      drift.similarity() * 100.0
+    );
+    if elsewhere {
+      let directory = if path.is_dir() {
+        path
+      } else {
+        path.parent().unwrap_or(path)
+      };
+      status!(
+        "  {} Its files aren't where uiget installs '{}'; add {} to uiget.json's \"overrides\" so \
+         updates land here",
+        "💡".blue(),
+        component.name,
+        format!("\"{}\": \"{}\"", component.name, directory.display()).cyan()
+      );
+    }
+
+    Ok(())
   }
 
-  /// Remove a component
-  pub fn remove_component(&self, component_name: &str) -> Result<()> {
-    println!(
-      "{} Removing component '{}'...",
-      "→".red(),
-      component_name.cyan()
-    );
+  /// Pair the installable files of `component` with local files: the file
+  /// at its target path, or else one with the same file name
+  fn pair_files<'c>(
+    &self,
+    component: &'c Component,
+    context: &ComponentContext,
+    local_files: &[PathBuf],
+  ) -> Result<Vec<(&'c ComponentFile, PathBuf, Option<PathBuf>)>> {
+    let current_dir = self.project_dir.clone();
+    self
+      .installable_files(&component.files, context)
+      .filter(|file| !self.injects_css(file, context))
+      .map(|file| {
+        let target = current_dir.join(self.target_path(file, context)?);
+        let local = local_files
+          .iter()
+          .find(|local| **local == target)
+          .or_else(|| {
+            local_files
+              .iter()
+              .find(|local| local.file_name() == target.file_name())
+          })
+          .cloned();
+        Ok((file, target, local))
+      })
+      .collect()
+  }
 
-    // This is a simplified implementation
-    // In a real implementation, you'd need to track installed components
-    // and their files to remove them properly
+  /// The registry component whose files are closest to `local_files`,
+  /// among the few whose names are closest to `name`
+  async fn find_similar_component(
+    &self,
+    name: &str,
+    local_files: &[PathBuf],
+    registry_namespace: Option<&str>,
+  ) -> Option<Component> {
+    let namespaces: Vec<String> = match registry_namespace {
+      Some(namespace) => vec![namespace.to_string()],
+      None => self
+        .registry_manager
+        .namespaces()
+        .into_iter()
+        .cloned()
+        .collect(),
+    };
 
-    println!(
-      "{} Component removal not fully implemented yet",
-      "!".yellow()
-    );
-    println!("  You'll need to manually remove the component files");
+    let mut candidates = Vec::new();
+    for namespace in namespaces {
+      let Some(registry) = self.registry_manager.get_registry(&namespace) else {
+        continue;
+      };
+      let Ok(index) = registry.fetch_index().await else {
+        continue;
+      };
+      for info in index.as_slice() {
+        candidates.push((
+          name_similarity(name, &info.name),
+          namespace.clone(),
+          info.name.clone(),
+        ));
+      }
+    }
+    candidates.sort_by(|a, b| b.0.total_cmp(&a.0));
+    candidates.truncate(ADOPT_CANDIDATES);
 
-    Ok(())
+    let mut best: Option<(f64, Component)> = None;
+    for (_, namespace, candidate) in candidates {
+      let Ok(component) = self
+        .registry_manager
+        .fetch_component(&namespace, &candidate)
+        .await
+      else {
+        continue;
+      };
+      let context = self.create_component_context(&component);
+      let Ok(pairs) = self.pair_files(&component, &context, local_files) else {
+        continue;
+      };
+      let mut stats = DiffStats::default();
+      for (file, _, local) in pairs {
+        let registry_content = self.installed_content(file, &context).unwrap_or_default();
+        let local_content = local
+          .and_then(|local| paths::read_to_string(&local).ok())
+          .unwrap_or_default();
+        let pair = DiffStats::of(&registry_content, &local_content);
+        stats.same += pair.same;
+        stats.added += pair.added;
+        stats.removed += pair.removed;
+      }
+      let score = stats.similarity();
+      if score >= ADOPT_MIN_SIMILARITY && best.as_ref().is_none_or(|(best, _)| score > *best) {
+        best = Some((score, component));
+      }
+    }
+
+    best.map(|(_, component)| component)
   }
 
   /// Search components across registries
@@ -735,14 +3582,14 @@ impl ComponentInstaller {
         let results = registry.search_components(query).await?;
         self.print_search_results_async(namespace, &results).await;
       } else {
-        return Err(anyhow!("Registry '{}' not found", namespace));
+        return Err(RegistryError::RegistryNotFound(namespace.to_string()).into());
       }
     } else {
       // Search in all registries
       let results = self.registry_manager.search_all(query).await?;
 
       if results.is_empty() {
-        println!(
+        status!(
           "{} No components found matching '{}'",
           "!".yellow(),
           query.cyan()
@@ -773,7 +3620,7 @@ impl ComponentInstaller {
     // Get list of installed components for this instance
     let installed_components = self.get_installed_components().unwrap_or_default();
 
-    println!("\n{} Registry: {}", "📦".blue(), namespace.cyan());
+    status!("\n{} Registry: {}", "📦".blue(), namespace.cyan());
 
     for component in components {
       let is_installed = installed_components.contains(&component.name);
@@ -798,7 +3645,13 @@ impl ComponentInstaller {
         )
       };
 
-      println!("  {} {} {}", "→".blue(), status_icon, name_display);
+      status!(
+        "  {} {} {}{}",
+        "→".blue(),
+        status_icon,
+        name_display,
+        deprecation_label(component)
+      );
 
       if let Some(comp_type) = &component.component_type {
         let type_display = match comp_type.as_str() {
@@ -808,14 +3661,14 @@ impl ComponentInstaller {
           "registry:lib" => "Library".purple(),
           _ => comp_type.dimmed(),
         };
-        println!("    Type: {}", type_display);
+        status!("    Type: {}", type_display);
       }
 
-      println!("    Status: {}", status_text);
+      status!("    Status: {}", status_text);
 
       if let Some(deps) = &component.registry_dependencies {
         if !deps.is_empty() {
-          println!("    Dependencies: {}", deps.join(", ").dimmed());
+          status!("    Dependencies: {}", deps.join(", ").dimmed());
         }
       }
     }
@@ -831,7 +3684,7 @@ impl ComponentInstaller {
     // Get list of installed components for this instance
     let installed_components = self.get_installed_components().unwrap_or_default();
 
-    println!("\n{} Registry: {}", "📦".blue(), namespace.cyan());
+    status!("\n{} Registry: {}", "📦".blue(), namespace.cyan());
 
     for component in components {
       let is_installed = installed_components.contains(&component.name);
@@ -846,7 +3699,13 @@ impl ComponentInstaller {
         component.name.cyan()
       };
 
-      println!("  {} {} {}", "→".blue(), status_icon, name_display);
+      status!(
+        "  {} {} {}{}",
+        "→".blue(),
+        status_icon,
+        name_display,
+        deprecation_label(component)
+      );
 
       if let Some(comp_type) = &component.component_type {
         let type_display = match comp_type.as_str() {
@@ -856,33 +3715,37 @@ impl ComponentInstaller {
           "registry:lib" => "Library".purple(),
           _ => comp_type.dimmed(),
         };
-        println!("    Type: {}", type_display);
+        status!("    Type: {}", type_display);
       }
 
       if is_installed {
-        println!("    Status: {}", "Installed".green());
+        status!("    Status: {}", "Installed".green());
       }
 
       if let Some(deps) = &component.registry_dependencies {
         if !deps.is_empty() {
-          println!("    Dependencies: {}", deps.join(", ").dimmed());
+          status!("    Dependencies: {}", deps.join(", ").dimmed());
         }
       }
     }
   }
 
   /// List components from a registry
-  pub async fn list_components(&self, registry_namespace: Option<&str>) -> Result<()> {
+  pub async fn list_components(
+    &self,
+    registry_namespace: Option<&str>,
+    category: Option<&str>,
+  ) -> Result<()> {
     if let Some(namespace) = registry_namespace {
       // List from specific registry
       if let Some(registry) = self.registry_manager.get_registry(namespace) {
         let index = registry.fetch_index().await?;
-        let components: Vec<_> = index.as_slice().into_iter().cloned().collect();
+        let components = filter_by_group(index.as_slice(), category);
         self
           .print_component_list_async(namespace, &components)
           .await;
       } else {
-        return Err(anyhow!("Registry '{}' not found", namespace));
+        return Err(RegistryError::RegistryNotFound(namespace.to_string()).into());
       }
     } else {
       // List from all registries
@@ -890,15 +3753,16 @@ impl ComponentInstaller {
         if let Some(registry) = self.registry_manager.get_registry(namespace) {
           match registry.fetch_index().await {
             Ok(index) => {
-              let components: Vec<_> = index.as_slice().into_iter().cloned().collect();
+              let components = filter_by_group(index.as_slice(), category);
               self
                 .print_component_list_async(namespace, &components)
                 .await;
             }
             Err(e) => {
-              eprintln!(
+              warning!(
                 "Warning: Failed to fetch components from '{}': {}",
-                namespace, e
+                namespace,
+                e
               );
             }
           }
@@ -922,50 +3786,36 @@ impl ComponentInstaller {
     // Get list of installed components for this instance
     let installed_components = self.get_installed_components().unwrap_or_default();
 
-    println!(
+    status!(
       "\n{} Registry: {} ({} components)",
       "📦".blue(),
       namespace.cyan(),
       components.len().to_string().yellow()
     );
 
-    // Group by type
-    let mut by_type: std::collections::HashMap<String, Vec<&crate::registry::ComponentInfo>> =
-      std::collections::HashMap::new();
-
-    for component in components {
-      let comp_type = component
-        .component_type
-        .as_deref()
-        .unwrap_or("other")
-        .to_string();
-      by_type.entry(comp_type).or_default().push(component);
-    }
-
-    // Display by type
-    for (comp_type, comps) in by_type {
-      let type_display = match comp_type.as_str() {
-        "registry:ui" => "UI Components".green(),
-        "registry:block" => "Blocks".blue(),
-        "registry:hook" => "Hooks".yellow(),
-        "registry:lib" => "Libraries".purple(),
-        "registry:style" => "Styles".cyan(),
-        _ => "Other".dimmed(),
-      };
+    // Check the installed components of this registry concurrently
+    let listed_installed: Vec<String> = components
+      .iter()
+      .filter(|component| installed_components.contains(&component.name))
+      .map(|component| component.name.clone())
+      .collect();
+    let outdated_components: std::collections::HashSet<String> = self
+      .check_outdated_components(&listed_installed, Some(namespace))
+      .await
+      .unwrap_or_default()
+      .into_iter()
+      .filter_map(|(name, is_outdated)| is_outdated.then_some(name))
+      .collect();
 
-      println!("  {}", type_display);
+    // Display by the registry's categories, or by type
+    for group in group_components(components) {
+      status!("  {}", group.styled_title());
 
-      for component in comps {
+      for component in group.components {
         let is_installed = installed_components.contains(&component.name);
 
         let (status_icon, name_display) = if is_installed {
-          // Check if component is outdated
-          let is_outdated = self
-            .is_component_outdated(&component.name, Some(namespace))
-            .await
-            .unwrap_or(false);
-
-          if is_outdated {
+          if outdated_components.contains(&component.name) {
             ("⚠".yellow(), component.name.yellow())
           } else {
             ("✓".green(), component.name.green())
@@ -974,7 +3824,13 @@ impl ComponentInstaller {
           (" ".normal(), component.name.normal())
         };
 
-        println!("    {} {} {}", "→".dimmed(), status_icon, name_display);
+        status!(
+          "    {} {} {}{}",
+          "→".dimmed(),
+          status_icon,
+          name_display,
+          deprecation_label(component)
+        );
       }
     }
   }
@@ -989,40 +3845,18 @@ impl ComponentInstaller {
     // Get list of installed components for this instance
     let installed_components = self.get_installed_components().unwrap_or_default();
 
-    println!(
+    status!(
       "\n{} Registry: {} ({} components)",
       "📦".blue(),
       namespace.cyan(),
       components.len().to_string().yellow()
     );
 
-    // Group by type
-    let mut by_type: std::collections::HashMap<String, Vec<&crate::registry::ComponentInfo>> =
-      std::collections::HashMap::new();
+    // Display by the registry's categories, or by type
+    for group in group_components(components) {
+      status!("  {}", group.styled_title());
 
-    for component in components {
-      let comp_type = component
-        .component_type
-        .as_deref()
-        .unwrap_or("other")
-        .to_string();
-      by_type.entry(comp_type).or_default().push(component);
-    }
-
-    // Display by type
-    for (comp_type, comps) in by_type {
-      let type_display = match comp_type.as_str() {
-        "registry:ui" => "UI Components".green(),
-        "registry:block" => "Blocks".blue(),
-        "registry:hook" => "Hooks".yellow(),
-        "registry:lib" => "Libraries".purple(),
-        "registry:style" => "Styles".cyan(),
-        _ => "Other".dimmed(),
-      };
-
-      println!("  {}", type_display);
-
-      for component in comps {
+      for component in group.components {
         let is_installed = installed_components.contains(&component.name);
         let status_icon = if is_installed {
           "✓".green()
@@ -1035,7 +3869,13 @@ impl ComponentInstaller {
           component.name.normal()
         };
 
-        println!("    {} {} {}", "→".dimmed(), status_icon, name_display);
+        status!(
+          "    {} {} {}{}",
+          "→".dimmed(),
+          status_icon,
+          name_display,
+          deprecation_label(component)
+        );
       }
     }
   }
@@ -1045,78 +3885,284 @@ impl ComponentInstaller {
     &self,
     component_name: &str,
     registry_namespace: Option<&str>,
+    show_files: bool,
   ) -> Result<()> {
-    let component = if let Some(namespace) = registry_namespace {
-      self
-        .registry_manager
-        .fetch_component(namespace, component_name)
-        .await?
-    } else {
-      self
-        .registry_manager
-        .fetch_component_auto(component_name)
-        .await?
-    };
+    let component = self
+      .fetch_component(component_name, registry_namespace)
+      .await?;
+
+    status!("\n{} Component: {}", "📦".blue(), component.name.cyan());
+
+    if let Some(description) = &component.description {
+      status!("{}", description.dimmed());
+    }
 
-    println!("\n{} Component: {}", "📦".blue(), component.name.cyan());
+    if let Some(deprecation) = self.deprecation_of(&component).await {
+      status!(
+        "{} {}",
+        "⚠ Deprecated".yellow(),
+        deprecation
+          .replaced_by
+          .map(|replacement| format!("(replaced by '{}')", replacement))
+          .unwrap_or_default()
+          .dimmed()
+      );
+    }
 
     if let Some(comp_type) = &component.component_type {
-      println!("Type: {}", comp_type.yellow());
+      status!("Type: {}", comp_type.yellow());
+    }
+
+    if let Some(version) = &component.version {
+      status!("Version: {}", version.yellow());
     }
 
     if let Some(registry) = &component.registry {
-      println!("Registry: {}", registry.yellow());
+      status!("Registry: {}", registry.yellow());
+    }
+
+    if let Some(license) = &component.license {
+      status!("License: {}", license.yellow());
+    }
+
+    if let Some(tags) = &component.tags {
+      if !tags.is_empty() {
+        status!("Tags: {}", tags.join(", ").cyan());
+      }
+    }
+
+    if let Some(docs) = &component.docs {
+      if is_url(docs) {
+        status!("Docs: {}", docs.blue());
+      } else {
+        status!(
+          "Docs: {}",
+          "available (run 'uiget docs <component>')".dimmed()
+        );
+      }
+    }
+
+    if let Some(links) = component.links.as_ref().filter(|links| !links.is_empty()) {
+      status!("Links:");
+      for (name, url) in links {
+        status!("  - {}: {}", name, url.blue());
+      }
+    }
+
+    if let Some(dependencies) = &component.registry_dependencies {
+      if !dependencies.is_empty() {
+        status!("Registry Dependencies:");
+        for dep in dependencies {
+          status!("  - {}", dep.cyan());
+        }
+      }
+    }
+
+    for (label, dependencies) in [
+      ("Dependencies", &component.dependencies),
+      ("Dev Dependencies", &component.dev_dependencies),
+    ] {
+      if let Some(dependencies) = dependencies {
+        if !dependencies.is_empty() {
+          status!("{}:", label);
+          for dep in dependencies {
+            let (name, version) = parse_dependency_spec(dep);
+            status!(
+              "  - {} {}",
+              name.cyan(),
+              version.unwrap_or("latest").dimmed()
+            );
+          }
+        }
+      }
+    }
+
+    status!("Files:");
+    for file in &component.files {
+      let condition = file
+        .condition
+        .as_ref()
+        .map(|condition| format!(" if {}", condition))
+        .unwrap_or_default();
+      status!(
+        "  - {} {}{}",
+        file.get_target_path().cyan(),
+        format!(
+          "({} lines, {:.1} KB)",
+          file.content.lines().count(),
+          file.content.len() as f64 / 1024.0
+        )
+        .dimmed(),
+        condition.yellow()
+      );
+    }
+
+    if show_files {
+      for file in &component.files {
+        let target = file.get_target_path();
+        status!("\n{} {}", "──".dimmed(), target.cyan());
+        status!("{}", highlight_code(&file.content, &target));
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Print the usage example of a component, highlighted for its language
+  pub async fn show_component_usage(
+    &self,
+    component_name: &str,
+    registry_namespace: Option<&str>,
+  ) -> Result<()> {
+    let component = self
+      .fetch_component(component_name, registry_namespace)
+      .await?;
+
+    match &component.usage {
+      Some(usage) => status!(
+        "{}",
+        highlight_code(usage.trim_end(), &usage_file_name(&component))
+      ),
+      None => status!(
+        "{} '{}' has no usage example",
+        "!".yellow(),
+        component.name.cyan()
+      ),
     }
 
-    if let Some(dependencies) = &component.registry_dependencies {
-      if !dependencies.is_empty() {
-        println!("Registry Dependencies:");
-        for dep in dependencies {
-          println!("  - {}", dep.cyan());
-        }
+    Ok(())
+  }
+
+  /// Open the component's link named `link` (e.g. "figma") in the browser
+  pub async fn open_component_link(
+    &self,
+    component_name: &str,
+    registry_namespace: Option<&str>,
+    link: &str,
+  ) -> Result<()> {
+    let component = self
+      .fetch_component(component_name, registry_namespace)
+      .await?;
+    let links = component.links.unwrap_or_default();
+    let Some(url) = links
+      .iter()
+      .find(|(name, _)| name.eq_ignore_ascii_case(link))
+      .map(|(_, url)| url)
+    else {
+      let available = links.keys().cloned().collect::<Vec<_>>();
+      bail!(
+        "'{}' has no '{}' link{}",
+        component.name,
+        link,
+        if available.is_empty() {
+          String::new()
+        } else {
+          format!(" (available: {})", available.join(", "))
+        }
+      );
+    };
+
+    status!("{} Opening {}...", "→".blue(), url.blue());
+    open_url(url)
+  }
+
+  /// Show the documentation of a component, rendering inline markdown in the
+  /// terminal or printing/opening a documentation URL
+  pub async fn show_component_docs(
+    &self,
+    component_name: &str,
+    registry_namespace: Option<&str>,
+    open: bool,
+  ) -> Result<()> {
+    let component = self
+      .fetch_component(component_name, registry_namespace)
+      .await?;
+
+    // Fall back to the index entry when the component itself has no docs
+    let docs = match component.docs.clone() {
+      Some(docs) => Some(docs),
+      None => match component
+        .registry
+        .as_deref()
+        .and_then(|namespace| self.registry_manager.get_registry(namespace))
+      {
+        Some(registry) => registry
+          .fetch_index()
+          .await
+          .ok()
+          .and_then(|index| {
+            index
+              .to_vec()
+              .into_iter()
+              .find(|info| info.name == component.name)
+          })
+          .and_then(|info| info.docs),
+        None => None,
+      },
+    };
+
+    let Some(docs) = docs else {
+      status!(
+        "{} No documentation available for '{}'",
+        "!".yellow(),
+        component.name.cyan()
+      );
+      return Ok(());
+    };
+
+    if is_url(&docs) {
+      if open {
+        status!("{} Opening {}...", "→".blue(), docs.blue());
+        open_url(&docs)?;
+      } else {
+        status!("{} Documentation: {}", "📖".blue(), docs.blue());
+        status!(
+          "  Run {} to open it in your browser",
+          format!("uiget docs {} --open", component.name).cyan()
+        );
       }
-    }
-
-    if let Some(dependencies) = &component.dev_dependencies {
-      if !dependencies.is_empty() {
-        println!("Dev Dependencies:");
-        for dep in dependencies {
-          println!("  - {}", dep.cyan());
-        }
+    } else {
+      if open {
+        status!(
+          "{} '{}' ships inline documentation, nothing to open",
+          "!".yellow(),
+          component.name.cyan()
+        );
       }
-    }
-
-    // Show registry dependencies from component info if available
-    // (This would need to be fetched from the index, but for now we'll use
-    // component.dependencies)
-
-    println!("Files:");
-    for file in &component.files {
-      println!("  - {}", file.get_target_path().cyan());
+      status!("{}", render_markdown(&docs));
     }
 
     Ok(())
   }
 
-  /// Check if a component is installed locally
-  pub fn is_component_installed(&self, component_name: &str) -> bool {
-    // Get the UI directory path where components are installed
+  /// Absolute path of the directory UI components are installed into
+  fn ui_components_dir(&self) -> PathBuf {
     let ui_path = self
       .config
       .aliases
       .ui
       .as_ref()
       .unwrap_or(&self.config.aliases.components);
+    self.alias_dir(ui_path)
+  }
 
+  /// Directory an alias such as `$lib/hooks` points to
+  fn alias_dir(&self, alias: &str) -> PathBuf {
     // Use the same resolution logic as resolve_file_path
-    let resolved_ui_path = if let Some(ref ts_paths) = self.typescript_paths {
-      self.resolve_path_with_typescript(ui_path, &ts_paths.paths)
+    let resolved_path = if let Some(ref ts_paths) = self.typescript_paths {
+      self.resolve_path_with_typescript(alias, &ts_paths.paths)
     } else {
-      self.resolve_path_manually(ui_path)
+      self.resolve_path_manually(alias)
     };
 
-    let current_dir = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
-    let components_dir = current_dir.join(&resolved_ui_path);
+    let current_dir = self.project_dir.clone();
+    current_dir.join(resolved_path)
+  }
+
+  /// Check if a component is installed locally
+  pub fn is_component_installed(&self, component_name: &str) -> bool {
+    // Get the UI directory path where components are installed
+    let components_dir = self.ui_components_dir();
 
     // Check if component directory exists (for @svelte registry style)
     let component_dir_path = components_dir.join(component_name);
@@ -1134,62 +4180,39 @@ impl ComponentInstaller {
       }
     }
 
-    false
+    // Styles and themes have nothing in the UI directory
+    Lockfile::load(&Lockfile::path_for(&self.project_dir))
+      .is_ok_and(|lockfile| lockfile.get(component_name).is_some())
   }
 
-  /// Get list of locally installed components
+  /// Get list of locally installed components. Components in a namespace
+  /// directory are listed by their lockfile key, e.g. `acme/button`.
   pub fn get_installed_components(&self) -> Result<Vec<String>> {
-    let ui_path = self
-      .config
-      .aliases
-      .ui
-      .as_ref()
-      .unwrap_or(&self.config.aliases.components);
+    let components_dir = self.ui_components_dir();
 
-    // Use the same resolution logic as resolve_file_path
-    let resolved_ui_path = if let Some(ref ts_paths) = self.typescript_paths {
-      self.resolve_path_with_typescript(ui_path, &ts_paths.paths)
+    // Directories that `namespaceDirs` creates for other registries
+    let namespace_dirs: HashSet<&str> = if self.config.namespace_dirs == Some(true) {
+      self
+        .config
+        .registries
+        .keys()
+        .map(|namespace| namespace.trim_start_matches('@'))
+        .collect()
     } else {
-      self.resolve_path_manually(ui_path)
+      HashSet::new()
     };
 
-    let current_dir = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
-    let components_dir = current_dir.join(&resolved_ui_path);
-
     let mut installed = Vec::new();
-
-    if components_dir.exists() {
-      for entry in fs::read_dir(&components_dir)? {
-        let entry = entry?;
-        let path = entry.path();
-
-        if path.is_dir() {
-          // Handle directory-based components (like @svelte registry)
-          if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-            // Skip hidden directories and common non-component directories
-            if !name.starts_with('.') && name != "index.ts" && name != "index.js" {
-              installed.push(name.to_string());
-            }
-          }
-        } else if path.is_file() {
-          // Handle file-based components (like @default registry)
-          if let Some(file_name) = path.file_name().and_then(|n| n.to_str()) {
-            // Skip hidden files and common non-component files
-            if !file_name.starts_with('.')
-              && !file_name.ends_with(".d.ts")
-              && !file_name.ends_with(".map")
-              && file_name != "index.ts"
-              && file_name != "index.js"
-            {
-              // Extract component name from file name (remove extension)
-              if let Some(component_name) = file_name.split('.').next() {
-                if !component_name.is_empty() {
-                  installed.push(component_name.to_string());
-                }
-              }
-            }
-          }
-        }
+    for (name, is_dir) in component_entries(&components_dir)? {
+      if is_dir && namespace_dirs.contains(name.as_str()) {
+        let namespaced = component_entries(&components_dir.join(&name))?;
+        installed.extend(
+          namespaced
+            .into_iter()
+            .map(|(component, _)| format!("{}/{}", name, component)),
+        );
+      } else {
+        installed.push(name);
       }
     }
 
@@ -1209,64 +4232,93 @@ impl ComponentInstaller {
       return Ok(false); // Not installed, so not outdated
     }
 
-    // Fetch the latest version from registry
-    let registry_component = if let Some(namespace) = registry_namespace {
-      match self
-        .registry_manager
-        .fetch_component(namespace, component_name)
-        .await
-      {
-        Ok(comp) => comp,
-        Err(_) => return Ok(false), // Can't fetch, assume not outdated
-      }
-    } else {
-      match self
-        .registry_manager
-        .fetch_component_auto(component_name)
-        .await
-      {
-        Ok(comp) => comp,
-        Err(_) => return Ok(false), // Can't fetch, assume not outdated
-      }
-    };
-
-    // Create component context for proper path resolution
-    let component_context = self.create_component_context(&registry_component);
+    // A component pinned to a version tag is outdated once there's a newer
+    // tag; its files at the pinned tag don't change
+    if let Some(newer) = self.newer_tag(component_name, registry_namespace).await {
+      return Ok(newer.is_some());
+    }
 
-    // Compare local files with registry files
-    for registry_file in &registry_component.files {
-      let local_path =
-        self.resolve_file_path(&registry_file.get_target_path(), &component_context)?;
+    // Fetch the latest version from the registry it was installed from
+    let registry_component = match self
+      .fetch_component(component_name, registry_namespace)
+      .await
+    {
+      Ok(comp) => comp,
+      Err(_) => return Ok(false), // Can't fetch, assume not outdated
+    };
 
-      if !local_path.exists() {
+    // Compare the project's files with what installing the registry version
+    // would write. A file that can't be installed, e.g. one whose types
+    // can't be stripped, was never installed that way.
+    let context = self.create_component_context(&registry_component);
+    let Ok(files) = self.compare_with_project(&registry_component, &context) else {
+      return Ok(true);
+    };
+    for (_, local, installed) in files {
+      let Some(local) = local else {
         return Ok(true); // File missing locally, component is outdated
-      }
-
-      let local_content = match fs::read_to_string(&local_path) {
-        Ok(content) => content,
-        Err(_) => return Ok(true), // Can't read local file, assume outdated
       };
 
-      // Normalize whitespace and line endings for comparison
-      let local_normalized = self.normalize_content(&local_content);
-      let registry_normalized = self.normalize_content(&registry_file.content);
+      // Only the managed region counts when the file has one
+      let (local, installed) = match region_content(&local, &registry_component.name) {
+        Some(local_region) => (
+          local_region,
+          region_content(&installed, &registry_component.name).unwrap_or(&installed),
+        ),
+        None => (local.as_str(), installed.as_str()),
+      };
+      if self.normalize_content(local) != self.normalize_content(installed) {
+        return Ok(true);
+      }
+    }
 
-      if local_normalized != registry_normalized {
-        return Ok(true); // Content differs, component is outdated
+    // CSS of styles and themes lives in its region of the Tailwind CSS file
+    let Ok(injected) = self.injected_css(&registry_component, &context) else {
+      return Ok(true);
+    };
+    if let Some(css) = injected {
+      let local = paths::read_to_string(&self.tailwind_css_path()?).unwrap_or_default();
+      let up_to_date = region_content(&local, &context.name)
+        .is_some_and(|region| self.normalize_content(region) == self.normalize_content(&css));
+      if !up_to_date {
+        return Ok(true);
       }
     }
 
     Ok(false) // All files match, component is up to date
   }
 
+  /// For a component installed from a Git registry at a version tag, the
+  /// repository's newer tag, if it has one. None when the component isn't
+  /// pinned to a tag or the tags can't be listed.
+  async fn newer_tag(
+    &self,
+    component_name: &str,
+    registry_namespace: Option<&str>,
+  ) -> Option<Option<String>> {
+    let lockfile = Lockfile::load(&Lockfile::path_for(&self.project_dir.clone())).ok()?;
+    let reference = lockfile
+      .get(component_name)?
+      .git_ref
+      .as_deref()
+      .filter(|reference| git::is_version_tag(reference))?;
+    let namespace = registry_namespace
+      .map(String::from)
+      .or_else(|| self.recorded_registry(&lockfile, component_name))?;
+    let registry = self.registry_manager.get_registry(&namespace)?;
+
+    registry.newer_tag(reference).await.ok()
+  }
+
   /// Normalize content for comparison (removes whitespace differences and
   /// processes placeholders)
   fn normalize_content(&self, content: &str) -> String {
-    // First process placeholders to ensure both local and registry content are
-    // comparable
+    // First drop the provenance header and process placeholders to ensure
+    // both local and registry content are comparable
+    let content = strip_header(content);
     let processed_content = self
-      .process_placeholders(content, None)
-      .unwrap_or_else(|_| content.to_string());
+      .process_placeholders(&content, None)
+      .unwrap_or_else(|_| content.clone());
 
     // Then normalize whitespace
     processed_content
@@ -1280,25 +4332,10 @@ impl ComponentInstaller {
   /// Get hash of local component files for comparison
   #[allow(dead_code)]
   fn get_component_hash(&self, component_name: &str) -> Result<String> {
-    let ui_path = self
-      .config
-      .aliases
-      .ui
-      .as_ref()
-      .unwrap_or(&self.config.aliases.components);
-
-    // Use the same resolution logic as resolve_file_path
-    let resolved_ui_path = if let Some(ref ts_paths) = self.typescript_paths {
-      self.resolve_path_with_typescript(ui_path, &ts_paths.paths)
-    } else {
-      self.resolve_path_manually(ui_path)
-    };
-
-    let current_dir = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
-    let component_dir = current_dir.join(&resolved_ui_path).join(component_name);
+    let component_dir = self.ui_components_dir().join(component_name);
 
     if !component_dir.exists() {
-      return Err(anyhow!("Component '{}' not found", component_name));
+      return Err(InstallError::NotInstalled(component_name.to_string()).into());
     }
 
     let mut hasher = Sha256::new();
@@ -1363,16 +4400,275 @@ impl ComponentInstaller {
     component_names: &[String],
     registry_namespace: Option<&str>,
   ) -> Result<Vec<(String, bool)>> {
-    let mut results = Vec::new();
+    // Each component is checked against the registry it was installed
+    // from; components the lockfile doesn't know fall back to auto-detection
+    let lockfile = Lockfile::load(&Lockfile::path_for(&self.project_dir))?;
+    // Ejected components belong to the project now
+    let component_names: Vec<String> = component_names
+      .iter()
+      .filter(|name| !lockfile.is_ejected(name))
+      .cloned()
+      .collect();
+    let owners: Vec<Option<String>> = component_names
+      .iter()
+      .map(|name| match registry_namespace {
+        Some(namespace) => Some(namespace.to_string())
+          .filter(|namespace| self.registry_manager.get_registry(namespace).is_some()),
+        None => self.recorded_registry(&lockfile, name),
+      })
+      .collect();
 
-    for component_name in component_names {
-      let is_outdated = self
-        .is_component_outdated(component_name, registry_namespace)
-        .await?;
-      results.push((component_name.clone(), is_outdated));
+    // Fetch each registry's index once, so components it no longer has need
+    // no request of their own. Registries without an index (served as an
+    // empty one) are asked per component.
+    let mut indexes: HashMap<&str, Option<HashSet<String>>> = HashMap::new();
+    for namespace in owners.iter().flatten() {
+      if indexes.contains_key(namespace.as_str()) {
+        continue;
+      }
+      let names = match self.registry_manager.get_registry(namespace) {
+        Some(registry) => registry
+          .fetch_index()
+          .await
+          .ok()
+          .filter(|index| !index.is_empty())
+          .map(|index| {
+            index
+              .as_slice()
+              .into_iter()
+              .map(|component| component.name.clone())
+              .collect()
+          }),
+        None => None,
+      };
+      indexes.insert(namespace, names);
+    }
+
+    // Registry requests run concurrently, a few at a time
+    stream::iter(component_names.iter().zip(&owners))
+      .map(|(component_name, owner)| {
+        let indexes = &indexes;
+        async move {
+          let is_outdated = match owner.as_deref() {
+            Some(namespace)
+              if indexes
+                .get(namespace)
+                .and_then(Option::as_ref)
+                .is_some_and(|names| !names.contains(self.key_parts(component_name).1)) =>
+            {
+              false
+            }
+            namespace => {
+              self
+                .is_component_outdated(component_name, namespace)
+                .await?
+            }
+          };
+          Ok((component_name.clone(), is_outdated))
+        }
+      })
+      .buffered(STATUS_CONCURRENCY)
+      .try_collect()
+      .await
+  }
+
+  /// Update status of every installed component for `outdated --json`,
+  /// with the registry's version of the outdated ones
+  pub async fn outdated_report(&self, registry_namespace: Option<&str>) -> Result<OutdatedReport> {
+    let current_dir = self.project_dir.clone();
+    let lockfile = Lockfile::load(&Lockfile::path_for(&current_dir))?;
+    let installed = self.get_installed_components()?;
+    let results = self
+      .check_outdated_components(&installed, registry_namespace)
+      .await?;
+
+    let mut components = Vec::new();
+    for (name, outdated) in results {
+      let locked = lockfile.get(&name);
+      // Components pinned to a tag are behind the newest tag
+      let latest = if !outdated {
+        None
+      } else if let Some(tag) = self.newer_tag(&name, registry_namespace).await {
+        tag
+      } else {
+        self
+          .fetch_component(&name, registry_namespace)
+          .await
+          .ok()
+          .and_then(|component| component.version)
+      };
+      components.push(OutdatedComponent {
+        registry: locked.and_then(|locked| locked.registry.clone()),
+        installed: locked.and_then(|locked| locked.version.clone()),
+        latest,
+        outdated,
+        modified: locked.is_some_and(|locked| locked.is_modified(&current_dir)),
+        name,
+      });
+    }
+    Ok(OutdatedReport::new(components))
+  }
+
+  /// Update installed components (or just `component_names`, in that
+  /// order) that differ from their registry. Every listed component must be
+  /// installed; the ones already up to date are left alone. What changed
+  /// since the installed version is shown from the registry's changelog
+  /// before asking to go ahead. Returns what the update changed in each
+  /// component.
+  pub async fn update_components(
+    &self,
+    component_names: &[String],
+    registry_namespace: Option<&str>,
+  ) -> Result<UpdateReport> {
+    if let Some(name) = component_names
+      .iter()
+      .find(|name| !self.is_component_installed(name))
+    {
+      return Err(InstallError::NotInstalled(name.clone()).into());
+    }
+    let names = match component_names {
+      [] => self.get_installed_components()?,
+      names => names.iter().fold(Vec::new(), |mut names, name| {
+        if !names.contains(name) {
+          names.push(name.clone());
+        }
+        names
+      }),
+    };
+    let outdated: Vec<String> = self
+      .check_outdated_components(&names, registry_namespace)
+      .await?
+      .into_iter()
+      .filter_map(|(name, is_outdated)| is_outdated.then_some(name))
+      .collect();
+    if outdated.is_empty() {
+      status!("{} All components are up to date!", "✓".green());
+      return Ok(UpdateReport::default());
+    }
+
+    let current_dir = self.project_dir.clone();
+    let lockfile_path = Lockfile::path_for(&current_dir);
+    let lockfile = Lockfile::load(&lockfile_path)?;
+    let mut updates = Vec::new();
+    for name in &outdated {
+      let component = self.fetch_component(name, registry_namespace).await?;
+      let installed = lockfile.get(name).and_then(|locked| locked.version.clone());
+      status!(
+        "\n{} {} {} {}",
+        "↑".yellow(),
+        name.cyan(),
+        installed.as_deref().unwrap_or("?").dimmed(),
+        match &component.version {
+          Some(version) => format!("→ {}", version).green(),
+          None => "→ latest".green(),
+        }
+      );
+      let changes = match self.changelog_entries(&component).await {
+        Ok(Some(entries)) => {
+          let changes = changes_since(&entries, installed.as_deref());
+          print_changelog(changes, None);
+          changes.to_vec()
+        }
+        Ok(None) => {
+          status!("  {}", "No changelog published".dimmed());
+          Vec::new()
+        }
+        Err(e) => {
+          status!("  {} Couldn't load the changelog: {}", "!".yellow(), e);
+          Vec::new()
+        }
+      };
+      updates.push((name, component, changes));
+    }
+
+    status!();
+    if !self.confirm(&format!("Update {} component(s)?", updates.len()), true)? {
+      return Ok(UpdateReport::default());
+    }
+
+    let mut report = UpdateReport::default();
+    for (name, component, changes) in updates {
+      // Keep what the managed files held, to summarize the diff afterwards
+      let before = lockfile.get(name).cloned().unwrap_or_default();
+      let old_contents = before
+        .files
+        .iter()
+        .filter_map(|file| {
+          let content = paths::read_to_string(&current_dir.join(&file.path)).ok()?;
+          Some((file.path.clone(), content))
+        })
+        .collect();
+
+      let namespace = registry_namespace
+        .map(String::from)
+        .or_else(|| component.registry.clone());
+      Box::pin(self.install_resolved_component(
+        component,
+        namespace.as_deref(),
+        true,
+        false,
+        Lifecycle::Update,
+      ))
+      .await?;
+
+      let after = Lockfile::load(&lockfile_path)?
+        .get(name)
+        .cloned()
+        .unwrap_or_default();
+      let mut update = ComponentUpdate::new(name, &before, &after, &old_contents, &current_dir);
+      update.changes = changes;
+      report.components.push(update);
+    }
+    Ok(report)
+  }
+
+  /// Changelog entries of a component, downloaded when the registry only
+  /// links to them. `None` when the component has no changelog.
+  async fn changelog_entries(&self, component: &Component) -> Result<Option<Vec<ChangelogEntry>>> {
+    match &component.changelog {
+      None => Ok(None),
+      Some(Changelog::Entries(entries)) => Ok(Some(entries.clone())),
+      Some(Changelog::Url(url)) => {
+        let registry = component
+          .registry
+          .as_deref()
+          .and_then(|namespace| self.registry_manager.get_registry(namespace))
+          .ok_or_else(|| {
+            anyhow!(
+              "'{}' has no registry to fetch its changelog from",
+              component.name
+            )
+          })?;
+        Ok(Some(registry.fetch_changelog(url, &component.name).await?))
+      }
     }
+  }
+
+  /// Print the changelog of a component, marking the installed version
+  pub async fn show_component_changelog(
+    &self,
+    component_name: &str,
+    registry_namespace: Option<&str>,
+  ) -> Result<()> {
+    let component = self
+      .fetch_component(component_name, registry_namespace)
+      .await?;
+    let Some(entries) = self.changelog_entries(&component).await? else {
+      status!(
+        "{} '{}' has no changelog",
+        "!".yellow(),
+        component.name.cyan()
+      );
+      return Ok(());
+    };
 
-    Ok(results)
+    let lockfile = Lockfile::load(&Lockfile::path_for(&self.project_dir))?;
+    let installed = lockfile
+      .get(&component.name)
+      .and_then(|locked| locked.version.as_deref());
+    status!("{} {}", "Changelog of".bold(), component.name.cyan().bold());
+    print_changelog(&entries, installed);
+    Ok(())
   }
 
   /// Process placeholders in file content based on configuration
@@ -1403,6 +4699,9 @@ impl ComponentInstaller {
       processed_content = processed_content.replace("$LIB$", &lib_path);
     }
 
+    // Replace project and user-defined template variables
+    processed_content = variables::substitute(&processed_content, &self.variables);
+
     // Post-process imports: remove .js extensions when TypeScript is enabled
     if self.is_typescript_enabled() {
       processed_content = self.remove_js_extensions_from_imports(&processed_content);
@@ -1413,11 +4712,11 @@ impl ComponentInstaller {
 
   /// Check if TypeScript is enabled in the configuration
   fn is_typescript_enabled(&self) -> bool {
-    match &self.config.typescript {
-      Some(crate::config::TypeScriptConfig::Boolean(true)) => true,
-      Some(crate::config::TypeScriptConfig::Object { .. }) => true,
-      _ => false,
-    }
+    matches!(
+      &self.config.typescript,
+      Some(crate::config::TypeScriptConfig::Boolean(true))
+        | Some(crate::config::TypeScriptConfig::Object { .. })
+    )
   }
 
   /// Remove .js extensions from import statements when TypeScript is enabled
@@ -1469,6 +4768,7 @@ impl ComponentInstaller {
   }
 
   /// Get the components import path based on configuration
+  #[allow(dead_code)]
   fn get_components_import_path(&self) -> Option<String> {
     let components_path = &self.config.aliases.components;
 
@@ -1490,25 +4790,29 @@ impl ComponentInstaller {
     context: Option<&ComponentContext>,
   ) -> Option<String> {
     let components_path = if let Some(ctx) = context {
-      // Use the alias based on component type
-      self.get_alias_for_component_type(ctx.component_type.as_deref())
+      // Use the alias based on registry override or component type
+      self.get_alias_for_context(ctx)
     } else {
       &self.config.aliases.components
     };
 
-    // First try to resolve using TypeScript paths if available
-    if let Some(ref ts_paths) = self.typescript_paths {
-      let resolved = self.resolve_import_path_with_typescript(components_path, &ts_paths.paths);
-      if !resolved.is_empty() {
-        return Some(resolved);
-      }
-    }
+    // First try to resolve using TypeScript paths if available, falling
+    // back to manual resolution
+    let resolved = self
+      .typescript_paths
+      .as_ref()
+      .map(|ts_paths| self.resolve_import_path_with_typescript(components_path, &ts_paths.paths))
+      .filter(|resolved| !resolved.is_empty())
+      .or_else(|| self.resolve_import_path_manually(components_path))?;
 
-    // Fallback to manual resolution
-    self.resolve_import_path_manually(components_path)
+    match context.and_then(|ctx| self.namespace_dir(ctx)) {
+      Some(dir) => Some(format!("{}/{}", resolved, dir)),
+      None => Some(resolved),
+    }
   }
 
   /// Get the hooks import path based on configuration
+  #[allow(dead_code)]
   fn get_hooks_import_path(&self) -> Option<String> {
     if let Some(hooks_path) = &self.config.aliases.hooks {
       // First try to resolve using TypeScript paths if available
@@ -1565,6 +4869,7 @@ impl ComponentInstaller {
   }
 
   /// Get the lib import path based on configuration
+  #[allow(dead_code)]
   fn get_lib_import_path(&self) -> Option<String> {
     if let Some(lib_path) = &self.config.aliases.lib {
       // First try to resolve using TypeScript paths if available
@@ -1619,20 +4924,55 @@ impl ComponentInstaller {
 
   /// Install dependencies using the detected package manager
   fn install_dependencies(&self, deps: &ComponentDependencies) -> Result<()> {
-    let Some(detection) = &self.package_manager else {
-      println!(
+    let total_deps = deps.dependencies.len() + deps.dev_dependencies.len();
+    if total_deps == 0 {
+      return Ok(());
+    }
+
+    if !self.run_package_manager {
+      self.report_skipped_dependencies(deps);
+      return Ok(());
+    }
+
+    let Some(detection) = self.package_manager() else {
+      status!(
         "{} Skipping dependency installation - no package manager detected",
         "!".yellow()
       );
+      self.report_skipped_dependencies(deps);
       return Ok(());
     };
 
-    let total_deps = deps.dependencies.len() + deps.dev_dependencies.len();
-    if total_deps == 0 {
+    let location = self.dependency_install_location(detection)?;
+
+    if self.print_commands {
+      for (dependencies, is_dev) in [(&deps.dependencies, false), (&deps.dev_dependencies, true)] {
+        if !dependencies.is_empty() {
+          println!(
+            "{}",
+            self.printed_command(detection, location, dependencies, is_dev)
+          );
+        }
+      }
+      self.report_skipped_dependencies(deps);
+      return Ok(());
+    }
+
+    if self.config.confirm_dependency_install != Some(false)
+      && !self.confirm(
+        &format!(
+          "Install {} npm dependencies with {}?",
+          total_deps,
+          detection.manager.name()
+        ),
+        true,
+      )?
+    {
+      self.report_skipped_dependencies(deps);
       return Ok(());
     }
 
-    println!(
+    status!(
       "{} Installing {} dependencies with {}",
       "📦".blue(),
       total_deps.to_string().cyan(),
@@ -1641,21 +4981,73 @@ impl ComponentInstaller {
 
     // Install regular dependencies first
     if !deps.dependencies.is_empty() {
-      self.install_dependency_type(&detection, &deps.dependencies, false)?;
+      self.install_dependency_type(detection, location, &deps.dependencies, false)?;
     }
 
     // Install dev dependencies
     if !deps.dev_dependencies.is_empty() {
-      self.install_dependency_type(&detection, &deps.dev_dependencies, true)?;
+      self.install_dependency_type(detection, location, &deps.dev_dependencies, true)?;
     }
 
     Ok(())
   }
 
+  /// Where dependencies go, following `dependencyInstallTarget` when the
+  /// project is a package of a monorepo
+  fn dependency_install_location(&self, detection: &Detection) -> Result<InstallLocation> {
+    if !detection.in_workspace_package() {
+      return Ok(InstallLocation::Root);
+    }
+
+    let target = self.config.dependency_install_target;
+    match target.unwrap_or(DependencyInstallTarget::Package) {
+      DependencyInstallTarget::Root => Ok(InstallLocation::Root),
+      DependencyInstallTarget::Package => Ok(InstallLocation::Package),
+      DependencyInstallTarget::Prompt => {
+        let package = read_package_name(&detection.project_root)
+          .unwrap_or_else(|| detection.project_root.display().to_string());
+        let choices = [
+          (
+            InstallLocation::Package,
+            format!("this package ({})", package),
+          ),
+          (InstallLocation::Root, "the workspace root".to_string()),
+        ];
+        let prompt = "Add the npm dependencies to";
+        if !self.interactive {
+          status!("{} {} {}", "?".dimmed(), prompt, choices[0].1.dimmed());
+          return Ok(choices[0].0);
+        }
+
+        let labels: Vec<_> = choices.iter().map(|(_, label)| label).collect();
+        let selection = Select::with_theme(&ColorfulTheme::default())
+          .with_prompt(prompt)
+          .items(&labels)
+          .default(0)
+          .interact()?;
+        Ok(choices[selection].0)
+      }
+    }
+  }
+
+  /// Leave dependencies for the user to add; they are recorded in the
+  /// lockfile and listed in the summary
+  fn report_skipped_dependencies(&self, deps: &ComponentDependencies) {
+    self.record(|report| {
+      report
+        .pending_packages
+        .extend(deps.dependencies.iter().cloned());
+      report
+        .pending_dev_packages
+        .extend(deps.dev_dependencies.iter().cloned());
+    });
+  }
+
   /// Install a specific type of dependencies (regular or dev)
   fn install_dependency_type(
     &self,
     detection: &Detection,
+    location: InstallLocation,
     dependencies: &[String],
     is_dev: bool,
   ) -> Result<()> {
@@ -1668,419 +5060,107 @@ impl ComponentInstaller {
     } else {
       "dependencies"
     };
-    println!(
+    status!(
       "{} Installing {} {} with {}",
       "→".blue(),
       dependencies.len().to_string().cyan(),
       dep_type.cyan(),
-      detection.manager.name().cyan()
-    );
-
-    // Build the command
-    let mut cmd = if is_dev {
-      detection.manager.install_dev_command()
-    } else {
-      detection.manager.install_command()
-    };
-    cmd.extend(dependencies.iter().cloned());
-
-    println!("{} Running: {}", "→".blue(), cmd.join(" ").cyan());
-
-    // Try to execute the command, with fallbacks for different package managers
-    let status = self.execute_package_manager_command(&cmd, &detection.project_root)?;
-
-    if status.success() {
-      println!("{} {} installed successfully", "✓".green(), dep_type);
-    } else {
-      println!("{} Failed to install {}", "✗".red(), dep_type);
-      return Err(anyhow!("Package manager command failed for {}", dep_type));
-    }
-
-    Ok(())
-  }
-
-  /// Detect the best execution strategy for the package manager
-  fn detect_execution_strategy(
-    &self,
-    cmd: &[String],
-    project_root: &std::path::Path,
-  ) -> Option<String> {
-    // Test direct execution first
-    if std::process::Command::new(&cmd[0])
-      .arg("--version")
-      .current_dir(project_root)
-      .stdout(std::process::Stdio::null())
-      .stderr(std::process::Stdio::null())
-      .status()
-      .map(|s| s.success())
-      .unwrap_or(false)
-    {
-      return Some("direct".to_string());
-    }
-
-    // Test npx for pnpm
-    if cmd[0] == "pnpm"
-      && std::process::Command::new("npx")
-        .args(&[&cmd[0], "--version"])
-        .current_dir(project_root)
-        .stdout(std::process::Stdio::null())
-        .stderr(std::process::Stdio::null())
-        .status()
-        .map(|s| s.success())
-        .unwrap_or(false)
-    {
-      return Some("npx".to_string());
-    }
-
-    // Test npm exec for pnpm/yarn
-    if (cmd[0] == "pnpm" || cmd[0] == "yarn")
-      && std::process::Command::new("npm")
-        .args(&["exec", &cmd[0], "--", "--version"])
-        .current_dir(project_root)
-        .stdout(std::process::Stdio::null())
-        .stderr(std::process::Stdio::null())
-        .status()
-        .map(|s| s.success())
-        .unwrap_or(false)
-    {
-      return Some("npm_exec".to_string());
-    }
-
-    // Test local binary
-    let local_cmd_path = project_root.join("node_modules").join(".bin").join(&cmd[0]);
-    if local_cmd_path.exists()
-      && std::process::Command::new(&local_cmd_path)
-        .arg("--version")
-        .current_dir(project_root)
-        .stdout(std::process::Stdio::null())
-        .stderr(std::process::Stdio::null())
-        .status()
-        .map(|s| s.success())
-        .unwrap_or(false)
-    {
-      return Some("local_bin".to_string());
-    }
-
-    // Test corepack
-    if std::process::Command::new("corepack")
-      .args(&[&cmd[0], "--version"])
-      .current_dir(project_root)
-      .stdout(std::process::Stdio::null())
-      .stderr(std::process::Stdio::null())
-      .status()
-      .map(|s| s.success())
-      .unwrap_or(false)
-    {
-      return Some("corepack".to_string());
-    }
-
-    // Test cmd.exe on Windows
-    #[cfg(windows)]
-    if std::process::Command::new("cmd")
-      .args(&["/C", &cmd[0], "--version"])
-      .current_dir(project_root)
-      .stdout(std::process::Stdio::null())
-      .stderr(std::process::Stdio::null())
-      .status()
-      .map(|s| s.success())
-      .unwrap_or(false)
-    {
-      return Some("cmd".to_string());
-    }
-
-    // Test PowerShell on Windows
-    #[cfg(windows)]
-    {
-      let ps_command = format!("& {} --version", cmd[0]);
-      if std::process::Command::new("powershell")
-        .args(&["-Command", &ps_command])
-        .current_dir(project_root)
-        .stdout(std::process::Stdio::null())
-        .stderr(std::process::Stdio::null())
-        .status()
-        .map(|s| s.success())
-        .unwrap_or(false)
-      {
-        return Some("powershell".to_string());
-      }
-    }
-
-    None
-  }
-
-  /// Execute package manager command using the detected strategy
-  fn execute_package_manager_command(
-    &self,
-    cmd: &[String],
-    project_root: &std::path::Path,
-  ) -> Result<std::process::ExitStatus> {
-    // Detect the best strategy first
-    let strategy = self.detect_execution_strategy(cmd, project_root);
-
-    match strategy.as_deref() {
-      Some("direct") => {
-        println!("{} Running: {}", "→".blue(), cmd.join(" ").cyan());
-        std::process::Command::new(&cmd[0])
-          .args(&cmd[1..])
-          .current_dir(project_root)
-          .status()
-          .map_err(Into::into)
-      }
-      Some("npx") => {
-        println!(
-          "{} Running via npx: npx {}",
-          "→".blue(),
-          cmd.join(" ").cyan()
-        );
-        let npx_cmd = ["npx".to_string()]
-          .into_iter()
-          .chain(cmd.iter().cloned())
-          .collect::<Vec<_>>();
-        std::process::Command::new(&npx_cmd[0])
-          .args(&npx_cmd[1..])
-          .current_dir(project_root)
-          .status()
-          .map_err(Into::into)
-      }
-      Some("npm_exec") => {
-        println!(
-          "{} Running via npm exec: npm exec {} -- {}",
-          "→".blue(),
-          cmd[0],
-          cmd[1..].join(" ").cyan()
-        );
-        let npm_exec_cmd = vec![
-          "npm".to_string(),
-          "exec".to_string(),
-          cmd[0].clone(),
-          "--".to_string(),
-        ]
-        .into_iter()
-        .chain(cmd[1..].iter().cloned())
-        .collect::<Vec<_>>();
-        std::process::Command::new(&npm_exec_cmd[0])
-          .args(&npm_exec_cmd[1..])
-          .current_dir(project_root)
-          .status()
-          .map_err(Into::into)
-      }
-      Some("local_bin") => {
-        let local_cmd_path = project_root.join("node_modules").join(".bin").join(&cmd[0]);
-        println!(
-          "{} Running local binary: {}",
-          "→".blue(),
-          local_cmd_path.display().to_string().cyan()
-        );
-        std::process::Command::new(&local_cmd_path)
-          .args(&cmd[1..])
-          .current_dir(project_root)
-          .status()
-          .map_err(Into::into)
-      }
-      Some("corepack") => {
-        println!(
-          "{} Running via corepack: corepack {} {}",
-          "→".blue(),
-          cmd[0],
-          cmd[1..].join(" ").cyan()
-        );
-        let corepack_cmd = vec!["corepack".to_string(), cmd[0].clone()]
-          .into_iter()
-          .chain(cmd[1..].iter().cloned())
-          .collect::<Vec<_>>();
-        std::process::Command::new(&corepack_cmd[0])
-          .args(&corepack_cmd[1..])
-          .current_dir(project_root)
-          .status()
-          .map_err(Into::into)
-      }
-      #[cfg(windows)]
-      Some("cmd") => {
-        println!(
-          "{} Running via cmd: cmd /C {} {}",
-          "→".blue(),
-          cmd[0],
-          cmd[1..].join(" ").cyan()
-        );
-        let cmd_args = vec!["/C".to_string(), cmd[0].clone()]
-          .into_iter()
-          .chain(cmd[1..].iter().cloned())
-          .collect::<Vec<_>>();
-        std::process::Command::new("cmd")
-          .args(&cmd_args)
-          .current_dir(project_root)
-          .status()
-          .map_err(Into::into)
-      }
-      #[cfg(windows)]
-      Some("powershell") => {
-        println!(
-          "{} Running via PowerShell: powershell -Command \"{}\"",
-          "→".blue(),
-          cmd.join(" ").cyan()
-        );
-        let ps_command = format!("& {} {}", cmd[0], cmd[1..].join(" "));
-        std::process::Command::new("powershell")
-          .args(&["-Command", &ps_command])
-          .current_dir(project_root)
-          .status()
-          .map_err(Into::into)
-      }
-      _ => {
-        // Fallback: try all strategies with detailed output
-        self.execute_with_fallback_strategies(cmd, project_root)
-      }
-    }
-  }
+      detection.manager.name().cyan()
+    );
 
-  /// Fallback method with all strategies (used when detection fails)
-  fn execute_with_fallback_strategies(
-    &self,
-    cmd: &[String],
-    project_root: &std::path::Path,
-  ) -> Result<std::process::ExitStatus> {
-    println!(
-      "{} No working strategy detected, trying all fallbacks...",
-      "⚠".yellow()
-    );
-
-    // First try: execute command directly
-    println!("{} Direct execution attempt", "→".blue());
-    match std::process::Command::new(&cmd[0])
-      .args(&cmd[1..])
-      .current_dir(project_root)
-      .status()
-    {
-      Ok(status) if status.success() => {
-        println!("{} Direct execution successful", "✓".green());
-        return Ok(status);
-      }
-      Ok(status) => {
-        println!(
-          "{} Direct execution failed with exit code: {}",
-          "✗".red(),
-          status.code().unwrap_or(-1)
-        );
-      }
-      Err(e) => {
-        println!("{} Direct execution error: {}", "✗".red(), e);
-      }
-    }
+    let cmd = self.dependency_install_command(detection, location, dependencies, is_dev);
 
-    // Helper function to check if a command is available (for fallback use)
-    fn is_command_available(command: &str) -> bool {
-      std::process::Command::new(command)
-        .arg("--version")
-        .stdout(std::process::Stdio::null())
-        .stderr(std::process::Stdio::null())
-        .status()
-        .map(|status| status.success())
-        .unwrap_or(false)
-    }
+    status!("{} Running: {}", "→".blue(), cmd.join(" ").cyan());
+    progress(
+      "deps-install",
+      serde_json::json!({
+        "manager": detection.manager.name(),
+        "packages": dependencies,
+        "dev": is_dev,
+      }),
+    );
 
-    // Try remaining strategies in order
-    // npx strategy
-    if cmd[0] == "pnpm" && is_command_available("npx") {
-      println!(
-        "{} Trying with npx: npx {}",
-        "→".blue(),
-        cmd.join(" ").cyan()
-      );
-      let npx_cmd = ["npx".to_string()]
-        .into_iter()
-        .chain(cmd.iter().cloned())
-        .collect::<Vec<_>>();
-      if let Ok(status) = std::process::Command::new(&npx_cmd[0])
-        .args(&npx_cmd[1..])
-        .current_dir(project_root)
-        .status()
-      {
-        if status.success() {
-          println!("{} npx execution successful", "✓".green());
-          return Ok(status);
-        } else {
-          println!(
-            "{} npx execution failed with exit code: {}",
-            "✗".red(),
-            status.code().unwrap_or(-1)
-          );
-        }
-      }
+    let status = self.package_manager_runner(detection, location).run(&cmd)?;
+
+    if status.success() {
+      self.record(|report| report.packages.extend(dependencies.iter().cloned()));
+      status!("{} {} installed successfully", "✓".green(), dep_type);
+    } else {
+      status!("{} Failed to install {}", "✗".red(), dep_type);
+      return Err(InstallError::PackageManager(dep_type.to_string()).into());
     }
 
-    // npm exec strategy
-    if (cmd[0] == "pnpm" || cmd[0] == "yarn") && is_command_available("npm") {
-      println!(
-        "{} Trying with npm exec: npm exec {} -- {}",
-        "→".blue(),
-        cmd[0],
-        cmd[1..].join(" ").cyan()
-      );
-      let npm_exec_cmd = vec![
-        "npm".to_string(),
-        "exec".to_string(),
-        cmd[0].clone(),
-        "--".to_string(),
-      ]
-      .into_iter()
-      .chain(cmd[1..].iter().cloned())
-      .collect::<Vec<_>>();
-      if let Ok(status) = std::process::Command::new(&npm_exec_cmd[0])
-        .args(&npm_exec_cmd[1..])
-        .current_dir(project_root)
-        .status()
-      {
-        if status.success() {
-          println!("{} npm exec execution successful", "✓".green());
-          return Ok(status);
-        } else {
-          println!(
-            "{} npm exec execution failed with exit code: {}",
-            "✗".red(),
-            status.code().unwrap_or(-1)
-          );
-        }
-      }
+    Ok(())
+  }
+
+  /// The install command as it would run, with the execution strategy
+  /// applied, quoted for the shell and preceded by a `cd` when the project
+  /// root isn't the current directory
+  fn printed_command(
+    &self,
+    detection: &Detection,
+    location: InstallLocation,
+    dependencies: &[String],
+    is_dev: bool,
+  ) -> String {
+    let cmd = self.dependency_install_command(detection, location, dependencies, is_dev);
+    let (root, _) = detection.install_scope(location);
+    let command = self
+      .package_manager_runner(detection, location)
+      .command(&cmd)
+      .iter()
+      .map(|arg| shell_quote(arg))
+      .collect::<Vec<_>>()
+      .join(" ");
+
+    let in_root = self
+      .project_dir
+      .canonicalize()
+      .ok()
+      .zip(root.canonicalize().ok())
+      .is_some_and(|(current, root)| current == root);
+    if in_root {
+      command
+    } else {
+      format!(
+        "cd {} && {}",
+        shell_quote(&root.display().to_string()),
+        command
+      )
     }
+  }
 
-    // cmd.exe strategy (Windows)
-    #[cfg(windows)]
-    {
-      println!(
-        "{} Trying with cmd.exe: cmd /C {} {}",
-        "→".blue(),
-        cmd[0],
-        cmd[1..].join(" ").cyan()
-      );
-      let cmd_args = vec!["/C".to_string(), cmd[0].clone()]
-        .into_iter()
-        .chain(cmd[1..].iter().cloned())
-        .collect::<Vec<_>>();
-      if let Ok(status) = std::process::Command::new("cmd")
-        .args(&cmd_args)
-        .current_dir(project_root)
-        .status()
-      {
-        if status.success() {
-          println!("{} cmd execution successful", "✓".green());
-          return Ok(status);
-        } else {
-          println!(
-            "{} cmd execution failed with exit code: {}",
-            "✗".red(),
-            status.code().unwrap_or(-1)
-          );
-        }
-      }
+  /// Build the install command, with the workspace flags for `location`
+  /// and the configured `packageManagerArgs` placed before the package names
+  fn dependency_install_command(
+    &self,
+    detection: &Detection,
+    location: InstallLocation,
+    dependencies: &[String],
+    is_dev: bool,
+  ) -> Vec<String> {
+    let mut cmd = if is_dev {
+      detection.manager.install_dev_command()
+    } else {
+      detection.manager.install_command()
+    };
+    cmd.extend(detection.install_scope(location).1);
+    if let Some(args) = &self.config.package_manager_args {
+      cmd.extend(args.iter().cloned());
     }
+    cmd.extend(dependencies.iter().cloned());
+    cmd
+  }
 
-    // Final attempt
-    println!("{} Final attempt with original command", "→".blue());
-    std::process::Command::new(&cmd[0])
-      .args(&cmd[1..])
-      .current_dir(project_root)
-      .status()
-      .map_err(Into::into)
+  /// Runs the package manager in the directory the install for `location`
+  /// happens in, with the way of invoking it cached in the state directory
+  fn package_manager_runner(
+    &self,
+    detection: &Detection,
+    location: InstallLocation,
+  ) -> PackageManagerRunner {
+    let cache_path = Some(pm_exec::strategy_cache_path(&self.project_dir));
+    PackageManagerRunner::new(detection.install_scope(location).0, cache_path)
   }
 
   /// Resolve import path using TypeScript path mappings
@@ -2090,7 +5170,7 @@ impl ComponentInstaller {
     ts_paths: &HashMap<String, String>,
   ) -> String {
     // Try to find a matching TypeScript path mapping for imports
-    for (alias, _) in ts_paths {
+    for alias in ts_paths.keys() {
       if import_path.starts_with(alias) {
         // For imports, we want to keep the alias, not resolve to file system path
         return import_path.to_string();
@@ -2116,10 +5196,14 @@ impl ComponentInstaller {
 
 #[cfg(test)]
 mod tests {
-  use std::collections::HashMap;
+  use indexmap::IndexMap;
+  use tempfile::TempDir;
 
   use super::*;
-  use crate::config::{AliasesConfig, TailwindConfig};
+  use crate::{
+    config::{AliasesConfig, InstallSizeLimits, TailwindConfig},
+    test_server::serve_files,
+  };
 
   fn create_test_config() -> Config {
     Config {
@@ -2129,6 +5213,7 @@ mod tests {
         css: "src/app.css".to_string(),
         base_color: "slate".to_string(),
         config: None,
+        extra: Default::default(),
       },
       aliases: AliasesConfig {
         components: "src/lib/components".to_string(),
@@ -2136,9 +5221,13 @@ mod tests {
         ui: Some("src/lib/components/ui".to_string()),
         hooks: None,
         lib: Some("src/lib".to_string()),
+        examples: None,
+        internal: None,
+        extra: Default::default(),
       },
-      registries: HashMap::new(),
+      registries: IndexMap::new(),
       typescript: None,
+      ..Config::default()
     }
   }
 
@@ -2147,28 +5236,528 @@ mod tests {
     let config = create_test_config();
     let installer = ComponentInstaller::new(config).unwrap();
 
-    // Create a test component context for UI components
-    let context = ComponentContext {
+    // Create a test component context for UI components
+    let context = ComponentContext {
+      name: "button".to_string(),
+      component_type: Some("registry:ui".to_string()),
+      registry: Some("test".to_string()),
+      version: None,
+    };
+
+    // Test with component target path format (like "button/button.svelte")
+    let path = installer
+      .resolve_file_path("button/button.svelte", &context)
+      .unwrap();
+    assert!(path
+      .to_string_lossy()
+      .contains("src/lib/components/ui/button/button.svelte"));
+
+    // Test with another component target
+    let path = installer
+      .resolve_file_path("card/index.ts", &context)
+      .unwrap();
+    assert!(path
+      .to_string_lossy()
+      .contains("src/lib/components/ui/card/index.ts"));
+  }
+
+  #[test]
+  fn test_file_type_routing() {
+    let mut config = create_test_config();
+    config.aliases.hooks = Some("src/lib/hooks".to_string());
+    let installer = ComponentInstaller::new(config).unwrap();
+    let context = ComponentContext {
+      name: "sidebar".to_string(),
+      component_type: Some("registry:ui".to_string()),
+      registry: None,
+      version: None,
+    };
+    let file = |file_type: Option<&str>, target: &str| ComponentFile {
+      file_type: file_type.map(String::from),
+      target: Some(target.to_string()),
+      ..Default::default()
+    };
+
+    let path = installer
+      .target_path(
+        &file(Some("registry:ui"), "ui/sidebar/sidebar.svelte"),
+        &context,
+      )
+      .unwrap();
+    assert!(path.ends_with("src/lib/components/ui/sidebar/sidebar.svelte"));
+
+    // Files without a type follow the component
+    let path = installer
+      .target_path(&file(None, "sidebar/index.ts"), &context)
+      .unwrap();
+    assert!(path.ends_with("src/lib/components/ui/sidebar/index.ts"));
+
+    let path = installer
+      .target_path(
+        &file(Some("registry:hook"), "is-mobile.svelte.ts"),
+        &context,
+      )
+      .unwrap();
+    assert!(path.ends_with("src/lib/hooks/is-mobile.svelte.ts"));
+
+    let path = installer
+      .target_path(&file(Some("registry:lib"), "sidebar-state.ts"), &context)
+      .unwrap();
+    assert!(path.ends_with("src/lib/sidebar-state.ts"));
+  }
+
+  #[test]
+  fn test_component_override() {
+    let mut config = create_test_config();
+    config.overrides = Some(IndexMap::from([(
+      "sidebar".to_string(),
+      "src/widgets".to_string(),
+    )]));
+    let installer = ComponentInstaller::new(config).unwrap();
+
+    let context = ComponentContext {
+      name: "sidebar".to_string(),
+      component_type: Some("registry:ui".to_string()),
+      registry: Some("test".to_string()),
+      version: None,
+    };
+    let path = installer
+      .resolve_file_path("ui/sidebar/sidebar.svelte", &context)
+      .unwrap();
+    assert!(path.ends_with("src/widgets/sidebar/sidebar.svelte"));
+
+    let other = ComponentContext {
+      name: "button".to_string(),
+      ..context
+    };
+    let path = installer
+      .resolve_file_path("ui/button/button.svelte", &other)
+      .unwrap();
+    assert!(path.ends_with("src/lib/components/ui/button/button.svelte"));
+  }
+
+  #[test]
+  fn test_example_and_internal_types() {
+    let mut config = create_test_config();
+    config.aliases.examples = Some("src/examples".to_string());
+    let installer = ComponentInstaller::new(config).unwrap();
+
+    assert_eq!(
+      installer.get_alias_for_component_type(Some("registry:example")),
+      "src/examples"
+    );
+    assert_eq!(
+      installer.get_alias_for_component_type(Some("registry:internal")),
+      "src/lib/components"
+    );
+    assert_eq!(
+      installer.get_alias_for_component_type(Some("registry:component")),
+      "src/lib/components"
+    );
+
+    let files = [
+      ComponentFile {
+        target: Some("card/card.svelte".to_string()),
+        file_type: Some("registry:ui".to_string()),
+        ..Default::default()
+      },
+      ComponentFile {
+        target: Some("card/card-demo.svelte".to_string()),
+        file_type: Some("registry:example".to_string()),
+        ..Default::default()
+      },
+    ];
+    let context = |component_type: &str| ComponentContext {
+      name: "card".to_string(),
+      component_type: Some(component_type.to_string()),
+      registry: None,
+      version: None,
+    };
+    assert_eq!(
+      installer
+        .installable_files(&files, &context("registry:ui"))
+        .count(),
+      1
+    );
+    assert_eq!(
+      installer
+        .installable_files(&files, &context("registry:example"))
+        .count(),
+      2
+    );
+  }
+
+  #[test]
+  fn test_project_rooted_target() {
+    let project_dir = Path::new("/work/app");
+    let rooted = |target| project_rooted_target(target, project_dir);
+
+    assert_eq!(
+      rooted("~/src/hooks/use-mobile.ts"),
+      Some(PathBuf::from("src/hooks/use-mobile.ts"))
+    );
+    assert_eq!(
+      rooted("src/hooks/use-mobile.ts"),
+      Some(PathBuf::from("src/hooks/use-mobile.ts"))
+    );
+    assert_eq!(
+      rooted("/work/app/app/page.tsx"),
+      Some(PathBuf::from("app/page.tsx"))
+    );
+    assert_eq!(rooted("/app/page.tsx"), Some(PathBuf::from("app/page.tsx")));
+    assert_eq!(rooted("button/button.svelte"), None);
+    assert_eq!(rooted("ui/sidebar.tsx"), None);
+  }
+
+  #[test]
+  fn test_contained_path() {
+    assert_eq!(
+      contained_path(Path::new("src/./hooks/../lib/utils.ts")),
+      Some(PathBuf::from("src/lib/utils.ts"))
+    );
+    assert_eq!(contained_path(Path::new("../../.bashrc")), None);
+    assert_eq!(contained_path(Path::new("src/../../x")), None);
+    assert_eq!(contained_path(Path::new("/etc/passwd")), None);
+  }
+
+  #[test]
+  fn test_resolve_file_path_outside_project() {
+    let installer = ComponentInstaller::new(create_test_config()).unwrap();
+    let context = ComponentContext {
+      name: "button".to_string(),
+      component_type: Some("registry:ui".to_string()),
+      registry: Some("test".to_string()),
+      version: None,
+    };
+
+    for target in [
+      "~/../../.bashrc",
+      "src/../../x",
+      "../../../.ssh/authorized_keys",
+      "button/../../../../x",
+    ] {
+      let err = installer.resolve_file_path(target, &context).unwrap_err();
+      assert!(
+        matches!(
+          err.downcast_ref::<InstallError>(),
+          Some(InstallError::UnsafeTarget(_))
+        ),
+        "{}",
+        target
+      );
+    }
+
+    let current_dir = std::env::current_dir().unwrap();
+    assert_eq!(
+      installer
+        .resolve_file_path("/etc/passwd", &context)
+        .unwrap(),
+      current_dir.join("etc/passwd")
+    );
+    assert!(installer
+      .resolve_file_path("button/../card/card.svelte", &context)
+      .unwrap()
+      .ends_with("ui/card/card.svelte"));
+  }
+
+  #[test]
+  fn test_inject_theme_css() {
+    let temp_dir = TempDir::new().unwrap();
+    let css_path = temp_dir.path().join("app.css");
+    fs::write(&css_path, "@import 'tailwindcss';\n").unwrap();
+
+    let mut config = create_test_config();
+    config.tailwind.css = css_path.to_string_lossy().to_string();
+    let installer = ComponentInstaller::new(config).unwrap();
+
+    let context = ComponentContext {
+      name: "ocean".to_string(),
+      component_type: Some("registry:theme".to_string()),
+      registry: None,
+      version: None,
+    };
+    let file = |content: &str| ComponentFile {
+      content: content.to_string(),
+      target: Some("themes/ocean.css".to_string()),
+      ..Default::default()
+    };
+    assert_eq!(
+      installer.target_path(&file(""), &context).unwrap(),
+      css_path
+    );
+
+    installer
+      .inject_css(":root { --primary: blue; }", &context)
+      .unwrap();
+    installer
+      .inject_css(":root { --primary: teal; }", &context)
+      .unwrap();
+
+    let css = fs::read_to_string(&css_path).unwrap();
+    assert!(css.starts_with("@import 'tailwindcss';\n"));
+    assert_eq!(css.matches("uiget:start ocean").count(), 1);
+    assert!(css.contains("--primary: teal"));
+    assert!(!css.contains("--primary: blue"));
+  }
+
+  #[tokio::test]
+  async fn test_install_css_vars() {
+    let temp_dir = TempDir::new().unwrap();
+    let css_path = temp_dir.path().join("app.css");
+    fs::write(&css_path, "@import 'tailwindcss';\n").unwrap();
+
+    let mut config = create_test_config();
+    config.tailwind.css = css_path.to_string_lossy().to_string();
+    let installer = ComponentInstaller::new(config)
+      .unwrap()
+      .with_project_dir(temp_dir.path());
+
+    let component: Component = serde_json::from_value(serde_json::json!({
+      "name": "ocean",
+      "type": "registry:theme",
+      "cssVars": {
+        "theme": { "font-heading": "Inter, sans-serif" },
+        "light": { "primary": "oklch(0.5 0.1 230)" },
+        "dark": { "primary": "oklch(0.8 0.1 230)" }
+      },
+      "css": {
+        "@layer base": { "h1": { "font-family": "var(--font-heading)", "@apply text-2xl": {} } }
+      },
+      "files": []
+    }))
+    .unwrap();
+    let context = ComponentContext {
+      name: "ocean".to_string(),
+      component_type: component.component_type.clone(),
+      registry: None,
+      version: None,
+    };
+
+    let written = installer
+      .install_component_files(&component, &context, false)
+      .await
+      .unwrap();
+    assert_eq!(written, vec![css_path.clone()]);
+
+    let css = fs::read_to_string(&css_path).unwrap();
+    assert!(css.starts_with("@import 'tailwindcss';\n"));
+    assert!(css.contains("@theme inline {\n  --font-heading: Inter, sans-serif;\n}"));
+    assert!(css.contains(":root {\n  --primary: oklch(0.5 0.1 230);\n}"));
+    assert!(css.contains(".dark {\n  --primary: oklch(0.8 0.1 230);\n}"));
+    assert!(css.contains(
+      "@layer base {\n  h1 {\n    font-family: var(--font-heading);\n    @apply text-2xl;\n  }\n}"
+    ));
+  }
+
+  #[tokio::test]
+  async fn test_theme_css_outdated_and_removed() {
+    let theme = serde_json::json!({
+      "name": "ocean",
+      "type": "registry:theme",
+      "cssVars": { "light": { "primary": "oklch(0.5 0.1 230)" } },
+      "files": [{ "target": "themes/ocean.css", "content": ".ocean { color: teal; }\n" }]
+    });
+    let address = serve_files(vec![("/r/ocean.json", theme.to_string())]).await;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let project_dir = temp_dir.path();
+    let css_path = project_dir.join("app.css");
+    fs::write(&css_path, "@import 'tailwindcss';\n").unwrap();
+    let mut config = create_test_config();
+    config.tailwind.css = css_path.to_string_lossy().to_string();
+    config.set_registry(
+      "acme".to_string(),
+      format!("http://{}/r/{{name}}.json", address),
+    );
+    let installer = ComponentInstaller::new(config)
+      .unwrap()
+      .with_project_dir(project_dir)
+      .with_interactive(false);
+
+    let component = installer
+      .fetch_component("ocean", Some("acme"))
+      .await
+      .unwrap();
+    let context = installer.create_component_context(&component);
+    let written = installer
+      .install_component_files(&component, &context, false)
+      .await
+      .unwrap();
+    installer
+      .record_installed_component(&component, &written, project_dir)
+      .unwrap();
+
+    // The region holds the stylesheet and the CSS file, as one
+    let css = fs::read_to_string(&css_path).unwrap();
+    assert!(css.contains("--primary: oklch(0.5 0.1 230)"));
+    assert!(css.contains(".ocean { color: teal; }"));
+    assert!(!installer
+      .is_component_outdated("ocean", Some("acme"))
+      .await
+      .unwrap());
+    fs::write(&css_path, css.replace("teal", "navy")).unwrap();
+    assert!(installer
+      .is_component_outdated("ocean", Some("acme"))
+      .await
+      .unwrap());
+
+    installer
+      .remove_component_in(project_dir, "ocean", true)
+      .unwrap();
+    assert_eq!(
+      fs::read_to_string(&css_path).unwrap(),
+      "@import 'tailwindcss';\n"
+    );
+  }
+
+  #[test]
+  fn test_strip_types() {
+    let mut config = create_test_config();
+    config.typescript = Some(crate::config::TypeScriptConfig::Boolean(false));
+    config.strip_types = Some(true);
+    let installer = ComponentInstaller::new(config).unwrap();
+
+    let context = ComponentContext {
+      name: "button".to_string(),
+      component_type: Some("registry:ui".to_string()),
+      registry: None,
+      version: None,
+    };
+    let file = ComponentFile {
+      content: "export const size: number = 1;\n".to_string(),
+      target: Some("ui/button/button.tsx".to_string()),
+      ..Default::default()
+    };
+
+    let path = installer.target_path(&file, &context).unwrap();
+    assert!(path.ends_with("ui/button/button.jsx"));
+    assert_eq!(
+      installer.installed_content(&file, &context).unwrap(),
+      "export const size = 1;\n"
+    );
+
+    let declarations = [ComponentFile {
+      target: Some("ui/button/types.d.ts".to_string()),
+      ..Default::default()
+    }];
+    assert_eq!(
+      installer.installable_files(&declarations, &context).count(),
+      0
+    );
+  }
+
+  #[test]
+  fn test_template_variables() {
+    let mut config = create_test_config();
+    config.variables = Some(IndexMap::from([("ORG".to_string(), "acme".to_string())]));
+    let installer = ComponentInstaller::new(config).unwrap();
+
+    let content = installer
+      .process_placeholders("// $ORG$ config for $PROJECT_NAME$, $UNKNOWN$ stays", None)
+      .unwrap();
+    assert!(content.starts_with("// acme config for "));
+    assert!(!content.contains("$PROJECT_NAME$"));
+    assert!(content.ends_with("$UNKNOWN$ stays"));
+  }
+
+  #[test]
+  fn test_conditional_files() {
+    let mut config = create_test_config();
+    config.style = Some("new-york".to_string());
+    let installer = ComponentInstaller::new(config)
+      .unwrap()
+      .with_framework(Some(Framework::Svelte));
+    let context = ComponentContext {
+      name: "form".to_string(),
+      component_type: Some("registry:ui".to_string()),
+      registry: None,
+      version: None,
+    };
+    let file = |target: &str, condition: Option<&str>| ComponentFile {
+      target: Some(target.to_string()),
+      condition: condition.map(String::from),
+      ..Default::default()
+    };
+
+    let files = [
+      file("ui/form/form.svelte", None),
+      file("ui/form/form.ts", Some("typescript")),
+      file("ui/form/form.js", Some("!typescript")),
+      file("ui/form/style.css", Some(r#"style == "new-york""#)),
+      file("ui/form/react.tsx", Some(r#"framework == "react""#)),
+    ];
+    let installed: Vec<_> = installer
+      .installable_files(&files, &context)
+      .map(ComponentFile::get_target_path)
+      .collect();
+    assert_eq!(
+      installed,
+      [
+        "ui/form/form.svelte",
+        "ui/form/form.js",
+        "ui/form/style.css"
+      ]
+    );
+
+    let component = Component {
+      name: "form".to_string(),
+      files: vec![file("ui/form/form.ts", Some("typescript &&"))],
+      ..Default::default()
+    };
+    let err = installer
+      .check_conditions(&component, &context)
+      .unwrap_err();
+    assert!(matches!(
+      err.downcast_ref::<InstallError>(),
+      Some(InstallError::InvalidCondition { .. })
+    ));
+  }
+
+  #[test]
+  fn test_namespace_dirs() {
+    let mut config = create_test_config();
+    config.namespace_dirs = Some(true);
+    config.set_registry(
+      "@acme".to_string(),
+      "https://acme.dev/r/{name}.json".to_string(),
+    );
+    config.set_registry(
+      "default".to_string(),
+      "https://ui.dev/r/{name}.json".to_string(),
+    );
+    let installer = ComponentInstaller::new(config).unwrap();
+
+    let acme = ComponentContext {
       name: "button".to_string(),
       component_type: Some("registry:ui".to_string()),
-      registry: Some("test".to_string()),
+      registry: Some("@acme".to_string()),
+      version: None,
     };
-
-    // Test with component target path format (like "button/button.svelte")
     let path = installer
-      .resolve_file_path("button/button.svelte", &context)
+      .resolve_file_path("ui/button/button.svelte", &acme)
       .unwrap();
-    assert!(path
-      .to_string_lossy()
-      .contains("src/lib/components/ui/button/button.svelte"));
+    assert!(path.ends_with("src/lib/components/ui/acme/button/button.svelte"));
+    assert_eq!(
+      installer.get_components_import_path_with_context(Some(&acme)),
+      Some("src/lib/components/ui/acme".to_string())
+    );
 
-    // Test with another component target
+    let default = ComponentContext {
+      registry: Some("default".to_string()),
+      ..acme
+    };
     let path = installer
-      .resolve_file_path("card/index.ts", &context)
+      .resolve_file_path("ui/button/button.svelte", &default)
       .unwrap();
-    assert!(path
-      .to_string_lossy()
-      .contains("src/lib/components/ui/card/index.ts"));
+    assert!(path.ends_with("src/lib/components/ui/button/button.svelte"));
+
+    let content = r#"import { Button } from "src/lib/components/ui/button/index.js";
+import { Card } from "src/lib/components/ui/card";"#;
+    assert_eq!(
+      installer.rewrite_namespaced_imports(content, "acme", &["button"]),
+      r#"import { Button } from "src/lib/components/ui/acme/button/index.js";
+import { Card } from "src/lib/components/ui/card";"#
+    );
   }
 
   #[test]
@@ -2213,6 +5802,900 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_registry_target_override() {
+    let mut config = create_test_config();
+    config.set_registry_config(
+      "acme".to_string(),
+      crate::config::RegistryConfig::Object {
+        url: "https://acme.dev/r/{name}.json".to_string(),
+        params: None,
+        headers: None,
+        username: None,
+        password: None,
+        style: None,
+        default_type: None,
+        target_override: Some("src/lib/components/acme".to_string()),
+        max_concurrent_requests: None,
+        user_agent: None,
+        signing: None,
+        response_path: None,
+        graphql: None,
+        extra: Default::default(),
+      },
+    );
+    let installer = ComponentInstaller::new(config).unwrap();
+
+    let context = ComponentContext {
+      name: "chart".to_string(),
+      component_type: Some("registry:ui".to_string()),
+      registry: Some("acme".to_string()),
+      version: None,
+    };
+    assert_eq!(
+      installer.get_alias_for_context(&context),
+      "src/lib/components/acme"
+    );
+
+    let other = ComponentContext {
+      registry: Some("default".to_string()),
+      ..context
+    };
+    assert_eq!(
+      installer.get_alias_for_context(&other),
+      "src/lib/components/ui"
+    );
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn test_link_component() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let source = temp_dir.path().join("registry/button.svelte");
+    fs::create_dir_all(source.parent().unwrap()).unwrap();
+    fs::write(&source, "<button />").unwrap();
+
+    let mut config = create_test_config();
+    config.aliases.ui = Some(temp_dir.path().join("app/ui").display().to_string());
+    let installer = ComponentInstaller::new(config).unwrap();
+
+    let files = vec![(source.clone(), "ui/button/button.svelte".to_string())];
+    let linked = installer
+      .link_component("button", Some("registry:ui"), &files, false, false)
+      .unwrap();
+
+    let target = temp_dir.path().join("app/ui/button/button.svelte");
+    assert_eq!(linked, std::slice::from_ref(&target));
+    assert_eq!(fs::read_link(&target).unwrap(), source);
+
+    // Edits to the source are visible through the link
+    fs::write(&source, "<button class=\"new\" />").unwrap();
+    assert_eq!(
+      fs::read_to_string(&target).unwrap(),
+      "<button class=\"new\" />"
+    );
+
+    let err = installer
+      .link_component("button", Some("registry:ui"), &files, false, false)
+      .unwrap_err();
+    assert!(matches!(
+      err.downcast_ref::<InstallError>(),
+      Some(InstallError::FileExists(_))
+    ));
+    assert!(installer
+      .link_component("button", Some("registry:ui"), &files, true, true)
+      .is_ok());
+  }
+
+  #[tokio::test]
+  async fn test_install_file_replaces_managed_region() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let mut config = create_test_config();
+    config.aliases.ui = Some(temp_dir.path().join("app/ui").display().to_string());
+    let installer = ComponentInstaller::new(config).unwrap();
+
+    let target = temp_dir.path().join("app/ui/theme.css");
+    fs::create_dir_all(target.parent().unwrap()).unwrap();
+    fs::write(
+      &target,
+      "/* mine */\n/* uiget:start theme */\nold\n/* uiget:end */\n.custom {}\n",
+    )
+    .unwrap();
+
+    let file = ComponentFile {
+      content: "new\n".to_string(),
+      path: Some("ui/theme.css".to_string()),
+      ..Default::default()
+    };
+    let context = ComponentContext {
+      name: "theme".to_string(),
+      component_type: Some("registry:ui".to_string()),
+      registry: None,
+      version: None,
+    };
+
+    // No --force needed: only the region is rewritten
+    installer
+      .install_files(&[&file], &context, false)
+      .await
+      .unwrap();
+    assert_eq!(
+      fs::read_to_string(&target).unwrap(),
+      "/* mine */\n/* uiget:start theme */\nnew\n/* uiget:end */\n.custom {}\n"
+    );
+  }
+
+  #[tokio::test]
+  async fn test_install_files_keeps_order() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let mut config = create_test_config();
+    config.aliases.ui = Some(temp_dir.path().join("app/ui").display().to_string());
+    config.provenance_header = Some(false);
+    let installer = ComponentInstaller::new(config).unwrap();
+    let context = ComponentContext {
+      name: "block".to_string(),
+      component_type: Some("registry:ui".to_string()),
+      registry: None,
+      version: None,
+    };
+
+    let files: Vec<_> = (0..40)
+      .map(|i| ComponentFile {
+        content: format!("export const n = {};\n", i),
+        path: Some(format!("ui/block/part-{}.ts", i)),
+        ..Default::default()
+      })
+      .collect();
+    let written = installer
+      .install_files(&files.iter().collect::<Vec<_>>(), &context, false)
+      .await
+      .unwrap();
+
+    for (i, path) in written.iter().enumerate() {
+      assert_eq!(
+        path,
+        &temp_dir.path().join(format!("app/ui/block/part-{}.ts", i))
+      );
+      assert_eq!(
+        fs::read_to_string(path).unwrap(),
+        format!("export const n = {};\n", i)
+      );
+    }
+
+    // A refused file stops the install before anything is written
+    let mut files = files;
+    files[0].content = "changed".to_string();
+    files.push(ComponentFile {
+      content: String::new(),
+      path: Some("ui/block/extra.ts".to_string()),
+      ..Default::default()
+    });
+    let refs: Vec<_> = files.iter().collect();
+    assert!(installer
+      .install_files(&refs, &context, false)
+      .await
+      .is_err());
+    assert!(!temp_dir.path().join("app/ui/block/extra.ts").exists());
+  }
+
+  #[tokio::test]
+  async fn test_confirm_install_size() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let mut config = create_test_config();
+    config.aliases.ui = Some(temp_dir.path().join("app/ui").display().to_string());
+    config.confirm_install_size = Some(InstallSizeLimits {
+      files: Some(1),
+      kilobytes: None,
+    });
+    let installer = ComponentInstaller::new(config)
+      .unwrap()
+      .with_interactive(false);
+
+    let file = |path: &str, content: &str| ComponentFile {
+      content: content.to_string(),
+      path: Some(path.to_string()),
+      ..Default::default()
+    };
+    let mut component = Component {
+      name: "card".to_string(),
+      component_type: Some("registry:ui".to_string()),
+      files: vec![
+        file("ui/card/card.svelte", "<div>\n  <slot />\n</div>\n"),
+        file("ui/card/index.ts", "export {};\n"),
+      ],
+      ..Default::default()
+    };
+    assert_eq!(
+      installer.install_size(&component).unwrap(),
+      InstallSize {
+        files: 2,
+        bytes: 35,
+        lines: 4
+      }
+    );
+
+    // Files already in the project as they would be installed don't count
+    let index = temp_dir.path().join("app/ui/card/index.ts");
+    fs::create_dir_all(index.parent().unwrap()).unwrap();
+    fs::write(&index, "export {};\n").unwrap();
+    assert_eq!(installer.install_size(&component).unwrap().files, 1);
+    installer
+      .confirm_install_size(&component, None, true)
+      .await
+      .unwrap();
+
+    component.files.push(file("ui/card/header.svelte", ""));
+    let err = installer
+      .confirm_install_size(&component, None, true)
+      .await
+      .unwrap_err();
+    assert!(matches!(
+      err.downcast_ref::<InstallError>(),
+      Some(InstallError::SizeNotConfirmed(_))
+    ));
+  }
+
+  #[test]
+  fn test_dependency_conflicts() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let mut config = create_test_config();
+    config.aliases.ui = Some(temp_dir.path().join("app/ui").display().to_string());
+    let installer = ComponentInstaller::new(config)
+      .unwrap()
+      .with_interactive(false);
+
+    let file = |path: &str, content: &str| ComponentFile {
+      content: content.to_string(),
+      path: Some(path.to_string()),
+      ..Default::default()
+    };
+    let component = Component {
+      name: "utils".to_string(),
+      component_type: Some("registry:ui".to_string()),
+      files: vec![
+        file("ui/utils.ts", "export const cn = 2;\n"),
+        file("ui/new.ts", ""),
+      ],
+      ..Default::default()
+    };
+    let context = installer.create_component_context(&component);
+    let utils = temp_dir.path().join("app/ui/utils.ts");
+    fs::create_dir_all(utils.parent().unwrap()).unwrap();
+    fs::write(&utils, "export const cn = 1;\n").unwrap();
+
+    let files = installer
+      .compare_with_project(&component, &context)
+      .unwrap();
+    assert_eq!(
+      files,
+      [
+        (
+          utils,
+          Some("export const cn = 1;\n".to_string()),
+          "export const cn = 2;\n".to_string()
+        ),
+        (temp_dir.path().join("app/ui/new.ts"), None, String::new()),
+      ]
+    );
+
+    // Without prompts, the project's files win unless --force is given
+    assert_eq!(
+      installer
+        .choose_conflict_resolution("utils", false)
+        .unwrap(),
+      ConflictResolution::KeepLocal
+    );
+    assert_eq!(
+      installer.choose_conflict_resolution("utils", true).unwrap(),
+      ConflictResolution::TakeNew
+    );
+  }
+
+  #[test]
+  fn test_untracked_files() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let project_dir = temp_dir.path();
+    let ui = project_dir.join("app/ui");
+    let hooks = project_dir.join("app/hooks");
+    let mut config = create_test_config();
+    config.aliases.ui = Some(ui.display().to_string());
+    config.aliases.hooks = Some(hooks.display().to_string());
+    config.barrel_exports = Some(true);
+    let installer = ComponentInstaller::new(config).unwrap();
+
+    for path in [
+      "app/ui/button/button.svelte",
+      "app/ui/button/notes.md",
+      "app/ui/card.svelte",
+      "app/ui/index.ts",
+      "app/ui/.DS_Store",
+      "app/hooks/use-mobile.ts",
+      "app/hooks/use-legacy.ts",
+    ] {
+      let path = project_dir.join(path);
+      fs::create_dir_all(path.parent().unwrap()).unwrap();
+      fs::write(path, "").unwrap();
+    }
+
+    let mut lockfile = Lockfile::default();
+    let tracked = |paths: &[&str]| LockedComponent {
+      files: paths
+        .iter()
+        .map(|path| LockedFile::new(project_dir, &project_dir.join(path), ""))
+        .collect(),
+      ..Default::default()
+    };
+    lockfile.insert("button", tracked(&["app/ui/button/button.svelte"]));
+    lockfile.insert("use-mobile", tracked(&["app/hooks/use-mobile.ts"]));
+    lockfile.insert("card", tracked(&["app/ui/card.svelte"]));
+    lockfile.eject("card");
+
+    assert_eq!(
+      installer.untracked_files(&lockfile, project_dir).unwrap(),
+      [ui.join("button/notes.md"), hooks.join("use-legacy.ts")]
+    );
+
+    let roots = installer.managed_dirs();
+    remove_with_empty_parents(&hooks.join("use-legacy.ts"), &roots).unwrap();
+    assert!(hooks.is_dir());
+    fs::create_dir_all(ui.join("old/parts")).unwrap();
+    fs::write(ui.join("old/parts/old.svelte"), "").unwrap();
+    remove_with_empty_parents(&ui.join("old/parts/old.svelte"), &roots).unwrap();
+    assert!(!ui.join("old").exists());
+    assert!(ui.is_dir());
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn test_remove_component() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let project_dir = temp_dir.path();
+    let ui = project_dir.join("app/ui");
+    let mut config = create_test_config();
+    config.aliases.ui = Some(ui.display().to_string());
+    config.hooks = Some(crate::config::HooksConfig {
+      pre_remove: Some("echo \"$UIGET_HOOK $UIGET_COMPONENT\" >> hooks.log".to_string()),
+      post_remove: Some("echo \"$UIGET_HOOK $UIGET_FILES\" >> hooks.log".to_string()),
+      ..Default::default()
+    });
+    let installer = ComponentInstaller::new(config)
+      .unwrap()
+      .with_interactive(false);
+
+    let files: Vec<PathBuf> = ["button/button.svelte", "button/index.ts"]
+      .iter()
+      .map(|path| ui.join(path))
+      .collect();
+    fs::create_dir_all(ui.join("button")).unwrap();
+    for path in &files {
+      fs::write(path, "").unwrap();
+    }
+    fs::write(ui.join("card.svelte"), "").unwrap();
+    let mut lockfile = Lockfile::default();
+    lockfile.insert(
+      "button",
+      LockedComponent {
+        files: files
+          .iter()
+          .map(|path| LockedFile::new(project_dir, path, ""))
+          .collect(),
+        ..Default::default()
+      },
+    );
+    lockfile.save(&Lockfile::path_for(project_dir)).unwrap();
+
+    let error = installer
+      .remove_component_in(project_dir, "card", false)
+      .unwrap_err();
+    assert!(matches!(
+      error.downcast_ref::<InstallError>(),
+      Some(InstallError::NotInstalled(_))
+    ));
+
+    // Edited files are kept unless forced
+    fs::write(&files[1], "export {};").unwrap();
+    let error = installer
+      .remove_component_in(project_dir, "button", false)
+      .unwrap_err();
+    assert!(matches!(
+      error.downcast_ref::<InstallError>(),
+      Some(InstallError::LocallyModified(_))
+    ));
+    assert!(files[0].exists());
+    assert!(!project_dir.join("hooks.log").exists());
+
+    installer
+      .remove_component_in(project_dir, "button", true)
+      .unwrap();
+    assert!(!ui.join("button").exists());
+    assert!(ui.join("card.svelte").exists());
+    let lockfile = Lockfile::load(&Lockfile::path_for(project_dir)).unwrap();
+    assert!(lockfile.get("button").is_none());
+    let report = installer.report.lock().unwrap();
+    assert_eq!(report.removed, ["button"]);
+    assert_eq!(report.deleted, files);
+    drop(report);
+    assert_eq!(
+      fs::read_to_string(project_dir.join("hooks.log")).unwrap(),
+      format!(
+        "preRemove button\npostRemove {}\n{}\n",
+        files[0].display(),
+        files[1].display()
+      )
+    );
+  }
+
+  #[test]
+  fn test_prune() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let project_dir = temp_dir.path();
+    let ui = project_dir.join("app/ui");
+    let mut config = create_test_config();
+    config.aliases.ui = Some(ui.display().to_string());
+    let installer = ComponentInstaller::new(config)
+      .unwrap()
+      .with_interactive(false);
+
+    for path in [
+      "button/button.svelte",
+      "button/old-part.svelte",
+      "custom/my-widget.svelte",
+      "legacy/legacy.svelte",
+    ] {
+      fs::create_dir_all(ui.join(path).parent().unwrap()).unwrap();
+      fs::write(ui.join(path), "").unwrap();
+    }
+    let tracked = |paths: &[&str]| LockedComponent {
+      files: paths
+        .iter()
+        .map(|path| LockedFile::new(project_dir, &ui.join(path), ""))
+        .collect(),
+      ..Default::default()
+    };
+    let mut lockfile = Lockfile::default();
+    lockfile.insert(
+      "button",
+      tracked(&["button/button.svelte", "button/old-part.svelte"]),
+    );
+    // An update of button stopped shipping old-part.svelte
+    lockfile.insert("button", tracked(&["button/button.svelte"]));
+    // Installed before the lockfile recorded files
+    lockfile.insert("legacy", LockedComponent::default());
+    lockfile.save(&Lockfile::path_for(project_dir)).unwrap();
+
+    // Without a terminal and without --yes nothing is deleted
+    let error = installer.prune_in(project_dir, false, false).unwrap_err();
+    assert!(matches!(
+      error.downcast_ref::<InstallError>(),
+      Some(InstallError::NonInteractive(_))
+    ));
+    assert!(ui.join("button/old-part.svelte").exists());
+
+    installer.prune_in(project_dir, true, true).unwrap();
+    assert!(ui.join("button/old-part.svelte").exists());
+
+    installer.prune_in(project_dir, false, true).unwrap();
+    assert!(!ui.join("button/old-part.svelte").exists());
+    assert!(ui.join("button/button.svelte").exists());
+    assert!(ui.join("custom/my-widget.svelte").exists());
+    assert!(ui.join("legacy/legacy.svelte").exists());
+    let lockfile = Lockfile::load(&Lockfile::path_for(project_dir)).unwrap();
+    assert!(lockfile.dropped.is_empty());
+  }
+
+  #[test]
+  fn test_prune_keeps_untracked_files_in_flat_layout() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let project_dir = temp_dir.path();
+    let ui = project_dir.join("components/ui");
+    let mut config = create_test_config();
+    config.aliases.ui = Some(ui.display().to_string());
+    let installer = ComponentInstaller::new(config)
+      .unwrap()
+      .with_interactive(false);
+
+    fs::create_dir_all(&ui).unwrap();
+    for name in ["button.tsx", "card.tsx", "dialog.tsx"] {
+      fs::write(ui.join(name), "").unwrap();
+    }
+    let tracked = |name: &str| LockedComponent {
+      files: vec![LockedFile::new(project_dir, &ui.join(name), "")],
+      ..Default::default()
+    };
+    let mut lockfile = Lockfile::default();
+    lockfile.insert("button", tracked("button.tsx"));
+    lockfile.insert("dialog", tracked("dialog.tsx"));
+    // dialog was renamed; its file is left behind
+    lockfile.remove("dialog");
+    lockfile.save(&Lockfile::path_for(project_dir)).unwrap();
+
+    installer.prune_in(project_dir, false, true).unwrap();
+    assert!(ui.join("button.tsx").exists());
+    // Installed by another tool or written by hand, never recorded
+    assert!(ui.join("card.tsx").exists());
+    assert!(!ui.join("dialog.tsx").exists());
+  }
+
+  #[test]
+  fn test_recorded_registry() {
+    let mut config = create_test_config();
+    config.set_registry(
+      "acme".to_string(),
+      "https://acme.dev/r/{name}.json".to_string(),
+    );
+    let installer = ComponentInstaller::new(config).unwrap();
+
+    let mut lockfile = Lockfile::default();
+    let locked_from = |registry: &str| LockedComponent {
+      registry: Some(registry.to_string()),
+      ..Default::default()
+    };
+    lockfile.insert("button", locked_from("acme"));
+    lockfile.insert("card", locked_from("removed"));
+
+    assert_eq!(
+      installer.recorded_registry(&lockfile, "button").as_deref(),
+      Some("acme")
+    );
+    // Registries no longer configured fall back to auto-detection
+    assert_eq!(installer.recorded_registry(&lockfile, "card"), None);
+    assert_eq!(installer.recorded_registry(&lockfile, "dialog"), None);
+  }
+
+  #[test]
+  fn test_group_components() {
+    let info =
+      |name: &str, component_type: &str, category: Option<&str>| crate::registry::ComponentInfo {
+        name: name.to_string(),
+        component_type: Some(component_type.to_string()),
+        category: category.map(String::from),
+        ..Default::default()
+      };
+
+    let by_type = [
+      info("use-toast", "registry:hook", None),
+      info("button", "registry:ui", None),
+      info("misc", "registry:internal", None),
+    ];
+    let titles: Vec<_> = group_components(&by_type)
+      .into_iter()
+      .map(|group| (group.title, group.components.len()))
+      .collect();
+    assert_eq!(
+      titles,
+      [
+        ("UI Components".to_string(), 1),
+        ("Hooks".to_string(), 1),
+        ("Other".to_string(), 1)
+      ]
+    );
+
+    let by_category = [
+      info("input", "registry:ui", Some("Forms")),
+      info("dialog", "registry:ui", Some("Overlays")),
+      info("select", "registry:ui", Some("Forms")),
+      info("utils", "registry:lib", None),
+    ];
+    let groups = group_components(&by_category);
+    let titles: Vec<_> = groups.iter().map(|group| group.title.as_str()).collect();
+    assert_eq!(titles, ["Forms", "Overlays", "Other"]);
+    assert_eq!(groups[0].components.len(), 2);
+  }
+
+  #[tokio::test]
+  async fn test_namespaced_lockfile_keys() {
+    let component = |content: &str| {
+      serde_json::json!({
+        "name": "button",
+        "type": "registry:ui",
+        "files": [{ "target": "ui/button/button.svelte", "content": content }]
+      })
+      .to_string()
+    };
+    let acme = serve_files(vec![("/r/button.json", component("acme"))]).await;
+    let default = serve_files(vec![("/r/button.json", component("default"))]).await;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let project_dir = temp_dir.path();
+    let mut config = create_test_config();
+    config.aliases.ui = Some(project_dir.join("ui").display().to_string());
+    config.namespace_dirs = Some(true);
+    config.set_registry(
+      "default".to_string(),
+      format!("http://{}/r/{{name}}.json", default),
+    );
+    config.set_registry(
+      "@acme".to_string(),
+      format!("http://{}/r/{{name}}.json", acme),
+    );
+    let installer = ComponentInstaller::new(config)
+      .unwrap()
+      .with_project_dir(project_dir)
+      .with_interactive(false);
+
+    for namespace in ["@acme", "default"] {
+      let component = installer
+        .fetch_component("button", Some(namespace))
+        .await
+        .unwrap();
+      let context = installer.create_component_context(&component);
+      let path = installer
+        .target_path(&component.files[0], &context)
+        .unwrap();
+      fs::create_dir_all(path.parent().unwrap()).unwrap();
+      fs::write(&path, &component.files[0].content).unwrap();
+      installer
+        .record_installed_component(&component, &[path], project_dir)
+        .unwrap();
+    }
+
+    // Listed, looked up and fetched by the same key the lockfile uses
+    let installed = installer.get_installed_components().unwrap();
+    assert_eq!(installed, ["acme/button", "button"]);
+    let lockfile = Lockfile::load(&Lockfile::path_for(project_dir)).unwrap();
+    for key in &installed {
+      assert!(lockfile.get(key).is_some(), "{}", key);
+      assert!(installer.is_component_installed(key), "{}", key);
+    }
+    assert_eq!(
+      installer.key_parts("acme/button"),
+      (Some("@acme".to_string()), "button")
+    );
+    let fetched = installer
+      .fetch_component("acme/button", None)
+      .await
+      .unwrap();
+    assert_eq!(fetched.files[0].content, "acme");
+
+    let outdated = installer
+      .check_outdated_components(&installed, None)
+      .await
+      .unwrap();
+    assert!(outdated.iter().all(|(_, outdated)| !outdated));
+    fs::write(project_dir.join("ui/acme/button/button.svelte"), "edited").unwrap();
+    let outdated = installer
+      .check_outdated_components(&installed, None)
+      .await
+      .unwrap();
+    assert_eq!(
+      outdated,
+      [
+        ("acme/button".to_string(), true),
+        ("button".to_string(), false)
+      ]
+    );
+  }
+
+  #[tokio::test]
+  async fn test_namespaced_dependency_import_is_up_to_date() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let project_dir = temp_dir.path();
+    let ui = project_dir.join("ui").display().to_string();
+    let card = serde_json::json!({
+      "name": "card",
+      "type": "registry:ui",
+      "registryDependencies": ["button"],
+      "files": [{
+        "target": "ui/card/card.svelte",
+        "content": format!("import {{ Button }} from \"{}/button/index.js\";\n", ui)
+      }]
+    });
+    let address = serve_files(vec![("/r/card.json", card.to_string())]).await;
+
+    let mut config = create_test_config();
+    config.aliases.ui = Some(ui.clone());
+    config.namespace_dirs = Some(true);
+    config.set_registry(
+      "@acme".to_string(),
+      format!("http://{}/r/{{name}}.json", address),
+    );
+    let installer = ComponentInstaller::new(config)
+      .unwrap()
+      .with_project_dir(project_dir)
+      .with_interactive(false);
+
+    let component = installer
+      .fetch_component("card", Some("@acme"))
+      .await
+      .unwrap();
+    let context = installer.create_component_context(&component);
+    installer
+      .install_component_files(&component, &context, false)
+      .await
+      .unwrap();
+
+    // The import follows the dependency into the namespace directory, and
+    // the installed file still matches the registry's
+    let installed = fs::read_to_string(project_dir.join("ui/acme/card/card.svelte")).unwrap();
+    assert!(installed.contains(&format!("{}/acme/button/index.js", ui)));
+    assert!(!installer
+      .is_component_outdated("acme/card", None)
+      .await
+      .unwrap());
+  }
+
+  #[tokio::test]
+  async fn test_outdated_report() {
+    let component = serde_json::json!({
+      "name": "button",
+      "type": "registry:ui",
+      "version": "2.0.0",
+      "files": [{ "target": "button/button.svelte", "content": "v2" }]
+    });
+    let card = serde_json::json!({
+      "name": "card",
+      "type": "registry:ui",
+      "files": [{ "target": "card/card.svelte", "content": "card" }]
+    });
+    let address = serve_files(vec![
+      ("/r/button.json", component.to_string()),
+      ("/r/card.json", card.to_string()),
+    ])
+    .await;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let project_dir = temp_dir.path();
+    let ui = project_dir.join("ui");
+    let mut config = create_test_config();
+    config.aliases.ui = Some(ui.display().to_string());
+    config.set_registry(
+      "acme".to_string(),
+      format!("http://{}/r/{{name}}.json", address),
+    );
+    let installer = ComponentInstaller::new(config)
+      .unwrap()
+      .with_project_dir(project_dir)
+      .with_interactive(false);
+
+    let mut lockfile = Lockfile::default();
+    for (name, content) in [("button", "v1"), ("card", "card")] {
+      let path = ui.join(name).join(format!("{}.svelte", name));
+      fs::create_dir_all(path.parent().unwrap()).unwrap();
+      fs::write(&path, content).unwrap();
+      lockfile.insert(
+        name,
+        LockedComponent {
+          files: vec![LockedFile::new(project_dir, &path, content)],
+          version: Some("1.0.0".to_string()),
+          registry: Some("acme".to_string()),
+          ..Default::default()
+        },
+      );
+    }
+    lockfile.save(&Lockfile::path_for(project_dir)).unwrap();
+
+    // What `outdated --json` prints is the report alone
+    let report = installer.outdated_report(None).await.unwrap();
+    let printed = serde_json::to_string_pretty(&report).unwrap();
+    let parsed: serde_json::Value = serde_json::from_str(&printed).unwrap();
+    assert_eq!(parsed["schemaVersion"], 1);
+    assert_eq!(
+      parsed["components"],
+      serde_json::json!([
+        {
+          "name": "button",
+          "registry": "acme",
+          "installed": "1.0.0",
+          "latest": "2.0.0",
+          "outdated": true,
+          "modified": false
+        },
+        {
+          "name": "card",
+          "registry": "acme",
+          "installed": "1.0.0",
+          "latest": null,
+          "outdated": false,
+          "modified": false
+        }
+      ])
+    );
+  }
+
+  #[cfg(unix)]
+  #[tokio::test]
+  async fn test_update_components() {
+    let component = serde_json::json!({
+      "name": "button",
+      "type": "registry:ui",
+      "version": "2.0.0",
+      "changelog": [
+        { "version": "2.0.0", "notes": "Rounder corners" },
+        { "version": "1.1.0", "notes": "Focus ring" },
+        { "version": "1.0.0", "notes": "First release" }
+      ],
+      "files": [{ "target": "button/button.svelte", "content": "v2" }]
+    });
+    let address = serve_files(vec![("/r/button.json", component.to_string())]).await;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let project_dir = temp_dir.path();
+    let ui = project_dir.join("ui");
+    let mut config = create_test_config();
+    config.aliases.ui = Some(ui.display().to_string());
+    config.set_registry(
+      "acme".to_string(),
+      format!("http://{}/r/{{name}}.json", address),
+    );
+    config.hooks = Some(crate::config::HooksConfig {
+      pre_add: Some("echo $UIGET_HOOK >> hooks.log".to_string()),
+      post_add: Some("echo $UIGET_HOOK >> hooks.log".to_string()),
+      pre_update: Some("echo $UIGET_HOOK >> hooks.log".to_string()),
+      post_update: Some("echo $UIGET_HOOK >> hooks.log".to_string()),
+      ..Default::default()
+    });
+    let installer = ComponentInstaller::new(config)
+      .unwrap()
+      .with_project_dir(project_dir)
+      .with_interactive(false);
+
+    let path = ui.join("button/button.svelte");
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(&path, "v1").unwrap();
+    let mut lockfile = Lockfile::default();
+    lockfile.insert(
+      "button",
+      LockedComponent {
+        files: vec![LockedFile::new(project_dir, &path, "v1")],
+        version: Some("1.0.0".to_string()),
+        registry: Some("acme".to_string()),
+        ..Default::default()
+      },
+    );
+    lockfile.save(&Lockfile::path_for(project_dir)).unwrap();
+
+    // Edits since install aren't overwritten without a confirmation
+    fs::write(&path, "mine").unwrap();
+    let error = installer.update_components(&[], None).await.unwrap_err();
+    assert!(matches!(
+      error.downcast_ref::<InstallError>(),
+      Some(InstallError::LocallyModified(_))
+    ));
+    assert_eq!(fs::read_to_string(&path).unwrap(), "mine");
+    assert_eq!(
+      fs::read_to_string(project_dir.join("hooks.log")).unwrap(),
+      "preUpdate\n"
+    );
+
+    fs::write(&path, "v1").unwrap();
+    fs::remove_file(project_dir.join("hooks.log")).unwrap();
+    let report = installer.update_components(&[], None).await.unwrap();
+    let update = &report.components[0];
+    assert_eq!(update.from.as_deref(), Some("1.0.0"));
+    assert_eq!(update.to.as_deref(), Some("2.0.0"));
+    let versions: Vec<&str> = update
+      .changes
+      .iter()
+      .map(|entry| entry.version.as_str())
+      .collect();
+    assert_eq!(versions, ["2.0.0", "1.1.0"]);
+    assert!(fs::read_to_string(&path).unwrap().ends_with("v2"));
+    assert_eq!(
+      fs::read_to_string(project_dir.join("hooks.log")).unwrap(),
+      "preUpdate\npostUpdate\n"
+    );
+    let lockfile = Lockfile::load(&Lockfile::path_for(project_dir)).unwrap();
+    assert_eq!(
+      lockfile.get("button").unwrap().version.as_deref(),
+      Some("2.0.0")
+    );
+  }
+
+  #[tokio::test]
+  async fn test_check_outdated_components_keeps_order() {
+    let installer = ComponentInstaller::new(create_test_config()).unwrap();
+    let names: Vec<String> = (0..20).map(|i| format!("missing-{}", i)).collect();
+
+    let results = installer
+      .check_outdated_components(&names, None)
+      .await
+      .unwrap();
+
+    let result_names: Vec<_> = results.iter().map(|(name, _)| name.clone()).collect();
+    assert_eq!(result_names, names);
+    assert!(results.iter().all(|(_, outdated)| !outdated));
+  }
+
   #[test]
   fn test_component_context_creation() {
     let config = create_test_config();
@@ -2222,11 +6705,8 @@ mod tests {
       schema: None,
       name: "test-button".to_string(),
       component_type: Some("registry:ui".to_string()),
-      dependencies: None,
-      dev_dependencies: None,
-      registry_dependencies: None,
-      files: vec![],
       registry: Some("test-registry".to_string()),
+      ..Default::default()
     };
 
     let context = installer.create_component_context(&component);
@@ -2235,4 +6715,93 @@ mod tests {
     assert_eq!(context.component_type, Some("registry:ui".to_string()));
     assert_eq!(context.registry, Some("test-registry".to_string()));
   }
+
+  #[tokio::test]
+  async fn test_non_interactive_defaults() {
+    let installer = ComponentInstaller::new(create_test_config())
+      .unwrap()
+      .with_interactive(false);
+
+    assert!(installer
+      .confirm("Install missing peer dependencies?", true)
+      .unwrap());
+    assert!(!installer.confirm("Continue anyway?", false).unwrap());
+
+    let err = installer
+      .install_components(None, None, false, false)
+      .await
+      .unwrap_err();
+    assert!(matches!(
+      err.downcast_ref::<InstallError>(),
+      Some(InstallError::NonInteractive(_))
+    ));
+  }
+
+  #[test]
+  fn test_package_manager_args() {
+    let mut config = create_test_config();
+    config.package_manager_args = Some(vec!["--frozen-lockfile".to_string()]);
+    let installer = ComponentInstaller::new(config).unwrap();
+
+    let detection = Detection {
+      manager: crate::package_manager::PackageManager::Pnpm,
+      source: crate::package_manager::DetectionSource::Heuristic,
+      project_root: PathBuf::from("."),
+      workspace: None,
+    };
+    let cmd = installer.dependency_install_command(
+      &detection,
+      InstallLocation::Package,
+      &["zod".to_string()],
+      true,
+    );
+    assert_eq!(
+      cmd,
+      ["pnpm", "add", "--save-dev", "--frozen-lockfile", "zod"]
+    );
+  }
+
+  #[test]
+  fn test_no_install() {
+    let installer = ComponentInstaller::new(create_test_config())
+      .unwrap()
+      .with_interactive(false)
+      .with_run_package_manager(false);
+
+    let deps = ComponentDependencies {
+      dependencies: vec!["zod@^3.23".to_string()],
+      dev_dependencies: Vec::new(),
+    };
+    installer.install_dependencies(&deps).unwrap();
+
+    // The package manager isn't even detected
+    assert!(installer.package_manager.get().is_none());
+  }
+
+  #[test]
+  fn test_shell_quote() {
+    assert_eq!(shell_quote("@scope/pkg@^1.2"), "@scope/pkg@^1.2");
+    assert_eq!(shell_quote("zod@>=3"), "'zod@>=3'");
+    assert_eq!(shell_quote("it's"), "'it'\\''s'");
+    assert_eq!(shell_quote(""), "''");
+  }
+
+  #[test]
+  fn test_adopt_helpers() {
+    assert_eq!(name_similarity("date-picker", "DatePicker"), 1.0);
+    assert!(name_similarity("my-button", "button") > name_similarity("my-button", "card"));
+    assert_eq!(name_similarity("ab", "cd"), 0.0);
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let dir = temp_dir.path().join("button");
+    fs::create_dir_all(dir.join("parts")).unwrap();
+    fs::write(dir.join("index.ts"), "").unwrap();
+    fs::write(dir.join("parts/button.svelte"), "").unwrap();
+    fs::write(dir.join(".DS_Store"), "").unwrap();
+    assert_eq!(
+      collect_local_files(&dir).unwrap(),
+      [dir.join("index.ts"), dir.join("parts/button.svelte")]
+    );
+    assert!(collect_local_files(&temp_dir.path().join("missing")).is_err());
+  }
 }
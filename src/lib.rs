@@ -0,0 +1,78 @@
+//! uiget as a library: the typed errors wrapping tools match on to tell
+//! failures apart, and the types their variants carry. The modules are
+//! public only for the `uiget` binary and aren't part of the API.
+
+mod barrel;
+mod browser;
+#[doc(hidden)]
+pub mod builder;
+#[doc(hidden)]
+pub mod cache;
+#[doc(hidden)]
+pub mod ci;
+#[doc(hidden)]
+pub mod cli;
+mod compression;
+mod condition;
+#[doc(hidden)]
+pub mod config;
+#[doc(hidden)]
+pub mod dev;
+mod diff;
+mod formatter;
+mod framework;
+mod git;
+mod highlight;
+mod hooks;
+#[doc(hidden)]
+pub mod i18n;
+mod imports;
+#[doc(hidden)]
+pub mod installer;
+#[doc(hidden)]
+pub mod lockfile;
+#[doc(hidden)]
+pub mod mcp;
+#[doc(hidden)]
+pub mod oauth;
+#[doc(hidden)]
+pub mod output;
+#[doc(hidden)]
+pub mod package_manager;
+#[doc(hidden)]
+pub mod paths;
+#[doc(hidden)]
+pub mod plugin;
+mod pm_exec;
+mod provenance;
+mod region;
+#[doc(hidden)]
+pub mod registry;
+#[doc(hidden)]
+pub mod report;
+#[doc(hidden)]
+pub mod scaffold;
+#[doc(hidden)]
+pub mod schema;
+#[doc(hidden)]
+pub mod self_update;
+#[doc(hidden)]
+pub mod server;
+mod state;
+mod storage;
+#[cfg(test)]
+mod test_server;
+mod transpile;
+mod validate;
+mod variables;
+#[doc(hidden)]
+pub mod workspace;
+
+pub use compression::{DecompressError, Encoding};
+pub use condition::ConditionError;
+pub use config::ConfigError;
+pub use framework::Framework;
+pub use installer::InstallError;
+pub use registry::RegistryError;
+pub use transpile::Unsupported;
+pub use validate::FieldError;
@@ -0,0 +1,412 @@
+use std::{
+  collections::{BTreeMap, BTreeSet},
+  fs,
+  path::{Path, PathBuf},
+  time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{paths, state};
+
+/// File name of the lockfile, stored in the project's state directory
+pub const LOCKFILE_NAME: &str = "lock.json";
+
+/// Current lockfile format version
+const LOCKFILE_VERSION: u32 = 1;
+
+/// Record of the components installed in a project
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Lockfile {
+  pub version: u32,
+  #[serde(default)]
+  pub components: BTreeMap<String, LockedComponent>,
+  /// Components the project took over with `uiget eject`, which uiget no
+  /// longer checks or updates
+  #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+  pub ejected: BTreeSet<String>,
+  /// Files components used to record but no longer do, because the
+  /// component was removed or stopped shipping them. Only these can be
+  /// pruned as orphans; other files next to installed ones may belong to
+  /// the project.
+  #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+  pub dropped: BTreeSet<String>,
+}
+
+/// An installed component as recorded in the lockfile
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LockedComponent {
+  /// Registry namespace the component was installed from
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub registry: Option<String>,
+  #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+  pub component_type: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub version: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub license: Option<String>,
+  /// Git ref a Git registry was read at, e.g. a tag
+  #[serde(rename = "ref", skip_serializing_if = "Option::is_none")]
+  pub git_ref: Option<String>,
+  /// Commit the Git ref resolved to at install time
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub commit: Option<String>,
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub dependencies: Vec<String>,
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub dev_dependencies: Vec<String>,
+  /// Files written for this component
+  #[serde(default)]
+  pub files: Vec<LockedFile>,
+  /// Unix timestamp (seconds) of the install
+  #[serde(default)]
+  pub installed_at: u64,
+}
+
+/// A file written by the installer with the hash of its content
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct LockedFile {
+  /// Path relative to the project directory, using forward slashes
+  pub path: String,
+  /// SHA-256 of the written content
+  pub hash: String,
+}
+
+impl Default for Lockfile {
+  fn default() -> Self {
+    Self {
+      version: LOCKFILE_VERSION,
+      components: BTreeMap::new(),
+      ejected: BTreeSet::new(),
+      dropped: BTreeSet::new(),
+    }
+  }
+}
+
+impl Lockfile {
+  /// Path of the lockfile for a project directory. Nothing on disk
+  /// changes; saving migrates an older layout.
+  pub fn path_for(project_dir: &Path) -> PathBuf {
+    state::ProjectState::at(project_dir).lockfile_path()
+  }
+
+  /// Load the lockfile, returning an empty one when it doesn't exist yet
+  pub fn load(path: &Path) -> Result<Self> {
+    if !path.exists() {
+      return Ok(Self::default());
+    }
+
+    let content = fs::read_to_string(path)?;
+    serde_json::from_str(&content)
+      .map_err(|e| anyhow!("Failed to parse lockfile '{}': {}", path.display(), e))
+  }
+
+  /// Save the lockfile atomically
+  pub fn save(&self, path: &Path) -> Result<()> {
+    let path = &state::writable_lockfile(path);
+    state::create_dir(paths::parent_dir(path))?;
+    let mut content = serde_json::to_string_pretty(self)?;
+    content.push('\n');
+    paths::write_atomic(path, content)?;
+    Ok(())
+  }
+
+  /// Load, change and save the lockfile while holding the directory's
+  /// advisory lock, so concurrent runs don't lose each other's entries
+  pub fn update(path: &Path, change: impl FnOnce(&mut Self)) -> Result<()> {
+    let path = &state::writable_lockfile(path);
+    state::create_dir(paths::parent_dir(path))?;
+    let _lock = paths::lock_for(path)?;
+    let mut lockfile = Self::load(path)?;
+    change(&mut lockfile);
+    lockfile.save(path)
+  }
+
+  /// Record (or replace) an installed component. Installing an ejected
+  /// component puts it back under uiget's management. Files the replaced
+  /// record had and the new one doesn't are remembered as dropped.
+  pub fn insert(&mut self, name: &str, component: LockedComponent) {
+    self.ejected.remove(name);
+    for file in &component.files {
+      self.dropped.remove(&file.path);
+    }
+    if let Some(old) = self.components.insert(name.to_string(), component) {
+      let current = &self.components[name].files;
+      for file in old.files {
+        if !current.iter().any(|kept| kept.path == file.path) {
+          self.dropped.insert(file.path);
+        }
+      }
+    }
+  }
+
+  /// Point components installed from registry `old` at `new`, returning
+  /// how many were changed
+  pub fn rename_registry(&mut self, old: &str, new: &str) -> usize {
+    let mut renamed = 0;
+    for component in self.components.values_mut() {
+      if component.registry.as_deref() == Some(old) {
+        component.registry = Some(new.to_string());
+        renamed += 1;
+      }
+    }
+    renamed
+  }
+
+  /// Get an installed component by name
+  pub fn get(&self, name: &str) -> Option<&LockedComponent> {
+    self.components.get(name)
+  }
+
+  /// Record of an installed file at `path`, from any component
+  pub fn find_file(&self, project_dir: &Path, path: &Path) -> Option<&LockedFile> {
+    let relative = LockedFile::new(project_dir, path, "").path;
+    self
+      .components
+      .values()
+      .flat_map(|component| &component.files)
+      .find(|file| file.path == relative)
+  }
+
+  /// Remove a component, returning its record. Its files are remembered
+  /// as dropped.
+  pub fn remove(&mut self, name: &str) -> Option<LockedComponent> {
+    let removed = self.components.remove(name)?;
+    self
+      .dropped
+      .extend(removed.files.iter().map(|file| file.path.clone()));
+    Some(removed)
+  }
+
+  /// Stop tracking a component the project now owns, returning its record.
+  /// Its files aren't dropped, since they stay in the project.
+  pub fn eject(&mut self, name: &str) -> Option<LockedComponent> {
+    self.ejected.insert(name.to_string());
+    self.components.remove(name)
+  }
+
+  /// Whether a component once recorded the file at `path`, and none does now
+  pub fn is_dropped(&self, project_dir: &Path, path: &Path) -> bool {
+    let relative = LockedFile::new(project_dir, path, "").path;
+    self.dropped.contains(&relative) && self.find_file(project_dir, path).is_none()
+  }
+
+  /// Whether the component was ejected
+  pub fn is_ejected(&self, name: &str) -> bool {
+    self.ejected.contains(name)
+  }
+}
+
+impl LockedComponent {
+  /// Check whether any recorded file was edited or deleted since install
+  pub fn is_modified(&self, project_dir: &Path) -> bool {
+    self.files.iter().any(|file| file.is_modified(project_dir))
+  }
+}
+
+impl LockedFile {
+  /// Create a record for a file written under `project_dir`
+  pub fn new(project_dir: &Path, path: &Path, content: &str) -> Self {
+    let relative = path.strip_prefix(project_dir).unwrap_or(path);
+    Self {
+      path: relative.to_string_lossy().replace('\\', "/"),
+      hash: hash_content(content),
+    }
+  }
+
+  /// Check whether the file was edited or deleted since install
+  pub fn is_modified(&self, project_dir: &Path) -> bool {
+    paths::read_to_string(&project_dir.join(&self.path))
+      .map(|content| hash_content(&content) != self.hash)
+      .unwrap_or(true)
+  }
+}
+
+/// SHA-256 hex digest of file content
+pub fn hash_content(content: &str) -> String {
+  format!("{:x}", Sha256::digest(content.as_bytes()))
+}
+
+/// Current time as a Unix timestamp in seconds
+pub fn now_timestamp() -> u64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|duration| duration.as_secs())
+    .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+  use tempfile::TempDir;
+
+  use super::*;
+
+  #[test]
+  fn test_lockfile_roundtrip() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = Lockfile::path_for(temp_dir.path());
+
+    let mut lockfile = Lockfile::load(&path).unwrap();
+    assert!(lockfile.components.is_empty());
+
+    lockfile.insert(
+      "button",
+      LockedComponent {
+        registry: Some("default".to_string()),
+        license: Some("MIT".to_string()),
+        files: vec![LockedFile::new(
+          temp_dir.path(),
+          &temp_dir.path().join("src/button.ts"),
+          "export {}",
+        )],
+        ..Default::default()
+      },
+    );
+    lockfile.save(&path).unwrap();
+
+    let loaded = Lockfile::load(&path).unwrap();
+    let button = loaded.get("button").unwrap();
+    assert_eq!(loaded.version, LOCKFILE_VERSION);
+    assert_eq!(button.registry.as_deref(), Some("default"));
+    assert_eq!(button.files[0].path, "src/button.ts");
+    assert_eq!(button.files[0].hash, hash_content("export {}"));
+  }
+
+  #[test]
+  fn test_update_keeps_existing_entries() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = Lockfile::path_for(temp_dir.path());
+
+    Lockfile::update(&path, |lockfile| {
+      lockfile.insert("button", LockedComponent::default())
+    })
+    .unwrap();
+    Lockfile::update(&path, |lockfile| {
+      lockfile.insert("card", LockedComponent::default())
+    })
+    .unwrap();
+
+    let loaded = Lockfile::load(&path).unwrap();
+    assert!(loaded.get("button").is_some());
+    assert!(loaded.get("card").is_some());
+  }
+
+  #[test]
+  fn test_eject() {
+    let mut lockfile = Lockfile::default();
+    lockfile.insert("button", LockedComponent::default());
+
+    assert!(lockfile.eject("button").is_some());
+    assert!(lockfile.get("button").is_none());
+    assert!(lockfile.is_ejected("button"));
+    let saved = serde_json::to_value(&lockfile).unwrap();
+    assert_eq!(saved["ejected"], serde_json::json!(["button"]));
+
+    // Installing it again hands it back to uiget
+    lockfile.insert("button", LockedComponent::default());
+    assert!(!lockfile.is_ejected("button"));
+  }
+
+  #[test]
+  fn test_dropped_files() {
+    let project_dir = Path::new("/project");
+    let tracked = |paths: &[&str]| LockedComponent {
+      files: paths
+        .iter()
+        .map(|path| LockedFile::new(project_dir, &project_dir.join(path), ""))
+        .collect(),
+      ..Default::default()
+    };
+    let is_dropped =
+      |lockfile: &Lockfile, path: &str| lockfile.is_dropped(project_dir, &project_dir.join(path));
+    let mut lockfile = Lockfile::default();
+    lockfile.insert("button", tracked(&["ui/button.tsx", "ui/button-old.tsx"]));
+    lockfile.insert("dialog", tracked(&["ui/dialog.tsx"]));
+    assert!(lockfile.dropped.is_empty());
+
+    // An update that stops shipping a file drops it
+    lockfile.insert("button", tracked(&["ui/button.tsx"]));
+    assert!(is_dropped(&lockfile, "ui/button-old.tsx"));
+    assert!(!is_dropped(&lockfile, "ui/button.tsx"));
+
+    lockfile.remove("dialog");
+    assert!(is_dropped(&lockfile, "ui/dialog.tsx"));
+
+    // Installing a file again takes it back
+    lockfile.insert("dialog", tracked(&["ui/dialog.tsx"]));
+    assert!(!is_dropped(&lockfile, "ui/dialog.tsx"));
+
+    // Ejected files stay with the project
+    lockfile.eject("button");
+    assert!(!is_dropped(&lockfile, "ui/button.tsx"));
+  }
+
+  #[test]
+  fn test_find_file() {
+    let project_dir = Path::new("/project");
+    let mut lockfile = Lockfile::default();
+    lockfile.insert(
+      "button",
+      LockedComponent {
+        files: vec![LockedFile::new(
+          project_dir,
+          &project_dir.join("src/ui/button.ts"),
+          "export {}",
+        )],
+        ..Default::default()
+      },
+    );
+
+    let file = lockfile
+      .find_file(project_dir, &project_dir.join("src/ui/button.ts"))
+      .unwrap();
+    assert_eq!(file.hash, hash_content("export {}"));
+    assert!(lockfile
+      .find_file(project_dir, &project_dir.join("src/ui/card.ts"))
+      .is_none());
+  }
+
+  #[test]
+  fn test_rename_registry() {
+    let mut lockfile = Lockfile::default();
+    let from = |registry: &str| LockedComponent {
+      registry: Some(registry.to_string()),
+      ..Default::default()
+    };
+    lockfile.insert("button", from("acme"));
+    lockfile.insert("card", from("acme"));
+    lockfile.insert("dialog", from("default"));
+
+    assert_eq!(lockfile.rename_registry("acme", "acme-ui"), 2);
+    assert_eq!(
+      lockfile.get("button").unwrap().registry.as_deref(),
+      Some("acme-ui")
+    );
+    assert_eq!(
+      lockfile.get("dialog").unwrap().registry.as_deref(),
+      Some("default")
+    );
+  }
+
+  #[test]
+  fn test_is_modified() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("button.ts");
+    fs::write(&path, "export {}").unwrap();
+
+    let component = LockedComponent {
+      files: vec![LockedFile::new(temp_dir.path(), &path, "export {}")],
+      ..Default::default()
+    };
+    assert!(!component.is_modified(temp_dir.path()));
+
+    fs::write(&path, "export const edited = true").unwrap();
+    assert!(component.is_modified(temp_dir.path()));
+
+    fs::remove_file(&path).unwrap();
+    assert!(component.is_modified(temp_dir.path()));
+  }
+}
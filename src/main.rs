@@ -1,21 +1,39 @@
-mod builder;
-mod cli;
-mod config;
-mod installer;
-mod package_manager;
-mod registry;
+use std::process::ExitCode;
 
 use anyhow::Result;
 use builder::RegistryBuilder;
 use clap::Parser;
-use cli::{Cli, Commands, RegistryAction};
+use cli::{CacheAction, Cli, Commands, NewAction, RegistryAction, WsAction};
 use colored::*;
-use config::Config;
-use installer::ComponentInstaller;
-use registry::RegistryManager;
+use config::{Config, ConfigError};
+use i18n::{tr, Message};
+use installer::{ComponentInstaller, InstallError};
+use output::{status, warning};
+use package_manager::DetectError;
+use registry::{Component, RegistryError, RegistryManager};
+use uiget::{
+  builder, cache, ci, cli, config, dev, i18n, installer, lockfile, mcp, oauth, output,
+  package_manager, paths, plugin, registry, report, scaffold, schema, self_update, server,
+  workspace,
+};
+
+/// Exit status for failures without a more specific class
+const EXIT_FAILURE: u8 = 1;
+/// Exit status when the configuration is missing or invalid
+const EXIT_CONFIG: u8 = 3;
+/// Exit status when a component or registry entry can't be found
+const EXIT_NOT_FOUND: u8 = 4;
+/// Exit status when a registry can't be reached
+const EXIT_NETWORK: u8 = 5;
+/// Exit status when a file would be overwritten without --force
+const EXIT_FILE_CONFLICT: u8 = 6;
+/// Exit status when the package manager fails
+const EXIT_PACKAGE_MANAGER: u8 = 7;
+/// Exit status when `uiget ci` finds drift its policy denies
+const EXIT_POLICY: u8 = 8;
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> ExitCode {
   let cli = Cli::parse();
 
   // Setup error handling and logging
@@ -23,15 +41,111 @@ async fn main() -> Result<()> {
     std::env::set_var("RUST_LOG", if cli.is_verbose() { "debug" } else { "info" });
   }
 
+  output::set_verbosity(cli.verbosity());
+  output::set_progress(cli.progress.is_some());
+  if let Some(locale) = cli.lang.or_else(i18n::Locale::from_env) {
+    i18n::set_locale(locale);
+  }
+
+  match run(&cli).await {
+    Ok(()) => {
+      output::progress("done", serde_json::json!({ "ok": true }));
+      ExitCode::SUCCESS
+    }
+    Err(err) => {
+      let code = exit_code_for(&err);
+      output::progress(
+        "done",
+        serde_json::json!({ "ok": false, "error": err.to_string(), "exitCode": code }),
+      );
+      eprintln!("{}", tr(Message::Error, &[&format!("{:?}", err)]));
+      ExitCode::from(code)
+    }
+  }
+}
+
+/// Map an error to the documented exit status of its failure class
+fn exit_code_for(err: &anyhow::Error) -> u8 {
+  for cause in err.chain() {
+    if let Some(err) = cause.downcast_ref::<ConfigError>() {
+      return match err {
+        ConfigError::AlreadyExists(_) => EXIT_FILE_CONFLICT,
+        _ => EXIT_CONFIG,
+      };
+    }
+
+    if let Some(err) = cause.downcast_ref::<RegistryError>() {
+      return match err {
+        RegistryError::RegistryNotFound(_) | RegistryError::InvalidUrl { .. } => EXIT_CONFIG,
+        RegistryError::ComponentNotFound(_) | RegistryError::NoFrameworkVariant { .. } => {
+          EXIT_NOT_FOUND
+        }
+        RegistryError::FrameworkRequired(_) => EXIT_CONFIG,
+        RegistryError::HttpStatus { .. }
+        | RegistryError::Network { .. }
+        | RegistryError::ResponseTooLarge { .. }
+        | RegistryError::InvalidJson { .. }
+        | RegistryError::InvalidComponent { .. }
+        | RegistryError::GraphQl { .. }
+        | RegistryError::InvalidFile { .. }
+        | RegistryError::InvalidCompression { .. }
+        | RegistryError::RateLimited { .. } => EXIT_NETWORK,
+      };
+    }
+
+    if let Some(err) = cause.downcast_ref::<InstallError>() {
+      return match err {
+        InstallError::FileExists(_)
+        | InstallError::CaseCollision(..)
+        | InstallError::LocallyModified(_) => EXIT_FILE_CONFLICT,
+        InstallError::NotInstalled(_) => EXIT_NOT_FOUND,
+        InstallError::NoRegistries => EXIT_CONFIG,
+        InstallError::PackageManager(_) => EXIT_PACKAGE_MANAGER,
+        InstallError::EngineMismatch { .. }
+        | InstallError::NonInteractive(_)
+        | InstallError::SizeNotConfirmed(_)
+        | InstallError::InvalidCondition { .. }
+        | InstallError::Untranspilable { .. }
+        | InstallError::UnsafeTarget(_) => EXIT_FAILURE,
+      };
+    }
+
+    if cause.downcast_ref::<ci::CiError>().is_some() {
+      return EXIT_POLICY;
+    }
+
+    if cause.downcast_ref::<DetectError>().is_some() {
+      return EXIT_PACKAGE_MANAGER;
+    }
+
+    if cause.downcast_ref::<reqwest::Error>().is_some() {
+      return EXIT_NETWORK;
+    }
+  }
+
+  EXIT_FAILURE
+}
+
+async fn run(cli: &Cli) -> Result<()> {
   match cli.command {
     Commands::Init {
       force,
+      ref from,
       ref base_color,
       ref css,
       ref components,
       ref utils,
     } => {
-      handle_init(&cli, force, base_color, css, components, utils).await?;
+      handle_init(
+        cli,
+        force,
+        from.as_deref(),
+        base_color.as_deref(),
+        css.as_deref(),
+        components.as_deref(),
+        utils.as_deref(),
+      )
+      .await?;
     }
 
     Commands::Add {
@@ -39,62 +153,244 @@ async fn main() -> Result<()> {
       ref registry,
       skip_deps,
       force,
+      ref file,
+      stdin,
+      all,
+      ref component_type,
+      registry_url: _,
+      sync: _,
+      no_install: _,
+      print_commands: _,
+      git_ref: _,
     } => {
-      handle_add(
-        &cli,
-        component.as_deref(),
-        registry.as_deref(),
-        skip_deps,
-        force,
-      )
-      .await?;
+      let force = force > 0;
+      if file.is_some() || stdin {
+        handle_add_local(cli, file.as_deref(), registry.as_deref(), skip_deps, force).await?;
+      } else {
+        handle_add(
+          cli,
+          component.as_deref(),
+          registry.as_deref(),
+          all,
+          component_type.as_deref(),
+          skip_deps,
+          force,
+        )
+        .await?;
+      }
+    }
+
+    Commands::Apply {
+      ref starter,
+      ref registry,
+      skip_deps,
+      force,
+      no_install: _,
+    } => {
+      handle_apply(cli, starter, registry.as_deref(), skip_deps, force).await?;
+    }
+
+    Commands::Link {
+      ref component,
+      ref registry,
+      hard,
+      force,
+    } => {
+      handle_link(cli, component, registry, hard, force)?;
+    }
+
+    Commands::Dev {
+      ref components,
+      ref registry,
+    } => {
+      handle_dev(cli, components, registry).await?;
+    }
+
+    Commands::Remove {
+      ref component,
+      force,
+    } => {
+      handle_remove(cli, component, force).await?;
     }
 
-    Commands::Remove { ref component } => {
-      handle_remove(&cli, component).await?;
+    Commands::Eject { ref component } => {
+      handle_eject(cli, component)?;
+    }
+
+    Commands::Prune { dry_run } => {
+      handle_prune(cli, dry_run)?;
+    }
+
+    Commands::Adopt {
+      ref path,
+      ref name,
+      ref registry,
+    } => {
+      handle_adopt(cli, path, name.as_deref(), registry.as_deref()).await?;
     }
 
     Commands::List {
       ref registry,
-      category: _,
+      registry_url: _,
+      ref category,
+      tree,
     } => {
-      handle_list(&cli, registry.as_deref()).await?;
+      if tree {
+        handle_list_tree(cli)?;
+      } else {
+        handle_list(cli, registry.as_deref(), category.as_deref()).await?;
+      }
     }
 
     Commands::Search {
       ref query,
       ref registry,
+      registry_url: _,
     } => {
-      handle_search(&cli, query, registry.as_deref()).await?;
+      handle_search(cli, query, registry.as_deref()).await?;
     }
 
     Commands::Registry { ref action } => {
-      handle_registry(&cli, action).await?;
+      handle_registry(cli, action).await?;
     }
 
     Commands::Update {
-      component: _,
-      registry: _,
+      ref component,
+      ref only,
+      ref registry,
+      ref write_report,
     } => {
-      println!("{} Update command not implemented yet", "!".yellow());
+      let components: Vec<String> = component.iter().chain(only).cloned().collect();
+      handle_update(
+        cli,
+        &components,
+        registry.as_deref(),
+        write_report.as_deref(),
+      )
+      .await?;
     }
 
     Commands::Info {
       ref component,
       ref registry,
+      files,
+      ref open,
+      usage,
+    } => {
+      handle_info(
+        cli,
+        component,
+        registry.as_deref(),
+        files,
+        open.as_deref(),
+        usage,
+      )
+      .await?;
+    }
+
+    Commands::Docs {
+      ref component,
+      ref registry,
+      open,
+    } => {
+      handle_docs(cli, component, registry.as_deref(), open).await?;
+    }
+
+    Commands::Changelog {
+      ref component,
+      ref registry,
+    } => {
+      handle_changelog(cli, component, registry.as_deref()).await?;
+    }
+
+    Commands::Outdated { ref registry, json } => {
+      handle_outdated(cli, registry.as_deref(), json).await?;
+    }
+
+    Commands::Licenses { json } => {
+      handle_licenses(cli, json)?;
+    }
+
+    Commands::Report { json, offline } => {
+      handle_report(cli, json, offline).await?;
+    }
+
+    Commands::Ci {
+      deny_modified,
+      deny_outdated,
+      max_age,
+      ref format,
+    } => {
+      let policy = ci::Policy {
+        deny_modified,
+        deny_outdated,
+        max_age,
+      };
+      handle_ci(cli, policy, format.as_deref()).await?;
+    }
+
+    Commands::Serve { api } => {
+      handle_serve(cli, api).await?;
+    }
+
+    Commands::Mcp { read_only } => {
+      handle_mcp(cli, read_only).await?;
+    }
+
+    Commands::Login {
+      ref provider,
+      no_browser,
     } => {
-      handle_info(&cli, component, registry.as_deref()).await?;
+      handle_login(cli, provider, no_browser).await?;
+    }
+
+    Commands::Logout { ref provider } => {
+      if oauth::logout(provider)? {
+        status!("{} Signed out of '{}'", "✓".green(), provider);
+      } else {
+        status!("{} Not signed in to '{}'", "!".yellow(), provider);
+      }
+    }
+
+    Commands::SelfUpdate { check } => {
+      self_update::self_update(check).await?;
     }
 
-    Commands::Outdated { ref registry } => {
-      handle_outdated(&cli, registry.as_deref()).await?;
+    Commands::Cache { ref action } => {
+      handle_cache(action)?;
+    }
+
+    Commands::Schema { ref kind } => {
+      if let Some(schema) = schema::schema(kind) {
+        print!("{}", schema);
+      }
+    }
+
+    Commands::New { ref action } => {
+      handle_new(action)?;
     }
 
     Commands::Build {
       ref registry,
       ref output,
+      infer_deps,
+      compress,
     } => {
-      handle_build(&cli, registry, output)?;
+      handle_build(cli, registry, output, infer_deps, compress)?;
+    }
+
+    Commands::Ws {
+      action,
+      ref globs,
+      max_parallel,
+      ref result,
+    } => match result {
+      Some(result) => handle_ws_project(cli, action, result).await?,
+      None => handle_ws(cli, action, globs, max_parallel.map(usize::from)).await?,
+    },
+
+    Commands::External(ref args) => {
+      handle_external(cli, args)?;
     }
   }
 
@@ -104,36 +400,49 @@ async fn main() -> Result<()> {
 async fn handle_init(
   cli: &Cli,
   force: bool,
-  base_color: &str,
-  css: &str,
-  components: &str,
-  utils: &str,
+  from: Option<&str>,
+  base_color: Option<&str>,
+  css: Option<&str>,
+  components: Option<&str>,
+  utils: Option<&str>,
 ) -> Result<()> {
   let config_path = cli.config_path();
 
   if config_path.exists() && !force {
-    return Err(anyhow::anyhow!(
-      "Configuration file '{}' already exists. Use --force to overwrite",
-      config_path.display()
-    ));
+    return Err(ConfigError::AlreadyExists(config_path).into());
   }
 
-  println!("{} Initializing uiget configuration...", "→".blue());
+  status!("{} Initializing uiget configuration...", "→".blue());
 
-  let mut config = Config::default();
-  config.tailwind.base_color = base_color.to_string();
-  config.tailwind.css = css.to_string();
-  config.aliases.components = components.to_string();
-  config.aliases.utils = utils.to_string();
+  let mut config = match from {
+    Some(location) => {
+      status!("  Template: {}", location.cyan());
+      Config::from_template(config::read_template(location).await?, location)?
+    }
+    None => Config::default(),
+  };
+  // Options given on the command line win over the template
+  if let Some(base_color) = base_color {
+    config.tailwind.base_color = base_color.to_string();
+  }
+  if let Some(css) = css {
+    config.tailwind.css = css.to_string();
+  }
+  if let Some(components) = components {
+    config.aliases.components = components.to_string();
+  }
+  if let Some(utils) = utils {
+    config.aliases.utils = utils.to_string();
+  }
 
   config.save_to_file(&config_path)?;
 
-  println!(
+  status!(
     "{} Configuration saved to {}",
     "✓".green(),
     config_path.display().to_string().cyan()
   );
-  println!(
+  status!(
     "  You can now add components with: {} {}",
     "uiget add".cyan(),
     "<component-name>".yellow()
@@ -146,11 +455,14 @@ async fn handle_add(
   cli: &Cli,
   component: Option<&str>,
   registry: Option<&str>,
+  all: bool,
+  component_type: Option<&str>,
   skip_deps: bool,
   force: bool,
 ) -> Result<()> {
-  let config = load_config(cli)?;
-  let installer = ComponentInstaller::new(config)?;
+  let installer = load_installer(cli).await?;
+  let adhoc_registry = cli.registry_url().map(adhoc_namespace);
+  let registry = adhoc_registry.as_deref().or(registry);
 
   // Parse component name to extract namespace if in @namespace/component format
   let (parsed_component, parsed_registry) = if let Some(comp_name) = component {
@@ -162,6 +474,25 @@ async fn handle_add(
     )
   };
 
+  // Wildcard patterns and --all expand against the registry index
+  let is_pattern = parsed_component
+    .as_deref()
+    .map(registry::is_glob_pattern)
+    .unwrap_or(false);
+
+  if all || is_pattern {
+    installer
+      .install_matching_components(
+        parsed_component.as_deref().unwrap_or("*"),
+        component_type,
+        parsed_registry.as_deref(),
+        force,
+        skip_deps,
+      )
+      .await?;
+    return installer.print_report();
+  }
+
   installer
     .install_components(
       parsed_component.as_deref(),
@@ -171,7 +502,91 @@ async fn handle_add(
     )
     .await?;
 
-  Ok(())
+  installer.print_report()
+}
+
+async fn handle_apply(
+  cli: &Cli,
+  starter: &str,
+  registry: Option<&str>,
+  skip_deps: bool,
+  force: bool,
+) -> Result<()> {
+  let installer = load_installer(cli).await?;
+  let (name, registry) = parse_component_with_namespace(starter, registry);
+  let name = name.as_deref().unwrap_or(starter);
+  let Some(starter) = installer.plan_starter(name, registry.as_deref()).await? else {
+    return Ok(());
+  };
+
+  // Settings such as aliases decide where the components go, so they are
+  // saved first and the installer reloaded
+  let installer = match starter
+    .config
+    .as_ref()
+    .filter(|settings| !settings.is_empty())
+  {
+    Some(settings) => {
+      let (allowed, _) = config::split_protected_settings(settings);
+      let config_path = cli.config_path();
+      Config::update(&config_path, |config| {
+        *config = config.with_settings(allowed, &starter.name)?;
+        Ok(())
+      })?;
+      status!(
+        "{} Applied the settings of '{}' to {}",
+        "✓".green(),
+        starter.name.cyan(),
+        config_path.display()
+      );
+      load_installer(cli).await?
+    }
+    None => installer,
+  };
+
+  installer
+    .install_starter(starter, registry.as_deref(), force, skip_deps)
+    .await?;
+  installer.print_report()
+}
+
+async fn handle_add_local(
+  cli: &Cli,
+  file: Option<&str>,
+  registry: Option<&str>,
+  skip_deps: bool,
+  force: bool,
+) -> Result<()> {
+  use std::io::Read;
+
+  let installer = load_installer(cli).await?;
+
+  // Read the component JSON from the given file, or from stdin
+  let (content, source) = if let Some(path) = file {
+    let content = std::fs::read_to_string(path)
+      .map_err(|e| anyhow::anyhow!("Failed to read component file '{}': {}", path, e))?;
+    (content, path.to_string())
+  } else {
+    let mut content = String::new();
+    std::io::stdin().read_to_string(&mut content)?;
+    (content, "<stdin>".to_string())
+  };
+
+  let component = Component::from_json(&content)
+    .map_err(|e| anyhow::anyhow!("Invalid component JSON in {}: {}", source, e))?;
+
+  status!(
+    "{} Installing component '{}' from {}...",
+    "→".blue(),
+    component.name.cyan(),
+    source.cyan()
+  );
+
+  installer
+    .install_local_component(component, registry, force, skip_deps)
+    .await?;
+
+  installer.print_report()
 }
 
 /// Parse component name to extract namespace if in @namespace/component format
@@ -205,84 +620,446 @@ fn parse_component_with_namespace(
   )
 }
 
-async fn handle_remove(cli: &Cli, component: &str) -> Result<()> {
+/// Path of a local registry.json given as a file or its directory
+fn local_registry_path(registry: &str) -> Result<std::path::PathBuf> {
+  let mut registry_path = std::path::PathBuf::from(registry);
+  if registry_path.is_dir() {
+    registry_path = registry_path.join("registry.json");
+  }
+  if !registry_path.exists() {
+    return Err(anyhow::anyhow!(
+      "Registry file '{}' not found",
+      registry_path.display()
+    ));
+  }
+
+  Ok(registry_path)
+}
+
+/// Style used from a local registry: the project's, then the registry's
+/// default
+fn local_style(config: &Config, registry_config: &builder::RegistryConfig) -> String {
+  config
+    .style
+    .clone()
+    .or_else(|| registry_config.default_style.clone())
+    .unwrap_or_else(|| "default".to_string())
+}
+
+fn handle_link(cli: &Cli, component: &str, registry: &str, hard: bool, force: bool) -> Result<()> {
+  use std::path::Path;
+
+  let registry_path = local_registry_path(registry)?;
+  let registry_config = builder::RegistryConfig::load(&registry_path)?;
+  let definition = registry_config
+    .components
+    .get(component)
+    .ok_or_else(|| RegistryError::ComponentNotFound(component.to_string()))?;
+
   let config = load_config(cli)?;
-  let installer = ComponentInstaller::new(config)?;
+  let style = local_style(&config, &registry_config);
+
+  let file_sources = definition.files_for_style(&style).ok_or_else(|| {
+    anyhow::anyhow!(
+      "No files defined for component '{}' with style '{}'",
+      component,
+      style
+    )
+  })?;
+
+  let base_path = registry_path
+    .parent()
+    .unwrap_or_else(|| Path::new("."))
+    .canonicalize()?;
+  let files = file_sources
+    .iter()
+    .map(|file| {
+      let source = base_path.join(&file.source);
+      if !source.exists() {
+        return Err(anyhow::anyhow!(
+          "Source file '{}' not found for component '{}'",
+          file.source,
+          component
+        ));
+      }
+      Ok((source, file.target.clone()))
+    })
+    .collect::<Result<Vec<_>>>()?;
+
+  status!(
+    "{} Linking {} from {}...",
+    "→".blue(),
+    component.cyan(),
+    registry_path.display().to_string().cyan()
+  );
+
+  let installer = ComponentInstaller::new(config)?.with_interactive(cli.is_interactive());
+  installer.link_component(
+    component,
+    definition.component_type.as_deref(),
+    &files,
+    hard,
+    force,
+  )?;
+
+  status!("{} Linked {}", "✓".green(), component.cyan());
+
+  // Linked files are used verbatim, so dependencies are left to the user
+  let registry_deps = definition.registry_dependencies.clone().unwrap_or_default();
+  let npm_deps = definition.dependencies.clone().unwrap_or_default();
+  if !registry_deps.is_empty() {
+    status!(
+      "  Registry dependencies are not linked: {}",
+      registry_deps.join(", ").yellow()
+    );
+  }
+  if !npm_deps.is_empty() {
+    status!(
+      "  npm dependencies are not installed: {}",
+      npm_deps.join(", ").yellow()
+    );
+  }
+
+  Ok(())
+}
+
+async fn handle_dev(cli: &Cli, components: &[String], registry: &str) -> Result<()> {
+  let registry_path = local_registry_path(registry)?;
+  let registry_config = builder::RegistryConfig::load(&registry_path)?;
+
+  let config = load_config(cli)?;
+  let style = local_style(&config, &registry_config);
+
+  // Without names, watch the registry's components installed in this project
+  let components = if components.is_empty() {
+    let lockfile =
+      lockfile::Lockfile::load(&lockfile::Lockfile::path_for(&std::env::current_dir()?))?;
+    let mut installed: Vec<String> = registry_config
+      .components
+      .keys()
+      .filter(|name| lockfile.get(name).is_some())
+      .cloned()
+      .collect();
+    installed.sort();
+    installed
+  } else {
+    components.to_vec()
+  };
+
+  if components.is_empty() {
+    status!(
+      "{} No components from {} are installed. Name the components to watch, e.g. {}",
+      "!".yellow(),
+      registry_path.display().to_string().cyan(),
+      "uiget dev button".cyan()
+    );
+    return Ok(());
+  }
+
+  let watcher = dev::DevWatcher::new(&registry_path, &style, components)?;
+  // The registry sources are the truth while developing: reinstalls don't
+  // stop to ask about edits to the installed copies
+  let installer = ComponentInstaller::new(config)?
+    .with_interactive(cli.is_interactive())
+    .with_overwrite_modified(true);
 
-  installer.remove_component(component)?;
+  watcher.run(&installer).await
+}
+
+async fn handle_remove(cli: &Cli, component: &str, force: bool) -> Result<()> {
+  let config = load_config(cli)?;
+  let installer = ComponentInstaller::new(config)?.with_interactive(cli.is_interactive());
+
+  installer.remove_component(component, force)?;
+
+  installer.print_report()
+}
+
+fn handle_eject(cli: &Cli, component: &str) -> Result<()> {
+  let config = load_config(cli)?;
+  let installer = ComponentInstaller::new(config)?.with_interactive(cli.is_interactive());
+
+  installer.eject_component(component)?;
 
   Ok(())
 }
 
-async fn handle_list(cli: &Cli, registry: Option<&str>) -> Result<()> {
+fn handle_prune(cli: &Cli, dry_run: bool) -> Result<()> {
   let config = load_config(cli)?;
-  let installer = ComponentInstaller::new(config)?;
+  let installer = ComponentInstaller::new(config)?.with_interactive(cli.is_interactive());
 
-  installer.list_components(registry).await?;
+  installer.prune(dry_run, cli.yes)?;
 
   Ok(())
 }
 
-async fn handle_search(cli: &Cli, query: &str, registry: Option<&str>) -> Result<()> {
+async fn handle_adopt(
+  cli: &Cli,
+  path: &str,
+  name: Option<&str>,
+  registry: Option<&str>,
+) -> Result<()> {
+  let installer = load_installer(cli).await?;
+  installer
+    .adopt_component(std::path::Path::new(path), name, registry)
+    .await?;
+
+  Ok(())
+}
+
+fn handle_list_tree(cli: &Cli) -> Result<()> {
   let config = load_config(cli)?;
   let installer = ComponentInstaller::new(config)?;
 
-  println!("{} Searching for '{}'...", "→".blue(), query.cyan());
+  installer.show_tree()?;
+
+  Ok(())
+}
+
+async fn handle_list(cli: &Cli, registry: Option<&str>, category: Option<&str>) -> Result<()> {
+  let installer = load_installer(cli).await?;
+  let adhoc_registry = cli.registry_url().map(adhoc_namespace);
+  let registry = adhoc_registry.as_deref().or(registry);
+
+  installer.list_components(registry, category).await?;
+
+  Ok(())
+}
+
+async fn handle_search(cli: &Cli, query: &str, registry: Option<&str>) -> Result<()> {
+  let installer = load_installer(cli).await?;
+  let adhoc_registry = cli.registry_url().map(adhoc_namespace);
+  let registry = adhoc_registry.as_deref().or(registry);
+
+  status!("{} Searching for '{}'...", "→".blue(), query.cyan());
   installer.search_components(query, registry).await?;
 
   Ok(())
 }
 
 async fn handle_registry(cli: &Cli, action: &RegistryAction) -> Result<()> {
+  // Authoring a registry doesn't need a project configuration
+  match action {
+    RegistryAction::Init { dir, name, force } => {
+      return handle_registry_init(dir, name.as_deref(), *force);
+    }
+    RegistryAction::Import {
+      dir,
+      registry,
+      force,
+    } => {
+      return handle_registry_import(dir, registry, *force);
+    }
+    _ => {}
+  }
+
   let config_path = cli.config_path();
-  let mut config = load_config(cli)?;
+  let config = load_config(cli)?;
 
   match action {
-    RegistryAction::Add { namespace, url } => {
+    RegistryAction::Init { .. } | RegistryAction::Import { .. } => {
+      unreachable!("registry authoring is handled before loading config")
+    }
+
+    RegistryAction::Add {
+      namespace,
+      url,
+      headers,
+      params,
+      style,
+    } => {
       // Validate URL by creating a registry client
       let mut manager = RegistryManager::new();
-      manager.add_registry_with_style(namespace.clone(), url.clone(), config.style.clone())?;
-
-      // Add to config
-      config.set_registry(namespace.clone(), url.clone());
-      config.save_to_file(&config_path)?;
-
-      println!(
-        "{} Added registry '{}' -> {}",
-        "✓".green(),
-        namespace.cyan(),
+      if headers.is_empty() && params.is_empty() && style.is_none() {
+        manager.add_registry_with_style(namespace.clone(), url.clone(), config.style.clone())?;
+        Config::update(&config_path, |config| {
+          config.set_registry(namespace.clone(), url.clone());
+          Ok(())
+        })?;
+      } else {
+        let to_map = |pairs: &Vec<(String, String)>| {
+          (!pairs.is_empty()).then(|| pairs.iter().cloned().collect())
+        };
+        let registry_config = config::RegistryConfig::Object {
+          url: url.clone(),
+          params: to_map(params),
+          headers: to_map(headers),
+          username: None,
+          password: None,
+          style: style.clone(),
+          default_type: None,
+          target_override: None,
+          max_concurrent_requests: None,
+          user_agent: None,
+          signing: None,
+          response_path: None,
+          graphql: None,
+          extra: Default::default(),
+        };
+        manager.add_registry_config_with_style(
+          namespace.clone(),
+          registry_config.clone(),
+          style.clone().or_else(|| config.style.clone()),
+        )?;
+        Config::update(&config_path, |config| {
+          config.set_registry_config(namespace.clone(), registry_config);
+          Ok(())
+        })?;
+      }
+
+      status!(
+        "{} Added registry '{}' -> {}",
+        "✓".green(),
+        namespace.cyan(),
         url.blue()
       );
     }
 
     RegistryAction::Remove { namespace } => {
-      if config.registries.remove(namespace).is_some() {
-        config.save_to_file(&config_path)?;
-        println!("{} Removed registry '{}'", "✓".green(), namespace.cyan());
+      let mut removed = false;
+      Config::update(&config_path, |config| {
+        removed = config.registries.shift_remove(namespace).is_some();
+        Ok(())
+      })?;
+      if removed {
+        status!("{} Removed registry '{}'", "✓".green(), namespace.cyan());
       } else {
-        println!("{} Registry '{}' not found", "!".yellow(), namespace.cyan());
+        status!("{} Registry '{}' not found", "!".yellow(), namespace.cyan());
+      }
+    }
+
+    RegistryAction::Rename { old, new } => {
+      let Some(registry_config) = config.registries.get(old) else {
+        return Err(RegistryError::RegistryNotFound(old.clone()).into());
+      };
+      if config.registries.contains_key(new) {
+        return Err(anyhow::anyhow!("Registry '{}' already exists", new));
+      }
+
+      // With namespaceDirs, the namespace is also the directory the
+      // registry's components live in
+      let lockfile_path = lockfile::Lockfile::path_for(&std::env::current_dir()?);
+      let lockfile = lockfile::Lockfile::load(&lockfile_path)?;
+      let namespace_dir = format!("{}/", old.trim_start_matches('@'));
+      if config.namespace_dirs == Some(true)
+        && !matches!(old.as_str(), "default" | "@default")
+        && registry_config.target_override().is_none()
+        && lockfile
+          .components
+          .keys()
+          .any(|name| name.starts_with(&namespace_dir))
+      {
+        return Err(anyhow::anyhow!(
+          "Components from '{}' are installed in its namespace directory ({}); remove them before renaming the registry, then add them again",
+          old,
+          namespace_dir
+        ));
+      }
+
+      Config::update(&config_path, |config| {
+        if config.registries.contains_key(new) {
+          return Err(anyhow::anyhow!("Registry '{}' already exists", new));
+        }
+        config.rename_registry(old, new);
+        Ok(())
+      })?;
+      let mut renamed = 0;
+      if lockfile_path.exists() {
+        lockfile::Lockfile::update(&lockfile_path, |lockfile| {
+          renamed = lockfile.rename_registry(old, new);
+        })?;
+      }
+
+      status!(
+        "{} Renamed registry '{}' to '{}' ({} installed {})",
+        "✓".green(),
+        old.cyan(),
+        new.cyan(),
+        renamed,
+        if renamed == 1 {
+          "component"
+        } else {
+          "components"
+        }
+      );
+    }
+
+    RegistryAction::Export { namespaces, output } => {
+      let mut set = config::RegistrySet::default();
+      for (namespace, registry_config) in &config.registries {
+        if !namespaces.is_empty() && !namespaces.contains(namespace) {
+          continue;
+        }
+        let (shared, removed) = registry_config.without_secrets();
+        if !removed.is_empty() {
+          // stderr, so the JSON on stdout stays clean
+          warning!(
+            "{} Left out of '{}': {}",
+            "!".yellow(),
+            namespace,
+            removed.join(", ")
+          );
+        }
+        set.registries.insert(namespace.clone(), shared);
+      }
+      if let Some(missing) = namespaces
+        .iter()
+        .find(|namespace| !config.registries.contains_key(*namespace))
+      {
+        return Err(RegistryError::RegistryNotFound(missing.clone()).into());
+      }
+
+      let json = serde_json::to_string_pretty(&set)? + "\n";
+      match output {
+        Some(output) => {
+          paths::write_atomic(std::path::Path::new(output), json)?;
+          status!(
+            "{} Exported {} registries to {}",
+            "✓".green(),
+            set.registries.len(),
+            output
+          );
+        }
+        None => print!("{}", json),
+      }
+    }
+
+    RegistryAction::ImportSet { source, force } => {
+      let registries = handle_registry_import_set(cli, &config, source, *force).await?;
+      if !registries.is_empty() {
+        Config::update(&config_path, |config| {
+          for (namespace, registry_config) in &registries {
+            config.set_registry_config(namespace.clone(), registry_config.clone());
+          }
+          Ok(())
+        })?;
+      }
+      for (namespace, _) in &registries {
+        status!("{} Added registry '{}'", "✓".green(), namespace.cyan());
       }
     }
 
     RegistryAction::List => {
       if config.registries.is_empty() {
-        println!("{} No registries configured", "!".yellow());
+        status!("{} No registries configured", "!".yellow());
       } else {
-        println!("{} Configured registries:", "📦".blue());
+        status!("{} Configured registries:", "📦".blue());
         for (namespace, registry_config) in &config.registries {
-          println!(
+          status!(
             "  {} {} -> {}",
             "→".blue(),
             namespace.cyan(),
-            registry_config.url().blue()
+            config::strip_user_info(registry_config.url())
+              .unwrap_or_else(|| registry_config.url().to_string())
+              .blue()
           );
         }
       }
     }
 
     RegistryAction::Test { namespace } => {
-      if let Some(registry_config) = config.get_registry(&namespace) {
-        println!("{} Testing registry '{}'...", "→".blue(), namespace.cyan());
+      if let Some(registry_config) = config.get_registry(namespace) {
+        status!("{} Testing registry '{}'...", "→".blue(), namespace.cyan());
 
         let mut manager = RegistryManager::new();
         manager.add_registry_config_with_style(
@@ -291,10 +1068,10 @@ async fn handle_registry(cli: &Cli, action: &RegistryAction) -> Result<()> {
           config.style.clone(),
         )?;
 
-        if let Some(registry) = manager.get_registry(&namespace) {
+        if let Some(registry) = manager.get_registry(namespace) {
           match registry.fetch_index().await {
             Ok(index) => {
-              println!(
+              status!(
                 "{} Registry '{}' is working ({} components available)",
                 "✓".green(),
                 namespace.cyan(),
@@ -302,7 +1079,7 @@ async fn handle_registry(cli: &Cli, action: &RegistryAction) -> Result<()> {
               );
             }
             Err(e) => {
-              println!(
+              status!(
                 "{} Registry '{}' failed: {}",
                 "✗".red(),
                 namespace.cyan(),
@@ -311,72 +1088,789 @@ async fn handle_registry(cli: &Cli, action: &RegistryAction) -> Result<()> {
             }
           }
         } else {
-          println!("{} Failed to create registry client", "✗".red());
+          status!("{} Failed to create registry client", "✗".red());
         }
       } else {
-        println!("{} Registry '{}' not found", "!".yellow(), namespace.cyan());
+        status!("{} Registry '{}' not found", "!".yellow(), namespace.cyan());
       }
     }
+
+    RegistryAction::Diff {
+      left,
+      right,
+      contents,
+    } => {
+      handle_registry_diff(&config, left, right, *contents).await?;
+    }
   }
 
   Ok(())
 }
 
-async fn handle_info(cli: &Cli, component: &str, registry: Option<&str>) -> Result<()> {
-  let config = load_config(cli)?;
-  let installer = ComponentInstaller::new(config)?;
+/// Add the registries of a shared set to `config`, asking about those
+/// configured differently and for credentials left out of the set
+/// Registries of a shared set to add to the config, asking what to do with
+/// the ones already configured differently
+async fn handle_registry_import_set(
+  cli: &Cli,
+  config: &Config,
+  source: &str,
+  force: bool,
+) -> Result<Vec<(String, config::RegistryConfig)>> {
+  let set: config::RegistrySet = serde_json::from_value(config::read_template(source).await?)
+    .map_err(|source_error| ConfigError::Template {
+      template: source.to_string(),
+      source: source_error,
+    })?;
+
+  let mut added = Vec::new();
+  for (namespace, mut registry_config) in set.registries {
+    let mut namespace = namespace;
+    if let Some(existing) = config.registries.get(&namespace) {
+      if serde_json::to_value(existing)? == serde_json::to_value(&registry_config)? {
+        continue;
+      }
+      status!(
+        "{} '{}' is already configured: {} (the set has {})",
+        "!".yellow(),
+        namespace.cyan(),
+        existing.url().blue(),
+        registry_config.url().blue()
+      );
+      let choices = ["Keep mine", "Use the set's", "Add it under another name"];
+      let choice = if cli.is_interactive() {
+        dialoguer::Select::with_theme(&dialoguer::theme::ColorfulTheme::default())
+          .with_prompt(format!("What should '{}' be?", namespace))
+          .items(choices)
+          .default(0)
+          .interact()?
+      } else {
+        usize::from(force)
+      };
+      match choice {
+        0 => continue,
+        1 => {}
+        _ => {
+          namespace =
+            dialoguer::Input::<String>::with_theme(&dialoguer::theme::ColorfulTheme::default())
+              .with_prompt("Namespace")
+              .validate_with(|name: &String| {
+                let taken = config.registries.contains_key(name)
+                  || added.iter().any(|(namespace, _)| namespace == name);
+                if taken {
+                  Err("that namespace is taken")
+                } else {
+                  Ok(())
+                }
+              })
+              .interact_text()?;
+        }
+      }
+    }
 
-  installer.show_component_info(component, registry).await?;
+    // A signer command would run on every request to the registry, so one
+    // from a shared set is only kept when the user confirms it
+    if let Some(command) = registry_config
+      .signing()
+      .and_then(|signing| signing.command.clone())
+    {
+      status!(
+        "{} '{}' signs each request by running: {}",
+        "!".yellow(),
+        namespace.cyan(),
+        command.bold()
+      );
+      let trusted = cli.is_interactive()
+        && dialoguer::Confirm::with_theme(&dialoguer::theme::ColorfulTheme::default())
+          .with_prompt(format!("Run this command for requests to '{}'?", namespace))
+          .default(false)
+          .interact()?;
+      if !trusted {
+        registry_config.remove_signing_command();
+        status!(
+          "{} Left out the signing command of '{}'; add it to {} if you trust it",
+          "!".yellow(),
+          namespace,
+          cli.config_path().display()
+        );
+      }
+    }
+
+    // Credentials are left out of shared sets
+    let empty: Vec<String> = registry_config
+      .empty_values()
+      .into_iter()
+      .map(String::from)
+      .collect();
+    for name in empty {
+      let value = if cli.is_interactive() {
+        Some(
+          dialoguer::Password::with_theme(&dialoguer::theme::ColorfulTheme::default())
+            .with_prompt(format!("{} for '{}' (empty to leave out)", name, namespace))
+            .allow_empty_password(true)
+            .interact()?,
+        )
+        .filter(|value| !value.is_empty())
+      } else {
+        None
+      };
+      if value.is_none() {
+        status!(
+          "{} '{}' needs '{}'; set it in {}",
+          "!".yellow(),
+          namespace,
+          name,
+          cli.config_path().display()
+        );
+      }
+      registry_config.fill_value(&name, value);
+    }
+
+    added.push((namespace, registry_config));
+  }
+
+  if added.is_empty() {
+    status!(
+      "{} All registries of the set are already configured",
+      "✓".green()
+    );
+  }
+  Ok(added)
+}
+
+async fn handle_registry_diff(
+  config: &Config,
+  left: &str,
+  right: &str,
+  contents: bool,
+) -> Result<()> {
+  let manager = RegistryManager::from_config(config)?;
+  let client = |namespace: &str| {
+    manager
+      .get_registry(namespace)
+      .ok_or_else(|| RegistryError::RegistryNotFound(namespace.to_string()))
+  };
+  let (left_client, right_client) = (client(left)?, client(right)?);
+
+  status!(
+    "{} Comparing '{}' with '{}'...",
+    "→".blue(),
+    left.cyan(),
+    right.cyan()
+  );
+
+  let (left_index, right_index) =
+    tokio::try_join!(left_client.fetch_index(), right_client.fetch_index())?;
+  let diff = registry::diff_indexes(&left_index, &right_index);
+
+  let print_names = |title: String, marker: ColoredString, names: &[String]| {
+    if names.is_empty() {
+      return;
+    }
+    status!("\n{} ({}):", title, names.len().to_string().yellow());
+    for name in names {
+      status!("  {} {}", marker, name);
+    }
+  };
+
+  print_names(format!("Only in '{}'", left), "-".red(), &diff.only_in_left);
+  print_names(
+    format!("Only in '{}'", right),
+    "+".green(),
+    &diff.only_in_right,
+  );
+
+  if contents {
+    let mut changed = Vec::new();
+    for name in &diff.in_both {
+      let (left_component, right_component) = tokio::try_join!(
+        left_client.fetch_component(name),
+        right_client.fetch_component(name)
+      )?;
+      if !registry::same_files(&left_component, &right_component) {
+        changed.push(name.clone());
+      }
+    }
+
+    print_names("Content differs".to_string(), "~".yellow(), &changed);
+    status!(
+      "\n{} {} of {} shared components are identical",
+      "✓".green(),
+      (diff.in_both.len() - changed.len()).to_string().cyan(),
+      diff.in_both.len()
+    );
+  } else {
+    status!(
+      "\n{} {} components in both (use --contents to compare files)",
+      "✓".green(),
+      diff.in_both.len().to_string().cyan()
+    );
+  }
 
   Ok(())
 }
 
-async fn handle_outdated(cli: &Cli, registry: Option<&str>) -> Result<()> {
+async fn handle_info(
+  cli: &Cli,
+  component: &str,
+  registry: Option<&str>,
+  show_files: bool,
+  open: Option<&str>,
+  usage: bool,
+) -> Result<()> {
+  let installer = load_installer(cli).await?;
+
+  match open {
+    Some(link) => {
+      installer
+        .open_component_link(component, registry, link)
+        .await?
+    }
+    None if usage => installer.show_component_usage(component, registry).await?,
+    None => {
+      installer
+        .show_component_info(component, registry, show_files)
+        .await?
+    }
+  }
+
+  Ok(())
+}
+
+async fn handle_docs(cli: &Cli, component: &str, registry: Option<&str>, open: bool) -> Result<()> {
+  let installer = load_installer(cli).await?;
+
+  installer
+    .show_component_docs(component, registry, open)
+    .await?;
+
+  Ok(())
+}
+
+async fn handle_login(cli: &Cli, provider: &str, no_browser: bool) -> Result<()> {
   let config = load_config(cli)?;
-  let installer = ComponentInstaller::new(config)?;
+  let Some(settings) = config
+    .oauth
+    .as_ref()
+    .and_then(|providers| providers.get(provider))
+  else {
+    let known: Vec<&str> = config
+      .oauth
+      .iter()
+      .flat_map(|providers| providers.keys().map(String::as_str))
+      .collect();
+    return Err(anyhow::anyhow!(
+      "No OAuth provider '{}' in the config (configured: {})",
+      provider,
+      if known.is_empty() {
+        "none".to_string()
+      } else {
+        known.join(", ")
+      }
+    ));
+  };
+
+  oauth::login(provider, settings, !no_browser && cli.is_interactive()).await
+}
+
+async fn handle_update(
+  cli: &Cli,
+  components: &[String],
+  registry: Option<&str>,
+  write_report: Option<&std::path::Path>,
+) -> Result<()> {
+  let installer = load_installer(cli).await?;
+
+  status!("{} Checking for updates...", "→".blue());
+  let report = installer.update_components(components, registry).await?;
+
+  installer.print_report()?;
+  if let Some(path) = write_report {
+    report.write(path)?;
+    status!(
+      "{} Update report written to {}",
+      "✓".green(),
+      path.display().to_string().cyan()
+    );
+  }
+  Ok(())
+}
+
+async fn handle_changelog(cli: &Cli, component: &str, registry: Option<&str>) -> Result<()> {
+  let installer = load_installer(cli).await?;
+
+  installer
+    .show_component_changelog(component, registry)
+    .await?;
 
-  println!("{} Checking for outdated components...", "→".blue());
+  Ok(())
+}
 
-  let installed_components = installer.get_installed_components()?;
+async fn handle_outdated(cli: &Cli, registry: Option<&str>, json: bool) -> Result<()> {
+  let installer = load_installer(cli).await?;
 
-  if installed_components.is_empty() {
-    println!("{} No components installed", "!".yellow());
+  if json {
+    let report = installer.outdated_report(registry).await?;
+    println!("{}", serde_json::to_string_pretty(&report)?);
     return Ok(());
   }
 
-  let outdated_results = installer
-    .check_outdated_components(&installed_components, registry)
-    .await?;
+  status!("{} Checking for outdated components...", "→".blue());
 
-  let outdated_components: Vec<&String> = outdated_results
+  let report = installer.outdated_report(registry).await?;
+  if report.components.is_empty() {
+    status!("{} No components installed", "!".yellow());
+    return Ok(());
+  }
+
+  let outdated_components: Vec<_> = report
+    .components
     .iter()
-    .filter_map(|(name, is_outdated)| if *is_outdated { Some(name) } else { None })
+    .filter(|component| component.outdated)
     .collect();
 
   if outdated_components.is_empty() {
-    println!("{} All components are up to date!", "✓".green());
+    status!("{} All components are up to date!", "✓".green());
   } else {
-    println!(
+    status!(
       "\n{} Found {} outdated component(s):",
       "⚠".yellow(),
       outdated_components.len().to_string().yellow()
     );
 
     for component in outdated_components {
-      println!("  {} {} {}", "→".dimmed(), "⚠".yellow(), component.yellow());
+      match (&component.installed, &component.latest) {
+        (Some(installed), Some(latest)) => status!(
+          "  {} {} {} {} → {}",
+          "→".dimmed(),
+          "⚠".yellow(),
+          component.name.yellow(),
+          installed.dimmed(),
+          latest.green()
+        ),
+        _ => status!(
+          "  {} {} {}",
+          "→".dimmed(),
+          "⚠".yellow(),
+          component.name.yellow()
+        ),
+      }
     }
 
-    println!(
+    status!(
       "\n{} Run {} to update components",
       "💡".blue(),
-      "uiget add <component> --force".cyan()
+      "uiget update".cyan()
+    );
+  }
+
+  Ok(())
+}
+
+async fn handle_ws(
+  cli: &Cli,
+  action: WsAction,
+  globs: &[String],
+  max_parallel: Option<usize>,
+) -> Result<()> {
+  if cli.config.is_some() {
+    anyhow::bail!("--config can't be used with 'ws'; each project uses its own configuration");
+  }
+
+  let current_dir = std::env::current_dir()?;
+  let root = package_manager::find_workspace(&current_dir)
+    .map(|workspace| workspace.root)
+    .unwrap_or(current_dir);
+  let projects = workspace::discover_projects(&root, globs);
+  if projects.is_empty() {
+    anyhow::bail!("No uiget projects found under {}", root.display());
+  }
+
+  // Package managers share the workspace's lockfile, so updates run one at
+  // a time unless asked otherwise
+  let max_parallel = max_parallel.unwrap_or(match action {
+    WsAction::Update => 1,
+    _ => std::thread::available_parallelism().map_or(1, usize::from),
+  });
+  let max_parallel = max_parallel.min(projects.len());
+  status!(
+    "{} {} project(s) in {}{}",
+    "→".blue(),
+    projects.len(),
+    root.display(),
+    match max_parallel {
+      1 => String::new(),
+      n => format!(", {} at a time", n),
+    }
+  );
+
+  // Runs in parallel can't prompt, so updates are confirmed once up front
+  if max_parallel > 1 && matches!(action, WsAction::Update) && cli.is_interactive() {
+    let confirmed = dialoguer::Confirm::with_theme(&dialoguer::theme::ColorfulTheme::default())
+      .with_prompt(format!(
+        "Update outdated components in {} projects without asking in each?",
+        projects.len()
+      ))
+      .default(true)
+      .interact()?;
+    if !confirmed {
+      return Ok(());
+    }
+  }
+
+  let mut args: Vec<std::ffi::OsString> = Vec::new();
+  if cli.is_verbose() {
+    args.push("--verbose".into());
+  }
+  if cli.is_quiet() {
+    args.push("--quiet".into());
+  }
+  if let Some(locale) = cli.lang {
+    args.push("--lang".into());
+    args.push(
+      match locale {
+        i18n::Locale::En => "en",
+        i18n::Locale::PtBr => "pt-BR",
+      }
+      .into(),
+    );
+  }
+  if let Some(framework) = cli.framework {
+    args.push("--framework".into());
+    args.push(framework.as_str().into());
+  }
+  if cli.yes || max_parallel > 1 {
+    args.push("--yes".into());
+  }
+  args.push("ws".into());
+  args.push(action.name().into());
+
+  let report = workspace::run_projects(projects, &args, max_parallel).await;
+
+  let (label, none) = match action {
+    WsAction::List => ("installed", "no components"),
+    WsAction::Outdated => ("outdated", "up to date"),
+    WsAction::Update => ("updated", "nothing to update"),
+  };
+  status!();
+  for line in report.lines(label, none) {
+    status!("{}", line);
+  }
+
+  match report.failures() {
+    0 => Ok(()),
+    failures => anyhow::bail!(
+      "'ws' failed in {} of {} projects",
+      failures,
+      report.projects.len()
+    ),
+  }
+}
+
+/// Run a `ws` command in the project in the current directory and write
+/// its outcome to `result` for the `ws` run that started it
+async fn handle_ws_project(cli: &Cli, action: WsAction, result: &std::path::Path) -> Result<()> {
+  let outcome = ws_run(cli, action).await;
+  let reported: workspace::ProjectOutcome = match &outcome {
+    Ok(components) => Ok(components.clone()),
+    Err(e) => Err(e.to_string()),
+  };
+  std::fs::write(result, serde_json::to_string(&reported)?)?;
+  outcome.map(|_| ())
+}
+
+/// Run a `ws` command in the project in the current directory, returning
+/// the components it listed, found outdated or updated
+async fn ws_run(cli: &Cli, action: WsAction) -> Result<Vec<String>> {
+  let installer = load_installer(cli).await?;
+  let installed = installer.get_installed_components()?;
+
+  match action {
+    WsAction::List => Ok(installed),
+    WsAction::Outdated => Ok(
+      installer
+        .check_outdated_components(&installed, None)
+        .await?
+        .into_iter()
+        .filter_map(|(name, is_outdated)| is_outdated.then_some(name))
+        .collect(),
+    ),
+    WsAction::Update => {
+      let updated = installer.update_components(&[], None).await?;
+      installer.print_report()?;
+      Ok(updated.names())
+    }
+  }
+}
+
+async fn handle_report(cli: &Cli, json: bool, offline: bool) -> Result<()> {
+  let installer = load_installer(cli).await?;
+  let report = installer.project_report(!offline).await?;
+
+  if json {
+    println!("{}", serde_json::to_string_pretty(&report)?);
+  } else {
+    for line in report.lines(lockfile::now_timestamp()) {
+      status!("{}", line);
+    }
+  }
+
+  Ok(())
+}
+
+async fn handle_ci(cli: &Cli, policy: ci::Policy, format: Option<&str>) -> Result<()> {
+  let installer = load_installer(cli).await?.with_interactive(false);
+  let current_dir = std::env::current_dir()?;
+  let lockfile = lockfile::Lockfile::load(&lockfile::Lockfile::path_for(&current_dir))?;
+
+  status!("{} Checking installed components...", "→".blue());
+  let mut findings =
+    ci::check_lockfile(&lockfile, &current_dir, &policy, lockfile::now_timestamp());
+  let installed = installer.get_installed_components()?;
+  let outdated: Vec<String> = installer
+    .check_outdated_components(&installed, None)
+    .await?
+    .into_iter()
+    .filter_map(|(name, outdated)| outdated.then_some(name))
+    .collect();
+  findings.extend(ci::outdated_findings(&lockfile, &outdated, &policy));
+
+  let github = match format {
+    Some(format) => format == "github",
+    None => std::env::var("GITHUB_ACTIONS").is_ok_and(|value| value == "true"),
+  };
+  if github {
+    // Annotations name files from the repository root, which is the
+    // workspace on GitHub runners
+    let prefix = std::env::var_os("GITHUB_WORKSPACE")
+      .and_then(|workspace| {
+        current_dir
+          .strip_prefix(std::path::Path::new(&workspace))
+          .ok()
+          .map(std::path::Path::to_path_buf)
+      })
+      .unwrap_or_default();
+    for mut finding in findings.clone() {
+      finding.file = finding
+        .file
+        .map(|file| prefix.join(file).to_string_lossy().replace('\\', "/"));
+      println!("{}", finding.annotation());
+    }
+  } else {
+    for finding in &findings {
+      status!("{}", finding.line());
+    }
+  }
+
+  let errors = ci::errors(&findings);
+  match findings.len() {
+    0 => status!("{} No drift found", "✓".green()),
+    total => status!(
+      "{} {} error(s), {} warning(s)",
+      "📊".blue(),
+      errors,
+      total - errors
+    ),
+  }
+  if errors > 0 {
+    return Err(ci::CiError::PolicyViolated(errors).into());
+  }
+
+  Ok(())
+}
+
+fn handle_cache(action: &CacheAction) -> Result<()> {
+  let cache =
+    cache::BlobCache::user().ok_or_else(|| anyhow::anyhow!("No cache directory on this system"))?;
+
+  match action {
+    CacheAction::Ls => {
+      let now = lockfile::now_timestamp();
+      for entry in cache.entries() {
+        println!(
+          "{}{}  {}  {}  {}",
+          if entry.pinned { "📌 " } else { "" },
+          entry.url,
+          entry.registry.as_deref().unwrap_or("-").cyan(),
+          cache::format_size(entry.size).dimmed(),
+          report::format_age(now.saturating_sub(entry.fetched_at)).dimmed()
+        );
+      }
+    }
+    CacheAction::Clean {
+      registry,
+      older_than,
+    } => {
+      let filter = cache::CacheFilter {
+        registry: registry.clone(),
+        component: None,
+        older_than: older_than.map(|days| days * 86_400),
+      };
+      let removed = cache.clean(&filter)?;
+      status!(
+        "{} Removed {} cached responses ({} freed)",
+        "✓".green(),
+        removed.entries,
+        cache::format_size(removed.bytes)
+      );
+    }
+    CacheAction::Pin {
+      component,
+      registry,
+    }
+    | CacheAction::Unpin {
+      component,
+      registry,
+    } => {
+      let pin = matches!(action, CacheAction::Pin { .. });
+      let filter = cache::CacheFilter {
+        registry: registry.clone(),
+        component: component.clone(),
+        older_than: None,
+      };
+      let changed = cache.set_pinned(&filter, pin)?;
+      status!(
+        "{} {} {} cached responses",
+        "✓".green(),
+        if pin { "Pinned" } else { "Unpinned" },
+        changed
+      );
+    }
+    CacheAction::Stats => {
+      let stats = cache.stats();
+      status!("Cache: {}", cache.root().display().to_string().cyan());
+      status!("  Responses: {}", stats.entries);
+      status!(
+        "  Blobs: {} ({})",
+        stats.blobs,
+        cache::format_size(stats.bytes)
+      );
+      status!("  Pinned: {}", stats.pinned);
+      for (registry, usage) in &stats.registries {
+        status!(
+          "  {}: {} responses ({})",
+          registry.cyan(),
+          usage.entries,
+          cache::format_size(usage.bytes)
+        );
+      }
+    }
+  }
+
+  Ok(())
+}
+
+fn handle_licenses(cli: &Cli, json: bool) -> Result<()> {
+  let config = load_config(cli)?;
+  let installer = ComponentInstaller::new(config)?.with_interactive(cli.is_interactive());
+
+  installer.show_licenses(json)?;
+
+  Ok(())
+}
+
+fn handle_registry_init(dir: &str, name: Option<&str>, force: bool) -> Result<()> {
+  let dir = std::path::Path::new(dir);
+  let name = match name {
+    Some(name) => name.to_string(),
+    None => std::path::absolute(dir)?
+      .file_name()
+      .map(|name| name.to_string_lossy().to_string())
+      .unwrap_or_else(|| "my-registry".to_string()),
+  };
+
+  status!(
+    "{} Scaffolding registry '{}' in {}...",
+    "→".blue(),
+    name.cyan(),
+    dir.display()
+  );
+
+  for file in scaffold::scaffold_registry(dir, &name, force)? {
+    let display = file.strip_prefix(dir).unwrap_or(&file);
+    status!("  {} {}", "✓".green(), display.display());
+  }
+
+  status!();
+  status!("{} Registry ready. Next steps:", "✓".green());
+  status!("  {} Add components to registry.json", "→".blue());
+  status!(
+    "  {} uiget build registry.json --output {}",
+    "→".blue(),
+    scaffold::OUTPUT_DIR
+  );
+  status!("  {} Push to GitHub to publish with Pages", "→".blue());
+
+  Ok(())
+}
+
+fn handle_new(action: &NewAction) -> Result<()> {
+  match action {
+    NewAction::Component {
+      name,
+      registry,
+      component_type,
+      description,
+      force,
+    } => {
+      let registry_path = std::path::Path::new(registry);
+      let files = scaffold::scaffold_component(
+        registry_path,
+        name,
+        component_type,
+        description.as_deref(),
+        *force,
+      )?;
+
+      status!(
+        "{} Added '{}' to {}",
+        "✓".green(),
+        name.cyan(),
+        registry_path.display()
+      );
+      for file in files {
+        status!("  {} {}", "→".blue(), file.display());
+      }
+    }
+  }
+
+  Ok(())
+}
+
+fn handle_registry_import(dir: &str, registry: &str, force: bool) -> Result<()> {
+  let registry_path = std::path::Path::new(registry);
+
+  status!("{} Importing components from {}...", "→".blue(), dir.cyan());
+
+  let added = scaffold::import_components(std::path::Path::new(dir), registry_path, force)?;
+
+  if added.is_empty() {
+    status!(
+      "{} No new components found (use --force to regenerate existing entries)",
+      "!".yellow()
+    );
+  } else {
+    for name in &added {
+      status!("  {} {}", "✓".green(), name.cyan());
+    }
+    status!(
+      "{} Added {} components to {}. Review the inferred dependencies before building.",
+      "✓".green(),
+      added.len(),
+      registry_path.display()
     );
   }
 
   Ok(())
 }
 
-fn handle_build(_cli: &Cli, registry_path: &str, output_path: &str) -> Result<()> {
+fn handle_build(
+  _cli: &Cli,
+  registry_path: &str,
+  output_path: &str,
+  infer_deps: bool,
+  compress: bool,
+) -> Result<()> {
   use std::path::Path;
 
   let registry_path = Path::new(registry_path);
@@ -389,15 +1883,17 @@ fn handle_build(_cli: &Cli, registry_path: &str, output_path: &str) -> Result<()
     ));
   }
 
-  println!(
+  status!(
     "{} Building components from {}...",
     "→".blue(),
     registry_path.display().to_string().cyan()
   );
 
-  let builder = RegistryBuilder::new(registry_path, output_path)?;
+  let builder = RegistryBuilder::new(registry_path, output_path)?
+    .with_dependency_inference(infer_deps)
+    .with_compression(compress);
 
-  println!(
+  status!(
     "{} Building components to {}...",
     "→".blue(),
     output_path.display().to_string().cyan()
@@ -405,9 +1901,9 @@ fn handle_build(_cli: &Cli, registry_path: &str, output_path: &str) -> Result<()
 
   builder.build()?;
 
-  println!();
-  println!("{} Registry built successfully!", "✓".green());
-  println!(
+  status!();
+  status!("{} Registry built successfully!", "✓".green());
+  status!(
     "  {} Generated files in {}",
     "→".blue(),
     output_path.display().to_string().cyan()
@@ -416,30 +1912,170 @@ fn handle_build(_cli: &Cli, registry_path: &str, output_path: &str) -> Result<()
   Ok(())
 }
 
+async fn handle_serve(cli: &Cli, api: bool) -> Result<()> {
+  if !api {
+    return Err(anyhow::anyhow!(
+      "Nothing to serve. Use 'uiget serve --api' to start the JSON-RPC API"
+    ));
+  }
+
+  let config = load_config(cli)?;
+  let project_dir = std::env::current_dir()?;
+  let config_path = cli.config.as_ref().map(std::path::PathBuf::from);
+
+  server::serve_stdio(server::Api::new(&config, &project_dir, config_path)?).await
+}
+
+async fn handle_mcp(cli: &Cli, read_only: bool) -> Result<()> {
+  let config = load_config(cli)?;
+  let project_dir = std::env::current_dir()?;
+  let config_path = cli.config.as_ref().map(std::path::PathBuf::from);
+  let api = server::Api::new(&config, &project_dir, config_path)?;
+
+  mcp::McpServer::new(api, read_only).serve_stdio().await
+}
+
+fn handle_external(cli: &Cli, args: &[String]) -> Result<()> {
+  let (name, args) = args
+    .split_first()
+    .ok_or_else(|| anyhow::anyhow!("No command given"))?;
+
+  let plugin_env = plugin::PluginEnv {
+    config_path: cli.config_path(),
+    verbose: cli.is_verbose(),
+    quiet: cli.is_quiet(),
+    interactive: cli.is_interactive(),
+  };
+  let status = plugin::run_plugin(name, args, &plugin_env)?;
+
+  // Pass the plugin's exit status through unchanged
+  if !status.success() {
+    std::process::exit(status.code().unwrap_or(EXIT_FAILURE as i32));
+  }
+
+  Ok(())
+}
+
+/// Load the configuration and create an installer for commands that fetch
+/// from registries. A `--registry-url` is added for this run only, under
+/// the namespace from `adhoc_namespace`, and never saved.
+async fn load_installer(cli: &Cli) -> Result<ComponentInstaller> {
+  let mut config = load_config(cli)?;
+  ensure_style(cli, &mut config).await?;
+
+  if let Some(url) = cli.registry_url() {
+    config.set_registry(adhoc_namespace(url), url.to_string());
+  }
+
+  Ok(
+    ComponentInstaller::new(config)?
+      .with_interactive(cli.is_interactive())
+      .with_sync_selection(cli.sync_selection())
+      .with_overwrite_modified(cli.overwrite_modified())
+      .with_run_package_manager(!cli.no_install())
+      .with_print_commands(cli.print_commands())
+      .with_git_ref(cli.git_ref().map(String::from))
+      .with_framework(cli.framework),
+  )
+}
+
+/// Namespace of an ad hoc registry: the URL's host, e.g. `acme.dev`
+fn adhoc_namespace(url: &str) -> String {
+  url::Url::parse(url)
+    .ok()
+    .and_then(|url| url.host_str().map(String::from))
+    .unwrap_or_else(|| "adhoc".to_string())
+}
+
+/// Registry URLs containing `{style}` 404 while no style is configured. Ask
+/// the registry which styles it offers, let the user pick one (or take the
+/// registry's default) and save the choice to the config file.
+async fn ensure_style(cli: &Cli, config: &mut Config) -> Result<()> {
+  if config.style.is_some() {
+    return Ok(());
+  }
+
+  let Some((namespace, registry_config)) = config.registries.iter().find(|(_, registry_config)| {
+    registry_config.url().contains("{style}") && registry_config.style().is_none()
+  }) else {
+    return Ok(());
+  };
+
+  let client =
+    registry::RegistryClient::new_with_config(registry_config.clone(), namespace.clone(), None)?;
+  let styles = client.fetch_styles().await?;
+
+  let Some(preferred) = styles.preferred() else {
+    status!(
+      "{} Registry '{}' uses a {{style}} URL but advertises no styles. Set \"style\" in {}",
+      "⚠".yellow(),
+      namespace.cyan(),
+      cli.config_path().display()
+    );
+    return Ok(());
+  };
+
+  let style = if cli.is_interactive() && styles.styles.len() > 1 {
+    let default = styles
+      .styles
+      .iter()
+      .position(|style| style == preferred)
+      .unwrap_or(0);
+    let selection = dialoguer::Select::with_theme(&dialoguer::theme::ColorfulTheme::default())
+      .with_prompt(format!("Registry '{}' needs a style", namespace))
+      .items(&styles.styles)
+      .default(default)
+      .interact()?;
+    styles.styles[selection].clone()
+  } else {
+    preferred.to_string()
+  };
+
+  config.style = Some(style.clone());
+  let config_path = cli.config_path();
+  Config::update(&config_path, |config| {
+    config.style = Some(style.clone());
+    Ok(())
+  })?;
+
+  status!(
+    "{} Using style '{}' (saved to {})",
+    "✓".green(),
+    style.cyan(),
+    config_path.display()
+  );
+
+  Ok(())
+}
+
 fn load_config(cli: &Cli) -> Result<Config> {
   let config_path = cli.config_path();
 
   if !config_path.exists() {
     // Check if we're looking for a specific config file or using defaults
     if cli.config.is_some() {
-      return Err(anyhow::anyhow!(
-        "Configuration file '{}' not found.",
-        config_path.display()
-      ));
+      return Err(ConfigError::NotFound(config_path).into());
     } else {
       // No uiget.json or components.json found
-      return Err(anyhow::anyhow!(
-        "No configuration file found. Looked for 'uiget.json' and 'components.json'. Run 'uiget \
-         init' to create one."
-      ));
+      return Err(ConfigError::Missing.into());
     }
   }
 
   let config = Config::load_from_file(&config_path)?;
 
+  // The config's locale applies unless --lang was given
+  if cli.lang.is_none() {
+    if let Some(tag) = &config.locale {
+      match i18n::Locale::parse(tag) {
+        Some(locale) => i18n::set_locale(locale),
+        None => output::warning!("{} Unsupported locale '{}' in config", "!".yellow(), tag),
+      }
+    }
+  }
+
   // Show which config file is being used for transparency
   if cli.is_verbose() {
-    println!("Using configuration from: {}", config_path.display());
+    status!("Using configuration from: {}", config_path.display());
   }
 
   Ok(config)
@@ -476,4 +2112,33 @@ mod tests {
     );
     assert_eq!(config.registries.len(), loaded_config.registries.len());
   }
+
+  #[test]
+  fn test_exit_code_for() {
+    let err: anyhow::Error = ConfigError::Missing.into();
+    assert_eq!(exit_code_for(&err), EXIT_CONFIG);
+
+    let err: anyhow::Error = RegistryError::ComponentNotFound("button".to_string()).into();
+    assert_eq!(exit_code_for(&err), EXIT_NOT_FOUND);
+
+    let err: anyhow::Error = InstallError::FileExists("button.tsx".into()).into();
+    assert_eq!(
+      exit_code_for(&err.context("Failed to install 'button'")),
+      EXIT_FILE_CONFLICT
+    );
+
+    let err: anyhow::Error = InstallError::PackageManager("dependencies".to_string()).into();
+    assert_eq!(exit_code_for(&err), EXIT_PACKAGE_MANAGER);
+
+    assert_eq!(exit_code_for(&anyhow::anyhow!("boom")), EXIT_FAILURE);
+  }
+
+  #[test]
+  fn test_adhoc_namespace() {
+    assert_eq!(
+      adhoc_namespace("https://acme.dev/r/{name}.json"),
+      "acme.dev"
+    );
+    assert_eq!(adhoc_namespace("not a url"), "adhoc");
+  }
 }
@@ -0,0 +1,303 @@
+use anyhow::Result;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+use crate::server::{
+  parse_params, parse_request, respond, write_message, AddParams, Api, RpcError, RpcRequest,
+  INVALID_PARAMS, METHOD_NOT_FOUND,
+};
+
+/// MCP protocol revision implemented by the server
+const PROTOCOL_VERSION: &str = "2024-11-05";
+
+/// Parameters of a `tools/call` request
+#[derive(Debug, Deserialize)]
+struct ToolCall {
+  name: String,
+  #[serde(default)]
+  arguments: Value,
+  #[serde(rename = "_meta")]
+  meta: Option<ToolCallMeta>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ToolCallMeta {
+  progress_token: Option<Value>,
+}
+
+/// Model Context Protocol server exposing registries and the installer as
+/// tools over stdio
+pub struct McpServer {
+  api: Api,
+  read_only: bool,
+}
+
+impl McpServer {
+  /// Create a server. With `read_only` the `add_component` tool is not
+  /// offered, so the assistant can only browse.
+  pub fn new(api: Api, read_only: bool) -> Self {
+    Self { api, read_only }
+  }
+
+  /// Serve newline-delimited JSON-RPC messages on stdin/stdout until stdin
+  /// is closed
+  pub async fn serve_stdio(&self) -> Result<()> {
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+
+    while let Some(line) = lines.next_line().await? {
+      if line.trim().is_empty() {
+        continue;
+      }
+
+      if let Some(response) = self.handle_line(&line, &mut write_message).await {
+        write_message(response);
+      }
+    }
+
+    Ok(())
+  }
+
+  async fn handle_line(&self, line: &str, notify: &mut impl FnMut(Value)) -> Option<Value> {
+    let request = match parse_request(line) {
+      Ok(request) => request,
+      Err(response) => return Some(response),
+    };
+
+    let id = request.id.clone();
+    let result = self.dispatch(request, notify).await;
+    respond(id, result)
+  }
+
+  async fn dispatch(
+    &self,
+    request: RpcRequest,
+    notify: &mut impl FnMut(Value),
+  ) -> std::result::Result<Value, RpcError> {
+    match request.method.as_str() {
+      "initialize" => Ok(json!({
+        "protocolVersion": PROTOCOL_VERSION,
+        "capabilities": { "tools": {} },
+        "serverInfo": { "name": "uiget", "version": env!("CARGO_PKG_VERSION") },
+      })),
+      "ping" => Ok(json!({})),
+      "tools/list" => Ok(json!({ "tools": self.tools() })),
+      "tools/call" => {
+        let call: ToolCall = parse_params(request.params)?;
+        self.call_tool(call, notify).await
+      }
+      method if method.starts_with("notifications/") => Ok(Value::Null),
+      method => Err(RpcError::new(
+        METHOD_NOT_FOUND,
+        format!("Method '{}' not found", method),
+      )),
+    }
+  }
+
+  /// Tool definitions advertised to the client
+  fn tools(&self) -> Vec<Value> {
+    let registry = json!({
+      "type": "string",
+      "description": "Registry namespace (defaults to all configured registries)",
+    });
+
+    let mut tools = vec![
+      json!({
+        "name": "list_components",
+        "description": "List the components available in the configured registries and whether \
+                        each is installed in the project",
+        "inputSchema": {
+          "type": "object",
+          "properties": { "registry": registry },
+        },
+      }),
+      json!({
+        "name": "search_components",
+        "description": "Search registry components by name or type",
+        "inputSchema": {
+          "type": "object",
+          "properties": {
+            "query": { "type": "string", "description": "Text to match against names and types" },
+            "registry": registry,
+          },
+          "required": ["query"],
+        },
+      }),
+      json!({
+        "name": "component_info",
+        "description": "Show a component's description, dependencies and file contents",
+        "inputSchema": {
+          "type": "object",
+          "properties": {
+            "component": { "type": "string", "description": "Component name" },
+            "registry": registry,
+          },
+          "required": ["component"],
+        },
+      }),
+    ];
+
+    if !self.read_only {
+      tools.push(json!({
+        "name": "add_component",
+        "description": "Install a component (and its registry and npm dependencies) into the \
+                        project",
+        "inputSchema": {
+          "type": "object",
+          "properties": {
+            "component": { "type": "string", "description": "Component name" },
+            "registry": registry,
+            "force": { "type": "boolean", "description": "Overwrite existing files" },
+            "skipDeps": { "type": "boolean", "description": "Skip npm dependency installation" },
+          },
+          "required": ["component"],
+        },
+      }));
+    }
+
+    tools
+  }
+
+  async fn call_tool(
+    &self,
+    call: ToolCall,
+    notify: &mut impl FnMut(Value),
+  ) -> std::result::Result<Value, RpcError> {
+    let arguments = if call.arguments.is_null() {
+      json!({})
+    } else {
+      call.arguments
+    };
+
+    let result = match call.name.as_str() {
+      "list_components" => self.api.list(parse_params(arguments)?).await,
+      "search_components" => self.api.search(parse_params(arguments)?).await,
+      "component_info" => self.api.info(parse_params(arguments)?).await,
+      "add_component" if !self.read_only => {
+        let params: AddParams = parse_params(arguments)?;
+        let progress_token = call.meta.and_then(|meta| meta.progress_token);
+        let mut progress = 0;
+
+        let outcome = self
+          .api
+          .add(params, |line| {
+            if let Some(token) = &progress_token {
+              progress += 1;
+              notify(json!({
+                "jsonrpc": "2.0",
+                "method": "notifications/progress",
+                "params": { "progressToken": token, "progress": progress, "message": line },
+              }));
+            }
+          })
+          .await;
+
+        return Ok(match outcome {
+          Ok(outcome) => tool_result(outcome.output.join("\n"), !outcome.success()),
+          Err(err) => tool_result(err.to_string(), true),
+        });
+      }
+      name => {
+        return Err(RpcError::new(
+          INVALID_PARAMS,
+          format!("Unknown tool '{}'", name),
+        ))
+      }
+    };
+
+    Ok(match result {
+      Ok(value) => tool_result(
+        serde_json::to_string_pretty(&value).unwrap_or_default(),
+        false,
+      ),
+      Err(err) => tool_result(err.to_string(), true),
+    })
+  }
+}
+
+/// Wrap text in an MCP tool result
+fn tool_result(text: String, is_error: bool) -> Value {
+  json!({
+    "content": [{ "type": "text", "text": text }],
+    "isError": is_error,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::config::Config;
+
+  fn test_server(read_only: bool) -> McpServer {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = Config {
+      registries: Default::default(),
+      ..Config::default()
+    };
+    McpServer::new(Api::new(&config, temp_dir.path(), None).unwrap(), read_only)
+  }
+
+  #[tokio::test]
+  async fn test_initialize_and_tools() {
+    let server = test_server(false);
+    let mut notify = |_: Value| {};
+
+    let response = server
+      .handle_line(
+        r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}"#,
+        &mut notify,
+      )
+      .await
+      .unwrap();
+    assert_eq!(response["result"]["protocolVersion"], PROTOCOL_VERSION);
+
+    assert!(server
+      .handle_line(
+        r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#,
+        &mut notify
+      )
+      .await
+      .is_none());
+
+    let response = server
+      .handle_line(
+        r#"{"jsonrpc":"2.0","id":2,"method":"tools/list"}"#,
+        &mut notify,
+      )
+      .await
+      .unwrap();
+    let tools = response["result"]["tools"].as_array().unwrap();
+    assert!(tools.iter().any(|tool| tool["name"] == "add_component"));
+
+    let read_only = test_server(true);
+    assert!(!read_only
+      .tools()
+      .iter()
+      .any(|tool| tool["name"] == "add_component"));
+  }
+
+  #[tokio::test]
+  async fn test_tool_call_results() {
+    let server = test_server(true);
+    let mut notify = |_: Value| {};
+
+    let response = server
+      .handle_line(
+        r#"{"jsonrpc":"2.0","id":3,"method":"tools/call","params":{"name":"list_components"}}"#,
+        &mut notify,
+      )
+      .await
+      .unwrap();
+    assert_eq!(response["result"]["isError"], false);
+
+    let response = server
+      .handle_line(
+        r#"{"jsonrpc":"2.0","id":4,"method":"tools/call","params":{"name":"add_component","arguments":{"component":"button"}}}"#,
+        &mut notify,
+      )
+      .await
+      .unwrap();
+    assert_eq!(response["error"]["code"], INVALID_PARAMS);
+  }
+}
@@ -0,0 +1,423 @@
+//! Sign-in to hosted registry services with the OAuth 2.0 device
+//! authorization grant (RFC 8628). Tokens are kept per user in
+//! `credentials.json`, readable only by its owner, and sent as bearer tokens
+//! to the hosts of the provider they came from.
+
+use std::{
+  fs, io,
+  path::{Path, PathBuf},
+  time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{anyhow, bail, Result};
+use colored::*;
+use indexmap::IndexMap;
+use reqwest::{
+  header::{ACCEPT, CONTENT_TYPE},
+  Client,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  browser,
+  config::OAuthProvider,
+  output::{status, warning},
+  paths,
+};
+
+/// File holding the tokens, in uiget's configuration directory
+const CREDENTIALS_FILE: &str = "credentials.json";
+
+/// Grant type for polling with a device code
+const DEVICE_CODE_GRANT: &str = "urn:ietf:params:oauth:grant-type:device_code";
+
+/// Seconds between polls when the provider doesn't say
+const DEFAULT_POLL_INTERVAL: u64 = 5;
+
+/// Tokens expiring within this many seconds are refreshed before use
+const EXPIRY_MARGIN: u64 = 60;
+
+#[derive(Debug, Deserialize)]
+struct DeviceAuthorization {
+  device_code: String,
+  user_code: String,
+  #[serde(alias = "verification_url")]
+  verification_uri: String,
+  verification_uri_complete: Option<String>,
+  expires_in: u64,
+  interval: Option<u64>,
+}
+
+/// A token endpoint response, successful or not. Some providers report
+/// errors with a 200 status, so both are read from the body.
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+  access_token: Option<String>,
+  refresh_token: Option<String>,
+  expires_in: Option<u64>,
+  error: Option<String>,
+  error_description: Option<String>,
+}
+
+impl TokenResponse {
+  /// The provider's description of the error, or its code
+  fn error_message(&self) -> String {
+    self
+      .error_description
+      .clone()
+      .or_else(|| self.error.clone())
+      .unwrap_or_else(|| "no access token in the response".to_string())
+  }
+}
+
+/// Tokens from signing in to a provider, with what's needed to refresh them
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredToken {
+  /// Registry hosts the token is sent to
+  pub hosts: Vec<String>,
+  #[serde(rename = "tokenUrl")]
+  pub token_url: String,
+  #[serde(rename = "clientId")]
+  pub client_id: String,
+  #[serde(rename = "accessToken")]
+  pub access_token: String,
+  #[serde(rename = "refreshToken", skip_serializing_if = "Option::is_none")]
+  pub refresh_token: Option<String>,
+  /// When the access token expires, in seconds since the Unix epoch
+  #[serde(rename = "expiresAt", skip_serializing_if = "Option::is_none")]
+  pub expires_at: Option<u64>,
+}
+
+impl StoredToken {
+  /// Whether the access token can still be used at `now`
+  pub fn is_fresh(&self, now: u64) -> bool {
+    self
+      .expires_at
+      .is_none_or(|expires_at| now + EXPIRY_MARGIN < expires_at)
+  }
+
+  /// Whether the token is sent to `host`
+  pub fn matches_host(&self, host: &str) -> bool {
+    self
+      .hosts
+      .iter()
+      .any(|candidate| candidate.eq_ignore_ascii_case(host))
+  }
+
+  /// Take the tokens from a token endpoint response
+  fn update(&mut self, response: TokenResponse, now: u64) -> Result<()> {
+    let message = response.error_message();
+    self.access_token = response.access_token.ok_or_else(|| anyhow!(message))?;
+    // Providers that don't rotate refresh tokens leave them out
+    if response.refresh_token.is_some() {
+      self.refresh_token = response.refresh_token;
+    }
+    self.expires_at = response.expires_in.map(|seconds| now + seconds);
+    Ok(())
+  }
+}
+
+/// Tokens of the providers signed in to, by provider name
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TokenStore {
+  pub providers: IndexMap<String, StoredToken>,
+}
+
+impl TokenStore {
+  /// Read the store at `path`; a missing file is an empty store
+  pub fn load(path: &Path) -> Result<Self> {
+    match fs::read_to_string(path) {
+      Ok(content) => Ok(serde_json::from_str(&content)?),
+      Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+      Err(err) => Err(err.into()),
+    }
+  }
+
+  /// Write the store to `path`, creating it readable by its owner only
+  pub fn save(&self, path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent() {
+      fs::create_dir_all(parent)?;
+    }
+    // `write_atomic` keeps the permissions of the file it replaces
+    if !path.exists() {
+      let mut options = fs::OpenOptions::new();
+      options.write(true).create_new(true);
+      #[cfg(unix)]
+      std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+      options.open(path)?;
+    }
+    paths::write_atomic(path, serde_json::to_string_pretty(self)?)?;
+    Ok(())
+  }
+
+  /// The provider whose token is sent to `host`
+  pub fn for_host(&self, host: &str) -> Option<(&String, &StoredToken)> {
+    self
+      .providers
+      .iter()
+      .find(|(_, token)| token.matches_host(host))
+  }
+}
+
+/// Location of the token store: `UIGET_CONFIG_DIR`, or uiget's directory in
+/// the user's configuration directory
+pub fn credentials_path() -> Option<PathBuf> {
+  let dir = match std::env::var_os("UIGET_CONFIG_DIR").filter(|dir| !dir.is_empty()) {
+    Some(dir) => PathBuf::from(dir),
+    None => dirs::config_dir()?.join("uiget"),
+  };
+  Some(dir.join(CREDENTIALS_FILE))
+}
+
+/// Seconds since the Unix epoch
+fn now() -> u64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|duration| duration.as_secs())
+    .unwrap_or_default()
+}
+
+fn http_client() -> Result<Client> {
+  Ok(
+    Client::builder()
+      .user_agent(format!("uiget-cli/{}", env!("CARGO_PKG_VERSION")))
+      .build()?,
+  )
+}
+
+/// POST a form to an OAuth endpoint and read the JSON response, whatever
+/// its status
+pub async fn post_form<T: serde::de::DeserializeOwned>(
+  client: &Client,
+  url: &str,
+  fields: &[(&str, &str)],
+) -> Result<T> {
+  let body = url::form_urlencoded::Serializer::new(String::new())
+    .extend_pairs(fields)
+    .finish();
+  let response = client
+    .post(url)
+    .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+    .header(ACCEPT, "application/json")
+    .body(body)
+    .send()
+    .await?;
+  let status = response.status();
+  let text = response.text().await?;
+  serde_json::from_str(&text)
+    .map_err(|_| anyhow!("Unexpected response from {} ({}): {}", url, status, text))
+}
+
+/// Sign in to `provider` with the device flow: show the user a code to enter
+/// at the provider, wait for them to approve it and store the tokens
+pub async fn login(name: &str, provider: &OAuthProvider, open_browser: bool) -> Result<()> {
+  let path =
+    credentials_path().ok_or_else(|| anyhow!("Couldn't find a configuration directory"))?;
+  let client = http_client()?;
+
+  let mut fields = vec![("client_id", provider.client_id.as_str())];
+  if let Some(scope) = &provider.scope {
+    fields.push(("scope", scope));
+  }
+  let authorization: DeviceAuthorization =
+    post_form(&client, &provider.device_authorization_url, &fields).await?;
+
+  status!(
+    "{} Enter the code {} at {}",
+    "→".blue(),
+    authorization.user_code.bold(),
+    authorization.verification_uri.cyan()
+  );
+  if open_browser {
+    let url = authorization
+      .verification_uri_complete
+      .as_deref()
+      .unwrap_or(&authorization.verification_uri);
+    // The URL is printed above, so a browser that won't open is no loss
+    let _ = browser::open_url(url);
+  }
+  status!("{} Waiting for sign-in to '{}'...", "→".blue(), name);
+
+  let mut interval = authorization.interval.unwrap_or(DEFAULT_POLL_INTERVAL);
+  let deadline = now() + authorization.expires_in;
+  let response = loop {
+    tokio::time::sleep(Duration::from_secs(interval)).await;
+    if now() > deadline {
+      bail!(
+        "The code expired before sign-in finished, run `uiget login {}` again",
+        name
+      );
+    }
+
+    let response: TokenResponse = post_form(
+      &client,
+      &provider.token_url,
+      &[
+        ("grant_type", DEVICE_CODE_GRANT),
+        ("device_code", &authorization.device_code),
+        ("client_id", &provider.client_id),
+      ],
+    )
+    .await?;
+    match response.error.as_deref() {
+      Some("authorization_pending") => {}
+      Some("slow_down") => interval += 5,
+      Some("access_denied") => bail!("Sign-in to '{}' was denied", name),
+      Some("expired_token") => {
+        bail!(
+          "The code expired before sign-in finished, run `uiget login {}` again",
+          name
+        )
+      }
+      _ => break response,
+    }
+  };
+
+  let mut token = StoredToken {
+    hosts: provider.hosts.clone(),
+    token_url: provider.token_url.clone(),
+    client_id: provider.client_id.clone(),
+    access_token: String::new(),
+    refresh_token: None,
+    expires_at: None,
+  };
+  token
+    .update(response, now())
+    .map_err(|err| anyhow!("Sign-in to '{}' failed: {}", name, err))?;
+
+  let mut store = TokenStore::load(&path)?;
+  store.providers.insert(name.to_string(), token);
+  store.save(&path)?;
+
+  status!(
+    "{} Signed in to '{}', the token is sent to {}",
+    "✓".green(),
+    name,
+    provider.hosts.join(", ").cyan()
+  );
+  Ok(())
+}
+
+/// Forget the tokens of `name`. Returns whether it was signed in.
+pub fn logout(name: &str) -> Result<bool> {
+  let Some(path) = credentials_path() else {
+    return Ok(false);
+  };
+  let mut store = TokenStore::load(&path)?;
+  if store.providers.shift_remove(name).is_none() {
+    return Ok(false);
+  }
+  store.save(&path)?;
+  Ok(true)
+}
+
+/// Exchange the refresh token for a new access token
+async fn refresh(client: &Client, token: &mut StoredToken) -> Result<()> {
+  let refresh_token = token
+    .refresh_token
+    .clone()
+    .ok_or_else(|| anyhow!("the token expired and can't be refreshed"))?;
+  let response: TokenResponse = post_form(
+    client,
+    &token.token_url,
+    &[
+      ("grant_type", "refresh_token"),
+      ("refresh_token", &refresh_token),
+      ("client_id", &token.client_id),
+    ],
+  )
+  .await?;
+  token.update(response, now())
+}
+
+/// The access token to send to `host`, refreshed first if it's about to
+/// expire. Returns `None` when no provider signed in to covers the host.
+pub async fn bearer_token(client: &Client, host: &str) -> Option<String> {
+  let path = credentials_path()?;
+  let mut store = TokenStore::load(&path).ok()?;
+  let (name, token) = store.for_host(host)?;
+  if token.is_fresh(now()) {
+    return Some(token.access_token.clone());
+  }
+
+  let name = name.clone();
+  let mut token = token.clone();
+  if let Err(err) = refresh(client, &mut token).await {
+    warning!(
+      "Warning: Sign-in to '{}' expired ({}), run `uiget login {}`",
+      name,
+      err,
+      name
+    );
+    return None;
+  }
+  let access_token = token.access_token.clone();
+  store.providers.insert(name, token);
+  if let Err(err) = store.save(&path) {
+    warning!("Warning: Couldn't save the refreshed token: {}", err);
+  }
+  Some(access_token)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn token(expires_at: Option<u64>) -> StoredToken {
+    StoredToken {
+      hosts: vec!["registry.acme.dev".to_string()],
+      token_url: "https://auth.acme.dev/token".to_string(),
+      client_id: "uiget".to_string(),
+      access_token: "abc".to_string(),
+      refresh_token: Some("refresh".to_string()),
+      expires_at,
+    }
+  }
+
+  #[test]
+  fn test_token_store() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let path = temp.path().join("uiget").join(CREDENTIALS_FILE);
+    assert!(TokenStore::load(&path).unwrap().providers.is_empty());
+
+    let mut store = TokenStore::default();
+    store
+      .providers
+      .insert("acme".to_string(), token(Some(1_000)));
+    store.save(&path).unwrap();
+    #[cfg(unix)]
+    {
+      use std::os::unix::fs::PermissionsExt;
+      let mode = fs::metadata(&path).unwrap().permissions().mode();
+      assert_eq!(mode & 0o777, 0o600);
+    }
+
+    let store = TokenStore::load(&path).unwrap();
+    let (name, stored) = store.for_host("Registry.Acme.dev").unwrap();
+    assert_eq!(name, "acme");
+    assert_eq!(stored.access_token, "abc");
+    assert!(store.for_host("acme.dev").is_none());
+
+    assert!(stored.is_fresh(900));
+    assert!(!stored.is_fresh(960));
+    assert!(token(None).is_fresh(u64::MAX - EXPIRY_MARGIN - 1));
+  }
+
+  #[test]
+  fn test_token_update() {
+    let mut stored = token(Some(1_000));
+    let response: TokenResponse =
+      serde_json::from_str(r#"{ "access_token": "new", "expires_in": 3600 }"#).unwrap();
+    stored.update(response, 2_000).unwrap();
+    assert_eq!(stored.access_token, "new");
+    // The refresh token is kept when the provider doesn't rotate it
+    assert_eq!(stored.refresh_token.as_deref(), Some("refresh"));
+    assert_eq!(stored.expires_at, Some(5_600));
+
+    let response: TokenResponse =
+      serde_json::from_str(r#"{ "error": "invalid_grant", "error_description": "Token revoked" }"#)
+        .unwrap();
+    let err = stored.update(response, 2_000).unwrap_err();
+    assert_eq!(err.to_string(), "Token revoked");
+  }
+}
@@ -0,0 +1,118 @@
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+
+use serde_json::{Map, Value};
+
+/// How much uiget prints, set once from the global flags
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+  /// Only errors (`--quiet`)
+  Quiet,
+  Normal,
+  /// Extra diagnostics (`--verbose`)
+  Verbose,
+}
+
+static VERBOSITY: AtomicU8 = AtomicU8::new(Verbosity::Normal as u8);
+
+/// Set the output level for the rest of the run
+pub fn set_verbosity(verbosity: Verbosity) {
+  VERBOSITY.store(verbosity as u8, Ordering::Relaxed);
+}
+
+/// The current output level
+pub fn verbosity() -> Verbosity {
+  match VERBOSITY.load(Ordering::Relaxed) {
+    0 => Verbosity::Quiet,
+    1 => Verbosity::Normal,
+    _ => Verbosity::Verbose,
+  }
+}
+
+/// Whether non-error output is suppressed
+pub fn is_quiet() -> bool {
+  verbosity() == Verbosity::Quiet
+}
+
+static PROGRESS: AtomicBool = AtomicBool::new(false);
+
+/// Emit progress events for the rest of the run (`--progress ndjson`)
+pub fn set_progress(enabled: bool) {
+  PROGRESS.store(enabled, Ordering::Relaxed);
+}
+
+/// A progress event as a single line of JSON: `{"event": ..., ...fields}`
+fn progress_line(event: &str, fields: Value) -> String {
+  let mut object = Map::new();
+  object.insert("event".to_string(), Value::from(event));
+  if let Value::Object(fields) = fields {
+    object.extend(fields);
+  }
+  Value::Object(object).to_string()
+}
+
+/// Write a progress event to stderr when `--progress ndjson` was given, so
+/// wrappers can follow long operations. Events: `resolve`, `download`,
+/// `write`, `deps-install` and `done`.
+pub fn progress(event: &str, fields: Value) {
+  if PROGRESS.load(Ordering::Relaxed) {
+    eprintln!("{}", progress_line(event, fields));
+  }
+}
+
+/// Print a line to stdout unless `--quiet` was given
+#[doc(hidden)]
+#[macro_export]
+macro_rules! status {
+  ($($arg:tt)*) => {
+    if !$crate::output::is_quiet() {
+      println!($($arg)*);
+    }
+  };
+}
+
+/// Print a line to stdout only with `--verbose`
+#[doc(hidden)]
+#[macro_export]
+macro_rules! detail {
+  ($($arg:tt)*) => {
+    if $crate::output::verbosity() == $crate::output::Verbosity::Verbose {
+      println!($($arg)*);
+    }
+  };
+}
+
+/// Print a warning to stderr unless `--quiet` was given
+#[doc(hidden)]
+#[macro_export]
+macro_rules! warning {
+  ($($arg:tt)*) => {
+    if !$crate::output::is_quiet() {
+      eprintln!($($arg)*);
+    }
+  };
+}
+
+pub use crate::detail;
+pub use crate::status;
+pub use crate::warning;
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_verbosity_order() {
+    assert!(Verbosity::Quiet < Verbosity::Normal);
+    assert!(Verbosity::Normal < Verbosity::Verbose);
+    assert_eq!(verbosity(), Verbosity::Normal);
+  }
+
+  #[test]
+  fn test_progress_line() {
+    assert_eq!(
+      progress_line("write", serde_json::json!({ "path": "src/button.tsx" })),
+      r#"{"event":"write","path":"src/button.tsx"}"#
+    );
+    assert_eq!(progress_line("done", Value::Null), r#"{"event":"done"}"#);
+  }
+}
@@ -1,13 +1,37 @@
 use std::{
+  collections::HashMap,
   env, fmt, fs,
+  io::Read,
   path::{Path, PathBuf},
   time::SystemTime,
 };
 
 use regex::Regex;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+use crate::{
+  i18n::{tr, Message},
+  paths,
+};
+
+/// Files whose changes can change which package manager a project uses
+const DETECTION_INPUTS: &[&str] = &[
+  "package.json",
+  "yarn.lock",
+  "pnpm-lock.yaml",
+  "package-lock.json",
+  "bun.lockb",
+  "bun.lock",
+  "pnpm-workspace.yaml",
+  "turbo.json",
+  ".yarnrc.yml",
+  ".pnp.cjs",
+  ".pnp.loader.mjs",
+  ".pnp.data.json",
+  ".yarn",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PackageManager {
   Npm,
   YarnClassic, // yarn 1.x
@@ -17,7 +41,7 @@ pub enum PackageManager {
   Unknown,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum DetectionSource {
   PackageJsonField,       // package.json "packageManager"
   Lockfile(PathBuf),      // yarn.lock, pnpm-lock.yaml, etc.
@@ -27,12 +51,35 @@ pub enum DetectionSource {
   Heuristic,              // fallback
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Detection {
   pub manager: PackageManager,
-  pub version_hint: Option<String>,
   pub source: DetectionSource,
   pub project_root: PathBuf,
+  /// The monorepo the project is a package of, when there is one
+  #[serde(default)]
+  pub workspace: Option<Workspace>,
+}
+
+/// A monorepo: npm, Yarn or Bun `workspaces` in package.json, a
+/// pnpm-workspace.yaml, or a Turborepo turbo.json
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Workspace {
+  /// Directory with the workspace definition and the shared lockfile
+  pub root: PathBuf,
+  /// Whether the packages are listed in pnpm-workspace.yaml
+  pub pnpm: bool,
+  /// Whether the root has a turbo.json
+  pub turbo: bool,
+}
+
+/// Where a package manager adds dependencies in a monorepo
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstallLocation {
+  /// The workspace root's package.json
+  Root,
+  /// The package.json of the project uiget runs in
+  Package,
 }
 
 #[derive(Debug)]
@@ -45,15 +92,11 @@ pub enum DetectError {
 impl fmt::Display for DetectError {
   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     match self {
-      DetectError::NoProject(path) => {
-        write!(
-          f,
-          "nenhum projeto Node encontrado (package.json) a partir de {}",
-          path
-        )
+      DetectError::NoProject(path) => write!(f, "{}", tr(Message::NoNodeProject, &[path])),
+      DetectError::Io(err) => write!(f, "{}", tr(Message::IoError, &[err])),
+      DetectError::BadJson(path, msg) => {
+        write!(f, "{}", tr(Message::InvalidJson, &[path, msg]))
       }
-      DetectError::Io(err) => write!(f, "erro de IO: {}", err),
-      DetectError::BadJson(path, msg) => write!(f, "json inválido em {}: {}", path, msg),
     }
   }
 }
@@ -75,71 +118,233 @@ impl From<std::io::Error> for DetectError {
 
 #[derive(Deserialize)]
 struct PackageJson {
+  #[serde(default, rename = "packageManager")]
+  package_manager: Option<String>,
+  #[serde(default)]
+  name: Option<String>,
   #[serde(default)]
-  packageManager: Option<String>,
+  workspaces: Option<serde_json::Value>,
+}
+
+fn read_package_json(root: &Path) -> Option<PackageJson> {
+  let data = fs::read_to_string(root.join("package.json")).ok()?;
+  serde_json::from_str(&data).ok()
+}
+
+/// Find the monorepo `project_root` belongs to, looking at the project
+/// itself and the directories above it. The nearest directory with a
+/// pnpm-workspace.yaml, a package.json declaring `workspaces`, or a
+/// turbo.json next to its package.json is the workspace root.
+pub fn find_workspace(project_root: &Path) -> Option<Workspace> {
+  project_root.ancestors().find_map(|dir| {
+    if !dir.join("package.json").exists() {
+      return None;
+    }
+    let pnpm = dir.join("pnpm-workspace.yaml").exists();
+    let turbo = dir.join("turbo.json").exists();
+    let workspaces = read_package_json(dir).is_some_and(|pj| pj.workspaces.is_some());
+    (pnpm || turbo || workspaces).then(|| Workspace {
+      root: dir.to_path_buf(),
+      pnpm,
+      turbo,
+    })
+  })
+}
+
+/// Package globs of the monorepo at `root`, from package.json `workspaces`
+/// (a list, or Yarn's `{ "packages": [...] }`) and pnpm-workspace.yaml
+pub fn read_workspace_patterns(root: &Path) -> Vec<String> {
+  let mut patterns: Vec<String> = read_package_json(root)
+    .and_then(|pj| pj.workspaces)
+    .map(|workspaces| match workspaces {
+      serde_json::Value::Object(mut object) => object.remove("packages").unwrap_or_default(),
+      workspaces => workspaces,
+    })
+    .and_then(|packages| serde_json::from_value::<Vec<String>>(packages).ok())
+    .unwrap_or_default();
+
+  // The `packages:` list of pnpm-workspace.yaml, one `- 'glob'` per line
+  if let Ok(yaml) = fs::read_to_string(root.join("pnpm-workspace.yaml")) {
+    let mut in_packages = false;
+    for line in yaml.lines() {
+      if !line.starts_with([' ', '\t', '-']) {
+        in_packages = line.trim_end() == "packages:";
+        continue;
+      }
+      if let Some(item) = line.trim().strip_prefix('-').filter(|_| in_packages) {
+        let item = item.split(" #").next().unwrap_or_default().trim();
+        patterns.push(item.trim_matches(['\'', '"']).to_string());
+      }
+    }
+  }
+
+  patterns
+    .into_iter()
+    .map(|pattern| {
+      let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+      pattern.to_string()
+    })
+    .filter(|pattern| !pattern.is_empty())
+    .collect()
+}
+
+/// The `name` in the package.json at `root`
+pub fn read_package_name(root: &Path) -> Option<String> {
+  read_package_json(root)?.name
 }
 
 pub fn detect_package_manager(start_dir: impl AsRef<Path>) -> Result<Detection, DetectError> {
   let start = start_dir.as_ref().canonicalize()?;
   let project_root =
     find_project_root(&start).ok_or_else(|| DetectError::NoProject(start.display().to_string()))?;
+  // In a monorepo the lockfile and packageManager field are at the root
+  let workspace = find_workspace(&project_root);
+  let lookup_root = workspace
+    .as_ref()
+    .map_or(project_root.clone(), |workspace| workspace.root.clone());
 
   // 0) user agent (se existir) – útil quando a CLI é invocada via
   //    npm/yarn/pnpm/bun
-  if let Some(ua) = env::var("npm_config_user_agent").ok() {
-    if let Some((pm, ver)) = parse_user_agent(&ua) {
+  if let Ok(ua) = env::var("npm_config_user_agent") {
+    if let Some((pm, _)) = parse_user_agent(&ua) {
       return Ok(Detection {
         manager: pm,
-        version_hint: ver,
         source: DetectionSource::UserAgent(ua),
         project_root,
+        workspace,
       });
     }
   }
 
   // 1) package.json → "packageManager"
-  if let Ok((pm, ver)) = read_package_manager_field(&project_root) {
+  if let Ok((pm, _)) = read_package_manager_field(&lookup_root) {
     return Ok(Detection {
       manager: pm,
-      version_hint: ver,
       source: DetectionSource::PackageJsonField,
       project_root,
+      workspace,
     });
   }
 
   // 2) artefatos específicos (yarn berry, pnpm)
-  if let Some(path) = find_yarn_artifacts(&project_root) {
+  if let Some(path) = find_yarn_artifacts(&lookup_root) {
     return Ok(Detection {
       manager: PackageManager::YarnBerry,
-      version_hint: None,
       source: DetectionSource::YarnArtifacts(path),
       project_root,
+      workspace,
     });
   }
-  if let Some(path) = find_pnpm_artifacts(&project_root) {
+  if let Some(path) = find_pnpm_artifacts(&lookup_root) {
     return Ok(Detection {
       manager: PackageManager::Pnpm,
-      version_hint: None,
       source: DetectionSource::PnpmArtifacts(path),
       project_root,
+      workspace,
     });
   }
 
   // 3) lockfiles (com desempate por mtime)
-  if let Some(det) = pick_by_lockfiles(&project_root)? {
-    return Ok(det);
+  if let Some((manager, path)) = pick_by_lockfiles(&lookup_root)? {
+    return Ok(Detection {
+      manager,
+      source: DetectionSource::Lockfile(path),
+      project_root,
+      workspace,
+    });
   }
 
   // 4) fallback explícito
   Ok(Detection {
     manager: PackageManager::Npm,
-    version_hint: None,
     source: DetectionSource::Heuristic,
     project_root,
+    workspace,
   })
 }
 
-fn find_project_root(from: &Path) -> Option<PathBuf> {
+/// A detection cached for a project, with the modification times of its
+/// detection inputs when it was made
+#[derive(Serialize, Deserialize)]
+struct CachedDetection {
+  detection: Detection,
+  signature: Vec<(String, u128)>,
+}
+
+/// Location of the package manager detection cache
+pub fn detection_cache_path() -> Option<PathBuf> {
+  dirs::cache_dir().map(|dir| dir.join("uiget").join("package-manager.json"))
+}
+
+/// Detect the package manager like `detect_package_manager`, reusing the
+/// result cached in `cache_path` while the project's package.json and
+/// lockfiles keep their modification times. Detections from the user agent
+/// depend on how uiget was invoked and are never cached.
+pub fn detect_package_manager_cached(
+  start_dir: impl AsRef<Path>,
+  cache_path: Option<&Path>,
+) -> Result<Detection, DetectError> {
+  let Some(cache_path) = cache_path.filter(|_| env::var_os("npm_config_user_agent").is_none())
+  else {
+    return detect_package_manager(start_dir);
+  };
+
+  let start = start_dir.as_ref().canonicalize()?;
+  let project_root =
+    find_project_root(&start).ok_or_else(|| DetectError::NoProject(start.display().to_string()))?;
+  let key = project_root.display().to_string();
+  let mut signature = detection_signature(&project_root);
+  if let Some(workspace) = find_workspace(&project_root).filter(|w| w.root != project_root) {
+    signature.extend(detection_signature(&workspace.root));
+  }
+
+  let mut cache: HashMap<String, CachedDetection> = fs::read_to_string(cache_path)
+    .ok()
+    .and_then(|content| serde_json::from_str(&content).ok())
+    .unwrap_or_default();
+  if let Some(cached) = cache.get(&key) {
+    if cached.signature == signature {
+      return Ok(cached.detection.clone());
+    }
+  }
+
+  let detection = detect_package_manager(&project_root)?;
+  cache.insert(
+    key,
+    CachedDetection {
+      detection: detection.clone(),
+      signature,
+    },
+  );
+
+  // A cache that can't be written only costs a detection next time
+  if let Some(parent) = cache_path.parent() {
+    let _ = fs::create_dir_all(parent);
+  }
+  if let Ok(content) = serde_json::to_string_pretty(&cache) {
+    let _ = paths::write_atomic(cache_path, content);
+  }
+
+  Ok(detection)
+}
+
+/// Modification times of the detection inputs present in `root`
+fn detection_signature(root: &Path) -> Vec<(String, u128)> {
+  DETECTION_INPUTS
+    .iter()
+    .filter_map(|name| {
+      let modified = fs::metadata(root.join(name)).ok()?.modified().ok()?;
+      let nanos = modified
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|duration| duration.as_nanos())
+        .unwrap_or(0);
+      Some((name.to_string(), nanos))
+    })
+    .collect()
+}
+
+/// Nearest directory at or above `from` with a package.json
+pub fn find_project_root(from: &Path) -> Option<PathBuf> {
   let mut cur = Some(from.to_path_buf());
   while let Some(dir) = cur {
     if dir.join("package.json").exists() {
@@ -158,7 +363,7 @@ fn read_package_manager_field(
   let pj: PackageJson = serde_json::from_str(&data)
     .map_err(|e| DetectError::BadJson(pj_path.display().to_string(), e.to_string()))?;
 
-  if let Some(pm_str) = pj.packageManager {
+  if let Some(pm_str) = pj.package_manager {
     // formato: "<name>@<version>", ex: "pnpm@8.15.4", "yarn@3.5.1", "npm@9.9.0",
     // "bun@1.1.8"
     let re = Regex::new(r"^(?P<name>[a-zA-Z]+)@(?P<ver>[\w\.\-]+)$").unwrap();
@@ -189,7 +394,8 @@ fn read_package_manager_field(
 }
 
 fn find_yarn_artifacts(root: &Path) -> Option<PathBuf> {
-  // Yarn Berry geralmente tem .yarn/ e/ou .pnp.cjs/.pnp.data.json
+  // Yarn Berry geralmente tem .yarn/ e/ou .pnp.cjs/.pnp.data.json.
+  // .yarnrc.yml is Berry's config file whatever it sets; Yarn 1 reads .yarnrc
   let candidates = [
     root.join(".pnp.cjs"),
     root.join(".pnp.loader.mjs"),
@@ -197,42 +403,188 @@ fn find_yarn_artifacts(root: &Path) -> Option<PathBuf> {
     root.join(".yarnrc.yml"),
     root.join(".yarn"),
   ];
-  for p in candidates {
-    if p.exists() {
-      // heurística extra: se existir .yarnrc.yml com "nodeLinker: pnp" ou "yarnPath"
-      if p.file_name()?.to_string_lossy() == ".yarnrc.yml" {
-        if let Ok(s) = fs::read_to_string(&p) {
-          if s.contains("nodeLinker: pnp") || s.contains("yarnPath:") {
-            return Some(p);
-          }
+  candidates.into_iter().find(|p| p.exists())
+}
+
+/// How Yarn Berry lays out installed packages (`nodeLinker`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeLinker {
+  /// Plug'n'Play: no node_modules at all (Yarn's default)
+  Pnp,
+  /// A pnpm-style node_modules of symlinks
+  Pnpm,
+  /// A classic node_modules
+  NodeModules,
+}
+
+/// The settings of a project's `.yarnrc.yml` that change how packages are
+/// installed and run
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct YarnSettings {
+  pub node_linker: Option<NodeLinker>,
+  /// Yarn release the project runs, relative to the project root
+  pub yarn_path: Option<PathBuf>,
+}
+
+impl YarnSettings {
+  /// The linker in effect: Plug'n'Play unless `nodeLinker` says otherwise
+  pub fn linker(&self) -> NodeLinker {
+    self.node_linker.unwrap_or(NodeLinker::Pnp)
+  }
+}
+
+/// Read `nodeLinker` and `yarnPath` from the project's `.yarnrc.yml`, or
+/// None when there isn't one. Only top-level scalar keys are read, which is
+/// all these two ever are.
+pub fn read_yarnrc(root: &Path) -> Option<YarnSettings> {
+  let content = fs::read_to_string(root.join(".yarnrc.yml")).ok()?;
+  let mut settings = YarnSettings::default();
+  for line in content.lines() {
+    if line.starts_with([' ', '\t']) {
+      continue;
+    }
+    let Some((key, value)) = line.split_once(':') else {
+      continue;
+    };
+    let value = value.split(" #").next().unwrap_or_default().trim();
+    let value = value.trim_matches(|c| c == '"' || c == '\'');
+    match key.trim() {
+      "nodeLinker" => {
+        settings.node_linker = match value {
+          "pnp" => Some(NodeLinker::Pnp),
+          "pnpm" => Some(NodeLinker::Pnpm),
+          "node-modules" => Some(NodeLinker::NodeModules),
+          _ => None,
         }
-        // mesmo sem essas chaves, manter como pista
-        return Some(p);
       }
-      return Some(p);
+      "yarnPath" if !value.is_empty() => settings.yarn_path = Some(PathBuf::from(value)),
+      _ => {}
     }
   }
-  None
+  Some(settings)
+}
+
+/// Whether the project installs packages with Plug'n'Play, so nothing is
+/// in node_modules and binaries run through `yarn <bin>`
+pub fn uses_pnp(root: &Path) -> bool {
+  root.join(".pnp.cjs").exists()
+    || read_yarnrc(root).is_some_and(|settings| settings.linker() == NodeLinker::Pnp)
 }
 
 fn find_pnpm_artifacts(root: &Path) -> Option<PathBuf> {
   let p = root.join("pnpm-workspace.yaml");
-  if p.exists() {
-    Some(p)
-  } else {
-    None
+  p.exists().then_some(p)
+}
+
+/// Lockfile names, with the format each is written in
+const LOCKFILES: [&str; 5] = [
+  "yarn.lock",
+  "pnpm-lock.yaml",
+  "package-lock.json",
+  "bun.lock",
+  "bun.lockb",
+];
+
+/// How far into a lockfile its format version is looked for; every format
+/// states it in the first few lines
+const LOCKFILE_HEAD_SIZE: u64 = 4096;
+
+/// A lockfile format, with the version it declares when it has one
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LockfileFormat {
+  /// package-lock.json and its `lockfileVersion`: 3 since npm 9, with only
+  /// the `packages` section
+  Npm(Option<u32>),
+  /// yarn.lock written by Yarn 1
+  YarnClassic,
+  /// yarn.lock written by Yarn 2+, with its `__metadata` version
+  YarnBerry(Option<u32>),
+  /// pnpm-lock.yaml and its `lockfileVersion`, e.g. "9.0" since pnpm 9
+  Pnpm(Option<String>),
+  /// Bun's text bun.lock (Bun 1.2+) and its `lockfileVersion`
+  BunText(Option<u32>),
+  /// Bun's binary bun.lockb
+  BunBinary,
+}
+
+impl LockfileFormat {
+  /// The package manager that writes this format
+  pub fn manager(&self) -> PackageManager {
+    match self {
+      LockfileFormat::Npm(_) => PackageManager::Npm,
+      LockfileFormat::YarnClassic => PackageManager::YarnClassic,
+      LockfileFormat::YarnBerry(_) => PackageManager::YarnBerry,
+      LockfileFormat::Pnpm(_) => PackageManager::Pnpm,
+      LockfileFormat::BunText(_) | LockfileFormat::BunBinary => PackageManager::Bun,
+    }
+  }
+}
+
+impl fmt::Display for LockfileFormat {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let version = |version: &Option<u32>| match version {
+      Some(version) => format!(" v{}", version),
+      None => String::new(),
+    };
+    match self {
+      LockfileFormat::Npm(v) => write!(f, "package-lock.json{}", version(v)),
+      LockfileFormat::YarnClassic => write!(f, "yarn.lock v1"),
+      LockfileFormat::YarnBerry(v) => write!(f, "yarn.lock (Berry){}", version(v)),
+      LockfileFormat::Pnpm(Some(v)) => write!(f, "pnpm-lock.yaml v{}", v),
+      LockfileFormat::Pnpm(None) => write!(f, "pnpm-lock.yaml"),
+      LockfileFormat::BunText(v) => write!(f, "bun.lock (text){}", version(v)),
+      LockfileFormat::BunBinary => write!(f, "bun.lockb (binary)"),
+    }
+  }
+}
+
+/// The format of the lockfile at `path`, from its name and the version it
+/// declares near the top
+pub fn read_lockfile_format(path: &Path) -> Option<LockfileFormat> {
+  let name = path.file_name()?.to_str()?;
+  if name == "bun.lockb" {
+    return path.exists().then_some(LockfileFormat::BunBinary);
   }
+
+  let mut head = String::new();
+  fs::File::open(path)
+    .ok()?
+    .take(LOCKFILE_HEAD_SIZE)
+    .read_to_string(&mut head)
+    .ok()?;
+  let number = |pattern: &str| {
+    Regex::new(pattern)
+      .unwrap()
+      .captures(&head)
+      .map(|captures| captures[1].to_string())
+  };
+
+  Some(match name {
+    "package-lock.json" => {
+      LockfileFormat::Npm(number(r#""lockfileVersion"\s*:\s*(\d+)"#).and_then(|v| v.parse().ok()))
+    }
+    "bun.lock" => LockfileFormat::BunText(
+      number(r#""lockfileVersion"\s*:\s*(\d+)"#).and_then(|v| v.parse().ok()),
+    ),
+    "pnpm-lock.yaml" => LockfileFormat::Pnpm(number(r#"(?m)^lockfileVersion:\s*['"]?([\d.]+)"#)),
+    "yarn.lock" if head.contains("__metadata:") => {
+      LockfileFormat::YarnBerry(number(r"(?m)^  version:\s*(\d+)").and_then(|v| v.parse().ok()))
+    }
+    "yarn.lock" => LockfileFormat::YarnClassic,
+    _ => return None,
+  })
 }
 
-fn pick_by_lockfiles(root: &Path) -> Result<Option<Detection>, std::io::Error> {
+fn pick_by_lockfiles(root: &Path) -> Result<Option<(PackageManager, PathBuf)>, std::io::Error> {
   let mut candidates: Vec<(PackageManager, PathBuf, SystemTime)> = Vec::new();
 
-  let map = [
-    (PackageManager::YarnClassic, root.join("yarn.lock")),
-    (PackageManager::Pnpm, root.join("pnpm-lock.yaml")),
-    (PackageManager::Npm, root.join("package-lock.json")),
-    (PackageManager::Bun, root.join("bun.lockb")),
-  ];
+  // Yarn Berry's yarn.lock and Bun's text lockfile are told apart by
+  // their content
+  let map = LOCKFILES.into_iter().filter_map(|name| {
+    let path = root.join(name);
+    let format = read_lockfile_format(&path)?;
+    Some((format.manager(), path))
+  });
 
   for (pm, path) in map {
     if path.exists() {
@@ -248,14 +600,8 @@ fn pick_by_lockfiles(root: &Path) -> Result<Option<Detection>, std::io::Error> {
 
   // desempate: lockfile mais recente
   candidates.sort_by_key(|(_, _, m)| *m);
-  let (pm, path, _) = candidates.last().unwrap().clone();
-
-  Ok(Some(Detection {
-    manager: pm,
-    version_hint: None,
-    source: DetectionSource::Lockfile(path),
-    project_root: root.to_path_buf(),
-  }))
+  let (pm, path, _) = candidates.pop().unwrap();
+  Ok(Some((pm, path)))
 }
 
 /// npm_config_user_agent exemplos:
@@ -280,9 +626,7 @@ fn parse_user_agent(ua: &str) -> Option<(PackageManager, Option<String>)> {
   let ver = it.next().map(|s| s.to_string());
 
   // If no version part, it's invalid format
-  if ver.is_none() {
-    return None;
-  }
+  ver.as_ref()?;
 
   let pm = match name.as_str() {
     "pnpm" => PackageManager::Pnpm,
@@ -306,6 +650,132 @@ fn parse_user_agent(ua: &str) -> Option<(PackageManager, Option<String>)> {
   Some((pm, ver))
 }
 
+/// Split an npm dependency specifier into its package name and optional
+/// version range, e.g. "zod@^3.23" -> ("zod", Some("^3.23")) and
+/// "@scope/pkg" -> ("@scope/pkg", None)
+pub fn parse_dependency_spec(spec: &str) -> (&str, Option<&str>) {
+  // Scoped packages start with '@', so look for the version separator after it
+  let search_from = usize::from(spec.starts_with('@'));
+  match spec[search_from..].find('@') {
+    Some(pos) => {
+      let split = search_from + pos;
+      let version = &spec[split + 1..];
+      (
+        &spec[..split],
+        if version.is_empty() {
+          None
+        } else {
+          Some(version)
+        },
+      )
+    }
+    None => (spec, None),
+  }
+}
+
+/// Check whether a version satisfies an npm-style range such as ">=18",
+/// "^18.17.0", "~20.1", "18.x" or "<20 || >=22". Unparseable ranges are
+/// treated as satisfied so that exotic constraints never block an install.
+pub fn satisfies_range(version: &str, range: &str) -> bool {
+  let version = parse_version(version.trim().trim_start_matches('v'));
+
+  range.split("||").any(|alternative| {
+    alternative
+      .split_whitespace()
+      .all(|comparator| satisfies_comparator(version, comparator))
+  })
+}
+
+/// Parse `major.minor.patch`, treating missing or non-numeric parts as 0
+pub fn parse_version(ver: &str) -> (u64, u64, u64) {
+  let mut nums = ver
+    .split(['.', '-', '+'])
+    .map(|s| s.parse::<u64>().unwrap_or(0));
+  (
+    nums.next().unwrap_or(0),
+    nums.next().unwrap_or(0),
+    nums.next().unwrap_or(0),
+  )
+}
+
+fn satisfies_comparator(version: (u64, u64, u64), comparator: &str) -> bool {
+  let (op, ver) = match comparator {
+    c if c.starts_with(">=") || c.starts_with("<=") => c.split_at(2),
+    c if c.starts_with('>') || c.starts_with('<') || c.starts_with('=') => c.split_at(1),
+    c if c.starts_with('^') || c.starts_with('~') => c.split_at(1),
+    c => ("", c),
+  };
+  let ver = ver.trim_start_matches('v');
+
+  if ver.is_empty() || ver == "*" || ver == "x" {
+    return true;
+  }
+
+  // Count the explicitly specified parts ("18" = 1, "18.2" = 2, "18.x" = 1)
+  let specified = ver
+    .split('.')
+    .take_while(|part| part.parse::<u64>().is_ok())
+    .count();
+  if specified == 0 {
+    return true;
+  }
+  let target = parse_version(ver);
+
+  match op {
+    ">=" => version >= target,
+    ">" => version > target,
+    "<=" => version <= target,
+    "<" => version < target,
+    "^" => {
+      version >= target
+        && match target {
+          (0, 0, _) if specified == 3 => version == target,
+          (0, minor, _) if specified >= 2 => version.0 == 0 && version.1 == minor,
+          (major, _, _) => version.0 == major,
+        }
+    }
+    "~" => version >= target && version.0 == target.0 && (specified == 1 || version.1 == target.1),
+    _ => match specified {
+      1 => version.0 == target.0,
+      2 => version.0 == target.0 && version.1 == target.1,
+      _ => version == target,
+    },
+  }
+}
+
+/// Collect every package declared in the dependencies, devDependencies and
+/// peerDependencies of the project's package.json
+pub fn read_declared_packages(project_root: &Path) -> Vec<String> {
+  let Ok(content) = fs::read_to_string(project_root.join("package.json")) else {
+    return Vec::new();
+  };
+  let Ok(package_json) = serde_json::from_str::<serde_json::Value>(&content) else {
+    return Vec::new();
+  };
+
+  ["dependencies", "devDependencies", "peerDependencies"]
+    .iter()
+    .filter_map(|key| package_json.get(key).and_then(|deps| deps.as_object()))
+    .flat_map(|deps| deps.keys().cloned())
+    .collect()
+}
+
+/// Get the version of the local Node.js installation, e.g. "20.14.0"
+pub fn node_version() -> Option<String> {
+  let output = std::process::Command::new("node")
+    .arg("--version")
+    .output()
+    .ok()?;
+  if !output.status.success() {
+    return None;
+  }
+  let version = String::from_utf8_lossy(&output.stdout)
+    .trim()
+    .trim_start_matches('v')
+    .to_string();
+  (!version.is_empty()).then_some(version)
+}
+
 fn is_semver_gte(ver: &str, maj: u64, min: u64, pat: u64) -> bool {
   // parse parcial: "3.6.1", "3.6", "3"
   let mut nums = ver.split('.').map(|s| s.parse::<u64>().unwrap_or(0));
@@ -315,6 +785,152 @@ fn is_semver_gte(ver: &str, maj: u64, min: u64, pat: u64) -> bool {
   (vmaj, vmin, vpat) >= (maj, min, pat)
 }
 
+impl PackageManager {
+  /// Retorna o comando para instalar dependências normais
+  pub fn install_command(&self) -> Vec<String> {
+    match self {
+      PackageManager::Npm => vec!["npm".to_string(), "install".to_string()],
+      PackageManager::YarnClassic => vec!["yarn".to_string(), "add".to_string()],
+      PackageManager::YarnBerry => vec!["yarn".to_string(), "add".to_string()],
+      PackageManager::Pnpm => vec!["pnpm".to_string(), "add".to_string()],
+      PackageManager::Bun => vec!["bun".to_string(), "add".to_string()],
+      PackageManager::Unknown => vec!["npm".to_string(), "install".to_string()],
+    }
+  }
+
+  /// Retorna o comando para instalar dev dependencies
+  pub fn install_dev_command(&self) -> Vec<String> {
+    match self {
+      PackageManager::Npm => vec![
+        "npm".to_string(),
+        "install".to_string(),
+        "--save-dev".to_string(),
+      ],
+      PackageManager::YarnClassic => {
+        vec!["yarn".to_string(), "add".to_string(), "--dev".to_string()]
+      }
+      PackageManager::YarnBerry => vec!["yarn".to_string(), "add".to_string(), "--dev".to_string()],
+      PackageManager::Pnpm => vec![
+        "pnpm".to_string(),
+        "add".to_string(),
+        "--save-dev".to_string(),
+      ],
+      PackageManager::Bun => vec!["bun".to_string(), "add".to_string(), "--dev".to_string()],
+      PackageManager::Unknown => vec![
+        "npm".to_string(),
+        "install".to_string(),
+        "--save-dev".to_string(),
+      ],
+    }
+  }
+
+  /// Retorna o nome do package manager para exibição
+  pub fn name(&self) -> &'static str {
+    match self {
+      PackageManager::Npm => "npm",
+      PackageManager::YarnClassic => "yarn (classic)",
+      PackageManager::YarnBerry => "yarn (berry)",
+      PackageManager::Pnpm => "pnpm",
+      PackageManager::Bun => "bun",
+      PackageManager::Unknown => "unknown",
+    }
+  }
+}
+
+impl Detection {
+  /// Directory with the lockfile: the workspace root in a monorepo, else
+  /// the project root
+  pub fn lookup_root(&self) -> &Path {
+    match &self.workspace {
+      Some(workspace) => &workspace.root,
+      None => &self.project_root,
+    }
+  }
+
+  /// Whether the project is a package of a monorepo rather than its root
+  pub fn in_workspace_package(&self) -> bool {
+    self
+      .workspace
+      .as_ref()
+      .is_some_and(|workspace| workspace.root != self.project_root)
+  }
+
+  /// The directory to run the install command in and the flags that make
+  /// it add dependencies at `location`. pnpm and npm run at the workspace
+  /// root, pointed at the package with `--filter`/`--workspace`; Yarn and
+  /// Bun run in the package itself. Adding to the root needs `-w` with
+  /// pnpm and `-W` with Yarn 1, which otherwise refuse.
+  pub fn install_scope(&self, location: InstallLocation) -> (PathBuf, Vec<String>) {
+    let Some(workspace) = &self.workspace else {
+      return (self.project_root.clone(), Vec::new());
+    };
+    let location = if self.in_workspace_package() {
+      location
+    } else {
+      InstallLocation::Root
+    };
+    let root = workspace.root.clone();
+    let package = read_package_name(&self.project_root);
+
+    match (location, self.manager, package) {
+      (InstallLocation::Root, PackageManager::Pnpm, _) => (root, vec!["-w".to_string()]),
+      (InstallLocation::Root, PackageManager::YarnClassic, _) => (root, vec!["-W".to_string()]),
+      (InstallLocation::Root, _, _) => (root, Vec::new()),
+      (InstallLocation::Package, PackageManager::Pnpm, Some(name)) => {
+        (root, vec!["--filter".to_string(), name])
+      }
+      (InstallLocation::Package, PackageManager::Npm | PackageManager::Unknown, Some(name)) => {
+        (root, vec!["--workspace".to_string(), name])
+      }
+      (InstallLocation::Package, _, _) => (self.project_root.clone(), Vec::new()),
+    }
+  }
+
+  /// The project's lockfile for the detected package manager, and its
+  /// format. Bun's text lockfile wins over a leftover binary one.
+  pub fn lockfile(&self) -> Option<(PathBuf, LockfileFormat)> {
+    LOCKFILES.into_iter().find_map(|name| {
+      let path = self.lookup_root().join(name);
+      let format = read_lockfile_format(&path)?;
+      let same_manager = match (format.manager(), self.manager) {
+        (PackageManager::YarnClassic | PackageManager::YarnBerry, manager) => {
+          matches!(
+            manager,
+            PackageManager::YarnClassic | PackageManager::YarnBerry
+          )
+        }
+        (manager, detected) => manager == detected,
+      };
+      same_manager.then_some((path, format))
+    })
+  }
+
+  /// Retorna informações sobre a detecção para logging
+  pub fn info(&self) -> String {
+    let source_desc = match &self.source {
+      DetectionSource::PackageJsonField => tr(Message::SourcePackageJsonField, &[]),
+      DetectionSource::Lockfile(path) => tr(Message::SourceLockfile, &[&path.display()]),
+      DetectionSource::YarnArtifacts(path) => tr(Message::SourceYarnArtifacts, &[&path.display()]),
+      DetectionSource::PnpmArtifacts(path) => tr(Message::SourcePnpmArtifacts, &[&path.display()]),
+      DetectionSource::UserAgent(ua) => tr(Message::SourceUserAgent, &[ua]),
+      DetectionSource::Heuristic => tr(Message::SourceHeuristic, &[]),
+    };
+
+    let info = tr(
+      Message::PackageManagerDetected,
+      &[
+        &self.manager.name(),
+        &source_desc,
+        &self.project_root.display(),
+      ],
+    );
+    match self.lockfile() {
+      Some((_, format)) => format!("{} ({})", info, format),
+      None => info,
+    }
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use std::fs;
@@ -420,6 +1036,55 @@ mod tests {
     assert!(parse_user_agent("invalid").is_none());
   }
 
+  #[test]
+  fn test_parse_dependency_spec() {
+    assert_eq!(parse_dependency_spec("zod"), ("zod", None));
+    assert_eq!(parse_dependency_spec("zod@^3.23"), ("zod", Some("^3.23")));
+    assert_eq!(
+      parse_dependency_spec("@tanstack/table-core"),
+      ("@tanstack/table-core", None)
+    );
+    assert_eq!(
+      parse_dependency_spec("@tanstack/table-core@8.20.5"),
+      ("@tanstack/table-core", Some("8.20.5"))
+    );
+    assert_eq!(parse_dependency_spec("zod@"), ("zod", None));
+  }
+
+  #[test]
+  fn test_satisfies_range() {
+    assert!(satisfies_range("20.14.0", ">=18"));
+    assert!(satisfies_range("v18.17.1", ">=18.17.0"));
+    assert!(!satisfies_range("16.20.0", ">=18"));
+    assert!(satisfies_range("18.19.0", "^18.17.0"));
+    assert!(!satisfies_range("19.0.0", "^18.17.0"));
+    assert!(satisfies_range("0.2.5", "^0.2.1"));
+    assert!(!satisfies_range("0.3.0", "^0.2.1"));
+    assert!(satisfies_range("20.1.9", "~20.1"));
+    assert!(!satisfies_range("20.2.0", "~20.1"));
+    assert!(satisfies_range("18.5.0", "18.x"));
+    assert!(satisfies_range("19.1.0", ">=18 <20"));
+    assert!(!satisfies_range("20.0.0", ">=18 <20"));
+    assert!(satisfies_range("22.1.0", "<20 || >=22"));
+    assert!(!satisfies_range("21.0.0", "<20 || >=22"));
+    assert!(satisfies_range("20.0.0", "*"));
+  }
+
+  #[test]
+  fn test_read_declared_packages() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+      temp_dir.path().join("package.json"),
+      r#"{"dependencies": {"svelte": "^5"}, "devDependencies": {"vite": "^5"}}"#,
+    )
+    .unwrap();
+
+    let mut packages = read_declared_packages(temp_dir.path());
+    packages.sort();
+    assert_eq!(packages, vec!["svelte", "vite"]);
+    assert!(read_declared_packages(&temp_dir.path().join("missing")).is_empty());
+  }
+
   #[test]
   fn test_is_semver_gte() {
     assert!(is_semver_gte("3.6.1", 3, 6, 0));
@@ -432,6 +1097,111 @@ mod tests {
     assert!(!is_semver_gte("3.6.0", 3, 6, 1));
   }
 
+  #[test]
+  fn test_detection_cache() {
+    if env::var_os("npm_config_user_agent").is_some() {
+      return;
+    }
+
+    let temp_dir = TempDir::new().unwrap();
+    let project_dir = temp_dir.path().join("project");
+    let cache_path = temp_dir.path().join("cache/package-manager.json");
+    fs::create_dir(&project_dir).unwrap();
+    fs::write(project_dir.join("package.json"), r#"{"name": "test"}"#).unwrap();
+    fs::write(project_dir.join("pnpm-lock.yaml"), "").unwrap();
+
+    let detection = detect_package_manager_cached(&project_dir, Some(&cache_path)).unwrap();
+    assert_eq!(detection.manager, PackageManager::Pnpm);
+    assert!(cache_path.exists());
+
+    // Unchanged inputs are answered from the cache
+    let cache = fs::read_to_string(&cache_path).unwrap();
+    fs::write(&cache_path, cache.replace("\"Pnpm\"", "\"Bun\"")).unwrap();
+    let cached = detect_package_manager_cached(&project_dir, Some(&cache_path)).unwrap();
+    assert_eq!(cached.manager, PackageManager::Bun);
+
+    // A new lockfile invalidates the entry
+    fs::remove_file(project_dir.join("pnpm-lock.yaml")).unwrap();
+    fs::write(project_dir.join("yarn.lock"), "").unwrap();
+    let detection = detect_package_manager_cached(&project_dir, Some(&cache_path)).unwrap();
+    assert_eq!(detection.manager, PackageManager::YarnClassic);
+  }
+
+  #[test]
+  fn test_workspace_install_scope() {
+    if env::var_os("npm_config_user_agent").is_some() {
+      return;
+    }
+
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().canonicalize().unwrap();
+    let package = root.join("apps/web");
+    fs::create_dir_all(&package).unwrap();
+    fs::write(root.join("package.json"), r#"{"name": "monorepo"}"#).unwrap();
+    fs::write(root.join("pnpm-workspace.yaml"), "packages:\n  - apps/*\n").unwrap();
+    fs::write(root.join("turbo.json"), "{}").unwrap();
+    fs::write(root.join("pnpm-lock.yaml"), "lockfileVersion: '9.0'\n").unwrap();
+    fs::write(package.join("package.json"), r#"{"name": "@acme/web"}"#).unwrap();
+
+    let detection = detect_package_manager(&package).unwrap();
+    assert_eq!(detection.manager, PackageManager::Pnpm);
+    assert_eq!(detection.project_root, package);
+    let workspace = detection.workspace.clone().unwrap();
+    assert_eq!(workspace.root, root);
+    assert!(workspace.pnpm && workspace.turbo);
+    assert!(detection.in_workspace_package());
+    assert_eq!(
+      detection.lockfile().unwrap().1,
+      LockfileFormat::Pnpm(Some("9.0".to_string()))
+    );
+
+    assert_eq!(
+      detection.install_scope(InstallLocation::Root),
+      (root.clone(), vec!["-w".to_string()])
+    );
+    assert_eq!(
+      detection.install_scope(InstallLocation::Package),
+      (
+        root.clone(),
+        vec!["--filter".to_string(), "@acme/web".to_string()]
+      )
+    );
+
+    let npm = Detection {
+      manager: PackageManager::Npm,
+      ..detection.clone()
+    };
+    assert_eq!(
+      npm.install_scope(InstallLocation::Package).1,
+      ["--workspace", "@acme/web"]
+    );
+    let yarn = Detection {
+      manager: PackageManager::YarnClassic,
+      ..detection.clone()
+    };
+    assert_eq!(
+      yarn.install_scope(InstallLocation::Root),
+      (root.clone(), vec!["-W".to_string()])
+    );
+    assert_eq!(
+      yarn.install_scope(InstallLocation::Package),
+      (package.clone(), Vec::new())
+    );
+
+    // At the workspace root, dependencies always go to the root
+    let at_root = detect_package_manager(&root).unwrap();
+    assert!(!at_root.in_workspace_package());
+    assert_eq!(
+      at_root.install_scope(InstallLocation::Package),
+      (root.clone(), vec!["-w".to_string()])
+    );
+
+    // A project outside any monorepo installs where it is
+    fs::remove_file(root.join("pnpm-workspace.yaml")).unwrap();
+    fs::remove_file(root.join("turbo.json")).unwrap();
+    assert!(find_workspace(&package).is_none());
+  }
+
   #[test]
   fn test_find_project_root() {
     let temp_dir = TempDir::new().unwrap();
@@ -454,82 +1224,101 @@ mod tests {
   #[test]
   fn test_detect_error_display() {
     let err = DetectError::NoProject("/path/to/project".to_string());
-    assert!(err.to_string().contains("nenhum projeto Node"));
+    assert!(err.to_string().contains("no Node project"));
 
     let err = DetectError::BadJson("file.json".to_string(), "invalid json".to_string());
-    assert!(err.to_string().contains("json inválido"));
+    assert!(err.to_string().contains("invalid JSON in file.json"));
   }
-}
 
-impl PackageManager {
-  /// Retorna o comando para instalar dependências normais
-  pub fn install_command(&self) -> Vec<String> {
-    match self {
-      PackageManager::Npm => vec!["npm".to_string(), "install".to_string()],
-      PackageManager::YarnClassic => vec!["yarn".to_string(), "add".to_string()],
-      PackageManager::YarnBerry => vec!["yarn".to_string(), "add".to_string()],
-      PackageManager::Pnpm => vec!["pnpm".to_string(), "add".to_string()],
-      PackageManager::Bun => vec!["bun".to_string(), "add".to_string()],
-      PackageManager::Unknown => vec!["npm".to_string(), "install".to_string()],
-    }
-  }
+  #[test]
+  fn test_read_yarnrc() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    assert_eq!(read_yarnrc(root), None);
+    assert!(!uses_pnp(root));
 
-  /// Retorna o comando para instalar dev dependencies
-  pub fn install_dev_command(&self) -> Vec<String> {
-    match self {
-      PackageManager::Npm => vec![
-        "npm".to_string(),
-        "install".to_string(),
-        "--save-dev".to_string(),
-      ],
-      PackageManager::YarnClassic => {
-        vec!["yarn".to_string(), "add".to_string(), "--dev".to_string()]
-      }
-      PackageManager::YarnBerry => vec!["yarn".to_string(), "add".to_string(), "--dev".to_string()],
-      PackageManager::Pnpm => vec![
-        "pnpm".to_string(),
-        "add".to_string(),
-        "--save-dev".to_string(),
-      ],
-      PackageManager::Bun => vec!["bun".to_string(), "add".to_string(), "--dev".to_string()],
-      PackageManager::Unknown => vec![
-        "npm".to_string(),
-        "install".to_string(),
-        "--save-dev".to_string(),
-      ],
-    }
-  }
+    fs::write(
+      root.join(".yarnrc.yml"),
+      "enableTelemetry: false\nyarnPath: \".yarn/releases/yarn-4.1.0.cjs\"\npackageExtensions:\n  nodeLinker: pnp\n",
+    )
+    .unwrap();
+    let settings = read_yarnrc(root).unwrap();
+    assert_eq!(settings.node_linker, None);
+    assert_eq!(
+      settings.yarn_path,
+      Some(PathBuf::from(".yarn/releases/yarn-4.1.0.cjs"))
+    );
+    // Plug'n'Play is Yarn Berry's default
+    assert!(uses_pnp(root));
 
-  /// Retorna o nome do package manager para exibição
-  pub fn name(&self) -> &'static str {
-    match self {
-      PackageManager::Npm => "npm",
-      PackageManager::YarnClassic => "yarn (classic)",
-      PackageManager::YarnBerry => "yarn (berry)",
-      PackageManager::Pnpm => "pnpm",
-      PackageManager::Bun => "bun",
-      PackageManager::Unknown => "unknown",
-    }
+    fs::write(
+      root.join(".yarnrc.yml"),
+      "nodeLinker: 'node-modules' # for React Native\n",
+    )
+    .unwrap();
+    assert_eq!(read_yarnrc(root).unwrap().linker(), NodeLinker::NodeModules);
+    assert!(!uses_pnp(root));
+    fs::write(root.join(".pnp.cjs"), "").unwrap();
+    assert!(uses_pnp(root));
   }
-}
 
-impl Detection {
-  /// Retorna informações sobre a detecção para logging
-  pub fn info(&self) -> String {
-    let source_desc = match &self.source {
-      DetectionSource::PackageJsonField => "package.json field".to_string(),
-      DetectionSource::Lockfile(path) => format!("lockfile: {}", path.display()),
-      DetectionSource::YarnArtifacts(path) => format!("yarn artifacts: {}", path.display()),
-      DetectionSource::PnpmArtifacts(path) => format!("pnpm artifacts: {}", path.display()),
-      DetectionSource::UserAgent(ua) => format!("user agent: {}", ua),
-      DetectionSource::Heuristic => "heuristic".to_string(),
+  #[test]
+  fn test_lockfile_formats() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    fs::write(root.join("package.json"), "{}").unwrap();
+    let format = |name: &str, content: &str| {
+      fs::write(root.join(name), content).unwrap();
+      read_lockfile_format(&root.join(name))
     };
 
-    format!(
-      "Detected {} via {} at {}",
-      self.manager.name(),
-      source_desc,
-      self.project_root.display()
-    )
+    assert_eq!(
+      format(
+        "package-lock.json",
+        "{\n  \"name\": \"app\",\n  \"lockfileVersion\": 3,\n  \"packages\": {}\n}"
+      ),
+      Some(LockfileFormat::Npm(Some(3)))
+    );
+    assert_eq!(
+      format(
+        "pnpm-lock.yaml",
+        "lockfileVersion: '9.0'\n\nsettings:\n  autoInstallPeers: true\n"
+      ),
+      Some(LockfileFormat::Pnpm(Some("9.0".to_string())))
+    );
+    assert_eq!(
+      format(
+        "yarn.lock",
+        "# yarn lockfile v1\n\n\"zod@^3\":\n  version \"3.23.8\"\n"
+      ),
+      Some(LockfileFormat::YarnClassic)
+    );
+    assert_eq!(
+      format("yarn.lock", "__metadata:\n  version: 8\n  cacheKey: 10c0\n"),
+      Some(LockfileFormat::YarnBerry(Some(8)))
+    );
+    fs::remove_file(root.join("yarn.lock")).unwrap();
+    fs::remove_file(root.join("pnpm-lock.yaml")).unwrap();
+    fs::remove_file(root.join("package-lock.json")).unwrap();
+
+    fs::write(root.join("bun.lockb"), [0u8, 1, 2]).unwrap();
+    assert_eq!(
+      format(
+        "bun.lock",
+        "{\n  \"lockfileVersion\": 1,\n  \"workspaces\": {}\n}"
+      ),
+      Some(LockfileFormat::BunText(Some(1)))
+    );
+    let detection = detect_package_manager(root).unwrap();
+    assert_eq!(detection.manager, PackageManager::Bun);
+    // The text lockfile wins over a leftover binary one
+    assert_eq!(
+      detection.lockfile().map(|(_, format)| format),
+      Some(LockfileFormat::BunText(Some(1)))
+    );
+    assert_eq!(
+      LockfileFormat::BunText(Some(1)).to_string(),
+      "bun.lock (text) v1"
+    );
   }
 }
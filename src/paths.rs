@@ -0,0 +1,277 @@
+use std::{
+  collections::HashMap,
+  fs::{self, File, OpenOptions},
+  io::{self, Write},
+  path::{Path, PathBuf},
+};
+
+/// Advisory lock file held while uiget updates files in a directory
+pub const LOCK_FILE_NAME: &str = ".uiget.lock";
+
+/// Prefix that makes Windows skip MAX_PATH limits and path normalization
+const VERBATIM_PREFIX: &str = r"\\?\";
+
+/// Turn an absolute Windows path into its verbatim form: `C:\a` becomes
+/// `\\?\C:\a` and `\\server\share\a` becomes `\\?\UNC\server\share\a`.
+/// Paths that are already verbatim or device paths are returned unchanged.
+pub fn verbatim(path: &str) -> String {
+  let path = path.replace('/', "\\");
+
+  if path.starts_with(VERBATIM_PREFIX) || path.starts_with(r"\\.\") {
+    path
+  } else if let Some(unc) = path.strip_prefix(r"\\") {
+    format!(r"{}UNC\{}", VERBATIM_PREFIX, unc)
+  } else {
+    format!("{}{}", VERBATIM_PREFIX, path)
+  }
+}
+
+/// Path to hand to file system calls. On Windows it is made absolute (which
+/// also resolves `.` and `..`) and verbatim, so deeply nested targets work
+/// past MAX_PATH. Elsewhere the path is returned as is.
+pub fn extended(path: &Path) -> PathBuf {
+  if !cfg!(windows) {
+    return path.to_path_buf();
+  }
+
+  match std::path::absolute(path) {
+    Ok(absolute) => PathBuf::from(verbatim(&absolute.to_string_lossy())),
+    Err(_) => path.to_path_buf(),
+  }
+}
+
+/// Key under which paths that differ only in case compare equal, as they do
+/// on Windows and macOS file systems
+pub fn collision_key(path: &Path) -> String {
+  path.to_string_lossy().replace('\\', "/").to_lowercase()
+}
+
+/// Pairs of paths in `paths` that differ only in case
+pub fn case_collisions(paths: &[PathBuf]) -> Vec<(PathBuf, PathBuf)> {
+  let mut seen: HashMap<String, &PathBuf> = HashMap::new();
+  let mut collisions = Vec::new();
+
+  for path in paths {
+    match seen.get(&collision_key(path)) {
+      Some(first) if *first != path => collisions.push(((*first).clone(), path.clone())),
+      Some(_) => {}
+      None => {
+        seen.insert(collision_key(path), path);
+      }
+    }
+  }
+
+  collisions
+}
+
+/// An existing entry next to `path` whose name differs from it only in case
+pub fn existing_case_variant(path: &Path) -> Option<PathBuf> {
+  let name = path.file_name()?.to_string_lossy().to_lowercase();
+  let parent = parent_dir(path);
+
+  fs::read_dir(extended(parent))
+    .ok()?
+    .filter_map(|entry| entry.ok())
+    .map(|entry| entry.file_name())
+    .find(|entry| {
+      entry.to_string_lossy().to_lowercase() == name && Some(entry.as_os_str()) != path.file_name()
+    })
+    .map(|entry| parent.join(entry))
+}
+
+/// Create `path` and its parents
+pub fn create_dir_all(path: &Path) -> io::Result<()> {
+  fs::create_dir_all(extended(path))
+}
+
+/// Whether something exists at `path`
+pub fn exists(path: &Path) -> bool {
+  extended(path).exists()
+}
+
+/// Read `path` as UTF-8
+pub fn read_to_string(path: &Path) -> io::Result<String> {
+  fs::read_to_string(extended(path))
+}
+
+/// Write `contents` to `path`, creating its parent directories
+pub fn write_file(path: &Path, contents: impl AsRef<[u8]>) -> io::Result<()> {
+  if let Some(parent) = path.parent() {
+    if !parent.as_os_str().is_empty() {
+      create_dir_all(parent)?;
+    }
+  }
+
+  fs::write(extended(path), contents)
+}
+
+/// [`write_file`] on tokio's blocking-I/O threads, for writes made from
+/// async code
+pub async fn write_file_async(path: &Path, contents: impl AsRef<[u8]>) -> io::Result<()> {
+  if let Some(parent) = path.parent() {
+    if !parent.as_os_str().is_empty() {
+      tokio::fs::create_dir_all(extended(parent)).await?;
+    }
+  }
+
+  tokio::fs::write(extended(path), contents).await
+}
+
+/// Directory holding `path`, `.` for bare file names
+pub fn parent_dir(path: &Path) -> &Path {
+  match path.parent() {
+    Some(parent) if !parent.as_os_str().is_empty() => parent,
+    _ => Path::new("."),
+  }
+}
+
+/// Replace `path` with `contents` so readers and crashes see either the old
+/// or the new file: the data goes to a temp file in the same directory, is
+/// fsync'd, then renamed over `path`. The existing file's permissions are
+/// kept.
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> io::Result<()> {
+  let dir = parent_dir(path);
+  let file_name = path
+    .file_name()
+    .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "path has no file name"))?;
+  let temp_path = dir.join(format!(
+    ".{}.{}.tmp",
+    file_name.to_string_lossy(),
+    std::process::id()
+  ));
+
+  let result = (|| {
+    let mut file = File::create(extended(&temp_path))?;
+    if let Ok(metadata) = fs::metadata(extended(path)) {
+      file.set_permissions(metadata.permissions())?;
+    }
+    file.write_all(contents.as_ref())?;
+    file.sync_all()?;
+    fs::rename(extended(&temp_path), extended(path))
+  })();
+
+  if result.is_err() {
+    let _ = fs::remove_file(extended(&temp_path));
+  }
+  result?;
+
+  // Persist the rename itself
+  #[cfg(unix)]
+  if let Ok(dir) = File::open(dir) {
+    let _ = dir.sync_all();
+  }
+
+  Ok(())
+}
+
+/// Exclusive advisory lock on a lock file, released when dropped
+pub struct DirLock {
+  _file: File,
+}
+
+/// Wait for the advisory lock of the directory holding `path`, so
+/// concurrent uiget runs don't interleave their updates
+pub fn lock_for(path: &Path) -> io::Result<DirLock> {
+  lock(&parent_dir(path).join(LOCK_FILE_NAME))
+}
+
+/// Wait for the advisory lock on `lock_path`, creating the file
+pub fn lock(lock_path: &Path) -> io::Result<DirLock> {
+  let file = OpenOptions::new()
+    .create(true)
+    .truncate(false)
+    .write(true)
+    .open(extended(lock_path))?;
+  file.lock()?;
+
+  Ok(DirLock { _file: file })
+}
+
+/// Remove the file (or link) at `path`
+pub fn remove_file(path: &Path) -> io::Result<()> {
+  fs::remove_file(extended(path))
+}
+
+/// [`remove_file`] on tokio's blocking-I/O threads
+pub async fn remove_file_async(path: &Path) -> io::Result<()> {
+  tokio::fs::remove_file(extended(path)).await
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_verbatim() {
+    assert_eq!(verbatim(r"C:\project\src"), r"\\?\C:\project\src");
+    assert_eq!(verbatim("C:/project/src"), r"\\?\C:\project\src");
+    assert_eq!(
+      verbatim(r"\\server\share\project"),
+      r"\\?\UNC\server\share\project"
+    );
+    assert_eq!(verbatim(r"\\?\C:\project"), r"\\?\C:\project");
+    assert_eq!(verbatim(r"\\.\pipe\name"), r"\\.\pipe\name");
+  }
+
+  #[test]
+  fn test_write_atomic() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("uiget.json");
+
+    write_atomic(&path, "{}").unwrap();
+    write_atomic(&path, "{ \"style\": \"default\" }").unwrap();
+
+    assert_eq!(
+      fs::read_to_string(&path).unwrap(),
+      "{ \"style\": \"default\" }"
+    );
+    let entries: Vec<_> = fs::read_dir(temp_dir.path()).unwrap().collect();
+    assert_eq!(entries.len(), 1);
+  }
+
+  #[test]
+  fn test_lock_for() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("uiget.json");
+
+    let lock = lock_for(&path).unwrap();
+    let other = File::open(temp_dir.path().join(LOCK_FILE_NAME)).unwrap();
+    assert!(other.try_lock().is_err());
+
+    drop(lock);
+    assert!(other.try_lock().is_ok());
+  }
+
+  #[test]
+  fn test_case_collisions() {
+    let paths = vec![
+      PathBuf::from("ui/Button.svelte"),
+      PathBuf::from("ui/card.svelte"),
+      PathBuf::from("ui/button.svelte"),
+      PathBuf::from("ui/card.svelte"),
+    ];
+
+    assert_eq!(
+      case_collisions(&paths),
+      [(
+        PathBuf::from("ui/Button.svelte"),
+        PathBuf::from("ui/button.svelte")
+      )]
+    );
+  }
+
+  #[test]
+  fn test_write_file_and_case_variant() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("a/b/c/Button.svelte");
+
+    write_file(&path, "<button />").unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "<button />");
+
+    assert_eq!(existing_case_variant(&path), None);
+    assert_eq!(
+      existing_case_variant(&temp_dir.path().join("a/b/c/button.svelte")),
+      Some(path)
+    );
+  }
+}
@@ -0,0 +1,126 @@
+use std::{
+  env,
+  ffi::OsStr,
+  path::{Path, PathBuf},
+  process::{Command, ExitStatus},
+};
+
+use anyhow::{anyhow, Result};
+
+/// Prefix of external command executables, e.g. `uiget-audit`
+pub const PLUGIN_PREFIX: &str = "uiget-";
+
+/// Settings forwarded to plugins through the environment
+#[derive(Debug, Clone, Default)]
+pub struct PluginEnv {
+  pub config_path: PathBuf,
+  pub verbose: bool,
+  pub quiet: bool,
+  pub interactive: bool,
+}
+
+/// Find the `uiget-<name>` executable on PATH
+pub fn find_plugin(name: &str) -> Option<PathBuf> {
+  find_plugin_in(name, &env::var_os("PATH")?)
+}
+
+/// Find the `uiget-<name>` executable in a PATH-style list of directories
+pub fn find_plugin_in(name: &str, paths: &OsStr) -> Option<PathBuf> {
+  let file_name = format!("{}{}", PLUGIN_PREFIX, name);
+
+  env::split_paths(paths).find_map(|dir| {
+    executable_candidates(&dir, &file_name)
+      .into_iter()
+      .find(|candidate| is_executable(candidate))
+  })
+}
+
+/// Candidate file names for an executable, including Windows extensions
+fn executable_candidates(dir: &Path, file_name: &str) -> Vec<PathBuf> {
+  let mut candidates = vec![dir.join(file_name)];
+
+  if cfg!(windows) {
+    let extensions = env::var("PATHEXT").unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".to_string());
+    candidates.extend(
+      extensions
+        .split(';')
+        .filter(|ext| !ext.is_empty())
+        .map(|ext| dir.join(format!("{}{}", file_name, ext.to_lowercase()))),
+    );
+  }
+
+  candidates
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+  use std::os::unix::fs::PermissionsExt;
+
+  path
+    .metadata()
+    .map(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+    .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+  path.is_file()
+}
+
+/// Run an external command with the remaining arguments. The plugin gets
+/// UIGET_CONFIG, UIGET_VERBOSE, UIGET_QUIET, UIGET_NON_INTERACTIVE and
+/// UIGET_BIN (the running uiget executable) in its environment.
+pub fn run_plugin(name: &str, args: &[String], plugin_env: &PluginEnv) -> Result<ExitStatus> {
+  let path = find_plugin(name).ok_or_else(|| {
+    anyhow!(
+      "Unknown command '{}'. No '{}{}' executable was found on PATH",
+      name,
+      PLUGIN_PREFIX,
+      name
+    )
+  })?;
+
+  let mut command = Command::new(&path);
+  command
+    .args(args)
+    .env("UIGET_CONFIG", &plugin_env.config_path)
+    .env("UIGET_VERBOSE", if plugin_env.verbose { "1" } else { "0" })
+    .env("UIGET_QUIET", if plugin_env.quiet { "1" } else { "0" })
+    .env(
+      "UIGET_NON_INTERACTIVE",
+      if plugin_env.interactive { "0" } else { "1" },
+    );
+
+  if let Ok(current_exe) = env::current_exe() {
+    command.env("UIGET_BIN", current_exe);
+  }
+
+  command
+    .status()
+    .map_err(|e| anyhow!("Failed to run '{}': {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[cfg(unix)]
+  #[test]
+  fn test_find_plugin_in() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let first = tempfile::tempdir().unwrap();
+    let second = tempfile::tempdir().unwrap();
+
+    // Not executable, so it must be skipped
+    std::fs::write(first.path().join("uiget-audit"), "").unwrap();
+
+    let plugin = second.path().join("uiget-audit");
+    std::fs::write(&plugin, "#!/bin/sh\n").unwrap();
+    std::fs::set_permissions(&plugin, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let paths = env::join_paths([first.path(), second.path()]).unwrap();
+    assert_eq!(find_plugin_in("audit", &paths), Some(plugin));
+    assert_eq!(find_plugin_in("missing", &paths), None);
+  }
+}
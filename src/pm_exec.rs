@@ -0,0 +1,405 @@
+//! Running package manager commands. The package manager isn't always on
+//! PATH as is (Corepack shims, local installs, Windows wrappers), so the
+//! first way of invoking it that answers `--version` is used. Probing takes
+//! up to seven processes, so the strategy that worked is cached in the
+//! project's state directory and reused until it stops working.
+
+use std::{
+  collections::HashMap,
+  fs, io,
+  path::{Path, PathBuf},
+  process::{Command, ExitStatus, Stdio},
+};
+
+use anyhow::Result;
+use colored::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  output::{detail, status},
+  package_manager, paths, state,
+};
+
+/// A way of invoking a package manager
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Strategy {
+  /// The program on PATH
+  Direct,
+  /// `npx pnpm ...`
+  Npx,
+  /// `npm exec pnpm -- ...`
+  NpmExec,
+  /// `node <yarnPath> ...`, the Yarn release a Berry project commits
+  YarnPath,
+  /// `node_modules/.bin/<program>`
+  LocalBin,
+  /// `corepack pnpm ...`
+  Corepack,
+  /// `cmd /C pnpm ...`
+  Cmd,
+  /// `powershell -Command "& pnpm ..."`
+  PowerShell,
+}
+
+impl Strategy {
+  /// Strategies in the order they're probed
+  pub const ALL: [Strategy; 8] = [
+    Strategy::Direct,
+    Strategy::YarnPath,
+    Strategy::Npx,
+    Strategy::NpmExec,
+    Strategy::LocalBin,
+    Strategy::Corepack,
+    Strategy::Cmd,
+    Strategy::PowerShell,
+  ];
+
+  /// Strategies tried one after the other when none answers `--version`
+  const FALLBACKS: [Strategy; 4] = [
+    Strategy::Direct,
+    Strategy::Npx,
+    Strategy::NpmExec,
+    Strategy::Cmd,
+  ];
+
+  pub fn name(self) -> &'static str {
+    match self {
+      Strategy::Direct => "direct",
+      Strategy::Npx => "npx",
+      Strategy::NpmExec => "npm exec",
+      Strategy::YarnPath => "yarnPath",
+      Strategy::LocalBin => "local binary",
+      Strategy::Corepack => "corepack",
+      Strategy::Cmd => "cmd",
+      Strategy::PowerShell => "PowerShell",
+    }
+  }
+
+  /// Whether the strategy can run `program` in the project on this
+  /// platform. Plug'n'Play projects have no node_modules/.bin.
+  fn applies_to(self, program: &str, project_root: &Path) -> bool {
+    match self {
+      Strategy::Npx => program == "pnpm",
+      Strategy::NpmExec => program == "pnpm" || program == "yarn",
+      Strategy::YarnPath => program == "yarn" && yarn_path(project_root).is_some(),
+      Strategy::LocalBin => !package_manager::uses_pnp(project_root),
+      Strategy::Cmd | Strategy::PowerShell => cfg!(windows),
+      Strategy::Direct | Strategy::Corepack => true,
+    }
+  }
+
+  /// The command line that runs `cmd` this way, e.g.
+  /// `corepack pnpm add zod`
+  pub fn command(self, cmd: &[String], project_root: &Path) -> Vec<String> {
+    let (program, args) = (cmd[0].as_str(), &cmd[1..]);
+    let prefix = match self {
+      Strategy::Direct => vec![program.to_string()],
+      Strategy::Npx => vec!["npx".to_string(), program.to_string()],
+      Strategy::NpmExec => ["npm", "exec", program, "--"].map(String::from).to_vec(),
+      Strategy::YarnPath => match yarn_path(project_root) {
+        Some(path) => vec!["node".to_string(), path.display().to_string()],
+        None => vec![program.to_string()],
+      },
+      Strategy::LocalBin => vec![local_bin(program, project_root).display().to_string()],
+      Strategy::Corepack => vec!["corepack".to_string(), program.to_string()],
+      Strategy::Cmd => ["cmd", "/C", program].map(String::from).to_vec(),
+      Strategy::PowerShell => {
+        return vec![
+          "powershell".to_string(),
+          "-Command".to_string(),
+          format!("& {}", cmd.join(" ")),
+        ]
+      }
+    };
+    prefix.into_iter().chain(args.iter().cloned()).collect()
+  }
+
+  /// Whether `program --version` succeeds when run this way
+  fn probe(self, program: &str, project_root: &Path) -> bool {
+    if self == Strategy::LocalBin && !local_bin(program, project_root).exists() {
+      return false;
+    }
+    let command = self.command(
+      &[program.to_string(), "--version".to_string()],
+      project_root,
+    );
+    Command::new(&command[0])
+      .args(&command[1..])
+      .current_dir(project_root)
+      .stdout(Stdio::null())
+      .stderr(Stdio::null())
+      .status()
+      .is_ok_and(|status| status.success())
+  }
+}
+
+/// The Yarn release set by `yarnPath` in `.yarnrc.yml`
+fn yarn_path(project_root: &Path) -> Option<PathBuf> {
+  let path = package_manager::read_yarnrc(project_root)?.yarn_path?;
+  Some(project_root.join(path))
+}
+
+/// A package manager installed in the project
+fn local_bin(program: &str, project_root: &Path) -> PathBuf {
+  project_root.join("node_modules").join(".bin").join(program)
+}
+
+/// The first strategy that runs `program`, probing each in turn
+pub fn detect_strategy(program: &str, project_root: &Path) -> Option<Strategy> {
+  Strategy::ALL
+    .into_iter()
+    .filter(|strategy| strategy.applies_to(program, project_root))
+    .find(|strategy| strategy.probe(program, project_root))
+}
+
+/// Location of the strategy cache for the project in `project_dir`
+pub fn strategy_cache_path(project_dir: &Path) -> PathBuf {
+  state::ProjectState::at(project_dir).package_manager_cache()
+}
+
+/// Strategies that worked, by program
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct StrategyCache {
+  strategies: HashMap<String, Strategy>,
+}
+
+impl StrategyCache {
+  fn load(path: &Path) -> Self {
+    fs::read_to_string(path)
+      .ok()
+      .and_then(|content| serde_json::from_str(&content).ok())
+      .unwrap_or_default()
+  }
+
+  fn save(&self, path: &Path) -> io::Result<()> {
+    state::create_dir(paths::parent_dir(path))?;
+    paths::write_atomic(path, serde_json::to_string_pretty(self)?)
+  }
+}
+
+/// Runs package manager commands, remembering how the package manager was
+/// invoked in `cache_path` when given
+pub struct PackageManagerRunner {
+  project_root: PathBuf,
+  cache_path: Option<PathBuf>,
+}
+
+impl PackageManagerRunner {
+  pub fn new(project_root: impl Into<PathBuf>, cache_path: Option<PathBuf>) -> Self {
+    Self {
+      project_root: project_root.into(),
+      cache_path,
+    }
+  }
+
+  /// The strategy for `program`: the cached one, or the one detected and
+  /// then cached. The second value is whether it came from the cache.
+  pub fn strategy(&self, program: &str) -> Option<(Strategy, bool)> {
+    let cached = self
+      .cache_path
+      .as_deref()
+      .and_then(|path| StrategyCache::load(path).strategies.get(program).copied())
+      // A local install may have been removed, or the project moved to
+      // Plug'n'Play, since
+      .filter(|strategy| {
+        strategy.applies_to(program, &self.project_root)
+          && (*strategy != Strategy::LocalBin || local_bin(program, &self.project_root).exists())
+      });
+    if let Some(strategy) = cached {
+      return Some((strategy, true));
+    }
+
+    let strategy = detect_strategy(program, &self.project_root)?;
+    self.remember(program, Some(strategy));
+    Some((strategy, false))
+  }
+
+  /// Cache the strategy for `program`, or forget it. A cache that can't be
+  /// written only costs probing again next time.
+  fn remember(&self, program: &str, strategy: Option<Strategy>) {
+    let Some(path) = &self.cache_path else {
+      return;
+    };
+    let mut cache = StrategyCache::load(path);
+    match strategy {
+      Some(strategy) => cache.strategies.insert(program.to_string(), strategy),
+      None => cache.strategies.remove(program),
+    };
+    let _ = cache.save(path);
+  }
+
+  /// The command line `run` would execute for `cmd`
+  pub fn command(&self, cmd: &[String]) -> Vec<String> {
+    let strategy = self
+      .strategy(&cmd[0])
+      .map_or(Strategy::Direct, |(strategy, _)| strategy);
+    strategy.command(cmd, &self.project_root)
+  }
+
+  /// Run `cmd` in the project root with the cached or detected strategy,
+  /// trying every fallback when none works
+  pub fn run(&self, cmd: &[String]) -> Result<ExitStatus> {
+    let Some((strategy, cached)) = self.strategy(&cmd[0]) else {
+      return self.run_with_fallbacks(cmd);
+    };
+
+    match self.run_with(strategy, cmd) {
+      // The cached strategy no longer works, e.g. the package manager was
+      // uninstalled: detect again
+      Err(err) if cached && err.kind() == io::ErrorKind::NotFound => {
+        detail!("Cached {} strategy failed: {}", strategy.name(), err);
+        self.remember(&cmd[0], None);
+        self.run(cmd)
+      }
+      result => Ok(result?),
+    }
+  }
+
+  fn run_with(&self, strategy: Strategy, cmd: &[String]) -> io::Result<ExitStatus> {
+    let command = strategy.command(cmd, &self.project_root);
+    if command != cmd {
+      status!(
+        "{} Running via {}: {}",
+        "→".blue(),
+        strategy.name(),
+        command.join(" ").cyan()
+      );
+    }
+    Command::new(&command[0])
+      .args(&command[1..])
+      .current_dir(&self.project_root)
+      .status()
+  }
+
+  /// Try each fallback strategy until one succeeds, then the original
+  /// command one last time
+  fn run_with_fallbacks(&self, cmd: &[String]) -> Result<ExitStatus> {
+    status!(
+      "{} No working strategy detected, trying all fallbacks...",
+      "⚠".yellow()
+    );
+
+    for strategy in Strategy::FALLBACKS
+      .into_iter()
+      .filter(|strategy| strategy.applies_to(&cmd[0], &self.project_root))
+    {
+      status!("{} Trying {} execution", "→".blue(), strategy.name());
+      match self.run_with(strategy, cmd) {
+        Ok(status) if status.success() => {
+          status!("{} {} execution successful", "✓".green(), strategy.name());
+          self.remember(&cmd[0], Some(strategy));
+          return Ok(status);
+        }
+        Ok(status) => status!(
+          "{} {} execution failed with exit code: {}",
+          "✗".red(),
+          strategy.name(),
+          status.code().unwrap_or(-1)
+        ),
+        Err(e) => status!("{} {} execution error: {}", "✗".red(), strategy.name(), e),
+      }
+    }
+
+    status!("{} Final attempt with original command", "→".blue());
+    Ok(
+      Command::new(&cmd[0])
+        .args(&cmd[1..])
+        .current_dir(&self.project_root)
+        .status()?,
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_strategy_command() {
+    let cmd = ["pnpm", "add", "zod@>=3"].map(String::from);
+    let root = Path::new("/app");
+    assert_eq!(Strategy::Direct.command(&cmd, root), cmd);
+    assert_eq!(
+      Strategy::Corepack.command(&cmd, root),
+      ["corepack", "pnpm", "add", "zod@>=3"]
+    );
+    assert_eq!(
+      Strategy::NpmExec.command(&cmd, root),
+      ["npm", "exec", "pnpm", "--", "add", "zod@>=3"]
+    );
+    assert_eq!(
+      Strategy::LocalBin.command(&cmd, root)[0],
+      Path::new("/app/node_modules/.bin/pnpm")
+        .display()
+        .to_string()
+    );
+    assert_eq!(
+      Strategy::PowerShell.command(&cmd, root),
+      ["powershell", "-Command", "& pnpm add zod@>=3"]
+    );
+    assert!(!Strategy::Npx.applies_to("yarn", root));
+  }
+
+  #[test]
+  fn test_yarn_berry_strategies() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let root = temp_dir.path();
+    let cmd = ["yarn", "add", "zod"].map(String::from);
+    assert!(Strategy::LocalBin.applies_to("yarn", root));
+    assert!(!Strategy::YarnPath.applies_to("yarn", root));
+
+    fs::write(
+      root.join(".yarnrc.yml"),
+      "yarnPath: .yarn/releases/yarn-4.1.0.cjs\n",
+    )
+    .unwrap();
+    // Plug'n'Play by default: nothing in node_modules/.bin to run
+    assert!(!Strategy::LocalBin.applies_to("yarn", root));
+    assert!(Strategy::YarnPath.applies_to("yarn", root));
+    assert!(!Strategy::YarnPath.applies_to("pnpm", root));
+    assert_eq!(
+      Strategy::YarnPath.command(&cmd, root),
+      [
+        "node".to_string(),
+        root
+          .join(".yarn/releases/yarn-4.1.0.cjs")
+          .display()
+          .to_string(),
+        "add".to_string(),
+        "zod".to_string(),
+      ]
+    );
+
+    fs::write(root.join(".yarnrc.yml"), "nodeLinker: node-modules\n").unwrap();
+    assert!(Strategy::LocalBin.applies_to("yarn", root));
+  }
+
+  #[test]
+  fn test_cached_strategy() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let root = temp_dir.path();
+    let cache_path = strategy_cache_path(root);
+    let runner = PackageManagerRunner::new(root, Some(cache_path.clone()));
+
+    // A strategy in the cache is used without probing
+    let mut cache = StrategyCache::default();
+    cache
+      .strategies
+      .insert("uiget-test-pm".to_string(), Strategy::Corepack);
+    cache.save(&cache_path).unwrap();
+    assert_eq!(
+      runner.strategy("uiget-test-pm"),
+      Some((Strategy::Corepack, true))
+    );
+    assert!(root.join(".uiget/.gitignore").exists());
+
+    // A cached local binary that's gone is probed again, and nothing runs
+    // a program that doesn't exist
+    runner.remember("uiget-test-pm", Some(Strategy::LocalBin));
+    assert_eq!(runner.strategy("uiget-test-pm"), None);
+
+    runner.remember("uiget-test-pm", None);
+    assert!(StrategyCache::load(&cache_path).strategies.is_empty());
+  }
+}
@@ -0,0 +1,114 @@
+use std::path::Path;
+
+use crate::lockfile::hash_content;
+
+/// Text that identifies the first line of a provenance header
+const MARKER: &str = "Managed by uiget:";
+
+/// Where an installed file came from, for its provenance header
+pub struct Provenance<'a> {
+  pub component: &'a str,
+  pub registry: Option<&'a str>,
+  pub version: Option<&'a str>,
+}
+
+/// Comment delimiters for a file, by extension. None for formats without
+/// comments or where a leading comment would break the file (JSON, Astro
+/// and Markdown frontmatter).
+fn comment_style(path: &Path) -> Option<(&'static str, &'static str)> {
+  match path.extension()?.to_str()? {
+    "ts" | "tsx" | "mts" | "cts" | "js" | "jsx" | "mjs" | "cjs" => Some(("// ", "")),
+    "css" | "scss" | "less" => Some(("/* ", " */")),
+    "svelte" | "vue" | "html" => Some(("<!-- ", " -->")),
+    _ => None,
+  }
+}
+
+/// Prepend a two-line header naming the component, its registry and
+/// version (or a content hash when unversioned) to `content`. Files whose
+/// type has no suitable comment syntax are returned unchanged.
+pub fn add_header(path: &Path, content: &str, provenance: &Provenance) -> String {
+  let Some((open, close)) = comment_style(path) else {
+    return content.to_string();
+  };
+
+  let version = match provenance.version {
+    Some(version) => version.to_string(),
+    None => format!("sha256:{}", &hash_content(content)[..12]),
+  };
+  let source = match provenance.registry {
+    Some(registry) => format!("from '{}'", registry),
+    None => "from a local file".to_string(),
+  };
+  let header = format!(
+    "{open}{MARKER} {} {source}, {version}{close}\n{open}Update with `uiget update {}`; local edits are reported as modifications{close}\n",
+    provenance.component, provenance.component,
+  );
+
+  // A shebang has to stay on the first line
+  match content.strip_prefix("#!") {
+    Some(_) => {
+      let (shebang, rest) = content.split_once('\n').unwrap_or((content, ""));
+      format!("{}\n{}{}", shebang, header, rest)
+    }
+    None => format!("{}{}", header, content),
+  }
+}
+
+/// Remove a header added by `add_header`, so installed files compare equal
+/// to the registry's
+pub fn strip_header(content: &str) -> String {
+  let mut lines: Vec<&str> = content.split_inclusive('\n').collect();
+  let start = usize::from(lines.first().is_some_and(|line| line.starts_with("#!")));
+
+  if lines.get(start).is_some_and(|line| line.contains(MARKER)) {
+    let end = (start + 2).min(lines.len());
+    lines.drain(start..end);
+  }
+
+  lines.concat()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_add_and_strip_header() {
+    let provenance = Provenance {
+      component: "button",
+      registry: Some("default"),
+      version: Some("1.2.0"),
+    };
+
+    let content = "export const a = 1;\n";
+    let with_header = add_header(Path::new("button.ts"), content, &provenance);
+    assert!(with_header.starts_with("// Managed by uiget: button from 'default', 1.2.0\n"));
+    assert!(with_header.ends_with(content));
+    assert_eq!(strip_header(&with_header), content);
+
+    let css = add_header(Path::new("theme.css"), ":root {}\n", &provenance);
+    assert!(css.starts_with("/* Managed by uiget:"));
+    assert!(css
+      .lines()
+      .all(|line| !line.starts_with("/*") || line.ends_with("*/")));
+
+    assert_eq!(add_header(Path::new("data.json"), "{}", &provenance), "{}");
+  }
+
+  #[test]
+  fn test_header_after_shebang_and_hash_fallback() {
+    let provenance = Provenance {
+      component: "cli",
+      registry: None,
+      version: None,
+    };
+
+    let content = "#!/usr/bin/env node\nconsole.log(1);\n";
+    let with_header = add_header(Path::new("cli.js"), content, &provenance);
+    let lines: Vec<_> = with_header.lines().collect();
+    assert_eq!(lines[0], "#!/usr/bin/env node");
+    assert!(lines[1].contains("from a local file, sha256:"));
+    assert_eq!(strip_header(&with_header), content);
+  }
+}
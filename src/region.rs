@@ -0,0 +1,191 @@
+/// Opening marker of a managed region, followed by the component name
+const START: &str = "uiget:start";
+/// Closing marker of a managed region
+const END: &str = "uiget:end";
+
+/// Whether `line` opens the managed region of `component`, e.g.
+/// `/* uiget:start theme */`
+fn is_start(line: &str, component: &str) -> bool {
+  line
+    .split_once(START)
+    .and_then(|(_, rest)| rest.split_whitespace().next())
+    .is_some_and(|name| name == component)
+}
+
+/// Byte range of the lines between the markers of `component`'s region,
+/// excluding the marker lines themselves
+fn inner_range(content: &str, component: &str) -> Option<(usize, usize)> {
+  let mut offset = 0;
+  let mut start = None;
+
+  for line in content.split_inclusive('\n') {
+    match start {
+      None if is_start(line, component) => start = Some(offset + line.len()),
+      Some(start) if line.contains(END) => return Some((start, offset)),
+      _ => {}
+    }
+    offset += line.len();
+  }
+
+  None
+}
+
+/// Byte range of `component`'s region including its marker lines
+fn outer_range(content: &str, component: &str) -> Option<(usize, usize)> {
+  let (start, end) = inner_range(content, component)?;
+  let open = content[..start - 1]
+    .rfind('\n')
+    .map_or(0, |newline| newline + 1);
+  let close = content[end..]
+    .find('\n')
+    .map_or(content.len(), |newline| end + newline + 1);
+  Some((open, close))
+}
+
+/// Content of `component`'s managed region in `content`, if it has one
+pub fn region_content<'a>(content: &'a str, component: &str) -> Option<&'a str> {
+  inner_range(content, component).map(|(start, end)| &content[start..end])
+}
+
+/// Replace the inside of `component`'s managed region in `existing` and
+/// keep everything around it. When `update` has a region of its own, only
+/// that part is used. Returns None when `existing` has no region.
+pub fn replace_region(existing: &str, component: &str, update: &str) -> Option<String> {
+  let (start, end) = inner_range(existing, component)?;
+  let inner = region_content(update, component).unwrap_or(update);
+
+  let mut merged = String::with_capacity(existing.len() + inner.len());
+  merged.push_str(&existing[..start]);
+  merged.push_str(inner);
+  if !inner.is_empty() && !inner.ends_with('\n') {
+    merged.push('\n');
+  }
+  merged.push_str(&existing[end..]);
+
+  Some(merged)
+}
+
+/// Remove `component`'s managed region from `existing`, markers included,
+/// with the blank line `append_css_region` put before it. Returns None when
+/// `existing` has no region.
+pub fn remove_region(existing: &str, component: &str) -> Option<String> {
+  let (start, end) = outer_range(existing, component)?;
+  let before = &existing[..start];
+  let before = before
+    .strip_suffix('\n')
+    .filter(|before| before.ends_with('\n'))
+    .unwrap_or(before);
+  Some(format!("{}{}", before, &existing[end..]))
+}
+
+/// Append a managed region for `component` holding `content` to a CSS
+/// file. When `content` has a region of its own, only that part is used.
+pub fn append_css_region(existing: &str, component: &str, content: &str) -> String {
+  let inner = region_content(content, component).unwrap_or(content);
+
+  let mut merged = existing.to_string();
+  if !merged.is_empty() {
+    if !merged.ends_with('\n') {
+      merged.push('\n');
+    }
+    merged.push('\n');
+  }
+  merged.push_str(&format!("/* {} {} */\n", START, component));
+  merged.push_str(inner);
+  if !inner.is_empty() && !inner.ends_with('\n') {
+    merged.push('\n');
+  }
+  merged.push_str(&format!("/* {} */\n", END));
+
+  merged
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const EXISTING: &str = "\
+@import 'tailwindcss';
+
+/* uiget:start theme */
+:root { --primary: red; }
+/* uiget:end */
+
+.custom { color: blue; }
+";
+
+  #[test]
+  fn test_replace_region() {
+    let merged = replace_region(EXISTING, "theme", ":root { --primary: green; }").unwrap();
+
+    assert_eq!(
+      merged,
+      "\
+@import 'tailwindcss';
+
+/* uiget:start theme */
+:root { --primary: green; }
+/* uiget:end */
+
+.custom { color: blue; }
+"
+    );
+    assert_eq!(
+      region_content(&merged, "theme"),
+      Some(":root { --primary: green; }\n")
+    );
+  }
+
+  #[test]
+  fn test_replace_region_uses_update_region() {
+    let update = "/* header */\n/* uiget:start theme */\n:root {}\n/* uiget:end */\n";
+    let merged = replace_region(EXISTING, "theme", update).unwrap();
+
+    assert_eq!(region_content(&merged, "theme"), Some(":root {}\n"));
+    assert!(merged.starts_with("@import 'tailwindcss';"));
+    assert!(!merged.contains("/* header */"));
+  }
+
+  #[test]
+  fn test_append_css_region() {
+    let appended = append_css_region("@import 'tailwindcss';", "theme", ":root {}");
+    assert_eq!(
+      appended,
+      "@import 'tailwindcss';\n\n/* uiget:start theme */\n:root {}\n/* uiget:end */\n"
+    );
+
+    // Appending again after a replace keeps a single region
+    let replaced = replace_region(&appended, "theme", ":root { --a: 1; }").unwrap();
+    assert_eq!(replaced.matches("uiget:start theme").count(), 1);
+    assert_eq!(
+      append_css_region("", "theme", ""),
+      "/* uiget:start theme */\n/* uiget:end */\n"
+    );
+  }
+
+  #[test]
+  fn test_remove_region() {
+    assert_eq!(
+      remove_region(EXISTING, "theme").unwrap(),
+      "@import 'tailwindcss';\n\n.custom { color: blue; }\n"
+    );
+
+    // Removing an appended region gives back the file it was appended to
+    let appended = append_css_region("@import 'tailwindcss';\n", "theme", ":root {}");
+    assert_eq!(
+      remove_region(&appended, "theme").unwrap(),
+      "@import 'tailwindcss';\n"
+    );
+    assert_eq!(remove_region(EXISTING, "card"), None);
+  }
+
+  #[test]
+  fn test_other_components_are_ignored() {
+    assert_eq!(replace_region(EXISTING, "themes", ""), None);
+    assert_eq!(replace_region(EXISTING, "card", ""), None);
+    assert_eq!(
+      replace_region("// uiget:start theme\nno end\n", "theme", ""),
+      None
+    );
+  }
+}
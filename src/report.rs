@@ -0,0 +1,660 @@
+use std::{
+  collections::BTreeMap,
+  path::{Path, PathBuf},
+};
+
+use anyhow::Result;
+use colored::*;
+use serde::Serialize;
+
+use crate::{
+  diff::DiffStats,
+  lockfile::{LockedComponent, Lockfile},
+  paths,
+  registry::ChangelogEntry,
+  schema::OUTDATED_SCHEMA_URL,
+};
+
+/// Registry name shown for components installed from a file or stdin
+const LOCAL_REGISTRY: &str = "(local)";
+
+/// What an `add` run changed, printed once at the end instead of being
+/// pieced together from the per-file lines
+#[derive(Debug, Default)]
+pub struct InstallReport {
+  /// Components that were installed
+  pub components: Vec<String>,
+  /// Selected components left alone because they were already up to date
+  pub unchanged: Vec<String>,
+  /// Files that didn't exist before
+  pub created: Vec<PathBuf>,
+  /// Existing files that were overwritten or had a region replaced
+  pub updated: Vec<PathBuf>,
+  /// Components that were removed
+  pub removed: Vec<String>,
+  /// Files that were deleted
+  pub deleted: Vec<PathBuf>,
+  /// npm packages the package manager installed
+  pub packages: Vec<String>,
+  /// npm packages left for the user to add
+  pub pending_packages: Vec<String>,
+  /// npm dev packages left for the user to add
+  pub pending_dev_packages: Vec<String>,
+  /// Things the user still has to do or should know about
+  pub follow_ups: Vec<String>,
+}
+
+impl InstallReport {
+  /// Whether nothing was recorded
+  pub fn is_empty(&self) -> bool {
+    self.components.is_empty()
+      && self.unchanged.is_empty()
+      && self.created.is_empty()
+      && self.updated.is_empty()
+      && self.removed.is_empty()
+      && self.deleted.is_empty()
+  }
+
+  /// Record a written file, once, as created or updated
+  pub fn record_file(&mut self, path: PathBuf, existed: bool) {
+    if self.created.contains(&path) || self.updated.contains(&path) {
+      return;
+    }
+    if existed {
+      self.updated.push(path);
+    } else {
+      self.created.push(path);
+    }
+  }
+
+  /// Summary lines, with paths shown relative to `project_dir`
+  pub fn lines(&self, project_dir: &Path) -> Vec<String> {
+    let display = |path: &PathBuf| {
+      path
+        .strip_prefix(project_dir)
+        .unwrap_or(path)
+        .display()
+        .to_string()
+    };
+
+    let mut lines = vec![format!("{} Summary:", "📊".blue())];
+    if !self.components.is_empty() {
+      lines.push(format!(
+        "  Components installed: {}",
+        self.components.join(", ").cyan()
+      ));
+    }
+    if !self.unchanged.is_empty() {
+      lines.push(format!(
+        "  Already up to date: {}",
+        self.unchanged.join(", ").dimmed()
+      ));
+    }
+    if !self.removed.is_empty() {
+      lines.push(format!(
+        "  Components removed: {}",
+        self.removed.join(", ").cyan()
+      ));
+    }
+    let mut counts = Vec::new();
+    if !self.created.is_empty() || !self.updated.is_empty() {
+      counts.push(format!("{} created", self.created.len()));
+      counts.push(format!("{} updated", self.updated.len()));
+    }
+    if !self.deleted.is_empty() {
+      counts.push(format!("{} deleted", self.deleted.len()));
+    }
+    if !counts.is_empty() {
+      lines.push(format!("  Files: {}", counts.join(", ")));
+    }
+    for path in &self.created {
+      lines.push(format!("    {} {}", "+".green(), display(path)));
+    }
+    for path in &self.updated {
+      lines.push(format!("    {} {}", "~".yellow(), display(path)));
+    }
+    for path in &self.deleted {
+      lines.push(format!("    {} {}", "-".red(), display(path)));
+    }
+    if !self.packages.is_empty() {
+      lines.push(format!(
+        "  npm packages added: {}",
+        self.packages.join(" ").cyan()
+      ));
+    }
+    let pending = [
+      ("Add npm packages", &self.pending_packages),
+      ("Add npm dev packages", &self.pending_dev_packages),
+    ];
+    if pending.iter().any(|(_, packages)| !packages.is_empty()) || !self.follow_ups.is_empty() {
+      lines.push("  Next steps:".to_string());
+      for (label, packages) in pending {
+        if !packages.is_empty() {
+          lines.push(format!(
+            "    {} {}: {}",
+            "→".blue(),
+            label,
+            packages.join(" ").cyan()
+          ));
+        }
+      }
+      for follow_up in &self.follow_ups {
+        lines.push(format!("    {} {}", "→".blue(), follow_up));
+      }
+    }
+
+    lines
+  }
+}
+
+/// State of a project's installed components for `uiget report`, built
+/// from the lockfile without sending anything anywhere
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectReport {
+  /// Installed components per registry namespace
+  pub registries: BTreeMap<String, usize>,
+  pub components: usize,
+  /// Components with a newer version in their registry, when checked
+  pub outdated: Option<usize>,
+  /// Files written by uiget
+  pub managed_files: usize,
+  /// Managed files edited or deleted since they were installed
+  pub modified_files: usize,
+  /// Unix timestamp (seconds) of the latest install
+  pub last_sync: Option<u64>,
+  /// Package manager the project uses, when one was detected
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub package_manager: Option<String>,
+  /// Format of the project's lockfile, e.g. "pnpm-lock.yaml v9.0"
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub lockfile: Option<String>,
+}
+
+impl ProjectReport {
+  /// Summarize the components recorded in `lockfile`
+  pub fn from_lockfile(lockfile: &Lockfile, project_dir: &Path) -> Self {
+    let mut registries = BTreeMap::new();
+    for locked in lockfile.components.values() {
+      let registry = locked.registry.as_deref().unwrap_or(LOCAL_REGISTRY);
+      *registries.entry(registry.to_string()).or_default() += 1;
+    }
+
+    let files = || {
+      lockfile
+        .components
+        .values()
+        .flat_map(|locked| &locked.files)
+    };
+    Self {
+      registries,
+      components: lockfile.components.len(),
+      outdated: None,
+      managed_files: files().count(),
+      modified_files: files().filter(|file| file.is_modified(project_dir)).count(),
+      last_sync: lockfile
+        .components
+        .values()
+        .map(|locked| locked.installed_at)
+        .filter(|installed_at| *installed_at > 0)
+        .max(),
+      package_manager: None,
+      lockfile: None,
+    }
+  }
+
+  /// Report lines; `now` is the current Unix timestamp
+  pub fn lines(&self, now: u64) -> Vec<String> {
+    let mut lines = vec![format!("{} Project report:", "📊".blue())];
+    lines.push(format!(
+      "  Components: {}",
+      self.components.to_string().cyan()
+    ));
+    for (registry, count) in &self.registries {
+      lines.push(format!("    {} {}", registry, count.to_string().dimmed()));
+    }
+    lines.push(format!(
+      "  Outdated: {}",
+      match self.outdated {
+        Some(0) => "0".green(),
+        Some(count) => count.to_string().yellow(),
+        None => "not checked".dimmed(),
+      }
+    ));
+    lines.push(format!(
+      "  Managed files: {} ({} modified)",
+      self.managed_files.to_string().cyan(),
+      self.modified_files
+    ));
+    lines.push(format!(
+      "  Last sync: {}",
+      match self.last_sync {
+        Some(timestamp) => format_age(now.saturating_sub(timestamp)),
+        None => "never".to_string(),
+      }
+    ));
+    if let Some(package_manager) = &self.package_manager {
+      lines.push(format!(
+        "  Package manager: {} ({})",
+        package_manager.cyan(),
+        self.lockfile.as_deref().unwrap_or("no lockfile")
+      ));
+    }
+    lines
+  }
+}
+
+/// Version of the `outdated --json` format, raised only on breaking changes
+pub const OUTDATED_SCHEMA_VERSION: u32 = 1;
+
+/// Output of `uiget outdated --json`, for bots that decide what to pass to
+/// `update --only`. Its shape is published as `uiget schema outdated`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutdatedReport {
+  #[serde(rename = "$schema")]
+  pub schema: &'static str,
+  pub schema_version: u32,
+  /// Every checked component, sorted by name
+  pub components: Vec<OutdatedComponent>,
+}
+
+/// Update status of one installed component
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OutdatedComponent {
+  pub name: String,
+  /// Registry namespace it was installed from, when recorded
+  pub registry: Option<String>,
+  /// Installed version, when recorded
+  pub installed: Option<String>,
+  /// Version in the registry, when the component is outdated and the
+  /// registry gives one
+  pub latest: Option<String>,
+  pub outdated: bool,
+  /// Managed files were edited or deleted since install, so updating needs
+  /// `--force --force` or a confirmation
+  pub modified: bool,
+}
+
+impl OutdatedReport {
+  pub fn new(mut components: Vec<OutdatedComponent>) -> Self {
+    components.sort_by(|a, b| a.name.cmp(&b.name));
+    Self {
+      schema: OUTDATED_SCHEMA_URL,
+      schema_version: OUTDATED_SCHEMA_VERSION,
+      components,
+    }
+  }
+}
+
+/// What `uiget update` changed, written by `--write-report` for the body
+/// of an automated dependency-update pull request
+#[derive(Debug, Default, Serialize)]
+pub struct UpdateReport {
+  pub components: Vec<ComponentUpdate>,
+}
+
+/// One updated component
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentUpdate {
+  pub name: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub registry: Option<String>,
+  /// Version installed before the update, when known
+  pub from: Option<String>,
+  pub to: Option<String>,
+  /// Managed files the update created, changed or stopped writing
+  pub files: Vec<FileChange>,
+  /// npm packages the new version depends on and the old one didn't
+  pub new_dependencies: Vec<String>,
+  pub new_dev_dependencies: Vec<String>,
+  /// Changelog entries published since the installed version
+  pub changes: Vec<ChangelogEntry>,
+}
+
+/// How a managed file changed in an update
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileChange {
+  /// Path relative to the project directory
+  pub path: String,
+  /// `added`, `modified` or `removed`
+  pub status: &'static str,
+  /// Lines added and removed
+  pub additions: usize,
+  pub deletions: usize,
+}
+
+impl ComponentUpdate {
+  /// Compare a component's lockfile records from before and after its
+  /// update. `old_contents` holds the content of each previously managed
+  /// file, read before the update; the new content is read from
+  /// `project_dir`.
+  pub fn new(
+    name: &str,
+    before: &LockedComponent,
+    after: &LockedComponent,
+    old_contents: &BTreeMap<String, String>,
+    project_dir: &Path,
+  ) -> Self {
+    let mut files = Vec::new();
+    for file in &after.files {
+      let new = paths::read_to_string(&project_dir.join(&file.path)).unwrap_or_default();
+      let (status, stats) = match old_contents.get(&file.path) {
+        Some(old) => ("modified", DiffStats::of(old, &new)),
+        None => ("added", DiffStats::of("", &new)),
+      };
+      if stats.added + stats.removed > 0 {
+        files.push(FileChange {
+          path: file.path.clone(),
+          status,
+          additions: stats.added,
+          deletions: stats.removed,
+        });
+      }
+    }
+    for file in &before.files {
+      if !after.files.iter().any(|new| new.path == file.path) {
+        files.push(FileChange {
+          path: file.path.clone(),
+          status: "removed",
+          additions: 0,
+          deletions: old_contents
+            .get(&file.path)
+            .map_or(0, |old| old.lines().count()),
+        });
+      }
+    }
+
+    let new_packages = |before: &[String], after: &[String]| {
+      after
+        .iter()
+        .filter(|package| !before.contains(package))
+        .cloned()
+        .collect()
+    };
+    Self {
+      name: name.to_string(),
+      registry: after.registry.clone(),
+      from: before.version.clone(),
+      to: after.version.clone(),
+      files,
+      new_dependencies: new_packages(&before.dependencies, &after.dependencies),
+      new_dev_dependencies: new_packages(&before.dev_dependencies, &after.dev_dependencies),
+      changes: Vec::new(),
+    }
+  }
+}
+
+impl UpdateReport {
+  /// Names of the updated components
+  pub fn names(&self) -> Vec<String> {
+    self
+      .components
+      .iter()
+      .map(|component| component.name.clone())
+      .collect()
+  }
+
+  /// The report as Markdown: a table of the updates, then each component's
+  /// files, new npm packages and changelog
+  pub fn markdown(&self) -> String {
+    let version = |version: &Option<String>| version.as_deref().unwrap_or("?").to_string();
+    let mut out = String::from("## UI component updates\n\n");
+    if self.components.is_empty() {
+      out.push_str("All components are up to date.\n");
+      return out;
+    }
+
+    out.push_str("| Component | From | To | Files | Lines |\n| --- | --- | --- | --- | --- |\n");
+    for component in &self.components {
+      let (additions, deletions) = component.files.iter().fold((0, 0), |(a, d), file| {
+        (a + file.additions, d + file.deletions)
+      });
+      out.push_str(&format!(
+        "| `{}` | {} | {} | {} | +{} -{} |\n",
+        component.name,
+        version(&component.from),
+        version(&component.to),
+        component.files.len(),
+        additions,
+        deletions
+      ));
+    }
+
+    for component in &self.components {
+      out.push_str(&format!(
+        "\n### `{}` {} → {}\n\n",
+        component.name,
+        version(&component.from),
+        version(&component.to)
+      ));
+      if component.files.is_empty() {
+        out.push_str("No file changes.\n");
+      }
+      for file in &component.files {
+        out.push_str(&format!(
+          "- `{}` ({}, +{} -{})\n",
+          file.path, file.status, file.additions, file.deletions
+        ));
+      }
+      for (label, packages) in [
+        ("New npm dependencies", &component.new_dependencies),
+        ("New npm dev dependencies", &component.new_dev_dependencies),
+      ] {
+        if !packages.is_empty() {
+          let packages: Vec<String> = packages.iter().map(|p| format!("`{}`", p)).collect();
+          out.push_str(&format!("\n{}: {}\n", label, packages.join(", ")));
+        }
+      }
+      if !component.changes.is_empty() {
+        out.push_str("\n<details>\n<summary>Changelog</summary>\n\n");
+        for entry in &component.changes {
+          match &entry.date {
+            Some(date) => out.push_str(&format!("#### {} ({})\n\n", entry.version, date)),
+            None => out.push_str(&format!("#### {}\n\n", entry.version)),
+          }
+          out.push_str(entry.notes.trim());
+          out.push_str("\n\n");
+        }
+        out.push_str("</details>\n");
+      }
+    }
+    out
+  }
+
+  /// Write the report to `path`: JSON for a `.json` file, Markdown
+  /// otherwise
+  pub fn write(&self, path: &Path) -> Result<()> {
+    let content = if path
+      .extension()
+      .is_some_and(|extension| extension == "json")
+    {
+      serde_json::to_string_pretty(self)? + "\n"
+    } else {
+      self.markdown()
+    };
+    paths::write_file(path, content)?;
+    Ok(())
+  }
+}
+
+/// Rough age of something `seconds` old, e.g. "3 days ago"
+pub fn format_age(seconds: u64) -> String {
+  let (count, unit) = match seconds {
+    0..60 => return "just now".to_string(),
+    60..3600 => (seconds / 60, "minute"),
+    3600..86400 => (seconds / 3600, "hour"),
+    _ => (seconds / 86400, "day"),
+  };
+  format!(
+    "{} {}{} ago",
+    count,
+    unit,
+    if count == 1 { "" } else { "s" }
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lockfile::LockedFile;
+
+  #[test]
+  fn test_record_file() {
+    let mut report = InstallReport::default();
+    assert!(report.is_empty());
+
+    report.record_file(PathBuf::from("/app/src/button.tsx"), false);
+    report.record_file(PathBuf::from("/app/src/app.css"), true);
+    report.record_file(PathBuf::from("/app/src/app.css"), true);
+    assert_eq!(report.created.len(), 1);
+    assert_eq!(report.updated.len(), 1);
+    assert!(!report.is_empty());
+  }
+
+  #[test]
+  fn test_lines() {
+    colored::control::set_override(false);
+    let report = InstallReport {
+      components: vec!["button".to_string()],
+      created: vec![PathBuf::from("/app/src/button.tsx")],
+      pending_packages: vec!["zod@^3.23".to_string()],
+      ..Default::default()
+    };
+
+    let lines = report.lines(Path::new("/app"));
+    assert!(lines.contains(&"  Components installed: button".to_string()));
+    assert!(lines.contains(&"  Files: 1 created, 0 updated".to_string()));
+    assert!(lines.contains(&"    + src/button.tsx".to_string()));
+    assert!(lines.contains(&"    → Add npm packages: zod@^3.23".to_string()));
+
+    let report = InstallReport {
+      removed: vec!["button".to_string()],
+      deleted: vec![PathBuf::from("/app/src/button.tsx")],
+      ..Default::default()
+    };
+    assert!(!report.is_empty());
+    let lines = report.lines(Path::new("/app"));
+    assert!(lines.contains(&"  Components removed: button".to_string()));
+    assert!(lines.contains(&"  Files: 1 deleted".to_string()));
+    assert!(lines.contains(&"    - src/button.tsx".to_string()));
+  }
+
+  #[test]
+  fn test_project_report() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    std::fs::write(temp_dir.path().join("button.tsx"), "edited").unwrap();
+
+    let mut lockfile = Lockfile::default();
+    let file = |path: &str| LockedFile::new(temp_dir.path(), &temp_dir.path().join(path), "");
+    lockfile.insert(
+      "button",
+      LockedComponent {
+        registry: Some("shadcn".to_string()),
+        files: vec![file("button.tsx")],
+        installed_at: 1_000,
+        ..Default::default()
+      },
+    );
+    lockfile.insert(
+      "card",
+      LockedComponent {
+        files: vec![file("card.tsx"), file("index.ts")],
+        installed_at: 4_600,
+        ..Default::default()
+      },
+    );
+
+    let report = ProjectReport::from_lockfile(&lockfile, temp_dir.path());
+    assert_eq!(report.components, 2);
+    assert_eq!(report.registries.get("shadcn"), Some(&1));
+    assert_eq!(report.registries.get(LOCAL_REGISTRY), Some(&1));
+    assert_eq!(report.managed_files, 3);
+    assert_eq!(report.modified_files, 3);
+    assert_eq!(report.last_sync, Some(4_600));
+  }
+
+  #[test]
+  fn test_format_age() {
+    assert_eq!(format_age(5), "just now");
+    assert_eq!(format_age(60), "1 minute ago");
+    assert_eq!(format_age(7_200), "2 hours ago");
+    assert_eq!(format_age(3 * 86_400), "3 days ago");
+  }
+
+  #[test]
+  fn test_update_report() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let project_dir = temp_dir.path();
+    std::fs::write(project_dir.join("button.tsx"), "a\nb2\nc\nd\n").unwrap();
+    std::fs::write(project_dir.join("utils.ts"), "export {}\n").unwrap();
+    std::fs::write(project_dir.join("same.ts"), "same\n").unwrap();
+
+    let file = |path: &str| LockedFile::new(project_dir, &project_dir.join(path), "");
+    let before = LockedComponent {
+      version: Some("1.0.0".to_string()),
+      dependencies: vec!["clsx".to_string()],
+      files: vec![file("button.tsx"), file("old.ts"), file("same.ts")],
+      ..Default::default()
+    };
+    let after = LockedComponent {
+      version: Some("1.1.0".to_string()),
+      dependencies: vec!["clsx".to_string(), "zod@^3.23".to_string()],
+      files: vec![file("button.tsx"), file("utils.ts"), file("same.ts")],
+      ..Default::default()
+    };
+    let old_contents = BTreeMap::from([
+      ("button.tsx".to_string(), "a\nb\nc\n".to_string()),
+      ("old.ts".to_string(), "x\ny\n".to_string()),
+      ("same.ts".to_string(), "same\n".to_string()),
+    ]);
+
+    let mut update = ComponentUpdate::new("button", &before, &after, &old_contents, project_dir);
+    assert_eq!(
+      update.files,
+      [
+        FileChange {
+          path: "button.tsx".to_string(),
+          status: "modified",
+          additions: 2,
+          deletions: 1,
+        },
+        FileChange {
+          path: "utils.ts".to_string(),
+          status: "added",
+          additions: 1,
+          deletions: 0,
+        },
+        FileChange {
+          path: "old.ts".to_string(),
+          status: "removed",
+          additions: 0,
+          deletions: 2,
+        },
+      ]
+    );
+    assert_eq!(update.new_dependencies, ["zod@^3.23"]);
+    update.changes = vec![ChangelogEntry {
+      version: "1.1.0".to_string(),
+      date: None,
+      notes: "Add sizes".to_string(),
+    }];
+
+    let report = UpdateReport {
+      components: vec![update],
+    };
+    let markdown = report.markdown();
+    assert!(markdown.contains("| `button` | 1.0.0 | 1.1.0 | 3 | +3 -3 |"));
+    assert!(markdown.contains("- `old.ts` (removed, +0 -2)"));
+    assert!(markdown.contains("New npm dependencies: `zod@^3.23`"));
+    assert!(markdown.contains("#### 1.1.0\n\nAdd sizes"));
+
+    let path = project_dir.join("report.json");
+    report.write(&path).unwrap();
+    let json: serde_json::Value =
+      serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+    assert_eq!(json["components"][0]["newDependencies"][0], "zod@^3.23");
+    assert_eq!(json["components"][0]["files"][2]["status"], "removed");
+  }
+}
@@ -0,0 +1,576 @@
+use std::{
+  collections::BTreeSet,
+  fs,
+  path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Result};
+use serde_json::{json, Value};
+use walkdir::WalkDir;
+
+use crate::{
+  imports::{npm_dependencies, parse_imports, registry_dependencies},
+  schema,
+};
+
+/// Styles created by the scaffold
+const STYLES: [&str; 2] = ["default", "new-york"];
+
+/// Name of the example component
+const EXAMPLE_COMPONENT: &str = "hello-button";
+
+/// Directory `uiget build` writes to, served by GitHub Pages
+pub const OUTPUT_DIR: &str = "public/r";
+
+/// Scaffold a new registry project in `dir`. Returns the created files.
+pub fn scaffold_registry(dir: &Path, name: &str, force: bool) -> Result<Vec<PathBuf>> {
+  let registry_path = dir.join("registry.json");
+  if registry_path.exists() && !force {
+    return Err(anyhow!(
+      "'{}' already exists. Use --force to overwrite",
+      registry_path.display()
+    ));
+  }
+
+  let config = json!({
+    "$schema": schema::REGISTRY_SCHEMA_URL,
+    "name": name,
+    "description": format!("Components published by {}", name),
+    "license": "MIT",
+    "styles": STYLES,
+    "default_style": STYLES[0],
+    "components": {},
+  });
+  fs::create_dir_all(dir)?;
+  save_registry_json(&registry_path, &config)?;
+
+  let mut files = vec![registry_path.clone()];
+  files.extend(scaffold_component(
+    &registry_path,
+    EXAMPLE_COMPONENT,
+    "registry:ui",
+    Some("An example component to copy from"),
+    force,
+  )?);
+
+  let extra_files = [
+    (dir.join("public/.nojekyll"), String::new()),
+    (
+      dir.join(".github/workflows/registry.yml"),
+      PAGES_WORKFLOW.to_string(),
+    ),
+    (dir.join("README.md"), readme(name)),
+  ];
+
+  for (path, content) in extra_files {
+    write_new_file(&path, &content, force)?;
+    files.push(path);
+  }
+
+  Ok(files)
+}
+
+/// Add a component to a registry: a stub source file for every configured
+/// style plus its `components` entry in registry.json. Returns the created
+/// source files.
+pub fn scaffold_component(
+  registry_path: &Path,
+  name: &str,
+  component_type: &str,
+  description: Option<&str>,
+  force: bool,
+) -> Result<Vec<PathBuf>> {
+  let valid_name = !name.is_empty()
+    && name
+      .chars()
+      .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+  if !valid_name {
+    return Err(anyhow!(
+      "Invalid component name '{}'. Use lowercase letters, digits and dashes",
+      name
+    ));
+  }
+
+  let mut registry = load_registry_json(registry_path)?;
+  let base_dir = registry_path.parent().unwrap_or_else(|| Path::new("."));
+
+  if registry["components"].get(name).is_some() && !force {
+    return Err(anyhow!(
+      "Component '{}' already exists in '{}'. Use --force to overwrite",
+      name,
+      registry_path.display()
+    ));
+  }
+
+  let styles: Vec<String> = registry["styles"]
+    .as_array()
+    .map(|styles| {
+      styles
+        .iter()
+        .filter_map(|style| style.as_str().map(String::from))
+        .collect()
+    })
+    .filter(|styles: &Vec<String>| !styles.is_empty())
+    .unwrap_or_else(|| vec!["default".to_string()]);
+
+  let file_name = stub_file_name(name, component_type);
+  let target = stub_target(name, component_type, &file_name);
+
+  let mut created = Vec::new();
+  let mut style_files = serde_json::Map::new();
+  for style in &styles {
+    let source = format!("registry/{}/{}/{}", style, name, file_name);
+    let path = base_dir.join(&source);
+    write_new_file(&path, &component_stub(name, component_type), force)?;
+    created.push(path);
+
+    style_files.insert(
+      style.clone(),
+      json!([{ "source": source, "target": target, "type": component_type }]),
+    );
+  }
+
+  let definition = json!({
+    "name": name,
+    "type": component_type,
+    "description": description
+      .map(String::from)
+      .unwrap_or_else(|| format!("TODO: describe {}", name)),
+    "version": "0.1.0",
+    "files": style_files,
+  });
+
+  if !registry["components"].is_object() {
+    registry["components"] = json!({});
+  }
+  registry["components"][name] = definition;
+  save_registry_json(registry_path, &registry)?;
+
+  Ok(created)
+}
+
+/// Component folders searched, in order, when importing a project
+const UI_DIRS: [&str; 4] = [
+  "components/ui",
+  "src/components/ui",
+  "src/lib/components/ui",
+  "lib/components/ui",
+];
+
+/// Extensions of component source files
+const SOURCE_EXTENSIONS: [&str; 6] = ["tsx", "ts", "jsx", "js", "svelte", "vue"];
+
+/// Generate registry.json entries for the components of an existing
+/// project. Each folder in its `components/ui` directory (or each file, for
+/// flat layouts) becomes a component, with npm and registry dependencies
+/// inferred from its imports. Returns the names of the added components.
+pub fn import_components(
+  project_dir: &Path,
+  registry_path: &Path,
+  force: bool,
+) -> Result<Vec<String>> {
+  let ui_dir = UI_DIRS
+    .iter()
+    .map(|dir| project_dir.join(dir))
+    .find(|dir| dir.is_dir())
+    .ok_or_else(|| {
+      anyhow!(
+        "No components folder found in '{}'. Looked for {}",
+        project_dir.display(),
+        UI_DIRS.join(", ")
+      )
+    })?;
+
+  let mut registry = if registry_path.exists() {
+    load_registry_json(registry_path)?
+  } else {
+    let name = std::path::absolute(project_dir)?
+      .file_name()
+      .map(|name| name.to_string_lossy().to_string())
+      .unwrap_or_else(|| "my-registry".to_string());
+    json!({ "name": name, "components": {} })
+  };
+  if !registry["components"].is_object() {
+    registry["components"] = json!({});
+  }
+
+  let found = find_components(&ui_dir)?;
+
+  let mut known: BTreeSet<String> = found.iter().map(|(name, _)| name.clone()).collect();
+  if let Some(existing) = registry["components"].as_object() {
+    known.extend(existing.keys().cloned());
+  }
+
+  let registry_dir = std::path::absolute(registry_path.parent().unwrap_or_else(|| Path::new(".")))?;
+  let mut added = Vec::new();
+
+  for (name, files) in found {
+    if registry["components"].get(&name).is_some() && !force {
+      continue;
+    }
+
+    let mut imports = Vec::new();
+    let mut file_entries = Vec::new();
+    for file in &files {
+      imports.extend(parse_imports(&fs::read_to_string(file)?));
+
+      let target = file.strip_prefix(&ui_dir).unwrap_or(file);
+      file_entries.push(json!({
+        "source": to_slash(&relative_path(&registry_dir, &std::path::absolute(file)?)),
+        "target": format!("ui/{}", to_slash(target)),
+        "type": "registry:ui",
+      }));
+    }
+
+    let mut definition = json!({
+      "name": name,
+      "type": "registry:ui",
+      "default_files": file_entries,
+    });
+
+    let dependencies = npm_dependencies(&imports);
+    if !dependencies.is_empty() {
+      definition["dependencies"] = json!(dependencies);
+    }
+    let registry_dependencies = registry_dependencies(&imports, &known, &name);
+    if !registry_dependencies.is_empty() {
+      definition["registryDependencies"] = json!(registry_dependencies);
+    }
+
+    registry["components"][&name] = definition;
+    added.push(name);
+  }
+
+  save_registry_json(registry_path, &registry)?;
+  Ok(added)
+}
+
+/// Components in a `ui` folder with their source files, sorted by name
+fn find_components(ui_dir: &Path) -> Result<Vec<(String, Vec<PathBuf>)>> {
+  let is_source = |path: &Path| {
+    path
+      .extension()
+      .and_then(|ext| ext.to_str())
+      .is_some_and(|ext| SOURCE_EXTENSIONS.contains(&ext))
+  };
+
+  let mut components = Vec::new();
+  for entry in fs::read_dir(ui_dir)? {
+    let path = entry?.path();
+    let name = path
+      .file_stem()
+      .map(|stem| stem.to_string_lossy().to_string())
+      .unwrap_or_default();
+
+    if path.is_dir() {
+      let mut files: Vec<PathBuf> = WalkDir::new(&path)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.into_path())
+        .filter(|file| file.is_file() && is_source(file))
+        .collect();
+      files.sort();
+      if !files.is_empty() {
+        components.push((name, files));
+      }
+    } else if is_source(&path) && name != "index" {
+      components.push((name, vec![path]));
+    }
+  }
+
+  components.sort_by(|a, b| a.0.cmp(&b.0));
+  Ok(components)
+}
+
+/// Path of `to` relative to the directory `from` (both absolute)
+fn relative_path(from: &Path, to: &Path) -> PathBuf {
+  let from: Vec<_> = from.components().collect();
+  let to: Vec<_> = to.components().collect();
+  let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+
+  let mut path = PathBuf::new();
+  for _ in common..from.len() {
+    path.push("..");
+  }
+  for component in &to[common..] {
+    path.push(component);
+  }
+  path
+}
+
+fn to_slash(path: &Path) -> String {
+  path.to_string_lossy().replace('\\', "/")
+}
+
+/// Load registry.json as raw JSON so edits keep unknown fields and the
+/// author's key order
+fn load_registry_json(path: &Path) -> Result<Value> {
+  let content =
+    fs::read_to_string(path).map_err(|e| anyhow!("Failed to read '{}': {}", path.display(), e))?;
+  serde_json::from_str(&content).map_err(|e| anyhow!("Failed to parse '{}': {}", path.display(), e))
+}
+
+fn save_registry_json(path: &Path, registry: &Value) -> Result<()> {
+  fs::write(
+    path,
+    format!("{}\n", serde_json::to_string_pretty(registry)?),
+  )?;
+  Ok(())
+}
+
+/// Write a file, keeping existing files unless `force` is set
+fn write_new_file(path: &Path, content: &str, force: bool) -> Result<()> {
+  if path.exists() && !force {
+    return Ok(());
+  }
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent)?;
+  }
+  fs::write(path, content)?;
+  Ok(())
+}
+
+/// Source file name for a component: hooks and libraries are plain
+/// TypeScript, everything else is a React component
+fn stub_file_name(name: &str, component_type: &str) -> String {
+  match component_type {
+    "registry:hook" | "registry:lib" => format!("{}.ts", name),
+    _ => format!("{}.tsx", name),
+  }
+}
+
+/// Install target for a component file, following the shadcn folder layout
+fn stub_target(name: &str, component_type: &str, file_name: &str) -> String {
+  match component_type {
+    "registry:hook" => format!("hooks/{}", file_name),
+    "registry:lib" => format!("lib/{}", file_name),
+    "registry:block" => format!("blocks/{}/{}", name, file_name),
+    _ => format!("ui/{}/{}", name, file_name),
+  }
+}
+
+/// `data-table` → `DataTable`
+fn pascal_case(name: &str) -> String {
+  name
+    .split(['-', '_', ' '])
+    .filter(|part| !part.is_empty())
+    .map(|part| {
+      let mut chars = part.chars();
+      chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+        .unwrap_or_default()
+    })
+    .collect()
+}
+
+fn component_stub(name: &str, component_type: &str) -> String {
+  let pascal = pascal_case(name);
+  let hook = format!(
+    "use{}",
+    pascal_case(name.strip_prefix("use-").unwrap_or(name))
+  );
+
+  match component_type {
+    "registry:hook" => format!(
+      r#"import * as React from "react"
+
+export function {hook}() {{
+  const [value, setValue] = React.useState<unknown>(null)
+  return {{ value, setValue }}
+}}
+"#
+    ),
+    "registry:lib" => format!(
+      r#"export function {}() {{
+  // TODO: implement
+}}
+"#,
+      pascal[..1].to_lowercase() + &pascal[1..]
+    ),
+    _ => format!(
+      r#"import * as React from "react"
+
+import {{ cn }} from "$UTILS$"
+
+export function {pascal}({{
+  className,
+  ...props
+}}: React.HTMLAttributes<HTMLDivElement>) {{
+  return <div className={{cn("", className)}} {{...props}} />
+}}
+"#
+    ),
+  }
+}
+
+fn readme(name: &str) -> String {
+  format!(
+    r#"# {name}
+
+A component registry built with [uiget](https://github.com/devzolo/uiget).
+
+## Layout
+
+- `registry.json` – registry metadata and component definitions
+- `registry/<style>/<component>/` – component sources, one folder per style
+- `{output}/` – generated JSON (`uiget build`), published by GitHub Pages
+
+## Publish
+
+```bash
+uiget build registry.json --output {output}
+```
+
+Pushing to `main` runs `.github/workflows/registry.yml`, which builds the registry and deploys `public/` to GitHub Pages. Enable Pages with the "GitHub Actions" source in the repository settings.
+
+## Use
+
+```bash
+uiget registry add {name} "https://<user>.github.io/<repo>/r/{{name}}.json"
+uiget add {component} --registry {name}
+```
+
+The default style is published at `r/<component>.json` and other styles at `r/<style>/<component>.json`.
+"#,
+    name = name,
+    output = OUTPUT_DIR,
+    component = EXAMPLE_COMPONENT
+  )
+}
+
+const PAGES_WORKFLOW: &str = r#"name: Publish registry
+
+on:
+  push:
+    branches: [main]
+  workflow_dispatch:
+
+permissions:
+  contents: read
+  pages: write
+  id-token: write
+
+jobs:
+  build:
+    runs-on: ubuntu-latest
+    steps:
+      - uses: actions/checkout@v4
+      - uses: dtolnay/rust-toolchain@stable
+      - run: cargo install --git https://github.com/devzolo/uiget
+      - run: uiget build registry.json --output public/r
+      - uses: actions/upload-pages-artifact@v3
+        with:
+          path: public
+
+  deploy:
+    needs: build
+    runs-on: ubuntu-latest
+    environment:
+      name: github-pages
+      url: ${{ steps.deployment.outputs.page_url }}
+    steps:
+      - id: deployment
+        uses: actions/deploy-pages@v4
+"#;
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::builder::RegistryBuilder;
+
+  #[test]
+  fn test_scaffold_builds() -> Result<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let files = scaffold_registry(temp_dir.path(), "acme", false)?;
+    assert!(files.contains(&temp_dir.path().join("registry.json")));
+
+    let output = temp_dir.path().join(OUTPUT_DIR);
+    RegistryBuilder::new(&temp_dir.path().join("registry.json"), &output)?.build()?;
+
+    assert!(output.join("index.json").exists());
+    assert!(output.join("hello-button.json").exists());
+    assert!(output.join("new-york/hello-button.json").exists());
+
+    // Refuses to overwrite an existing registry without force
+    assert!(scaffold_registry(temp_dir.path(), "acme", false).is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_scaffold_component() -> Result<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let registry_path = temp_dir.path().join("registry.json");
+    fs::write(
+      &registry_path,
+      r#"{ "name": "acme", "homepage": "https://acme.dev", "styles": ["default", "dark"], "components": {} }"#,
+    )?;
+
+    let files = scaffold_component(&registry_path, "use-toggle", "registry:hook", None, false)?;
+    assert_eq!(files.len(), 2);
+    let stub = fs::read_to_string(
+      temp_dir
+        .path()
+        .join("registry/dark/use-toggle/use-toggle.ts"),
+    )?;
+    assert!(stub.contains("export function useToggle()"));
+
+    let registry = load_registry_json(&registry_path)?;
+    let keys: Vec<&String> = registry.as_object().unwrap().keys().collect();
+    assert_eq!(keys, ["name", "homepage", "styles", "components"]);
+    assert_eq!(
+      registry["components"]["use-toggle"]["files"]["dark"][0]["target"],
+      "hooks/use-toggle.ts"
+    );
+
+    assert!(
+      scaffold_component(&registry_path, "use-toggle", "registry:hook", None, false).is_err()
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn test_import_components() -> Result<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let ui_dir = temp_dir.path().join("app/src/components/ui");
+    fs::create_dir_all(ui_dir.join("dialog"))?;
+    fs::write(
+      ui_dir.join("button.tsx"),
+      "import { Slot } from \"@radix-ui/react-slot\"\nimport * as React from \"react\"\n",
+    )?;
+    fs::write(
+      ui_dir.join("dialog/dialog.tsx"),
+      "import { Button } from \"@/components/ui/button\"\nimport { X } from \"lucide-react\"\n",
+    )?;
+    fs::write(ui_dir.join("dialog/README.md"), "not a source")?;
+
+    let registry_path = temp_dir.path().join("registry/registry.json");
+    fs::create_dir_all(registry_path.parent().unwrap())?;
+    let added = import_components(&temp_dir.path().join("app"), &registry_path, false)?;
+    assert_eq!(added, ["button", "dialog"]);
+
+    let registry = load_registry_json(&registry_path)?;
+    let dialog = &registry["components"]["dialog"];
+    assert_eq!(dialog["dependencies"], json!(["lucide-react"]));
+    assert_eq!(dialog["registryDependencies"], json!(["button"]));
+    assert_eq!(
+      dialog["default_files"][0]["source"],
+      "../app/src/components/ui/dialog/dialog.tsx"
+    );
+    assert_eq!(dialog["default_files"][0]["target"], "ui/dialog/dialog.tsx");
+    assert_eq!(
+      registry["components"]["button"]["dependencies"],
+      json!(["@radix-ui/react-slot"])
+    );
+
+    // Existing entries are kept unless forced
+    assert!(import_components(&temp_dir.path().join("app"), &registry_path, false)?.is_empty());
+    Ok(())
+  }
+
+  #[test]
+  fn test_pascal_case() {
+    assert_eq!(pascal_case("data-table"), "DataTable");
+    assert_eq!(pascal_case("button"), "Button");
+  }
+}
@@ -0,0 +1,319 @@
+//! JSON Schemas for uiget.json and registry.json, for editor autocompletion
+//! and validation, and for the output of `outdated --json`, for the bots
+//! that read it
+
+/// Where the uiget.json schema is published
+pub const CONFIG_SCHEMA_URL: &str =
+  "https://raw.githubusercontent.com/devzolo/uiget/main/schema/uiget.json";
+
+/// Where the registry.json schema is published
+pub const REGISTRY_SCHEMA_URL: &str =
+  "https://raw.githubusercontent.com/devzolo/uiget/main/schema/registry.json";
+
+/// Where the `outdated --json` schema is published
+pub const OUTDATED_SCHEMA_URL: &str =
+  "https://raw.githubusercontent.com/devzolo/uiget/main/schema/outdated.json";
+
+const CONFIG_SCHEMA: &str = include_str!("../schema/uiget.json");
+const REGISTRY_SCHEMA: &str = include_str!("../schema/registry.json");
+const OUTDATED_SCHEMA: &str = include_str!("../schema/outdated.json");
+
+/// The schema `uiget schema <kind>` prints: "config", "registry" or
+/// "outdated"
+pub fn schema(kind: &str) -> Option<&'static str> {
+  match kind {
+    "config" => Some(CONFIG_SCHEMA),
+    "registry" => Some(REGISTRY_SCHEMA),
+    "outdated" => Some(OUTDATED_SCHEMA),
+    _ => None,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::collections::{BTreeSet, HashMap};
+
+  use indexmap::IndexMap;
+  use serde_json::{json, Value};
+
+  use super::*;
+  use crate::{builder, config::*};
+
+  fn keys(value: &Value) -> BTreeSet<&str> {
+    value
+      .as_object()
+      .unwrap()
+      .keys()
+      .map(String::as_str)
+      .collect()
+  }
+
+  fn properties<'a>(schema: &'a Value, pointer: &str) -> BTreeSet<&'a str> {
+    keys(&schema.pointer(pointer).unwrap()["properties"])
+  }
+
+  /// Check `value` against the parts of JSON Schema the schemas use, so a
+  /// field whose type changed fails like a missing one. Returns the path
+  /// of the first mismatch. `null` counts as leaving an optional field out.
+  fn check(root: &Value, schema: &Value, value: &Value, path: &str) -> Result<(), String> {
+    if let Some(reference) = schema["$ref"].as_str() {
+      let target = root.pointer(reference.trim_start_matches('#')).unwrap();
+      return check(root, target, value, path);
+    }
+    if let Some(options) = schema["oneOf"].as_array().or(schema["anyOf"].as_array()) {
+      if options
+        .iter()
+        .any(|option| check(root, option, value, path).is_ok())
+      {
+        return Ok(());
+      }
+      return Err(format!(
+        "{}: no option of oneOf/anyOf accepts {}",
+        path, value
+      ));
+    }
+    if let Some(types) = schema.get("type") {
+      let types: Vec<&str> = match types {
+        Value::Array(types) => types.iter().filter_map(Value::as_str).collect(),
+        types => vec![types.as_str().unwrap()],
+      };
+      let accepted = types.iter().any(|kind| match *kind {
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "integer" => value.is_u64() || value.is_i64(),
+        "number" => value.is_number(),
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "null" => value.is_null(),
+        _ => false,
+      });
+      if !accepted {
+        return Err(format!("{}: {} is not {:?}", path, value, types));
+      }
+    }
+    if let Some(allowed) = schema["enum"].as_array() {
+      if !allowed.contains(value) {
+        return Err(format!("{}: {} is not one of {:?}", path, value, allowed));
+      }
+    }
+    match value {
+      Value::Object(object) => {
+        let required = schema["required"].as_array();
+        for (key, value) in object {
+          let path = format!("{}/{}", path, key);
+          if value.is_null() && !required.is_some_and(|required| required.contains(&json!(key))) {
+            continue;
+          }
+          match schema["properties"].get(key) {
+            Some(property) => check(root, property, value, &path)?,
+            None => match &schema["additionalProperties"] {
+              Value::Bool(false) => return Err(format!("{}: not in the schema", path)),
+              additional if additional.is_object() => check(root, additional, value, &path)?,
+              _ => {}
+            },
+          }
+        }
+        Ok(())
+      }
+      Value::Array(items) if schema["items"].is_object() => {
+        for (index, item) in items.iter().enumerate() {
+          check(root, &schema["items"], item, &format!("{}/{}", path, index))?;
+        }
+        Ok(())
+      }
+      _ => Ok(()),
+    }
+  }
+
+  #[test]
+  fn test_config_schema_matches_config() {
+    let schema: Value = serde_json::from_str(schema("config").unwrap()).unwrap();
+    assert_eq!(schema["$id"], CONFIG_SCHEMA_URL);
+
+    // Every field set, so a field missing from the schema fails the test
+    let registry = RegistryConfig::Object {
+      url: "https://acme.dev/r/{name}.json".to_string(),
+      params: Some(HashMap::new()),
+      headers: Some(HashMap::new()),
+      username: Some("ci".to_string()),
+      password: Some("${ACME_PASSWORD}".to_string()),
+      style: Some("new-york".to_string()),
+      default_type: Some("registry:ui".to_string()),
+      target_override: Some("$lib/acme".to_string()),
+      max_concurrent_requests: Some(2),
+      user_agent: Some("acme-ci".to_string()),
+      signing: Some(RequestSigning {
+        secret: Some("${ACME_SIGNING_KEY}".to_string()),
+        header: Some("X-Acme-Signature".to_string()),
+        message: Some("{path}{timestamp}".to_string()),
+        timestamp_header: Some("X-Acme-Timestamp".to_string()),
+        command: Some("acme-sign".to_string()),
+      }),
+      response_path: Some("/data".to_string()),
+      graphql: Some(GraphQlQueries {
+        component: "query($name: String!) { component(name: $name) { name files } }".to_string(),
+        index: Some("{ components { name type } }".to_string()),
+        search: Some("query($query: String!) { search(query: $query) { name } }".to_string()),
+      }),
+      extra: Default::default(),
+    };
+    let provider = OAuthProvider {
+      device_authorization_url: "https://auth.acme.dev/device/code".to_string(),
+      token_url: "https://auth.acme.dev/token".to_string(),
+      client_id: "uiget".to_string(),
+      scope: Some("registry:read".to_string()),
+      hosts: vec!["registry.acme.dev".to_string()],
+    };
+    let hook = Some("true".to_string());
+    let config = Config {
+      schema: Some(CONFIG_SCHEMA_URL.to_string()),
+      style: Some("default".to_string()),
+      tailwind: TailwindConfig {
+        css: "src/app.css".to_string(),
+        base_color: "slate".to_string(),
+        config: Some("tailwind.config.js".to_string()),
+        extra: Default::default(),
+      },
+      aliases: AliasesConfig {
+        components: "$lib/components".to_string(),
+        utils: "$lib/utils".to_string(),
+        ui: Some("$lib/components/ui".to_string()),
+        hooks: Some("$lib/hooks".to_string()),
+        lib: Some("$lib".to_string()),
+        examples: Some("$lib/examples".to_string()),
+        internal: Some("$lib/internal".to_string()),
+        extra: Default::default(),
+      },
+      registries: IndexMap::from([("acme".to_string(), registry.clone())]),
+      typescript: Some(TypeScriptConfig::Boolean(true)),
+      post_install_messages: Some(true),
+      format_on_install: Some(true),
+      barrel_exports: Some(true),
+      overrides: Some(IndexMap::new()),
+      namespace_dirs: Some(true),
+      install_examples: Some(true),
+      strip_types: Some(true),
+      provenance_header: Some(true),
+      package_manager_args: Some(Vec::new()),
+      confirm_dependency_install: Some(true),
+      dependency_install_target: Some(DependencyInstallTarget::Prompt),
+      hooks: Some(HooksConfig {
+        pre_add: hook.clone(),
+        post_add: hook.clone(),
+        pre_remove: hook.clone(),
+        post_remove: hook.clone(),
+        pre_update: hook.clone(),
+        post_update: hook,
+        extra: Default::default(),
+      }),
+      locale: Some("en".to_string()),
+      max_response_size: Some(50),
+      variables: Some(IndexMap::new()),
+      confirm_install_size: Some(InstallSizeLimits {
+        files: Some(20),
+        kilobytes: Some(100),
+      }),
+      oauth: Some(IndexMap::from([("acme".to_string(), provider.clone())])),
+      extra: Default::default(),
+    };
+    let config = serde_json::to_value(&config).unwrap();
+
+    assert_eq!(keys(&config), properties(&schema, ""));
+    for object in ["tailwind", "aliases", "hooks", "confirmInstallSize"] {
+      assert_eq!(
+        keys(&config[object]),
+        properties(&schema, &format!("/properties/{}", object)),
+        "{}",
+        object
+      );
+    }
+    assert_eq!(
+      keys(&serde_json::to_value(&registry).unwrap()),
+      properties(&schema, "/definitions/registry/oneOf/1")
+    );
+    let registry = serde_json::to_value(&registry).unwrap();
+    assert_eq!(
+      keys(&registry["signing"]),
+      properties(&schema, "/definitions/registry/oneOf/1/properties/signing")
+    );
+    assert_eq!(
+      keys(&registry["graphql"]),
+      properties(&schema, "/definitions/registry/oneOf/1/properties/graphql")
+    );
+    assert_eq!(
+      keys(&serde_json::to_value(&provider).unwrap()),
+      properties(&schema, "/definitions/oauthProvider")
+    );
+    check(&schema, &schema, &config, "").unwrap();
+    assert!(check(&schema, &schema, &json!({ "barrelExports": "yes" }), "").is_err());
+  }
+
+  #[test]
+  fn test_registry_schema_matches_registry() {
+    let schema: Value = serde_json::from_str(schema("registry").unwrap()).unwrap();
+    assert_eq!(schema["$id"], REGISTRY_SCHEMA_URL);
+
+    // registry.json types serialize every field, set or not
+    let registry: builder::RegistryConfig = serde_json::from_value(json!({
+      "name": "acme",
+      "author": { "name": "Acme" },
+      "components": {
+        "button": {
+          "name": "button",
+          "type": "registry:ui",
+          "version": "1.0.0",
+          "tags": ["form"],
+          "links": { "figma": "https://figma.com/acme" },
+          "changelog": [{ "version": "1.0.0", "notes": "First release" }],
+          "deprecated": false,
+          "dependencies": ["bits-ui@^1"],
+          "engines": { "node": ">=18" },
+          "cssVars": { "light": { "primary": "blue" } },
+          "css": { "@layer base": { "body": { "@apply bg-background": {} } } },
+          "default_files": [{ "source": "button.svelte", "target": "ui/button.svelte" }]
+        }
+      }
+    }))
+    .unwrap();
+    let registry = serde_json::to_value(&registry).unwrap();
+
+    assert_eq!(keys(&registry), properties(&schema, ""));
+    assert_eq!(
+      keys(&registry["author"]),
+      properties(&schema, "/properties/author")
+    );
+    let button = &registry["components"]["button"];
+    assert_eq!(keys(button), properties(&schema, "/definitions/component"));
+    assert_eq!(
+      keys(&button["default_files"][0]),
+      properties(&schema, "/definitions/files/items")
+    );
+    check(&schema, &schema, &registry, "").unwrap();
+  }
+
+  #[test]
+  fn test_outdated_schema_matches_report() {
+    let schema: Value = serde_json::from_str(schema("outdated").unwrap()).unwrap();
+    assert_eq!(schema["$id"], OUTDATED_SCHEMA_URL);
+
+    let report = crate::report::OutdatedReport::new(vec![crate::report::OutdatedComponent {
+      name: "button".to_string(),
+      registry: None,
+      installed: Some("1.0.0".to_string()),
+      latest: None,
+      outdated: false,
+      modified: false,
+    }]);
+    let report = serde_json::to_value(&report).unwrap();
+    assert_eq!(keys(&report), properties(&schema, ""));
+    assert_eq!(
+      report["schemaVersion"],
+      schema["properties"]["schemaVersion"]["const"]
+    );
+    assert_eq!(
+      keys(&report["components"][0]),
+      properties(&schema, "/properties/components/items")
+    );
+    check(&schema, &schema, &report, "").unwrap();
+  }
+}
@@ -0,0 +1,258 @@
+use std::{
+  env, fs,
+  path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Result};
+use colored::*;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::{output::status, package_manager::satisfies_range};
+
+/// GitHub repository that publishes uiget releases
+const RELEASES_URL: &str = "https://api.github.com/repos/devzolo/uiget/releases/latest";
+
+/// Checksum manifests searched when an asset has no `<asset>.sha256` file
+const CHECKSUM_MANIFESTS: [&str; 2] = ["SHA256SUMS", "checksums.txt"];
+
+#[derive(Debug, Deserialize)]
+struct Release {
+  tag_name: String,
+  assets: Vec<ReleaseAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReleaseAsset {
+  name: String,
+  browser_download_url: String,
+}
+
+impl Release {
+  fn version(&self) -> &str {
+    self.tag_name.trim_start_matches('v')
+  }
+
+  fn asset(&self, name: &str) -> Option<&ReleaseAsset> {
+    self.assets.iter().find(|asset| asset.name == name)
+  }
+}
+
+/// Name of the release binary for the running platform, e.g.
+/// `uiget-linux-x86_64` or `uiget-windows-x86_64.exe`
+pub fn asset_name() -> String {
+  format!(
+    "uiget-{}-{}{}",
+    env::consts::OS,
+    env::consts::ARCH,
+    env::consts::EXE_SUFFIX
+  )
+}
+
+/// Find the expected SHA-256 of `asset` in a checksum file. Accepts a bare
+/// hash or `sha256sum` style lines (`<hash>  <file>`).
+pub fn find_checksum(content: &str, asset: &str) -> Option<String> {
+  content.lines().find_map(|line| {
+    let mut parts = line.split_whitespace();
+    let hash = parts.next()?;
+    let valid_hash = hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit());
+
+    match parts.next() {
+      Some(file) if valid_hash && file.trim_start_matches('*') == asset => {
+        Some(hash.to_lowercase())
+      }
+      None if valid_hash => Some(hash.to_lowercase()),
+      _ => None,
+    }
+  })
+}
+
+/// Check GitHub for a newer release and, unless `check_only`, replace the
+/// running executable with it after verifying its checksum
+pub async fn self_update(check_only: bool) -> Result<()> {
+  let current = env!("CARGO_PKG_VERSION");
+  let client = reqwest::Client::builder()
+    .user_agent(format!("uiget-cli/{}", current))
+    .build()?;
+
+  status!("{} Checking for updates...", "→".blue());
+  let release: Release = client
+    .get(RELEASES_URL)
+    .header("Accept", "application/vnd.github+json")
+    .send()
+    .await?
+    .error_for_status()?
+    .json()
+    .await?;
+
+  let latest = release.version();
+  if !satisfies_range(latest, &format!(">{}", current)) {
+    status!("{} uiget {} is up to date", "✓".green(), current.cyan());
+    return Ok(());
+  }
+
+  status!(
+    "{} uiget {} is available (current: {})",
+    "⚠".yellow(),
+    latest.green(),
+    current.yellow()
+  );
+
+  if check_only {
+    status!("  Run 'uiget self-update' to install it");
+    return Ok(());
+  }
+
+  let name = asset_name();
+  let asset = release.asset(&name).ok_or_else(|| {
+    anyhow!(
+      "Release {} has no binary for this platform ('{}')",
+      release.tag_name,
+      name
+    )
+  })?;
+
+  let expected = fetch_expected_checksum(&client, &release, &name).await?;
+
+  status!("{} Downloading {}...", "→".blue(), name.cyan());
+  let binary = client
+    .get(&asset.browser_download_url)
+    .send()
+    .await?
+    .error_for_status()?
+    .bytes()
+    .await?;
+
+  let actual = format!("{:x}", Sha256::digest(&binary));
+  if actual != expected {
+    return Err(anyhow!(
+      "Checksum mismatch for {}: expected {}, got {}",
+      name,
+      expected,
+      actual
+    ));
+  }
+  status!("{} Checksum verified", "✓".green());
+
+  let current_exe = env::current_exe()?;
+  replace_executable(&current_exe, &binary)?;
+
+  status!(
+    "{} Updated uiget {} → {}",
+    "✓".green(),
+    current.yellow(),
+    latest.green()
+  );
+  Ok(())
+}
+
+/// Download the checksum published for `asset_name`
+async fn fetch_expected_checksum(
+  client: &reqwest::Client,
+  release: &Release,
+  asset_name: &str,
+) -> Result<String> {
+  let sidecar = format!("{}.sha256", asset_name);
+  let candidates = std::iter::once(sidecar.as_str()).chain(CHECKSUM_MANIFESTS);
+
+  for file in candidates {
+    let Some(asset) = release.asset(file) else {
+      continue;
+    };
+
+    let content = client
+      .get(&asset.browser_download_url)
+      .send()
+      .await?
+      .error_for_status()?
+      .text()
+      .await?;
+
+    if let Some(checksum) = find_checksum(&content, asset_name) {
+      return Ok(checksum);
+    }
+  }
+
+  Err(anyhow!(
+    "Release {} publishes no checksum for '{}'; refusing to install an unverified binary",
+    release.tag_name,
+    asset_name
+  ))
+}
+
+/// Swap the executable at `path` for `binary`. The new file is written next
+/// to it first so a failed download never leaves a broken install.
+fn replace_executable(path: &Path, binary: &[u8]) -> Result<()> {
+  let staged = sibling(path, "new");
+  fs::write(&staged, binary)?;
+
+  #[cfg(unix)]
+  {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(&staged, fs::Permissions::from_mode(0o755))?;
+  }
+
+  // Windows can't overwrite a running executable but can rename it
+  #[cfg(windows)]
+  {
+    let old = sibling(path, "old");
+    let _ = fs::remove_file(&old);
+    fs::rename(path, &old)?;
+  }
+
+  fs::rename(&staged, path).map_err(|e| {
+    let _ = fs::remove_file(&staged);
+    anyhow!("Failed to replace '{}': {}", path.display(), e)
+  })
+}
+
+/// `uiget` → `uiget.<suffix>` in the same directory
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+  let mut name = path.file_name().unwrap_or_default().to_os_string();
+  name.push(format!(".{}", suffix));
+  path.with_file_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_find_checksum() {
+    let hash = "a".repeat(64);
+    let other = "b".repeat(64);
+
+    let manifest = format!(
+      "{}  uiget-linux-x86_64\n{} *uiget-windows-x86_64.exe\n",
+      hash, other
+    );
+    assert_eq!(
+      find_checksum(&manifest, "uiget-linux-x86_64"),
+      Some(hash.clone())
+    );
+    assert_eq!(
+      find_checksum(&manifest, "uiget-windows-x86_64.exe"),
+      Some(other)
+    );
+    assert_eq!(find_checksum(&manifest, "uiget-macos-aarch64"), None);
+
+    // Sidecar files may contain just the hash
+    assert_eq!(
+      find_checksum(&format!("{}\n", hash), "anything"),
+      Some(hash)
+    );
+    assert_eq!(find_checksum("not-a-hash  uiget", "uiget"), None);
+  }
+
+  #[test]
+  fn test_replace_executable() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let exe = temp_dir.path().join("uiget");
+    fs::write(&exe, "old").unwrap();
+
+    replace_executable(&exe, b"new").unwrap();
+
+    assert_eq!(fs::read_to_string(&exe).unwrap(), "new");
+    assert!(!sibling(&exe, "new").exists());
+  }
+}
@@ -0,0 +1,623 @@
+use std::{
+  collections::BTreeSet,
+  io::Write,
+  path::{Path, PathBuf},
+  process::Stdio,
+};
+
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
+
+use crate::{
+  config::Config,
+  installer::lockfile_key_parts,
+  lockfile::Lockfile,
+  registry::{ComponentInfo, RegistryManager},
+};
+
+/// JSON-RPC error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+pub(crate) const METHOD_NOT_FOUND: i64 = -32601;
+pub(crate) const INVALID_PARAMS: i64 = -32602;
+const SERVER_ERROR: i64 = -32000;
+
+/// A JSON-RPC 2.0 request (or notification when `id` is absent)
+#[derive(Debug, Deserialize)]
+pub(crate) struct RpcRequest {
+  jsonrpc: String,
+  pub(crate) id: Option<Value>,
+  pub(crate) method: String,
+  #[serde(default)]
+  pub(crate) params: Value,
+}
+
+/// A JSON-RPC error object
+#[derive(Debug)]
+pub(crate) struct RpcError {
+  code: i64,
+  message: String,
+  data: Option<Value>,
+}
+
+impl RpcError {
+  pub(crate) fn new(code: i64, message: impl Into<String>) -> Self {
+    Self {
+      code,
+      message: message.into(),
+      data: None,
+    }
+  }
+
+  fn to_json(&self) -> Value {
+    let mut error = json!({ "code": self.code, "message": self.message });
+    if let Some(data) = &self.data {
+      error["data"] = data.clone();
+    }
+    error
+  }
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ListParams {
+  pub registry: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SearchParams {
+  pub query: String,
+  pub registry: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct InfoParams {
+  pub component: String,
+  pub registry: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddParams {
+  pub component: String,
+  pub registry: Option<String>,
+  #[serde(default)]
+  pub force: bool,
+  #[serde(default)]
+  pub skip_deps: bool,
+}
+
+impl AddParams {
+  /// Arguments of the `uiget add` command that installs the component. The
+  /// registry is passed as `--registry=<value>` and the component after
+  /// `--`, so client input is never read as another flag.
+  fn args(&self) -> Vec<String> {
+    let mut args = vec!["--yes".to_string(), "add".to_string()];
+    if let Some(registry) = &self.registry {
+      args.push(format!("--registry={}", registry));
+    }
+    if self.force {
+      args.push("--force".to_string());
+    }
+    if self.skip_deps {
+      args.push("--skip-deps".to_string());
+    }
+    args.push("--".to_string());
+    args.push(self.component.clone());
+    args
+  }
+}
+
+/// Result of running `uiget add` on behalf of an API client
+#[derive(Debug)]
+pub struct AddOutcome {
+  pub exit_code: i32,
+  pub output: Vec<String>,
+}
+
+impl AddOutcome {
+  pub fn success(&self) -> bool {
+    self.exit_code == 0
+  }
+}
+
+/// Machine-readable access to registries and the installer, shared by the
+/// JSON-RPC server and other integrations
+pub struct Api {
+  config: Config,
+  registry_manager: RegistryManager,
+  project_dir: PathBuf,
+  config_path: Option<PathBuf>,
+}
+
+impl Api {
+  /// Create the API for a project. `config_path` is forwarded to the
+  /// installer process when set.
+  pub fn new(config: &Config, project_dir: &Path, config_path: Option<PathBuf>) -> Result<Self> {
+    Ok(Self {
+      config: config.clone(),
+      registry_manager: RegistryManager::from_config(config)?,
+      project_dir: project_dir.to_path_buf(),
+      config_path,
+    })
+  }
+
+  /// Components recorded in the project lockfile, by the registry their
+  /// key names (for `<namespace>/<name>` keys) and their name
+  fn installed_components(&self) -> Installed {
+    Lockfile::load(&Lockfile::path_for(&self.project_dir))
+      .map(|lockfile| {
+        lockfile
+          .components
+          .keys()
+          .map(|key| {
+            let (namespace, name) = lockfile_key_parts(&self.config, key);
+            (namespace, name.to_string())
+          })
+          .collect()
+      })
+      .unwrap_or_default()
+  }
+
+  /// Namespaces to query: the requested one, or every configured registry
+  fn namespaces(&self, registry: Option<&str>) -> Result<Vec<String>> {
+    match registry {
+      Some(namespace) if self.registry_manager.get_registry(namespace).is_none() => {
+        Err(anyhow!("Registry '{}' not found", namespace))
+      }
+      Some(namespace) => Ok(vec![namespace.to_string()]),
+      None => {
+        let mut namespaces: Vec<String> = self
+          .registry_manager
+          .namespaces()
+          .into_iter()
+          .cloned()
+          .collect();
+        namespaces.sort();
+        Ok(namespaces)
+      }
+    }
+  }
+
+  /// List the components of each registry
+  pub async fn list(&self, params: ListParams) -> Result<Value> {
+    let installed = self.installed_components();
+    let mut registries = Vec::new();
+
+    for namespace in self.namespaces(params.registry.as_deref())? {
+      let registry = self
+        .registry_manager
+        .get_registry(&namespace)
+        .ok_or_else(|| anyhow!("Registry '{}' not found", namespace))?;
+      let mut components = registry.fetch_index().await?.to_vec();
+      components.sort_by(|a, b| a.name.cmp(&b.name));
+
+      registries.push(registry_entry(&namespace, &components, &installed));
+    }
+
+    Ok(json!({ "registries": registries }))
+  }
+
+  /// Search components by name or type
+  pub async fn search(&self, params: SearchParams) -> Result<Value> {
+    let installed = self.installed_components();
+    let mut registries = Vec::new();
+
+    for namespace in self.namespaces(params.registry.as_deref())? {
+      let registry = self
+        .registry_manager
+        .get_registry(&namespace)
+        .ok_or_else(|| anyhow!("Registry '{}' not found", namespace))?;
+      let mut components = registry.search_components(&params.query).await?;
+      if components.is_empty() {
+        continue;
+      }
+      components.sort_by(|a, b| a.name.cmp(&b.name));
+
+      registries.push(registry_entry(&namespace, &components, &installed));
+    }
+
+    Ok(json!({ "registries": registries }))
+  }
+
+  /// Fetch the full component JSON
+  pub async fn info(&self, params: InfoParams) -> Result<Value> {
+    let component = match &params.registry {
+      Some(namespace) => {
+        self
+          .registry_manager
+          .fetch_component(namespace, &params.component)
+          .await?
+      }
+      None => {
+        self
+          .registry_manager
+          .fetch_component_auto(&params.component)
+          .await?
+      }
+    };
+
+    let mut value = serde_json::to_value(&component)?;
+    value["registry"] = json!(component.registry);
+    value["installed"] = json!(is_installed(
+      &self.installed_components(),
+      component.registry.as_deref(),
+      &component.name
+    ));
+    Ok(value)
+  }
+
+  /// Install a component by running `uiget --yes add` in the project,
+  /// reporting each line of its output through `on_progress`
+  pub async fn add(
+    &self,
+    params: AddParams,
+    mut on_progress: impl FnMut(&str),
+  ) -> Result<AddOutcome> {
+    let mut command = tokio::process::Command::new(std::env::current_exe()?);
+    if let Some(config_path) = &self.config_path {
+      command.arg("--config").arg(config_path);
+    }
+    command.args(params.args());
+
+    let mut child = command
+      .current_dir(&self.project_dir)
+      .env("NO_COLOR", "1")
+      .stdin(Stdio::null())
+      .stdout(Stdio::piped())
+      .stderr(Stdio::piped())
+      .spawn()?;
+
+    // Drain stderr concurrently so a chatty child can't block on it
+    let mut stderr = child.stderr.take().expect("stderr is piped");
+    let stderr_task = tokio::spawn(async move {
+      let mut content = String::new();
+      let _ = stderr.read_to_string(&mut content).await;
+      content
+    });
+
+    let mut output = Vec::new();
+    let mut lines = BufReader::new(child.stdout.take().expect("stdout is piped")).lines();
+    while let Some(line) = lines.next_line().await? {
+      on_progress(&line);
+      output.push(line);
+    }
+
+    let status = child.wait().await?;
+    let stderr = stderr_task.await.unwrap_or_default();
+    output.extend(stderr.lines().map(|line| line.to_string()));
+
+    Ok(AddOutcome {
+      exit_code: status.code().unwrap_or(1),
+      output,
+    })
+  }
+}
+
+/// Installed components by registry namespace and name. Keys without a
+/// namespace directory have no namespace.
+type Installed = BTreeSet<(Option<String>, String)>;
+
+/// Whether the component `name` of the `namespace` registry is installed,
+/// under a plain key or its `<namespace>/<name>` one
+fn is_installed(installed: &Installed, namespace: Option<&str>, name: &str) -> bool {
+  installed.contains(&(None, name.to_string()))
+    || namespace
+      .is_some_and(|namespace| installed.contains(&(Some(namespace.to_string()), name.to_string())))
+}
+
+/// Describe a registry and its components for API responses
+fn registry_entry(namespace: &str, components: &[ComponentInfo], installed: &Installed) -> Value {
+  let components: Vec<Value> = components
+    .iter()
+    .map(|component| {
+      json!({
+        "name": component.name,
+        "type": component.component_type,
+        "category": component.group(),
+        "description": component.description,
+        "deprecated": component.is_deprecated(),
+        "replacedBy": component.replaced_by,
+        "installed": is_installed(installed, Some(namespace), &component.name),
+      })
+    })
+    .collect();
+
+  json!({ "namespace": namespace, "components": components })
+}
+
+/// Serve the API as newline-delimited JSON-RPC 2.0 over stdin/stdout until
+/// stdin is closed
+pub async fn serve_stdio(api: Api) -> Result<()> {
+  let mut lines = BufReader::new(tokio::io::stdin()).lines();
+
+  while let Some(line) = lines.next_line().await? {
+    if line.trim().is_empty() {
+      continue;
+    }
+
+    if let Some(response) = handle_line(&api, &line, &mut write_message).await {
+      write_message(response);
+    }
+  }
+
+  Ok(())
+}
+
+/// Write one JSON message per line to stdout
+pub(crate) fn write_message(message: Value) {
+  let mut stdout = std::io::stdout().lock();
+  let _ = writeln!(stdout, "{}", message);
+  let _ = stdout.flush();
+}
+
+/// Handle one request line, returning the response to send (none for
+/// notifications). Progress notifications are passed to `notify`.
+async fn handle_line(api: &Api, line: &str, notify: &mut impl FnMut(Value)) -> Option<Value> {
+  let request = match parse_request(line) {
+    Ok(request) => request,
+    Err(response) => return Some(response),
+  };
+
+  let id = request.id.clone();
+  let result = dispatch(api, request, notify).await;
+  respond(id, result)
+}
+
+/// Parse a JSON-RPC 2.0 request line, or build the error response to send
+/// back when it's malformed
+pub(crate) fn parse_request(line: &str) -> std::result::Result<RpcRequest, Value> {
+  let request: RpcRequest = match serde_json::from_str::<Value>(line) {
+    Err(e) => {
+      return Err(error_response(
+        Value::Null,
+        RpcError::new(PARSE_ERROR, e.to_string()),
+      ))
+    }
+    Ok(value) => serde_json::from_value(value)
+      .map_err(|e| error_response(Value::Null, RpcError::new(INVALID_REQUEST, e.to_string())))?,
+  };
+
+  if request.jsonrpc != "2.0" {
+    return Err(error_response(
+      request.id.unwrap_or(Value::Null),
+      RpcError::new(INVALID_REQUEST, "Only JSON-RPC 2.0 is supported"),
+    ));
+  }
+
+  Ok(request)
+}
+
+/// Build the response for a request. Requests without an id are
+/// notifications and get no response.
+pub(crate) fn respond(
+  id: Option<Value>,
+  result: std::result::Result<Value, RpcError>,
+) -> Option<Value> {
+  let id = id?;
+  Some(match result {
+    Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+    Err(error) => error_response(id, error),
+  })
+}
+
+async fn dispatch(
+  api: &Api,
+  request: RpcRequest,
+  notify: &mut impl FnMut(Value),
+) -> std::result::Result<Value, RpcError> {
+  let params = if request.params.is_null() {
+    json!({})
+  } else {
+    request.params
+  };
+
+  match request.method.as_str() {
+    "list" => api.list(parse_params(params)?).await.map_err(server_error),
+    "search" => api
+      .search(parse_params(params)?)
+      .await
+      .map_err(server_error),
+    "info" => api.info(parse_params(params)?).await.map_err(server_error),
+    "add" => {
+      let params: AddParams = parse_params(params)?;
+      let component = params.component.clone();
+      let id = request.id.unwrap_or(Value::Null);
+
+      let outcome = api
+        .add(params, |line| {
+          notify(json!({
+            "jsonrpc": "2.0",
+            "method": "progress",
+            "params": { "id": id, "message": line },
+          }))
+        })
+        .await
+        .map_err(server_error)?;
+
+      if outcome.success() {
+        Ok(json!({ "component": component, "installed": true }))
+      } else {
+        Err(RpcError {
+          code: SERVER_ERROR,
+          message: format!("Failed to install '{}'", component),
+          data: Some(json!({ "exitCode": outcome.exit_code, "output": outcome.output })),
+        })
+      }
+    }
+    method => Err(RpcError::new(
+      METHOD_NOT_FOUND,
+      format!("Method '{}' not found", method),
+    )),
+  }
+}
+
+pub(crate) fn parse_params<T: for<'de> Deserialize<'de>>(
+  params: Value,
+) -> std::result::Result<T, RpcError> {
+  serde_json::from_value(params).map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))
+}
+
+fn server_error(err: anyhow::Error) -> RpcError {
+  RpcError::new(SERVER_ERROR, err.to_string())
+}
+
+fn error_response(id: Value, error: RpcError) -> Value {
+  json!({ "jsonrpc": "2.0", "id": id, "error": error.to_json() })
+}
+
+#[cfg(test)]
+mod tests {
+  use clap::Parser;
+
+  use super::*;
+  use crate::cli::{Cli, Commands};
+
+  fn test_api() -> Api {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = Config {
+      registries: Default::default(),
+      ..Config::default()
+    };
+    Api::new(&config, temp_dir.path(), None).unwrap()
+  }
+
+  #[tokio::test]
+  async fn test_handle_line_errors() {
+    let api = test_api();
+    let mut notify = |_: Value| {};
+
+    let response = handle_line(&api, "{ nope", &mut notify).await.unwrap();
+    assert_eq!(response["error"]["code"], PARSE_ERROR);
+
+    let response = handle_line(
+      &api,
+      r#"{"jsonrpc":"2.0","id":1,"method":"explode"}"#,
+      &mut notify,
+    )
+    .await
+    .unwrap();
+    assert_eq!(response["id"], 1);
+    assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+
+    let response = handle_line(
+      &api,
+      r#"{"jsonrpc":"2.0","id":2,"method":"search","params":{}}"#,
+      &mut notify,
+    )
+    .await
+    .unwrap();
+    assert_eq!(response["error"]["code"], INVALID_PARAMS);
+
+    // Notifications get no response
+    assert!(
+      handle_line(&api, r#"{"jsonrpc":"2.0","method":"list"}"#, &mut notify)
+        .await
+        .is_none()
+    );
+  }
+
+  #[test]
+  fn test_add_args() {
+    let parse = |component: &str, registry: Option<&str>| {
+      let params = AddParams {
+        component: component.to_string(),
+        registry: registry.map(String::from),
+        force: true,
+        skip_deps: false,
+      };
+      let args = std::iter::once("uiget".to_string()).chain(params.args());
+      match Cli::try_parse_from(args).unwrap().command {
+        Commands::Add {
+          component,
+          all,
+          file,
+          registry,
+          registry_url,
+          force,
+          ..
+        } => (component, all, file, registry, registry_url, force),
+        _ => panic!("expected add"),
+      }
+    };
+
+    assert_eq!(
+      parse("button", Some("@acme")),
+      (
+        Some("button".to_string()),
+        false,
+        None,
+        Some("@acme".to_string()),
+        None,
+        1
+      )
+    );
+    // Flags sent as the component or registry stay values
+    for component in [
+      "--all",
+      "--file=/etc/passwd",
+      "--registry-url=https://evil.test",
+    ] {
+      let (parsed, all, file, _, registry_url, _) = parse(component, None);
+      assert_eq!(parsed.as_deref(), Some(component));
+      assert!(!all && file.is_none() && registry_url.is_none());
+    }
+    let (_, _, _, registry, registry_url, _) = parse("button", Some("--registry-url=x"));
+    assert_eq!(registry.as_deref(), Some("--registry-url=x"));
+    assert!(registry_url.is_none());
+  }
+
+  #[test]
+  fn test_installed_namespaced_components() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let mut config = Config {
+      registries: Default::default(),
+      namespace_dirs: Some(true),
+      ..Config::default()
+    };
+    config.set_registry(
+      "@acme".to_string(),
+      "https://acme.test/r/{name}.json".to_string(),
+    );
+    let mut lockfile = Lockfile::default();
+    for key in ["acme/button", "card"] {
+      lockfile.insert(key, Default::default());
+    }
+    lockfile.save(&Lockfile::path_for(temp_dir.path())).unwrap();
+    let api = Api::new(&config, temp_dir.path(), None).unwrap();
+
+    let components = [
+      ComponentInfo {
+        name: "button".to_string(),
+        ..Default::default()
+      },
+      ComponentInfo {
+        name: "card".to_string(),
+        ..Default::default()
+      },
+    ];
+    let entry = registry_entry("@acme", &components, &api.installed_components());
+    assert_eq!(entry["components"][0]["installed"], true);
+    assert_eq!(entry["components"][1]["installed"], true);
+
+    // Another registry's `button` isn't the one installed
+    let entry = registry_entry("@other", &components, &api.installed_components());
+    assert_eq!(entry["components"][0]["installed"], false);
+  }
+
+  #[tokio::test]
+  async fn test_list_without_registries() {
+    let api = test_api();
+    let mut notify = |_: Value| {};
+
+    let response = handle_line(
+      &api,
+      r#"{"jsonrpc":"2.0","id":"a","method":"list"}"#,
+      &mut notify,
+    )
+    .await
+    .unwrap();
+    assert_eq!(response["result"], json!({ "registries": [] }));
+  }
+}
@@ -0,0 +1,267 @@
+//! Per-project state kept in `.uiget/`: the lockfile, plus local caches
+//! such as how the package manager is run. The layout is versioned in
+//! `state.json`, and projects using an older layout are migrated the first
+//! time uiget writes to them; reads find the older layout as it is.
+
+use std::{
+  fs, io,
+  path::{Path, PathBuf},
+};
+
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  lockfile::LOCKFILE_NAME,
+  output::{status, warning},
+  paths,
+};
+
+/// Directory holding the project state, next to the configuration
+pub const STATE_DIR: &str = ".uiget";
+
+/// Current layout of the state directory
+const LAYOUT_VERSION: u32 = 1;
+
+/// File recording the layout version
+const STATE_FILE: &str = "state.json";
+
+/// Where the lockfile lived before the state directory existed
+const LEGACY_LOCKFILE_NAME: &str = "uiget-lock.json";
+
+/// How the package manager was run last time, so it isn't probed again
+const PACKAGE_MANAGER_CACHE: &str = "package-manager.json";
+
+/// Commit the lockfile and layout version, keep everything else local
+const GITIGNORE: &str = "\
+# Managed by uiget: the lockfile belongs in version control, the rest doesn't
+*
+!.gitignore
+!lock.json
+!state.json
+";
+
+/// Contents of `state.json`
+#[derive(Debug, Deserialize, Serialize)]
+struct StateFile {
+  version: u32,
+}
+
+/// Steps from each layout version to the next: `MIGRATIONS[n]` upgrades
+/// version n
+const MIGRATIONS: [fn(&ProjectState) -> io::Result<()>; LAYOUT_VERSION as usize] =
+  [move_legacy_lockfile];
+
+/// The `.uiget/` directory of a project
+#[derive(Debug, Clone)]
+pub struct ProjectState {
+  project_dir: PathBuf,
+  dir: PathBuf,
+}
+
+impl ProjectState {
+  /// State of the project in `project_dir`, as it is on disk
+  pub fn at(project_dir: &Path) -> Self {
+    Self {
+      project_dir: project_dir.to_path_buf(),
+      dir: project_dir.join(STATE_DIR),
+    }
+  }
+
+  /// State of the project in `project_dir`, migrating an older layout
+  /// first, before writing to it. Nothing is created for projects without
+  /// any state yet.
+  pub fn open(project_dir: &Path) -> Self {
+    let state = Self::at(project_dir);
+
+    let version = state.version();
+    if version > LAYOUT_VERSION {
+      warning!(
+        "Warning: {} was written by a newer uiget (layout {}, this one knows {}). Update uiget.",
+        state.dir.display(),
+        version,
+        LAYOUT_VERSION
+      );
+    } else if version < LAYOUT_VERSION && state.has_state() {
+      if let Err(e) = state.migrate(version) {
+        warning!(
+          "Warning: Couldn't migrate {} to the current layout: {}",
+          state.dir.display(),
+          e
+        );
+      }
+    }
+
+    state
+  }
+
+  /// Path of the lockfile: the legacy one in the project root while a
+  /// project that has only that one isn't migrated yet
+  pub fn lockfile_path(&self) -> PathBuf {
+    let lockfile = self.dir.join(LOCKFILE_NAME);
+    let legacy = self.project_dir.join(LEGACY_LOCKFILE_NAME);
+    if !lockfile.exists() && legacy.exists() {
+      return legacy;
+    }
+    lockfile
+  }
+
+  /// Path of the cache of how the package manager is run
+  pub fn package_manager_cache(&self) -> PathBuf {
+    self.dir.join(PACKAGE_MANAGER_CACHE)
+  }
+
+  /// Layout version on disk: 0 when the directory predates `state.json` or
+  /// doesn't exist
+  fn version(&self) -> u32 {
+    fs::read_to_string(self.dir.join(STATE_FILE))
+      .ok()
+      .and_then(|content| serde_json::from_str::<StateFile>(&content).ok())
+      .map_or(0, |state| state.version)
+  }
+
+  /// Whether the project has anything to migrate
+  fn has_state(&self) -> bool {
+    self.dir.exists() || self.project_dir.join(LEGACY_LOCKFILE_NAME).exists()
+  }
+
+  fn migrate(&self, from: u32) -> io::Result<()> {
+    for migration in &MIGRATIONS[from as usize..] {
+      migration(self)?;
+    }
+    create_dir(&self.dir)?;
+    write_version(&self.dir)
+  }
+}
+
+/// Path to write the lockfile read from `path` to. A legacy lockfile gets
+/// its project migrated first, so writes always use the current layout.
+pub fn writable_lockfile(path: &Path) -> PathBuf {
+  let Some(dir) = path.parent() else {
+    return path.to_path_buf();
+  };
+  if path
+    .file_name()
+    .is_some_and(|name| name == LEGACY_LOCKFILE_NAME)
+  {
+    return ProjectState::open(dir).dir.join(LOCKFILE_NAME);
+  }
+  if dir.file_name().is_some_and(|name| name == STATE_DIR) {
+    if let Some(project_dir) = dir.parent() {
+      ProjectState::open(project_dir);
+    }
+  }
+  path.to_path_buf()
+}
+
+/// Create a state directory with its `.gitignore` and `state.json`, unless
+/// they exist
+pub fn create_dir(dir: &Path) -> io::Result<()> {
+  fs::create_dir_all(dir)?;
+
+  let gitignore = dir.join(".gitignore");
+  if !gitignore.exists() {
+    paths::write_atomic(&gitignore, GITIGNORE)?;
+  }
+  if !dir.join(STATE_FILE).exists() {
+    write_version(dir)?;
+  }
+  Ok(())
+}
+
+/// Record the current layout version in `state.json`
+fn write_version(dir: &Path) -> io::Result<()> {
+  let mut content = serde_json::to_string_pretty(&StateFile {
+    version: LAYOUT_VERSION,
+  })?;
+  content.push('\n');
+  paths::write_atomic(&dir.join(STATE_FILE), content)
+}
+
+/// Layout 0 → 1: `uiget-lock.json` moves from the project root into
+/// `.uiget/lock.json`
+fn move_legacy_lockfile(state: &ProjectState) -> io::Result<()> {
+  let legacy = state.project_dir.join(LEGACY_LOCKFILE_NAME);
+  if !legacy.exists() {
+    return Ok(());
+  }
+
+  fs::create_dir_all(&state.dir)?;
+  let lockfile = state.dir.join(LOCKFILE_NAME);
+  if lockfile.exists() {
+    warning!(
+      "Warning: Both {} and {} exist; keeping {}",
+      LEGACY_LOCKFILE_NAME,
+      lockfile.display(),
+      lockfile.display()
+    );
+    return Ok(());
+  }
+
+  match fs::rename(&legacy, &lockfile) {
+    // Another uiget run moved it first
+    Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+    result => result?,
+  }
+  status!(
+    "{} Moved {} to {}/{}",
+    "→".blue(),
+    LEGACY_LOCKFILE_NAME,
+    STATE_DIR,
+    LOCKFILE_NAME
+  );
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_open_without_state() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let state = ProjectState::open(temp_dir.path());
+    assert_eq!(
+      state.package_manager_cache(),
+      temp_dir.path().join(".uiget/package-manager.json")
+    );
+    assert_eq!(
+      state.lockfile_path(),
+      temp_dir.path().join(".uiget/lock.json")
+    );
+    assert!(!&state.dir.exists());
+
+    create_dir(&state.dir).unwrap();
+    assert_eq!(state.version(), LAYOUT_VERSION);
+    assert!(fs::read_to_string(state.dir.join(".gitignore"))
+      .unwrap()
+      .contains("!lock.json"));
+  }
+
+  #[test]
+  fn test_migrate_legacy_lockfile() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let legacy = temp_dir.path().join(LEGACY_LOCKFILE_NAME);
+    fs::write(&legacy, "{\"version\":1,\"components\":{}}").unwrap();
+
+    // Reading finds the legacy lockfile where it is
+    let state = ProjectState::at(temp_dir.path());
+    assert_eq!(state.lockfile_path(), legacy);
+    assert!(!state.dir.exists());
+
+    // Writing migrates it first
+    let lockfile = writable_lockfile(&state.lockfile_path());
+    assert_eq!(lockfile, temp_dir.path().join(".uiget/lock.json"));
+    assert!(!legacy.exists());
+    assert_eq!(
+      fs::read_to_string(&lockfile).unwrap(),
+      "{\"version\":1,\"components\":{}}"
+    );
+    assert_eq!(state.version(), LAYOUT_VERSION);
+    assert_eq!(state.lockfile_path(), lockfile);
+
+    // Opening again finds nothing to do
+    let state = ProjectState::open(temp_dir.path());
+    assert!(state.lockfile_path().exists());
+  }
+}
@@ -0,0 +1,69 @@
+use std::{net::SocketAddr, sync::Arc};
+
+use tokio::{
+  io::{AsyncReadExt, AsyncWriteExt},
+  net::TcpListener,
+};
+
+/// Answer each request on a local port with what `respond` returns for it,
+/// given the request's number (from 0) and its text, then close the
+/// connection
+pub async fn serve_with(
+  respond: impl Fn(usize, &str) -> Vec<u8> + Send + Sync + 'static,
+) -> SocketAddr {
+  let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+  let address = listener.local_addr().unwrap();
+  let respond = Arc::new(respond);
+  tokio::spawn(async move {
+    let mut count = 0;
+    while let Ok((mut stream, _)) = listener.accept().await {
+      let respond = respond.clone();
+      let number = count;
+      count += 1;
+      tokio::spawn(async move {
+        let mut request = [0; 8192];
+        let read = stream.read(&mut request).await.unwrap_or(0);
+        let response = respond(number, &String::from_utf8_lossy(&request[..read]));
+        let _ = stream.write_all(&response).await;
+      });
+    }
+  });
+  address
+}
+
+/// Answer the n-th request with the n-th of `responses`; the last one
+/// repeats
+pub async fn serve_responses(responses: Vec<Vec<u8>>) -> SocketAddr {
+  serve_with(move |number, _| responses[number.min(responses.len() - 1)].clone()).await
+}
+
+/// Serve `files` by request path, with a 404 for any other path
+pub async fn serve_files(files: Vec<(&'static str, String)>) -> SocketAddr {
+  serve_with(move |_, request| {
+    match files
+      .iter()
+      .find(|(path, _)| *path == request_path(request))
+    {
+      Some((_, body)) => response("200 OK", body),
+      None => response("404 Not Found", ""),
+    }
+  })
+  .await
+}
+
+/// Path a request asks for, without the query
+pub fn request_path(request: &str) -> &str {
+  let target = request.split(' ').nth(1).unwrap_or_default();
+  target.split('?').next().unwrap_or_default()
+}
+
+/// Response with `status`, like "200 OK", and `body`
+pub fn response(status: &str, body: &str) -> Vec<u8> {
+  format!(
+    "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+    status,
+    body.len(),
+    body
+  )
+  .into_bytes()
+}
@@ -0,0 +1,1869 @@
+use std::{
+  collections::HashSet,
+  path::{Path, PathBuf},
+};
+
+use regex::Regex;
+use thiserror::Error;
+
+/// Multi-character operators, longest first. `>>` is deliberately missing so
+/// nested generics like `Array<Array<T>>` close one bracket at a time.
+const OPERATORS: &[&str] = &[
+  "...", "===", "!==", "=>", "==", "!=", "<=", ">=", "&&", "||", "??", "?.", "+=", "-=", "*=",
+  "/=", "++", "--", "**",
+];
+
+/// Keywords after which a `/` starts a regular expression
+const REGEX_PREFIX_KEYWORDS: &[&str] = &[
+  "return",
+  "typeof",
+  "case",
+  "do",
+  "else",
+  "in",
+  "of",
+  "new",
+  "delete",
+  "void",
+  "throw",
+  "instanceof",
+  "yield",
+  "await",
+];
+
+/// Class member modifiers that only exist in TypeScript
+const CLASS_MODIFIERS: &[&str] = &[
+  "public",
+  "private",
+  "protected",
+  "readonly",
+  "override",
+  "abstract",
+];
+
+/// Type operators that are followed by another type
+const TYPE_PREFIX_KEYWORDS: &[&str] = &[
+  "keyof", "typeof", "readonly", "unique", "infer", "new", "asserts", "abstract",
+];
+
+/// TypeScript that has no JavaScript equivalent without a compiler, found
+/// while stripping types. Nothing half-stripped is returned.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{0} can't be stripped to JavaScript")]
+pub struct Unsupported(pub &'static str);
+
+/// Declaration files carry only types and are skipped in JavaScript projects
+pub fn is_declaration_file(path: &Path) -> bool {
+  path
+    .file_name()
+    .and_then(|name| name.to_str())
+    .is_some_and(|name| {
+      [".d.ts", ".d.mts", ".d.cts"]
+        .iter()
+        .any(|ext| name.ends_with(ext))
+    })
+}
+
+/// Path of a TypeScript file after transpiling: `.ts` becomes `.js`,
+/// `.tsx` becomes `.jsx`. Other files keep their path.
+pub fn js_path(path: &Path) -> PathBuf {
+  let extension = match path.extension().and_then(|ext| ext.to_str()) {
+    Some("ts") => "js",
+    Some("tsx") => "jsx",
+    Some("mts") => "mjs",
+    Some("cts") => "cjs",
+    _ => return path.to_path_buf(),
+  };
+  path.with_extension(extension)
+}
+
+/// Strip TypeScript syntax from a file, chosen by its extension. TypeScript
+/// modules are stripped whole; Svelte and Vue files only in their
+/// `<script lang="ts">` blocks. Anything else is returned unchanged.
+pub fn strip_types(path: &Path, content: &str) -> Result<String, Unsupported> {
+  match path.extension().and_then(|ext| ext.to_str()) {
+    Some("ts" | "tsx" | "mts" | "cts") => strip_script(content),
+    Some("svelte" | "vue") => strip_script_blocks(content),
+    _ => Ok(content.to_string()),
+  }
+}
+
+/// Strip the `<script lang="ts">` blocks of a component file and drop their
+/// `lang` and `generics` attributes
+fn strip_script_blocks(content: &str) -> Result<String, Unsupported> {
+  let script = Regex::new(r"(?s)<script\b([^>]*)>(.*?)</script>").unwrap();
+  let lang = Regex::new(r#"\s+lang\s*=\s*["']ts["']"#).unwrap();
+  let generics = Regex::new(r#"\s+generics\s*=\s*("[^"]*"|'[^']*')"#).unwrap();
+
+  let mut error = None;
+  let stripped = script
+    .replace_all(content, |captures: &regex::Captures| {
+      let attributes = &captures[1];
+      if !lang.is_match(attributes) {
+        return captures[0].to_string();
+      }
+      let attributes = lang.replace(attributes, "");
+      let attributes = generics.replace(&attributes, "");
+      let body = strip_script(&captures[2]).unwrap_or_else(|unsupported| {
+        error.get_or_insert(unsupported);
+        String::new()
+      });
+      format!("<script{}>{}</script>", attributes, body)
+    })
+    .into_owned();
+  match error {
+    Some(unsupported) => Err(unsupported),
+    None => Ok(stripped),
+  }
+}
+
+/// Strip type annotations, type-only declarations and imports, casts,
+/// generic arguments, overload signatures and non-null assertions from
+/// TypeScript source. This is a token-level transform for the code
+/// registries ship, not a full compiler: enums, namespaces, parameter
+/// properties, other syntax that needs code generated for it and types
+/// followed by something no type can be are reported as unsupported rather
+/// than passed through.
+pub fn strip_script(source: &str) -> Result<String, Unsupported> {
+  let mut stripper = Stripper::new(source);
+  let end = stripper.tokens.len();
+  stripper.walk(0, end, Frame::Block);
+  match stripper.unsupported {
+    Some(unsupported) => Err(unsupported),
+    None => Ok(stripper.out.concat()),
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+  Space,
+  Comment,
+  Str,
+  Ident,
+  Num,
+  Punct,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Token<'a> {
+  kind: Kind,
+  text: &'a str,
+}
+
+/// What the tokens between a pair of brackets are
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Frame {
+  /// Statements: the file, a block or a function body
+  Block,
+  /// Members of a class body
+  Class,
+  /// Function parameters
+  Params,
+  /// `{ a, type B }` of an import or export
+  Specifiers,
+  /// Object literals, call arguments, arrays and anything else
+  Other,
+}
+
+/// Where a type being skipped ends
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TypeEnd {
+  /// `(a: T, b: T = x)`
+  Param,
+  /// `): T {` or `): T =>`
+  Return,
+  /// `const a: T = x`
+  Declarator,
+  /// `field: T;` in a class body
+  Member,
+  /// `type A = T;`
+  Alias,
+  /// `x as T`
+  Cast,
+}
+
+impl TypeEnd {
+  fn ends_at_newline(self) -> bool {
+    matches!(
+      self,
+      Self::Declarator | Self::Member | Self::Alias | Self::Cast
+    )
+  }
+
+  fn arrow_continues(self) -> bool {
+    !matches!(self, Self::Return | Self::Cast)
+  }
+
+  /// Punctuation that can directly follow a type ending this way
+  fn followers(self) -> &'static [&'static str] {
+    match self {
+      Self::Param => &[",", ")", "="],
+      Self::Return => &["{", "=>", ";"],
+      Self::Declarator => &["=", ";", ",", ")", "}"],
+      Self::Member => &["=", ";", "}"],
+      Self::Alias => &[";", "}"],
+      Self::Cast => &[],
+    }
+  }
+}
+
+struct Stripper<'a> {
+  tokens: Vec<Token<'a>>,
+  /// Text emitted for each token; removed tokens are empty
+  out: Vec<&'a str>,
+  /// Index of the closing bracket for each opening one
+  matching: Vec<Option<usize>>,
+  class_bodies: HashSet<usize>,
+  specifier_braces: HashSet<usize>,
+  /// The first construct found that can't be stripped
+  unsupported: Option<Unsupported>,
+}
+
+impl<'a> Stripper<'a> {
+  fn new(source: &'a str) -> Self {
+    let tokens = tokenize(source);
+    let out = tokens.iter().map(|token| token.text).collect();
+
+    let mut matching = vec![None; tokens.len()];
+    let mut open = Vec::new();
+    for (index, token) in tokens.iter().enumerate() {
+      if token.kind != Kind::Punct {
+        continue;
+      }
+      match token.text {
+        "(" | "[" | "{" => open.push(index),
+        ")" | "]" | "}" => {
+          if let Some(start) = open.pop() {
+            matching[start] = Some(index);
+          }
+        }
+        _ => {}
+      }
+    }
+
+    Self {
+      tokens,
+      out,
+      matching,
+      class_bodies: HashSet::new(),
+      specifier_braces: HashSet::new(),
+      unsupported: None,
+    }
+  }
+
+  /// Record syntax that can't be stripped
+  fn unsupported(&mut self, construct: &'static str) {
+    self.unsupported.get_or_insert(Unsupported(construct));
+  }
+
+  fn text(&self, index: usize) -> &'a str {
+    self.tokens[index].text
+  }
+
+  fn is_punct(&self, index: usize, text: &str) -> bool {
+    self.tokens[index].kind == Kind::Punct && self.tokens[index].text == text
+  }
+
+  fn is_ident(&self, index: usize, text: &str) -> bool {
+    self.tokens[index].kind == Kind::Ident && self.tokens[index].text == text
+  }
+
+  fn is_sig(&self, index: usize) -> bool {
+    !matches!(self.tokens[index].kind, Kind::Space | Kind::Comment) && !self.out[index].is_empty()
+  }
+
+  fn next_sig(&self, index: usize) -> Option<usize> {
+    (index + 1..self.tokens.len()).find(|&next| self.is_sig(next))
+  }
+
+  fn prev_sig(&self, index: usize) -> Option<usize> {
+    (0..index).rev().find(|&prev| self.is_sig(prev))
+  }
+
+  fn next_is(&self, index: usize, text: &str) -> bool {
+    self
+      .next_sig(index)
+      .is_some_and(|next| self.text(next) == text)
+  }
+
+  fn remove(&mut self, start: usize, end: usize) {
+    for text in &mut self.out[start..=end] {
+      *text = "";
+    }
+  }
+
+  /// Remove a token and the whitespace before it
+  fn remove_with_space_before(&mut self, start: usize, end: usize) {
+    let start = match start.checked_sub(1) {
+      Some(prev) if self.tokens[prev].kind == Kind::Space => prev,
+      _ => start,
+    };
+    self.remove(start, end);
+  }
+
+  /// Remove a token and the whitespace after it
+  fn remove_with_space_after(&mut self, index: usize) {
+    let end = match self.tokens.get(index + 1) {
+      Some(next) if next.kind == Kind::Space => index + 1,
+      _ => index,
+    };
+    self.remove(index, end);
+  }
+
+  /// Remove a whole statement. When it sat on its own lines, the line
+  /// breaks after it go too, keeping the next line's indentation.
+  fn remove_statement(&mut self, start: usize, end: usize) {
+    self.remove(start, end);
+
+    let before = start
+      .checked_sub(1)
+      .filter(|&prev| self.tokens[prev].kind == Kind::Space);
+    let own_line = match before {
+      Some(prev) => self.tokens[prev].text.contains('\n'),
+      None => start == 0,
+    };
+    if !own_line {
+      return;
+    }
+
+    if let Some(prev) = before {
+      let text = self.out[prev];
+      self.out[prev] = &text[..text.rfind('\n').map_or(0, |newline| newline + 1)];
+    }
+    if let Some(next) = self
+      .tokens
+      .get(end + 1)
+      .filter(|next| next.kind == Kind::Space)
+    {
+      if let Some(newline) = next.text.rfind('\n') {
+        self.out[end + 1] = &next.text[newline + 1..];
+      }
+    }
+  }
+
+  /// Whether a token begins a statement: it follows `;`, a brace, or a
+  /// line break
+  fn at_statement_start(&self, index: usize) -> bool {
+    let Some(prev) = self.prev_sig(index) else {
+      return true;
+    };
+    if matches!(self.text(prev), ";" | "{" | "}") && self.tokens[prev].kind == Kind::Punct {
+      return true;
+    }
+    (prev + 1..index).any(|between| {
+      self.tokens[between].kind == Kind::Space && self.tokens[between].text.contains('\n')
+    })
+  }
+
+  fn walk(&mut self, start: usize, end: usize, frame: Frame) {
+    let mut ternary = 0usize;
+    // In the declarator list of `const`/`let`/`var`, where `,` starts the
+    // next declarator
+    let mut declaring = false;
+    let mut index = start;
+
+    while index < end {
+      if !self.is_sig(index) {
+        index += 1;
+        continue;
+      }
+
+      if frame == Frame::Block && self.at_statement_start(index) {
+        declaring &= self
+          .prev_sig(index)
+          .is_some_and(|prev| self.is_punct(prev, ","));
+        if let Some(next) = self.statement(index) {
+          index = next;
+          continue;
+        }
+      }
+      if frame == Frame::Params && self.parameter_property(index) {
+        self.unsupported("A constructor parameter property");
+      }
+      if frame == Frame::Class {
+        self.class_modifier(index);
+        if !self.is_sig(index) {
+          index += 1;
+          continue;
+        }
+        if let Some(end) = self.index_signature(index) {
+          self.remove_statement(index, end);
+          index = end + 1;
+          continue;
+        }
+      }
+
+      let token = self.tokens[index];
+      match (token.kind, token.text) {
+        (Kind::Punct, "(" | "[" | "{") => {
+          let Some(close) = self.matching[index] else {
+            index += 1;
+            continue;
+          };
+          let inner = match token.text {
+            "(" => self.paren_frame(index, close, ternary),
+            "{" => self.brace_frame(index),
+            _ => Frame::Other,
+          };
+          self.walk(index + 1, close, inner);
+          if inner == Frame::Params {
+            self.return_type(close);
+            let body = self
+              .next_sig(close)
+              .is_some_and(|next| matches!(self.text(next), "{" | "=>"));
+            if frame == Frame::Class && !body {
+              self.unsupported("A method signature without a body");
+            }
+          }
+          index = close + 1;
+          continue;
+        }
+        (Kind::Punct, "?") if !self.next_is(index, ":") => ternary += 1,
+        (Kind::Punct, ":") => {
+          if ternary > 0 {
+            ternary -= 1;
+          } else if frame == Frame::Params && self.this_parameter(index) {
+            // Removed with its type and comma
+          } else if matches!(frame, Frame::Params | Frame::Class) {
+            let end = if frame == Frame::Params {
+              TypeEnd::Param
+            } else {
+              TypeEnd::Member
+            };
+            self.annotation(index, end);
+          }
+        }
+        (Kind::Punct, ";") => {
+          ternary = 0;
+          declaring = false;
+        }
+        (Kind::Punct, ",") if declaring && ternary == 0 => self.declarator(index),
+        (Kind::Punct, "!") => self.non_null(index),
+        (Kind::Punct, "<") => self.type_arguments(index),
+        (Kind::Ident, "as" | "satisfies") if frame != Frame::Specifiers => self.cast(index),
+        (Kind::Ident, "class") => self.class_heading(index),
+        (Kind::Ident, "const" | "let" | "var") => {
+          self.declarator(index);
+          declaring = true;
+        }
+        _ => {}
+      }
+      index += 1;
+    }
+  }
+
+  /// Type-only statements and import/export specifiers. Returns the index
+  /// to continue from when the statement was removed.
+  fn statement(&mut self, index: usize) -> Option<usize> {
+    match self.text(index) {
+      "export" => {
+        let next = self.next_sig(index)?;
+        match self.text(next) {
+          "type"
+            if self
+              .next_sig(next)
+              .is_some_and(|after| matches!(self.text(after), "{" | "*")) =>
+          {
+            let end = self.module_statement_end(next)?;
+            self.remove_statement(index, end);
+            Some(end + 1)
+          }
+          "type" | "interface" | "declare" => {
+            let end = self.declaration(next)?;
+            self.remove_statement(index, end);
+            Some(end + 1)
+          }
+          "function" => {
+            let end = self.overload_end(next)?;
+            self.remove_statement(index, end);
+            Some(end + 1)
+          }
+          "enum" | "const" | "namespace" | "module" => {
+            self.unsupported_declaration(next);
+            None
+          }
+          "{" => {
+            self.strip_specifiers(index, next);
+            None
+          }
+          _ => None,
+        }
+      }
+      "import" => {
+        let next = self.next_sig(index)?;
+        let type_only = self.is_ident(next, "type")
+          && self
+            .next_sig(next)
+            .is_some_and(|after| !matches!(self.text(after), "," | "from"));
+        if type_only {
+          let end = self.module_statement_end(index)?;
+          self.remove_statement(index, end);
+          return Some(end + 1);
+        }
+
+        let mut brace = next;
+        while self.tokens[brace].kind != Kind::Str && !self.is_ident(brace, "from") {
+          if self.is_punct(brace, "{") {
+            self.strip_specifiers(index, brace);
+            break;
+          }
+          if self.is_punct(brace, "(") || self.is_punct(brace, ".") || self.is_punct(brace, ";") {
+            break;
+          }
+          brace = self.next_sig(brace)?;
+        }
+        None
+      }
+      "type" | "interface" | "declare" => {
+        let end = self.declaration(index)?;
+        self.remove_statement(index, end);
+        Some(end + 1)
+      }
+      "abstract" if self.next_is(index, "class") => {
+        self.remove_with_space_after(index);
+        Some(index + 1)
+      }
+      "function" => {
+        let end = self.overload_end(index)?;
+        self.remove_statement(index, end);
+        Some(end + 1)
+      }
+      "enum" | "const" | "namespace" | "module" => {
+        self.unsupported_declaration(index);
+        None
+      }
+      _ => None,
+    }
+  }
+
+  /// Report `enum`, `const enum`, `namespace` and `module` declarations
+  /// starting at `index`, which need code generated for them
+  fn unsupported_declaration(&mut self, index: usize) {
+    let mut keyword = index;
+    if self.is_ident(keyword, "const") {
+      match self.next_sig(keyword) {
+        Some(next) if self.is_ident(next, "enum") => keyword = next,
+        _ => return,
+      }
+    }
+    let Some(name) = self.next_sig(keyword) else {
+      return;
+    };
+    match self.text(keyword) {
+      "enum" if self.tokens[name].kind == Kind::Ident => self.unsupported("An enum"),
+      "namespace" | "module"
+        if matches!(self.tokens[name].kind, Kind::Ident | Kind::Str)
+          && self
+            .next_sig(name)
+            .is_some_and(|next| matches!(self.text(next), "{" | ".")) =>
+      {
+        self.unsupported("A namespace")
+      }
+      _ => {}
+    }
+  }
+
+  /// Last token of a function overload signature, `function f(a: T): R;`,
+  /// or None when the function at `keyword` has a body
+  fn overload_end(&mut self, keyword: usize) -> Option<usize> {
+    let mut index = self.next_sig(keyword)?;
+    if self.is_punct(index, "*") {
+      index = self.next_sig(index)?;
+    }
+    if self.tokens[index].kind != Kind::Ident {
+      return None;
+    }
+    index = self.next_sig(index)?;
+    if self.is_punct(index, "<") {
+      index = self.next_sig(self.angle_end(index)?)?;
+    }
+    if !self.is_punct(index, "(") {
+      return None;
+    }
+    let mut end = self.matching[index]?;
+    if self.next_is(end, ":") {
+      end = self.type_end(self.next_sig(end)? + 1, TypeEnd::Return)?;
+    }
+    match self.next_sig(end) {
+      Some(next) if self.is_punct(next, "{") => None,
+      _ => Some(self.with_semicolon(end)),
+    }
+  }
+
+  /// `this: T` as the first parameter only types `this`: remove it with
+  /// the comma after it
+  fn this_parameter(&mut self, colon: usize) -> bool {
+    let Some(name) = self.prev_sig(colon) else {
+      return false;
+    };
+    let first = self
+      .prev_sig(name)
+      .is_some_and(|open| self.is_punct(open, "("));
+    if !self.is_ident(name, "this") || !first {
+      return false;
+    }
+    let Some(end) = self.type_end(colon + 1, TypeEnd::Param) else {
+      return false;
+    };
+    let end = match self.next_sig(end) {
+      Some(comma) if self.is_punct(comma, ",") => {
+        let mut end = comma;
+        while self
+          .tokens
+          .get(end + 1)
+          .is_some_and(|next| next.kind == Kind::Space)
+        {
+          end += 1;
+        }
+        end
+      }
+      _ => end,
+    };
+    self.remove(name, end);
+    true
+  }
+
+  /// Whether `index` is an access modifier declaring a constructor
+  /// parameter property, `constructor(private value: T)`
+  fn parameter_property(&self, index: usize) -> bool {
+    self.tokens[index].kind == Kind::Ident
+      && CLASS_MODIFIERS.contains(&self.text(index))
+      && self
+        .prev_sig(index)
+        .is_some_and(|prev| matches!(self.text(prev), "(" | ","))
+      && self.next_sig(index).is_some_and(|next| {
+        self.tokens[next].kind == Kind::Ident || matches!(self.text(next), "{" | "[")
+      })
+  }
+
+  /// Last token of a `type`, `interface` or `declare` declaration starting
+  /// at `index`, or None when the keyword is used as a plain identifier
+  fn declaration(&mut self, index: usize) -> Option<usize> {
+    let name = self.next_sig(index)?;
+    match self.text(index) {
+      "type" => {
+        if self.tokens[name].kind != Kind::Ident {
+          return None;
+        }
+        let mut equals = self.next_sig(name)?;
+        if self.is_punct(equals, "<") {
+          equals = self.next_sig(self.angle_end(equals)?)?;
+        }
+        if !self.is_punct(equals, "=") {
+          return None;
+        }
+        let end = self.type_end(equals + 1, TypeEnd::Alias)?;
+        Some(self.with_semicolon(end))
+      }
+      "interface" => {
+        if self.tokens[name].kind != Kind::Ident {
+          return None;
+        }
+        let body = (name..self.tokens.len()).find(|&brace| self.is_punct(brace, "{"))?;
+        Some(self.with_semicolon(self.matching[body]?))
+      }
+      _ => {
+        if matches!(self.text(name), "global" | "module" | "namespace") {
+          let body = (name..self.tokens.len()).find(|&brace| self.is_punct(brace, "{"))?;
+          return self.matching[body];
+        }
+        let mut end = name;
+        let mut next = Some(name);
+        while let Some(current) = next {
+          if self.is_punct(current, ";") {
+            return Some(current);
+          }
+          let newline = (end + 1..current).any(|between| self.tokens[between].text.contains('\n'));
+          if newline && current != name {
+            return Some(end);
+          }
+          end = match self.text(current) {
+            "(" | "[" | "{" => self.matching[current]?,
+            _ => current,
+          };
+          next = self.next_sig(end);
+        }
+        Some(end)
+      }
+    }
+  }
+
+  /// Extend a statement over a following `;`
+  fn with_semicolon(&self, end: usize) -> usize {
+    match self.next_sig(end) {
+      Some(next) if self.is_punct(next, ";") => next,
+      _ => end,
+    }
+  }
+
+  /// Last token of an import or export statement: its module string, or
+  /// the closing brace of `export { ... }` without `from`
+  fn module_statement_end(&self, start: usize) -> Option<usize> {
+    let mut index = start;
+    loop {
+      index = self.next_sig(index)?;
+      match self.tokens[index].kind {
+        Kind::Str => return Some(self.with_semicolon(index)),
+        Kind::Punct if self.text(index) == "{" => {
+          index = self.matching[index]?;
+          if !self.next_is(index, "from") {
+            return Some(self.with_semicolon(index));
+          }
+        }
+        Kind::Punct if self.text(index) == ";" => return Some(index),
+        _ => {}
+      }
+    }
+  }
+
+  /// Drop `type X` specifiers from `import { ... }` / `export { ... }`, and
+  /// the whole statement when nothing is left
+  fn strip_specifiers(&mut self, statement: usize, brace: usize) {
+    let Some(close) = self.matching[brace] else {
+      return;
+    };
+    self.specifier_braces.insert(brace);
+
+    // (first token, last token, comma after) of each specifier
+    let mut specifiers = Vec::new();
+    let mut first = None;
+    let mut last = None;
+    for index in brace + 1..close {
+      if !self.is_sig(index) {
+        continue;
+      }
+      if self.is_punct(index, ",") {
+        if let (Some(first), Some(last)) = (first.take(), last.take()) {
+          specifiers.push((first, last, Some(index)));
+        }
+      } else {
+        first.get_or_insert(index);
+        last = Some(index);
+      }
+    }
+    if let (Some(first), Some(last)) = (first, last) {
+      specifiers.push((first, last, None));
+    }
+
+    let is_type = |stripper: &Self, first: usize, last: usize| {
+      stripper.is_ident(first, "type")
+        && first != last
+        && stripper
+          .next_sig(first)
+          .is_some_and(|name| !stripper.is_ident(name, "as"))
+    };
+    let kept = specifiers
+      .iter()
+      .filter(|&&(first, last, _)| !is_type(self, first, last))
+      .count();
+
+    if kept == 0 && !specifiers.is_empty() {
+      let prev = self.prev_sig(brace);
+      if prev.is_some_and(|prev| self.is_punct(prev, ",")) {
+        // `import Default, { type A } from "x"`
+        self.remove(prev.unwrap(), close);
+      } else if let Some(end) = self.module_statement_end(statement) {
+        self.remove_statement(statement, end);
+      }
+      return;
+    }
+
+    let mut previous_end = None;
+    for &(first, last, comma) in &specifiers {
+      if !is_type(self, first, last) {
+        previous_end = Some(comma.unwrap_or(last));
+        continue;
+      }
+      match comma {
+        Some(comma) => {
+          let mut end = comma;
+          while end + 1 < close && self.tokens[end + 1].kind == Kind::Space {
+            end += 1;
+          }
+          self.remove(first, end);
+        }
+        None => {
+          // The last specifier takes the comma before it
+          let start = previous_end.map_or(first, |end| end);
+          self.remove(start, last);
+        }
+      }
+    }
+  }
+
+  fn paren_frame(&self, open: usize, close: usize, ternary: usize) -> Frame {
+    let control = self.prev_sig(open).is_some_and(|prev| {
+      self.tokens[prev].kind == Kind::Ident
+        && matches!(
+          self.text(prev),
+          "if" | "for" | "while" | "switch" | "with" | "case" | "return" | "typeof" | "await"
+        )
+    });
+    if control {
+      return Frame::Other;
+    }
+
+    match self.next_sig(close).map(|next| self.text(next)) {
+      Some("=>") | Some("{") => Frame::Params,
+      Some(":") if ternary == 0 || self.arrow_in_conditional(close) => Frame::Params,
+      _ => Frame::Other,
+    }
+  }
+
+  /// Whether `): T => ...` inside the true branch of a conditional is an
+  /// arrow function with a return type rather than the conditional's `:`.
+  /// Like TypeScript, it is when the conditional's own `:` follows.
+  fn arrow_in_conditional(&self, close: usize) -> bool {
+    let Some(end) = self
+      .next_sig(close)
+      .and_then(|colon| self.skip_type(colon + 1, TypeEnd::Return))
+    else {
+      return false;
+    };
+    let Some(arrow) = self
+      .next_sig(end)
+      .filter(|&arrow| self.is_punct(arrow, "=>"))
+    else {
+      return false;
+    };
+
+    let mut nested = 0usize;
+    let mut index = arrow;
+    while let Some(next) = self.next_sig(index) {
+      index = next;
+      if self.tokens[index].kind != Kind::Punct {
+        continue;
+      }
+      match self.text(index) {
+        "(" | "[" | "{" => match self.matching[index] {
+          Some(close) => index = close,
+          None => return false,
+        },
+        "?" => nested += 1,
+        ":" if nested == 0 => return true,
+        ":" => nested -= 1,
+        ";" | "," | ")" | "]" | "}" => return false,
+        _ => {}
+      }
+    }
+    false
+  }
+
+  fn brace_frame(&self, open: usize) -> Frame {
+    if self.class_bodies.contains(&open) {
+      return Frame::Class;
+    }
+    if self.specifier_braces.contains(&open) {
+      return Frame::Specifiers;
+    }
+    match self.prev_sig(open) {
+      None => Frame::Block,
+      Some(prev) => match (self.tokens[prev].kind, self.text(prev)) {
+        (Kind::Punct, ")" | "=>" | ";" | "{" | "}") => Frame::Block,
+        (Kind::Ident, "else" | "try" | "finally" | "do") => Frame::Block,
+        _ => Frame::Other,
+      },
+    }
+  }
+
+  /// Last token of a class index signature, `[key: string]: T;`, starting
+  /// at `open`
+  fn index_signature(&mut self, open: usize) -> Option<usize> {
+    if !self.is_punct(open, "[") {
+      return None;
+    }
+    let close = self.matching[open]?;
+    let key = self.next_sig(open)?;
+    let key_colon = self.next_sig(key)?;
+    let colon = self.next_sig(close)?;
+    if self.tokens[key].kind != Kind::Ident
+      || !self.is_punct(key_colon, ":")
+      || !self.is_punct(colon, ":")
+    {
+      return None;
+    }
+    let end = self.type_end(colon + 1, TypeEnd::Member)?;
+    Some(self.with_semicolon(end))
+  }
+
+  /// `: T` after a parameter or class field, with its `?`/`!` marker
+  fn annotation(&mut self, colon: usize, end: TypeEnd) {
+    let Some(prev) = self.prev_sig(colon) else {
+      return;
+    };
+    let target = match (self.tokens[prev].kind, self.text(prev)) {
+      (Kind::Punct, "?" | "!") => {
+        let Some(name) = self.prev_sig(prev) else {
+          return;
+        };
+        self.tokens[name].kind == Kind::Ident || self.is_punct(name, "]")
+      }
+      (Kind::Ident, _) => true,
+      (Kind::Punct, "}") => end == TypeEnd::Param,
+      (Kind::Punct, "]") => matches!(end, TypeEnd::Param | TypeEnd::Member),
+      _ => false,
+    };
+    if !target {
+      return;
+    }
+
+    let Some(type_end) = self.type_end(colon + 1, end) else {
+      return;
+    };
+    if matches!(self.text(prev), "?" | "!") && self.tokens[prev].kind == Kind::Punct {
+      self.remove(prev, type_end);
+    } else {
+      self.remove(colon, type_end);
+    }
+  }
+
+  /// `): T` after a parameter list
+  fn return_type(&mut self, close: usize) {
+    let Some(colon) = self
+      .next_sig(close)
+      .filter(|&next| self.is_punct(next, ":"))
+    else {
+      return;
+    };
+    if let Some(end) = self.type_end(colon + 1, TypeEnd::Return) {
+      self.remove(close + 1, end);
+    }
+  }
+
+  /// `: T` after the binding that follows `const`/`let`/`var` or the `,`
+  /// before another declarator
+  fn declarator(&mut self, keyword: usize) {
+    let Some(binding) = self.next_sig(keyword) else {
+      return;
+    };
+    let binding_end = match (self.tokens[binding].kind, self.text(binding)) {
+      (Kind::Ident, _) => binding,
+      (Kind::Punct, "{" | "[") => match self.matching[binding] {
+        Some(close) => close,
+        None => return,
+      },
+      _ => return,
+    };
+
+    let Some(mut colon) = self.next_sig(binding_end) else {
+      return;
+    };
+    // Definite assignment: `let a!: T`
+    if self.is_punct(colon, "!") {
+      match self.next_sig(colon) {
+        Some(next) => colon = next,
+        None => return,
+      }
+    }
+    if !self.is_punct(colon, ":") {
+      return;
+    }
+    if let Some(end) = self.type_end(colon + 1, TypeEnd::Declarator) {
+      self.remove(binding_end + 1, end);
+    }
+  }
+
+  /// `x as T` and `x satisfies T`
+  fn cast(&mut self, keyword: usize) {
+    let Some(prev) = self.prev_sig(keyword) else {
+      return;
+    };
+    let operand = match self.tokens[prev].kind {
+      Kind::Ident | Kind::Str | Kind::Num => true,
+      Kind::Punct => matches!(self.text(prev), ")" | "]" | "}"),
+      _ => false,
+    };
+    // `as="a"` is a JSX attribute, `* as ns` a namespace import
+    if !operand || self.next_is(keyword, "=") {
+      return;
+    }
+    if let Some(end) = self.skip_type(keyword + 1, TypeEnd::Cast) {
+      self.remove_with_space_before(keyword, end);
+    }
+  }
+
+  /// Non-null assertion: `value!.field`, `a! + b!`. Right after an operand
+  /// a `!` can't be a logical not, unless the `)` ends a condition.
+  fn non_null(&mut self, index: usize) {
+    let Some(prev) = index.checked_sub(1) else {
+      return;
+    };
+    let after_operand = match (self.tokens[prev].kind, self.text(prev)) {
+      (Kind::Ident, keyword) => !REGEX_PREFIX_KEYWORDS.contains(&keyword),
+      (Kind::Punct, "]") => true,
+      (Kind::Punct, ")") => !self.closes_condition(prev),
+      _ => false,
+    };
+    if after_operand {
+      self.remove(index, index);
+    }
+  }
+
+  /// Whether the `)` at `close` ends the condition of `if`, `while`, `for`
+  /// or `with`
+  fn closes_condition(&self, close: usize) -> bool {
+    (0..close)
+      .rev()
+      .find(|&open| self.matching[open] == Some(close))
+      .and_then(|open| self.prev_sig(open))
+      .is_some_and(|prev| {
+        self.tokens[prev].kind == Kind::Ident
+          && matches!(self.text(prev), "if" | "while" | "for" | "with")
+      })
+  }
+
+  /// Generic arguments of calls, `useState<string>(...)`, and type
+  /// parameters of arrow functions, `<T,>(value: T) => ...`
+  fn type_arguments(&mut self, open: usize) {
+    let Some(close) = self.angle_end(open) else {
+      return;
+    };
+    if !self.next_is(close, "(") {
+      return;
+    }
+
+    // `useState<string>(...)`: type arguments follow the callee directly
+    // and, unlike type parameters, have no defaults
+    let attached = open.checked_sub(1).is_some_and(|prev| {
+      self.tokens[prev].kind == Kind::Ident || self.is_punct(prev, ")") || self.is_punct(prev, "]")
+    }) && !(open + 1..close).any(|index| self.is_punct(index, "="));
+    // `function f<T = X>(...)` and `method<T = X>(...) {`
+    let declared = open
+      .checked_sub(1)
+      .filter(|&name| self.tokens[name].kind == Kind::Ident)
+      .is_some_and(|name| {
+        let function = self.prev_sig(name).is_some_and(|prev| {
+          self.is_ident(prev, "function")
+            || (self.is_punct(prev, "*")
+              && self
+                .prev_sig(prev)
+                .is_some_and(|keyword| self.is_ident(keyword, "function")))
+        });
+        let body = self
+          .next_sig(close)
+          .and_then(|paren| self.matching[paren])
+          .is_some_and(|params_end| self.next_is(params_end, "{"));
+        function || body
+      });
+    // `<T>(value: T) => ...` stands where an expression starts; after an
+    // operand, `b < c, d = e > (f)` compares
+    let after_operand =
+      self
+        .prev_sig(open)
+        .is_some_and(|prev| match (self.tokens[prev].kind, self.text(prev)) {
+          (Kind::Ident, "async") => false,
+          (Kind::Ident, keyword) => !REGEX_PREFIX_KEYWORDS.contains(&keyword),
+          (Kind::Str | Kind::Num, _) => true,
+          (Kind::Punct, text) => matches!(text, ")" | "]" | "}"),
+          _ => false,
+        });
+    let arrow_parameters = !after_operand
+      && self.next_sig(close).is_some_and(|paren| {
+        self.matching[paren].is_some_and(|params_end| {
+          self
+            .next_sig(params_end)
+            .is_some_and(|next| matches!(self.text(next), "=>" | ":"))
+        })
+      });
+    if attached || declared || arrow_parameters {
+      self.remove(open, close);
+    }
+  }
+
+  /// Matching `>` of a `<` when everything between looks like a type
+  fn angle_end(&self, open: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    for index in open..self.tokens.len() {
+      let token = self.tokens[index];
+      match token.kind {
+        Kind::Space | Kind::Comment | Kind::Ident | Kind::Str | Kind::Num => {}
+        Kind::Punct => match token.text {
+          "<" => depth += 1,
+          ">" => {
+            depth -= 1;
+            if depth == 0 {
+              return Some(index);
+            }
+          }
+          "." | "," | "|" | "&" | "[" | "]" | "(" | ")" | "{" | "}" | "?" | ":" | "=>" | "="
+          | ";" | "..." => {}
+          _ => return None,
+        },
+      }
+    }
+    None
+  }
+
+  /// `class A<T> extends B<T> implements C {`: drop the type parameters
+  /// and `implements`, and remember the body as a class
+  fn class_heading(&mut self, keyword: usize) {
+    let mut index = keyword;
+    let mut implements = None;
+    loop {
+      let Some(next) = self.next_sig(index) else {
+        return;
+      };
+      index = next;
+      match self.text(index) {
+        "{" if self.tokens[index].kind == Kind::Punct => break,
+        "<" => {
+          let Some(close) = self.angle_end(index) else {
+            return;
+          };
+          self.remove(index, close);
+          index = close;
+        }
+        "(" | "[" => match self.matching[index] {
+          Some(close) => index = close,
+          None => return,
+        },
+        "implements" => implements = Some(index),
+        ";" | "}" | ")" => return,
+        _ => {}
+      }
+    }
+
+    if let Some(implements) = implements {
+      if let Some(last) = self.prev_sig(index) {
+        self.remove_with_space_before(implements, last);
+      }
+    }
+    self.class_bodies.insert(index);
+  }
+
+  /// Access modifiers at the start of a class member
+  fn class_modifier(&mut self, index: usize) {
+    if self.tokens[index].kind != Kind::Ident || !CLASS_MODIFIERS.contains(&self.text(index)) {
+      return;
+    }
+    let member_start = self.prev_sig(index).is_none_or(|prev| {
+      matches!(self.text(prev), "{" | "}" | ";" | "static")
+        || CLASS_MODIFIERS.contains(&self.text(prev))
+        || self.at_statement_start(index)
+    });
+    let modifies = self
+      .next_sig(index)
+      .is_some_and(|next| self.tokens[next].kind == Kind::Ident || self.is_punct(next, "["));
+    if member_start && modifies {
+      self.remove_with_space_after(index);
+    }
+  }
+
+  /// Last token of the type starting after `start`, recording it as
+  /// unsupported when what follows can't follow a type there: the type
+  /// was not understood, and stripping it would leave part of it behind
+  fn type_end(&mut self, start: usize, end: TypeEnd) -> Option<usize> {
+    let last = self.skip_type(start, end)?;
+    let follows = match self.next_sig(last) {
+      None => true,
+      Some(next) => {
+        end == TypeEnd::Cast
+          || (last + 1..next).any(|between| self.tokens[between].text.contains('\n'))
+          || (self.tokens[next].kind == Kind::Punct && end.followers().contains(&self.text(next)))
+      }
+    };
+    if !follows {
+      self.unsupported("An unrecognized type");
+    }
+    Some(last)
+  }
+
+  /// Last token of the type starting after `start`
+  fn skip_type(&self, start: usize, end: TypeEnd) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut expect_operand = true;
+    // `<T>(value: T) => T`: type parameters are followed by the parameters
+    let mut type_parameters = false;
+    let mut conditional = 0usize;
+    let mut last = None;
+
+    for index in start..self.tokens.len() {
+      let token = self.tokens[index];
+      match token.kind {
+        Kind::Space => {
+          if depth == 0 && !expect_operand && end.ends_at_newline() && token.text.contains('\n') {
+            let continues = self
+              .next_sig(index)
+              .is_some_and(|next| matches!(self.text(next), "|" | "&" | "."));
+            if !continues {
+              break;
+            }
+          }
+          continue;
+        }
+        Kind::Comment => continue,
+        _ => {}
+      }
+
+      let punct = token.kind == Kind::Punct;
+      if depth > 0 {
+        if punct {
+          match token.text {
+            "(" | "[" | "{" | "<" => depth += 1,
+            ")" | "]" | "}" | ">" => depth -= 1,
+            _ => {}
+          }
+        } else {
+          depth = depth.saturating_add_signed(template_nesting(&token));
+        }
+        if depth == 0 {
+          expect_operand = type_parameters;
+          type_parameters = false;
+        }
+        last = Some(index);
+        continue;
+      }
+
+      if expect_operand {
+        match (token.kind, token.text) {
+          (Kind::Punct, "(" | "[" | "{" | "<") => {
+            depth += 1;
+            type_parameters = token.text == "<";
+          }
+          // `on${string}`: a template literal type with placeholders
+          (Kind::Str, _) if template_nesting(&token) > 0 => depth += 1,
+          (Kind::Punct, "|" | "&" | "-" | "...") => {}
+          (Kind::Ident, keyword) if TYPE_PREFIX_KEYWORDS.contains(&keyword) => {}
+          (Kind::Ident | Kind::Str | Kind::Num, _) => expect_operand = false,
+          _ => break,
+        }
+      } else {
+        match (token.kind, token.text) {
+          (Kind::Punct, "|" | "&" | ".") => expect_operand = true,
+          (Kind::Punct, "[" | "<") => depth += 1,
+          // `typeof import("./module")`
+          (Kind::Punct, "(") if last.is_some_and(|last| self.is_ident(last, "import")) => {
+            depth += 1
+          }
+          (Kind::Ident, "extends" | "is") => expect_operand = true,
+          (Kind::Punct, "?") if end != TypeEnd::Cast => {
+            conditional += 1;
+            expect_operand = true;
+          }
+          (Kind::Punct, ":") if conditional > 0 => {
+            conditional -= 1;
+            expect_operand = true;
+          }
+          (Kind::Punct, "=>") if end.arrow_continues() => expect_operand = true,
+          _ => break,
+        }
+      }
+      last = Some(index);
+    }
+
+    last
+  }
+}
+
+/// Split source into tokens whose texts concatenate back to the source.
+/// Template literals are split around their `${...}` expressions, which
+/// are tokenized like any other code.
+fn tokenize(source: &str) -> Vec<Token<'_>> {
+  let mut tokens: Vec<Token> = Vec::new();
+  let mut position = 0;
+  // Open braces in each `${...}` being tokenized, innermost last
+  let mut templates: Vec<usize> = Vec::new();
+
+  while position < source.len() {
+    let rest = &source[position..];
+    let first = rest.chars().next().unwrap();
+
+    let (kind, length) = if first == '`' || (first == '}' && templates.last() == Some(&0)) {
+      if first == '}' {
+        templates.pop();
+      }
+      let (length, expression) = template_chunk(rest);
+      if expression {
+        templates.push(0);
+      }
+      (Kind::Str, length)
+    } else if first.is_whitespace() {
+      let length = rest
+        .find(|c: char| !c.is_whitespace())
+        .unwrap_or(rest.len());
+      (Kind::Space, length)
+    } else if rest.starts_with("//") {
+      (Kind::Comment, rest.find('\n').unwrap_or(rest.len()))
+    } else if let Some(comment) = rest.strip_prefix("/*") {
+      let length = comment.find("*/").map_or(rest.len(), |end| end + 4);
+      (Kind::Comment, length)
+    } else if first == '"' || first == '\'' {
+      match quoted_length(rest, first) {
+        Some(length) => (Kind::Str, length),
+        None => (Kind::Punct, 1),
+      }
+    } else if first == '/' && regex_allowed(&tokens) {
+      match regex_length(rest) {
+        Some(length) => (Kind::Str, length),
+        None => (Kind::Punct, 1),
+      }
+    } else if first.is_alphabetic() || matches!(first, '_' | '$' | '#') {
+      let length = rest[first.len_utf8()..]
+        .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+        .map_or(rest.len(), |end| end + first.len_utf8());
+      (Kind::Ident, length)
+    } else if first.is_ascii_digit() {
+      let length = rest
+        .find(|c: char| !(c.is_alphanumeric() || c == '.' || c == '_'))
+        .unwrap_or(rest.len());
+      (Kind::Num, length)
+    } else {
+      let operator = OPERATORS
+        .iter()
+        .find(|operator| rest.starts_with(*operator))
+        // `a ?.5 : b` is a ternary, not optional chaining
+        .filter(|operator| {
+          **operator != "?." || !rest[2..].starts_with(|c: char| c.is_ascii_digit())
+        });
+      (
+        Kind::Punct,
+        operator.map_or(first.len_utf8(), |operator| operator.len()),
+      )
+    };
+
+    if kind == Kind::Punct {
+      if let Some(depth) = templates.last_mut() {
+        match first {
+          '{' => *depth += 1,
+          '}' => *depth -= 1,
+          _ => {}
+        }
+      }
+    }
+    tokens.push(Token {
+      kind,
+      text: &rest[..length],
+    });
+    position += length;
+  }
+
+  tokens
+}
+
+/// Length of a quoted string, or None when the line ends first
+fn quoted_length(rest: &str, quote: char) -> Option<usize> {
+  let mut chars = rest.char_indices().skip(1);
+  while let Some((index, c)) = chars.next() {
+    match c {
+      '\\' => {
+        chars.next();
+      }
+      '\n' => return None,
+      c if c == quote => return Some(index + 1),
+      _ => {}
+    }
+  }
+  None
+}
+
+/// Length of the template literal text starting at `rest` (its opening
+/// backtick, or the `}` closing an expression), up to and including the
+/// closing backtick or the next `${`, and whether it ends with `${`
+fn template_chunk(rest: &str) -> (usize, bool) {
+  let bytes = rest.as_bytes();
+  let mut index = 1;
+  while index < bytes.len() {
+    match bytes[index] {
+      b'\\' => index += 1,
+      b'`' => return (index + 1, false),
+      b'$' if bytes.get(index + 1) == Some(&b'{') => return (index + 2, true),
+      _ => {}
+    }
+    index += 1;
+  }
+  (rest.len(), false)
+}
+
+/// How a template literal chunk changes the nesting of types: +1 for the
+/// head of a template with expressions, -1 for its tail
+fn template_nesting(token: &Token) -> isize {
+  if token.kind != Kind::Str {
+    return 0;
+  }
+  let opens = token.text.ends_with("${");
+  let closes = token.text.starts_with('}');
+  (opens as isize) - (closes as isize)
+}
+
+/// Whether a `/` here starts a regular expression rather than a division
+fn regex_allowed(tokens: &[Token]) -> bool {
+  let Some(prev) = tokens
+    .iter()
+    .rev()
+    .find(|token| !matches!(token.kind, Kind::Space | Kind::Comment))
+  else {
+    return true;
+  };
+  match prev.kind {
+    Kind::Punct => !matches!(prev.text, ")" | "]" | "}"),
+    Kind::Ident => REGEX_PREFIX_KEYWORDS.contains(&prev.text),
+    _ => false,
+  }
+}
+
+/// Length of a regular expression literal with its flags
+fn regex_length(rest: &str) -> Option<usize> {
+  let mut in_class = false;
+  let mut chars = rest.char_indices().skip(1);
+  while let Some((index, c)) = chars.next() {
+    match c {
+      '\\' => {
+        chars.next();
+      }
+      '\n' => return None,
+      '[' => in_class = true,
+      ']' => in_class = false,
+      '/' if !in_class => {
+        let flags = rest[index + 1..]
+          .find(|c: char| !c.is_ascii_alphabetic())
+          .unwrap_or(rest.len() - index - 1);
+        return Some(index + 1 + flags);
+      }
+      _ => {}
+    }
+  }
+  None
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_strip_declarations_and_imports() {
+    let source = r#"import type { ClassValue } from "clsx";
+import { clsx, type Foo } from "clsx";
+import { type Bar } from "./bar";
+import { twMerge } from "tailwind-merge";
+
+export type Size = "sm" | "md"
+  | "lg";
+
+interface Props {
+  size: Size;
+}
+
+export function cn(...inputs: ClassValue[]): string {
+  return twMerge(clsx(inputs));
+}
+"#;
+
+    assert_eq!(
+      strip_script(source).unwrap(),
+      r#"import { clsx } from "clsx";
+import { twMerge } from "tailwind-merge";
+
+export function cn(...inputs) {
+  return twMerge(clsx(inputs));
+}
+"#
+    );
+  }
+
+  #[test]
+  fn test_strip_expressions() {
+    let source = r#"const ref = useRef<HTMLDivElement>(null);
+let count: number = 0;
+const items = data as Item[];
+const handler = (event: MouseEvent, flag?: boolean) => event.target!.id;
+const value = flag ? a : b;
+const options = { size: "sm", onClick: () => {} } satisfies Options;
+"#;
+
+    assert_eq!(
+      strip_script(source).unwrap(),
+      r#"const ref = useRef(null);
+let count = 0;
+const items = data;
+const handler = (event, flag) => event.target.id;
+const value = flag ? a : b;
+const options = { size: "sm", onClick: () => {} };
+"#
+    );
+  }
+
+  #[test]
+  fn test_strip_class_and_generics() {
+    let source = r#"class Store<T> implements Readable<T> {
+  private value: T;
+  readonly count = 0;
+  constructor(value: T) {
+    this.value = value;
+  }
+  get(): T {
+    return this.value;
+  }
+}
+function first<T extends unknown[]>(items: T): T[0] {
+  return items[0];
+}
+"#;
+
+    assert_eq!(
+      strip_script(source).unwrap(),
+      r#"class Store {
+  value;
+  count = 0;
+  constructor(value) {
+    this.value = value;
+  }
+  get() {
+    return this.value;
+  }
+}
+function first(items) {
+  return items[0];
+}
+"#
+    );
+  }
+
+  #[test]
+  fn test_strip_svelte_script() {
+    let source = r#"<script lang="ts" generics="T">
+  import type { Snippet } from "svelte";
+  let { children, class: className }: { children: Snippet; class?: string } = $props();
+  const label = "a: b";
+</script>
+
+<div class={className}>{@render children()}</div>
+"#;
+
+    assert_eq!(
+      strip_types(Path::new("card.svelte"), source).unwrap(),
+      r#"<script>
+  let { children, class: className } = $props();
+  const label = "a: b";
+</script>
+
+<div class={className}>{@render children()}</div>
+"#
+    );
+  }
+
+  #[test]
+  fn test_strip_regressions() {
+    let strip = |source: &str| strip_script(source).unwrap();
+
+    // Comparisons aren't type arguments
+    assert_eq!(
+      strip("const a = b < c, d = e > (f);"),
+      "const a = b < c, d = e > (f);"
+    );
+    assert_eq!(
+      strip("const a = b<c, d = e>(f);"),
+      "const a = b<c, d = e>(f);"
+    );
+    // Overload signatures go, the implementation stays
+    assert_eq!(
+      strip(
+        "function f(a: string): void;\nexport function f(a: number): void;\nexport function f(a: any) {}\n"
+      ),
+      "export function f(a) {}\n"
+    );
+    // Type parameters of async arrow functions
+    assert_eq!(
+      strip("const id = async <T>(x: T): Promise<T> => x;"),
+      "const id = async (x) => x;"
+    );
+    // Casts inside template literals, and template literal types
+    assert_eq!(
+      strip("const s = `${a as string}-${b!.c}`;\ntype E = `on${string}`;\n"),
+      "const s = `${a}-${b.c}`;\n"
+    );
+    // A `this` parameter only types `this`
+    assert_eq!(
+      strip("function on(this: Window, event: Event) {}\nfunction off(this: Window) {}"),
+      "function on(event) {}\nfunction off() {}"
+    );
+    // Non-null assertions before operators
+    assert_eq!(strip("let u = a! + b!;"), "let u = a + b;");
+    assert_eq!(
+      strip("if (a)!b.c();\nlet d = e()! * f[0]!;"),
+      "if (a)!b.c();\nlet d = e() * f[0];"
+    );
+    // Generic function types
+    assert_eq!(strip("let fn: <T>(x: T) => T;"), "let fn;");
+    // Import types
+    assert_eq!(
+      strip("type X = typeof import('./x');\nconst y = 1;"),
+      "const y = 1;"
+    );
+    // An arrow function with a return type as the true branch of a
+    // conditional, but not a conditional whose false branch is an arrow
+    assert_eq!(strip("c ? (x): number => 1 : 2"), "c ? (x) => 1 : 2");
+    assert_eq!(strip("c ? (x) : y => 1"), "c ? (x) : y => 1");
+    assert_eq!(
+      strip("c ? (x): T => d ? 1 : 2 : 3"),
+      "c ? (x) => d ? 1 : 2 : 3"
+    );
+  }
+
+  #[test]
+  fn test_strip_syntax() {
+    for (source, expected) in [
+      // Annotations
+      ("let a: string;", "let a;"),
+      ("let a: string, b: number = 1;", "let a, b = 1;"),
+      ("let a!: string;", "let a;"),
+      ("const { a, b }: Props = props;", "const { a, b } = props;"),
+      (
+        "const [a, b]: [number, string] = pair;",
+        "const [a, b] = pair;",
+      ),
+      ("let a: A | B & C = x;", "let a = x;"),
+      ("let a: A.B.C<D>[] = [];", "let a = [];"),
+      ("let a: Array<Array<T>> = [];", "let a = [];"),
+      ("let a: Record<string, () => void> = {};", "let a = {};"),
+      ("let a: { b: string; c?: number } = x;", "let a = x;"),
+      ("let a: (string | number)[] = [];", "let a = [];"),
+      ("let a: readonly string[] = [];", "let a = [];"),
+      ("let a: keyof typeof b = 'c';", "let a = 'c';"),
+      ("let a: `on${string}` = 'onClick';", "let a = 'onClick';"),
+      ("let a: \"x\" | 'y' | 1 | -1 = 1;", "let a = 1;"),
+      ("let a: T[K] extends string ? K : never;", "let a;"),
+      ("let f: new () => T;", "let f;"),
+      ("let f: (a: A, ...b: B[]) => void = g;", "let f = g;"),
+      (
+        "for (let i: number = 0; i < n; i++) {}",
+        "for (let i = 0; i < n; i++) {}",
+      ),
+      // Functions
+      (
+        "function f(a: string, b?: number): void {}",
+        "function f(a, b) {}",
+      ),
+      ("function f(a: string = 'x') {}", "function f(a = 'x') {}"),
+      (
+        "function f({ a }: Props, [b]: T[]) {}",
+        "function f({ a }, [b]) {}",
+      ),
+      (
+        "function f<T, U extends T = T>(a: T): U {}",
+        "function f(a) {}",
+      ),
+      ("function* g(): Generator<number> {}", "function* g() {}"),
+      (
+        "async function f(): Promise<void> {}",
+        "async function f() {}",
+      ),
+      (
+        "function is(a: unknown): a is string {}",
+        "function is(a) {}",
+      ),
+      ("function ok(a: unknown): asserts a {}", "function ok(a) {}"),
+      ("const f = (a: T): R => a;", "const f = (a) => a;"),
+      ("const f = <T,>(a: T) => a;", "const f = (a) => a;"),
+      (
+        "const f = async (a: T): Promise<R> => a;",
+        "const f = async (a) => a;",
+      ),
+      (
+        "const o = { m(a: T): R { return a; } };",
+        "const o = { m(a) { return a; } };",
+      ),
+      (
+        "items.map((item: Item, i: number) => i);",
+        "items.map((item, i) => i);",
+      ),
+      // Expressions
+      ("const a = b as unknown as C;", "const a = b;"),
+      ("const a = (b as C).d;", "const a = (b).d;"),
+      ("const a = <const>['x'];", "const a = <const>['x'];"),
+      ("const a = b as const;", "const a = b;"),
+      ("const a = { b } satisfies C;", "const a = { b };"),
+      ("const a = b!.c!.d;", "const a = b.c.d;"),
+      ("const a = b![0]!(c);", "const a = b[0](c);"),
+      ("const a = b! == c;", "const a = b == c;"),
+      ("const a = !b && !c;", "const a = !b && !c;"),
+      ("const a = b !== c;", "const a = b !== c;"),
+      (
+        "const a = new Map<string, number>();",
+        "const a = new Map();",
+      ),
+      ("const a = f<T>(b)<U>(c);", "const a = f(b)(c);"),
+      ("const a = b ? c : d;", "const a = b ? c : d;"),
+      ("const a = b ? (c) : d;", "const a = b ? (c) : d;"),
+      ("const a = b?.c ?? d;", "const a = b?.c ?? d;"),
+      ("const a = b < c && d > e;", "const a = b < c && d > e;"),
+      (
+        "const a = /<T>(x: T)/g.test(b);",
+        "const a = /<T>(x: T)/g.test(b);",
+      ),
+      (
+        "const a = 'x: T' + \"y as z\";",
+        "const a = 'x: T' + \"y as z\";",
+      ),
+      ("const a = `${b}: ${c as D}`;", "const a = `${b}: ${c}`;"),
+      (
+        "// a: T\nconst b = c; /* d as E */",
+        "// a: T\nconst b = c; /* d as E */",
+      ),
+      (
+        "switch (a) { case b: break; default: c(); }",
+        "switch (a) { case b: break; default: c(); }",
+      ),
+      (
+        "label: for (;;) { break label; }",
+        "label: for (;;) { break label; }",
+      ),
+      // Declarations
+      ("type A<T> = { a: T };\nconst b = 1;", "const b = 1;"),
+      (
+        "interface A extends B<C> { a(): void }\nconst b = 1;",
+        "const b = 1;",
+      ),
+      ("declare const a: string;\nconst b = 1;", "const b = 1;"),
+      (
+        "declare global { interface Window { a: string } }\nconst b = 1;",
+        "const b = 1;",
+      ),
+      (
+        "declare module \"x\" { export const a: string; }\nconst b = 1;",
+        "const b = 1;",
+      ),
+      (
+        "export type { A, B } from \"./a\";\nconst b = 1;",
+        "const b = 1;",
+      ),
+      ("export { a, type B };", "export { a };"),
+      ("export { type B };\nconst b = 1;", "const b = 1;"),
+      ("import type A from \"a\";\nconst b = 1;", "const b = 1;"),
+      ("import A, { type B } from \"a\";", "import A from \"a\";"),
+      (
+        "import { a as type } from \"a\";",
+        "import { a as type } from \"a\";",
+      ),
+      ("import * as a from \"a\";", "import * as a from \"a\";"),
+      ("const type = 1;\ntype;", "const type = 1;\ntype;"),
+      // Classes
+      ("class A extends B<C> {}", "class A extends B {}"),
+      ("class A implements B, C<D> {}", "class A {}"),
+      ("abstract class A {}", "class A {}"),
+      ("class A { a?: string; b!: number; }", "class A { a; b; }"),
+      ("class A { static a: T = 1; }", "class A { static a = 1; }"),
+      (
+        "class A { private static readonly a = 1; }",
+        "class A { static a = 1; }",
+      ),
+      ("class A { public m(): void {} }", "class A { m() {} }"),
+      (
+        "class A { protected get a(): T { return 1; } }",
+        "class A { get a() { return 1; } }",
+      ),
+      ("class A { override m<T>(a: T) {} }", "class A { m(a) {} }"),
+      ("class A { #a: number = 0; }", "class A { #a = 0; }"),
+      (
+        "class A {\n  [key: string]: unknown;\n  a = 1;\n}",
+        "class A {\n  a = 1;\n}",
+      ),
+      ("class A { [key]: T; }", "class A { [key]; }"),
+      // JSX
+      (
+        "const a = <Button as=\"a\" onClick={(e: Event) => f(e!)} />;",
+        "const a = <Button as=\"a\" onClick={(e) => f(e)} />;",
+      ),
+      (
+        "return <div>{items.map((item: Item) => <Row key={item.id} {...item} />)}</div>;",
+        "return <div>{items.map((item) => <Row key={item.id} {...item} />)}</div>;",
+      ),
+      (
+        "const A = <T,>({ a }: Props<T>) => <p>{a as string}</p>;",
+        "const A = ({ a }) => <p>{a}</p>;",
+      ),
+      // Multi-line
+      (
+        "const a: {\n  b: string;\n} = {\n  b: \"c\",\n};\n",
+        "const a = {\n  b: \"c\",\n};\n",
+      ),
+      (
+        "function f(\n  a: string,\n  b: number,\n): void {\n  g();\n}\n",
+        "function f(\n  a,\n  b,\n) {\n  g();\n}\n",
+      ),
+      (
+        "let a = 1,\n  b: string = \"c\";\n",
+        "let a = 1,\n  b = \"c\";\n",
+      ),
+      (
+        "const a = b\n  ? (c as D)\n  : e;\n",
+        "const a = b\n  ? (c)\n  : e;\n",
+      ),
+      (
+        "export type A =\n  | { kind: \"a\" }\n  | { kind: \"b\" };\nexport const b = 1;\n",
+        "export const b = 1;\n",
+      ),
+      (
+        "export interface A {\n  b: string;\n}\n\nexport const c = 1;\n",
+        "export const c = 1;\n",
+      ),
+      (
+        "const a = b as\n  | C\n  | D;\nconst e = 1;\n",
+        "const a = b;\nconst e = 1;\n",
+      ),
+      ("const a = f<\n  string\n>(b);\n", "const a = f(b);\n"),
+    ] {
+      assert_eq!(strip_script(source).as_deref(), Ok(expected), "{}", source);
+    }
+  }
+
+  #[test]
+  fn test_strip_unsupported() {
+    for (source, construct) in [
+      ("enum Color { Red, Green }", "An enum"),
+      ("export const enum Size { Sm = 1 }", "An enum"),
+      ("namespace Shapes { export const a = 1; }", "A namespace"),
+      (
+        "class A { constructor(private value: string) {} }",
+        "A constructor parameter property",
+      ),
+      (
+        "abstract class A { abstract draw(): void; }",
+        "A method signature without a body",
+      ),
+      (
+        "class A { f(a: string): void;\n  f(a: any) {} }",
+        "A method signature without a body",
+      ),
+      ("let a: T b = 1;", "An unrecognized type"),
+      ("function f(a: T b) {}", "An unrecognized type"),
+      ("type A = B C;", "An unrecognized type"),
+      ("class A { a: T b; }", "An unrecognized type"),
+    ] {
+      assert_eq!(
+        strip_script(source),
+        Err(Unsupported(construct)),
+        "{}",
+        source
+      );
+    }
+    assert!(strip_types(
+      Path::new("a.svelte"),
+      "<script lang=\"ts\">enum A { B }</script>"
+    )
+    .is_err());
+
+    // Identifiers that merely share the keywords are fine
+    assert_eq!(
+      strip_script("const module = { enum: 1 };\nmodule.exports = enumerate;").unwrap(),
+      "const module = { enum: 1 };\nmodule.exports = enumerate;"
+    );
+  }
+
+  #[test]
+  fn test_js_path() {
+    assert_eq!(
+      js_path(Path::new("ui/button.tsx")),
+      Path::new("ui/button.jsx")
+    );
+    assert_eq!(
+      js_path(Path::new("lib/utils.ts")),
+      Path::new("lib/utils.js")
+    );
+    assert_eq!(
+      js_path(Path::new("ui/button.svelte")),
+      Path::new("ui/button.svelte")
+    );
+    assert!(is_declaration_file(Path::new("types/env.d.ts")));
+    assert!(!is_declaration_file(Path::new("lib/utils.ts")));
+  }
+}
@@ -0,0 +1,244 @@
+//! Shape checks for component JSON served by registries, so a malformed
+//! item is reported by the field at fault instead of serde's line and
+//! column
+
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+/// A field of a component without the expected shape, e.g.
+/// `files[0].content` holding a number
+#[derive(Debug, Error, PartialEq)]
+#[error("`{field}` {problem}")]
+pub struct FieldError {
+  pub field: String,
+  pub problem: String,
+}
+
+/// Value a field must hold
+#[derive(Clone, Copy)]
+enum Shape {
+  String,
+  Bool,
+  Strings,
+  /// npm dependencies: specifiers or `{ "name", "version" }` objects
+  Dependencies,
+  /// An object of strings, like `engines`
+  StringMap,
+  /// Any object
+  Object,
+  Files,
+  /// A URL, or `{ "version", "date", "notes" }` entries
+  Changelog,
+}
+
+/// Fields of a registry item: name, shape and whether it's required
+const COMPONENT_FIELDS: &[(&str, Shape, bool)] = &[
+  ("name", Shape::String, true),
+  ("type", Shape::String, false),
+  ("description", Shape::String, false),
+  ("version", Shape::String, false),
+  ("tags", Shape::Strings, false),
+  ("docs", Shape::String, false),
+  ("license", Shape::String, false),
+  ("links", Shape::StringMap, false),
+  ("usage", Shape::String, false),
+  ("changelog", Shape::Changelog, false),
+  ("config", Shape::Object, false),
+  ("postInstallMessage", Shape::String, false),
+  ("deprecated", Shape::Bool, false),
+  ("replacedBy", Shape::String, false),
+  ("dependencies", Shape::Dependencies, false),
+  ("devDependencies", Shape::Dependencies, false),
+  ("peerDependencies", Shape::Dependencies, false),
+  ("engines", Shape::StringMap, false),
+  ("registryDependencies", Shape::Strings, false),
+  ("frameworks", Shape::StringMap, false),
+  ("cssVars", Shape::Object, false),
+  ("css", Shape::Object, false),
+  ("files", Shape::Files, true),
+];
+
+/// Fields of an entry in `changelog`
+const CHANGELOG_FIELDS: &[(&str, Shape, bool)] = &[
+  ("version", Shape::String, true),
+  ("date", Shape::String, false),
+  ("notes", Shape::String, true),
+];
+
+/// Fields of an entry in `files`
+const FILE_FIELDS: &[(&str, Shape, bool)] = &[
+  ("content", Shape::String, true),
+  ("type", Shape::String, false),
+  ("target", Shape::String, false),
+  ("path", Shape::String, false),
+  ("condition", Shape::String, false),
+];
+
+/// Check that `value` has the shape of a registry item. Unknown fields are
+/// allowed; `null` counts as leaving an optional field out.
+pub fn check_component(value: &Value) -> Result<(), FieldError> {
+  let Some(object) = value.as_object() else {
+    return Err(FieldError {
+      field: "$".to_string(),
+      problem: format!("must be an object, found {}", kind(value)),
+    });
+  };
+  check_fields(object, COMPONENT_FIELDS, "")
+}
+
+fn check_fields(
+  object: &Map<String, Value>,
+  fields: &[(&str, Shape, bool)],
+  prefix: &str,
+) -> Result<(), FieldError> {
+  for &(name, shape, required) in fields {
+    let field = format!("{}{}", prefix, name);
+    match object.get(name) {
+      None | Some(Value::Null) if required => {
+        return Err(FieldError {
+          field,
+          problem: "is required".to_string(),
+        })
+      }
+      None | Some(Value::Null) => {}
+      Some(value) => check_shape(value, shape, &field)?,
+    }
+  }
+  Ok(())
+}
+
+fn check_shape(value: &Value, shape: Shape, field: &str) -> Result<(), FieldError> {
+  let expect = |field: &str, expected: &str, value: &Value| {
+    Err(FieldError {
+      field: field.to_string(),
+      problem: format!("must be {}, found {}", expected, kind(value)),
+    })
+  };
+
+  match shape {
+    Shape::String if !value.is_string() => expect(field, "a string", value),
+    Shape::Bool if !value.is_boolean() => expect(field, "a boolean", value),
+    Shape::Object if !value.is_object() => expect(field, "an object", value),
+    Shape::String | Shape::Bool | Shape::Object => Ok(()),
+    Shape::Changelog if value.is_string() => Ok(()),
+    Shape::Changelog if !value.is_array() => expect(field, "a URL or an array", value),
+    Shape::Strings | Shape::Dependencies | Shape::Files | Shape::Changelog => {
+      let Some(items) = value.as_array() else {
+        return expect(field, "an array", value);
+      };
+      for (index, item) in items.iter().enumerate() {
+        let field = format!("{}[{}]", field, index);
+        match (shape, item) {
+          (Shape::Strings, item) => check_shape(item, Shape::String, &field)?,
+          (Shape::Dependencies, Value::String(_)) => {}
+          (Shape::Dependencies, Value::Object(dependency)) => check_fields(
+            dependency,
+            &[
+              ("name", Shape::String, true),
+              ("version", Shape::String, false),
+            ],
+            &format!("{}.", field),
+          )?,
+          (Shape::Dependencies, item) => {
+            return expect(&field, "a string or a { name, version } object", item)
+          }
+          (Shape::Changelog, Value::Object(entry)) => {
+            check_fields(entry, CHANGELOG_FIELDS, &format!("{}.", field))?
+          }
+          (_, Value::Object(file)) => check_fields(file, FILE_FIELDS, &format!("{}.", field))?,
+          (_, item) => return expect(&field, "an object", item),
+        }
+      }
+      Ok(())
+    }
+    Shape::StringMap => {
+      let Some(entries) = value.as_object() else {
+        return expect(field, "an object", value);
+      };
+      for (key, entry) in entries {
+        check_shape(entry, Shape::String, &format!("{}.{}", field, key))?;
+      }
+      Ok(())
+    }
+  }
+}
+
+/// How a JSON value is described in errors
+fn kind(value: &Value) -> &'static str {
+  match value {
+    Value::Null => "null",
+    Value::Bool(_) => "a boolean",
+    Value::Number(_) => "a number",
+    Value::String(_) => "a string",
+    Value::Array(_) => "an array",
+    Value::Object(_) => "an object",
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use super::*;
+
+  fn problem(value: Value) -> String {
+    check_component(&value).unwrap_err().to_string()
+  }
+
+  #[test]
+  fn test_check_component() {
+    let valid = json!({
+      "name": "button",
+      "type": "registry:ui",
+      "description": null,
+      "dependencies": ["zod@^3.23", { "name": "bits-ui", "version": "^1.0" }],
+      "engines": { "node": ">=18" },
+      "changelog": [{ "version": "1.1.0", "notes": "Add the ghost variant" }],
+      "files": [{ "content": "export {}", "target": "button/index.ts", "extra": 1 }]
+    });
+    assert_eq!(check_component(&valid), Ok(()));
+
+    assert_eq!(problem(json!([])), "`$` must be an object, found an array");
+    assert_eq!(problem(json!({ "name": "button" })), "`files` is required");
+    assert_eq!(
+      problem(json!({ "name": 1, "files": [] })),
+      "`name` must be a string, found a number"
+    );
+    assert_eq!(
+      problem(json!({ "name": "button", "files": {} })),
+      "`files` must be an array, found an object"
+    );
+    assert_eq!(
+      problem(json!({ "name": "button", "files": [{ "content": "", "target": 3 }] })),
+      "`files[0].target` must be a string, found a number"
+    );
+    assert_eq!(
+      problem(json!({ "name": "button", "files": [{ "path": "a.ts" }] })),
+      "`files[0].content` is required"
+    );
+    assert_eq!(
+      problem(json!({ "name": "button", "dependencies": [{ "version": "1" }], "files": [] })),
+      "`dependencies[0].name` is required"
+    );
+    assert_eq!(
+      problem(json!({ "name": "button", "registryDependencies": "card", "files": [] })),
+      "`registryDependencies` must be an array, found a string"
+    );
+    assert_eq!(
+      problem(json!({ "name": "button", "engines": { "node": 18 }, "files": [] })),
+      "`engines.node` must be a string, found a number"
+    );
+    assert_eq!(
+      problem(json!({ "name": "button", "changelog": [{ "version": "1.1.0" }], "files": [] })),
+      "`changelog[0].notes` is required"
+    );
+    assert_eq!(
+      problem(json!({ "name": "button", "changelog": 2, "files": [] })),
+      "`changelog` must be a URL or an array, found a number"
+    );
+    assert_eq!(
+      problem(json!({ "name": "kit", "config": ["aliases"], "files": [] })),
+      "`config` must be an object, found an array"
+    );
+  }
+}
@@ -0,0 +1,107 @@
+//! Template variables substituted in installed file content: `$NAME$` for
+//! project values uiget knows and for the `variables` of uiget.json
+
+use std::{fs, path::Path};
+
+use indexmap::IndexMap;
+use regex::{Captures, Regex};
+
+/// Values for `$NAME$` placeholders. `PROJECT_NAME` and `ORG` come from the
+/// package.json name (`@org/name`), or the directory name; configured
+/// variables are added last and win over them.
+pub fn project_variables(
+  project_root: &Path,
+  configured: Option<&IndexMap<String, String>>,
+) -> IndexMap<String, String> {
+  let mut variables = IndexMap::new();
+
+  let package_name = fs::read_to_string(project_root.join("package.json"))
+    .ok()
+    .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+    .and_then(|package_json| package_json["name"].as_str().map(String::from));
+  match package_name {
+    Some(package_name) => match package_name.split_once('/') {
+      Some((scope, name)) => {
+        variables.insert("PROJECT_NAME".to_string(), name.to_string());
+        variables.insert("ORG".to_string(), scope.trim_start_matches('@').to_string());
+      }
+      None => {
+        variables.insert("PROJECT_NAME".to_string(), package_name);
+      }
+    },
+    None => {
+      if let Some(name) = project_root.file_name() {
+        variables.insert(
+          "PROJECT_NAME".to_string(),
+          name.to_string_lossy().to_string(),
+        );
+      }
+    }
+  }
+
+  if let Some(configured) = configured {
+    variables.extend(configured.clone());
+  }
+  variables
+}
+
+/// Replace `$NAME$` for every variable in `variables`. Other text between
+/// dollar signs is left alone.
+pub fn substitute(content: &str, variables: &IndexMap<String, String>) -> String {
+  if variables.is_empty() || !content.contains('$') {
+    return content.to_string();
+  }
+
+  let placeholder = Regex::new(r"\$([A-Za-z_][A-Za-z0-9_]*)\$").unwrap();
+  placeholder
+    .replace_all(content, |captures: &Captures| {
+      variables
+        .get(&captures[1])
+        .cloned()
+        .unwrap_or_else(|| captures[0].to_string())
+    })
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_project_variables() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    fs::write(
+      temp_dir.path().join("package.json"),
+      r#"{ "name": "@acme/web" }"#,
+    )
+    .unwrap();
+    let configured = IndexMap::from([
+      ("API_URL".to_string(), "https://api.acme.dev".to_string()),
+      ("ORG".to_string(), "Acme Inc.".to_string()),
+    ]);
+
+    let variables = project_variables(temp_dir.path(), Some(&configured));
+    assert_eq!(variables["PROJECT_NAME"], "web");
+    assert_eq!(variables["ORG"], "Acme Inc.");
+    assert_eq!(variables["API_URL"], "https://api.acme.dev");
+
+    let variables = project_variables(&temp_dir.path().join("site"), None);
+    assert_eq!(variables["PROJECT_NAME"], "site");
+    assert!(!variables.contains_key("ORG"));
+  }
+
+  #[test]
+  fn test_substitute() {
+    let variables = IndexMap::from([
+      ("PROJECT_NAME".to_string(), "web".to_string()),
+      ("ORG".to_string(), "acme".to_string()),
+    ]);
+    assert_eq!(
+      substitute(
+        "name: $PROJECT_NAME$ by $ORG$, costs $5 or $OTHER$",
+        &variables
+      ),
+      "name: web by acme, costs $5 or $OTHER$"
+    );
+  }
+}
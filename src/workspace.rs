@@ -0,0 +1,300 @@
+//! Projects of a monorepo that have their own uiget configuration, for the
+//! `ws` commands
+
+use std::{
+  ffi::OsString,
+  path::{Path, PathBuf},
+  process::Stdio,
+};
+
+use anyhow::{anyhow, Result};
+use colored::*;
+use futures_util::{stream, StreamExt};
+use walkdir::WalkDir;
+
+use crate::{
+  output::{status, warning},
+  package_manager::read_workspace_patterns,
+  registry::glob_match,
+};
+
+/// Files that make a directory a uiget project, in order of preference
+const CONFIG_FILES: [&str; 2] = ["uiget.json", "components.json"];
+
+/// Directories never searched for projects, besides hidden ones
+const SKIPPED_DIRS: [&str; 4] = ["node_modules", "target", "dist", "build"];
+
+/// A directory of the workspace with its own configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Project {
+  /// Path from the workspace root with forward slashes, `.` for the root
+  pub name: String,
+  pub dir: PathBuf,
+}
+
+/// Find the uiget projects under `root` whose path matches one of
+/// `patterns` (globs like `apps/*`; a leading `!` excludes). Without
+/// patterns, the monorepo's package globs are used, and without those every
+/// project counts.
+pub fn discover_projects(root: &Path, patterns: &[String]) -> Vec<Project> {
+  let patterns = if patterns.is_empty() {
+    read_workspace_patterns(root)
+  } else {
+    patterns.to_vec()
+  };
+  let (excluded, included): (Vec<&str>, Vec<&str>) = patterns
+    .iter()
+    .map(|pattern| pattern.trim_start_matches("./").trim_end_matches('/'))
+    .partition(|pattern| pattern.starts_with('!'));
+  let selected = |name: &str| {
+    (included.is_empty() || included.iter().any(|pattern| glob_match(pattern, name)))
+      && !excluded
+        .iter()
+        .any(|pattern| glob_match(&pattern[1..], name))
+  };
+
+  let walker = WalkDir::new(root).sort_by_file_name().into_iter();
+  walker
+    .filter_entry(|entry| {
+      let name = entry.file_name().to_string_lossy();
+      entry.depth() == 0
+        || !entry.file_type().is_dir()
+        || !(name.starts_with('.') || SKIPPED_DIRS.contains(&name.as_ref()))
+    })
+    .filter_map(Result::ok)
+    .filter(|entry| entry.file_type().is_dir())
+    .filter(|entry| {
+      CONFIG_FILES
+        .iter()
+        .any(|file| entry.path().join(file).is_file())
+    })
+    .map(|entry| {
+      let relative = entry.path().strip_prefix(root).unwrap_or(entry.path());
+      let name = if relative.as_os_str().is_empty() {
+        ".".to_string()
+      } else {
+        relative.to_string_lossy().replace('\\', "/")
+      };
+      Project {
+        name,
+        dir: entry.path().to_path_buf(),
+      }
+    })
+    .filter(|project| selected(&project.name))
+    .collect()
+}
+
+/// What a `ws` command did in one project: the components it listed, found
+/// outdated or updated, or why it failed
+pub type ProjectOutcome = Result<Vec<String>, String>;
+
+/// Run uiget with `args` in every project, `max_parallel` at a time, and
+/// collect what each run reports through its `--result` file. Each run is a
+/// process of its own in the project's directory, so projects share no
+/// installer state, working directory or lockfile. One at a time, runs
+/// print as they go and may prompt; in parallel, each project's output is
+/// printed in one block once it finishes.
+pub async fn run_projects(
+  projects: Vec<Project>,
+  args: &[OsString],
+  max_parallel: usize,
+) -> WorkspaceReport {
+  let parallel = max_parallel > 1;
+  let mut outcomes: Vec<(usize, String, ProjectOutcome)> =
+    stream::iter(projects.into_iter().enumerate())
+      .map(|(index, project)| async move {
+        if !parallel {
+          status!("\n{} {}", "▸".blue(), project.name.cyan().bold());
+        }
+        let outcome = run_project(&project, args, index, parallel)
+          .await
+          .unwrap_or_else(|e| Err(e.to_string()));
+        (index, project.name, outcome)
+      })
+      .buffer_unordered(max_parallel.max(1))
+      .collect()
+      .await;
+
+  outcomes.sort_by_key(|(index, _, _)| *index);
+  WorkspaceReport {
+    projects: outcomes
+      .into_iter()
+      .map(|(_, name, outcome)| (name, outcome))
+      .collect(),
+  }
+}
+
+/// Run uiget for one project and read back its outcome
+async fn run_project(
+  project: &Project,
+  args: &[OsString],
+  index: usize,
+  parallel: bool,
+) -> Result<ProjectOutcome> {
+  let result_path =
+    std::env::temp_dir().join(format!("uiget-ws-{}-{}.json", std::process::id(), index));
+  let mut command = tokio::process::Command::new(std::env::current_exe()?);
+  command
+    .args(args)
+    .arg("--result")
+    .arg(&result_path)
+    .current_dir(&project.dir);
+
+  let status = if parallel {
+    // Captured output isn't a terminal, so keep colors when ours has them
+    if colored::control::SHOULD_COLORIZE.should_colorize() {
+      command.env("CLICOLOR_FORCE", "1");
+    }
+    let output = command.stdin(Stdio::null()).output().await?;
+    status!("\n{} {}", "▸".blue(), project.name.cyan().bold());
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+      status!("{}", line);
+    }
+    for line in String::from_utf8_lossy(&output.stderr).lines() {
+      warning!("{}", line);
+    }
+    output.status
+  } else {
+    command.status().await?
+  };
+
+  let result = std::fs::read_to_string(&result_path);
+  let _ = std::fs::remove_file(&result_path);
+  match result {
+    Ok(json) => Ok(serde_json::from_str(&json)?),
+    Err(_) => Err(anyhow!("uiget exited with {}", status)),
+  }
+}
+
+/// Summary of a `ws` command over every project
+#[derive(Debug, Default)]
+pub struct WorkspaceReport {
+  pub projects: Vec<(String, ProjectOutcome)>,
+}
+
+impl WorkspaceReport {
+  /// Number of projects the command failed in
+  pub fn failures(&self) -> usize {
+    self
+      .projects
+      .iter()
+      .filter(|(_, outcome)| outcome.is_err())
+      .count()
+  }
+
+  /// Summary lines: one per project, then the totals. `label` names the
+  /// components, e.g. "outdated", and `none` is shown for projects without
+  /// any.
+  pub fn lines(&self, label: &str, none: &str) -> Vec<String> {
+    let width = self
+      .projects
+      .iter()
+      .map(|(name, _)| name.len())
+      .max()
+      .unwrap_or(0);
+
+    let mut lines = vec![format!("{} Workspace summary:", "📊".blue())];
+    for (name, outcome) in &self.projects {
+      let detail = match outcome {
+        Ok(components) if components.is_empty() => none.dimmed().to_string(),
+        Ok(components) => format!(
+          "{} {}: {}",
+          components.len(),
+          label,
+          components.join(", ").cyan()
+        ),
+        Err(error) => format!("{} {}", "✗".red(), error),
+      };
+      lines.push(format!("  {:width$}  {}", name, detail, width = width));
+    }
+
+    let total: usize = self
+      .projects
+      .iter()
+      .filter_map(|(_, outcome)| outcome.as_ref().ok())
+      .map(Vec::len)
+      .sum();
+    lines.push(format!(
+      "  {} {} in {} project(s){}",
+      total,
+      label,
+      self.projects.len(),
+      match self.failures() {
+        0 => String::new(),
+        failures => format!(", {} failed", failures).red().to_string(),
+      }
+    ));
+    lines
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::fs;
+
+  use tempfile::TempDir;
+
+  use super::*;
+
+  #[test]
+  fn test_discover_projects() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    for dir in [
+      "apps/web",
+      "apps/docs",
+      "packages/ui",
+      "packages/ui/node_modules/dep",
+      ".cache/old",
+    ] {
+      fs::create_dir_all(root.join(dir)).unwrap();
+      fs::write(root.join(dir).join("uiget.json"), "{}").unwrap();
+    }
+    fs::create_dir_all(root.join("apps/admin")).unwrap();
+    fs::write(root.join("apps/admin/components.json"), "{}").unwrap();
+    fs::write(root.join("uiget.json"), "{}").unwrap();
+
+    let names = |patterns: &[&str]| {
+      let patterns: Vec<String> = patterns.iter().map(|p| p.to_string()).collect();
+      discover_projects(root, &patterns)
+        .into_iter()
+        .map(|project| project.name)
+        .collect::<Vec<_>>()
+    };
+    assert_eq!(
+      names(&[]),
+      [".", "apps/admin", "apps/docs", "apps/web", "packages/ui"]
+    );
+    assert_eq!(names(&["apps/*", "!apps/docs"]), ["apps/admin", "apps/web"]);
+
+    fs::write(
+      root.join("package.json"),
+      r#"{ "workspaces": { "packages": ["packages/*"] } }"#,
+    )
+    .unwrap();
+    fs::write(
+      root.join("pnpm-workspace.yaml"),
+      "packages:\n  - 'apps/web' # the app\n  - \"!apps/docs\"\ncatalog:\n  - apps/admin\n",
+    )
+    .unwrap();
+    assert_eq!(names(&[]), ["apps/web", "packages/ui"]);
+  }
+
+  #[test]
+  fn test_workspace_report() {
+    let report = WorkspaceReport {
+      projects: vec![
+        ("apps/web".to_string(), Ok(vec!["button".to_string()])),
+        ("packages/ui".to_string(), Ok(vec![])),
+        ("apps/docs".to_string(), Err("No configuration".to_string())),
+      ],
+    };
+    colored::control::set_override(false);
+    let lines = report.lines("outdated", "up to date");
+    assert_eq!(lines[1], "  apps/web     1 outdated: button");
+    assert_eq!(lines[2], "  packages/ui  up to date");
+    assert_eq!(lines[3], "  apps/docs    ✗ No configuration");
+    assert_eq!(lines[4], "  1 outdated in 3 project(s), 1 failed");
+    assert_eq!(report.failures(), 1);
+  }
+}